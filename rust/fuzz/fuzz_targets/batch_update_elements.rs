@@ -0,0 +1,37 @@
+//! Arbitrary bytes through the gpui_batch_update_elements FFI entry point
+//! Every input must come back as an FfiResult, never a crash
+
+#![no_main]
+
+use std::ffi::CString;
+
+use gpui_renderer::{FfiResult, gpui_batch_update_elements, gpui_free_result};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+	// Interior NULs can't cross the C string boundary; split on the first one
+	let json = data.split(|b| *b == 0).next().unwrap_or_default();
+	let Ok(json) = CString::new(json) else {
+		return;
+	};
+
+	// Window ids from the input exercise both unknown-window and pointer paths
+	let mut window_id = [0u8; 8];
+	window_id[..data.len().min(8)].copy_from_slice(&data[..data.len().min(8)]);
+	let count = (data.len() as u64).to_le_bytes();
+
+	let mut result = FfiResult::success();
+	gpui_batch_update_elements(window_id.as_ptr(), count.as_ptr(), json.as_ptr(), &mut result);
+	gpui_free_result(result);
+
+	// Null pointers must be rejected, not dereferenced
+	let mut result = FfiResult::success();
+	gpui_batch_update_elements(std::ptr::null(), std::ptr::null(), std::ptr::null(), &mut result);
+	gpui_free_result(result);
+	gpui_batch_update_elements(
+		window_id.as_ptr(),
+		count.as_ptr(),
+		json.as_ptr(),
+		std::ptr::null_mut(),
+	);
+});
@@ -0,0 +1,21 @@
+//! Arbitrary queries and ids through the find-in-page FFI entry points
+
+#![no_main]
+
+use std::ffi::CString;
+
+use gpui_renderer::{gpui_find, gpui_find_next, gpui_free_event_string};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+	let query = data.split(|b| *b == 0).next().unwrap_or_default();
+	let Ok(query) = CString::new(query) else {
+		return;
+	};
+	let mut ids = [0u8; 16];
+	ids[..data.len().min(16)].copy_from_slice(&data[..data.len().min(16)]);
+
+	gpui_free_event_string(gpui_find(ids[..8].as_ptr(), ids[8..].as_ptr(), query.as_ptr()));
+	gpui_free_event_string(gpui_find(ids[..8].as_ptr(), ids[8..].as_ptr(), std::ptr::null()));
+	gpui_free_event_string(gpui_find_next(ids[..8].as_ptr(), ids[8..].as_ptr(), data.len() % 2 == 0));
+});
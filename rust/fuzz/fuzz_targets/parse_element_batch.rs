@@ -0,0 +1,9 @@
+//! Arbitrary JSON through batch parsing, style parsing and GPUI style building
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+	gpui_renderer::fuzzing::parse_element_batch(data);
+});
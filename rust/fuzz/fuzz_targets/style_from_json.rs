@@ -0,0 +1,9 @@
+//! Arbitrary JSON through ElementStyle::from_json and style inheritance
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+	gpui_renderer::fuzzing::parse_style(data);
+});
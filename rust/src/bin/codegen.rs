@@ -1,8 +1,10 @@
 //! Code generator for TypeScript event types and event data
 //! Generates TypeScript definitions and Rust structs from a single source of
-//! truth
+//! truth. Protocol typings (FFI symbols, element kinds, style keys) are read
+//! from the Rust sources themselves, so the JS package can't drift from them.
 //!
 //! Run with: cargo run --bin codegen
+//! Check that generated files are current: cargo run --bin codegen -- --check
 
 use std::{fs, path::Path};
 
@@ -45,6 +47,12 @@ const EVENT_DEFINITIONS: &[EventDef] = &[
 		category:   EventCategory::Mouse,
 	},
 	EventDef { prop_name: "onHover", event_type: "hover", category: EventCategory::Mouse },
+	EventDef {
+		prop_name:  "onLongPress",
+		event_type: "longpress",
+		category:   EventCategory::Mouse,
+	},
+	EventDef { prop_name: "onDismiss", event_type: "dismiss", category: EventCategory::Mouse },
 	// Keyboard events
 	EventDef { prop_name: "onKeyDown", event_type: "keydown", category: EventCategory::Keyboard },
 	EventDef { prop_name: "onKeyUp", event_type: "keyup", category: EventCategory::Keyboard },
@@ -144,6 +152,13 @@ const KEYBOARD_EVENT_FIELDS: &[EventField] = &[
 		json_key:  "repeat",
 		optional:  false,
 	},
+	EventField {
+		name:      "repeat_count",
+		rust_type: "u32",
+		ts_type:   "number",
+		json_key:  "repeatCount",
+		optional:  false,
+	},
 	EventField {
 		name:      "ctrl",
 		rust_type: "bool",
@@ -238,6 +253,13 @@ const INPUT_EVENT_FIELDS: &[EventField] = &[
 		json_key:  "isComposing",
 		optional:  false,
 	},
+	EventField {
+		name:      "checked",
+		rust_type: "Option<bool>",
+		ts_type:   "boolean | null",
+		json_key:  "checked",
+		optional:  true,
+	},
 ];
 
 fn generate_typescript() -> String {
@@ -666,29 +688,237 @@ fn prop_to_const_name(prop: &str) -> String {
 /// Convert event type like "mousedown" to const name like "MOUSEDOWN"
 fn event_type_to_const_name(event_type: &str) -> String { event_type.to_uppercase() }
 
+/// Line width generated TypeScript is kept within (prettier's printWidth)
+const TS_PRINT_WIDTH: usize = 100;
+
+/// An exported FFI function, as declared in lib.rs
+struct FfiFunction {
+	name:    String,
+	args:    Vec<&'static str>,
+	returns: &'static str,
+}
+
+/// Every `pub extern "C" fn` in `source`
+fn parse_ffi_functions(source: &str) -> Vec<FfiFunction> {
+	let mut functions = Vec::new();
+	let mut rest = source;
+	while let Some(start) = rest.find("pub extern \"C\" fn ") {
+		rest = &rest[start + "pub extern \"C\" fn ".len()..];
+		let open = rest.find('(').expect("FFI function without parameters");
+		let name = rest[..open].trim().to_string();
+
+		let mut depth = 0;
+		let close = rest[open..]
+			.char_indices()
+			.find_map(|(index, c)| {
+				match c {
+					'(' => depth += 1,
+					')' => depth -= 1,
+					_ => {}
+				}
+				(depth == 0).then_some(open + index)
+			})
+			.expect("unbalanced FFI parameter list");
+
+		let args = rest[open + 1..close]
+			.split(',')
+			.map(str::trim)
+			.filter(|param| !param.is_empty())
+			.map(|param| {
+				let (_, rust_type) = param.split_once(':').expect("FFI parameter without a type");
+				ffi_type(&name, rust_type.trim())
+			})
+			.collect();
+
+		let signature_end = rest[close..].find('{').map_or(rest.len(), |index| close + index);
+		let returns = match rest[close + 1..signature_end].trim().strip_prefix("->") {
+			Some(rust_type) => ffi_type(&name, rust_type.trim()),
+			None => "void",
+		};
+
+		functions.push(FfiFunction { name, args, returns });
+		rest = &rest[close..];
+	}
+	functions
+}
+
+/// bun:ffi type of a Rust FFI parameter or return type
+/// Result structs passed by value are bound as pointers, as they always were
+fn ffi_type(function: &str, rust_type: &str) -> &'static str {
+	match rust_type {
+		t if t.starts_with('*') => "ptr",
+		"FfiResult" | "WindowCreateResult" => "ptr",
+		"bool" => "bool",
+		"i32" => "i32",
+		"u32" => "u32",
+		"u64" => "u64",
+		"f32" => "f32",
+		"f64" => "f64",
+		_ => panic!("{}: no bun:ffi type for {}", function, rust_type),
+	}
+}
+
+/// Element type names from the `"name" => ElementKind::...` arms of
+/// ElementKind::from_str
+fn parse_element_kinds(source: &str) -> Vec<String> {
+	source
+		.lines()
+		.filter_map(|line| {
+			let (name, kind) = line.trim().split_once(" => ")?;
+			if !kind.starts_with("ElementKind::") {
+				return None;
+			}
+			Some(name.strip_prefix('"')?.strip_suffix('"')?.to_string())
+		})
+		.collect()
+}
+
+/// Style keys read by ElementStyle::from_json, in order of first use
+fn parse_style_keys(source: &str) -> Vec<String> {
+	let start = source.find("pub fn from_json").expect("ElementStyle::from_json not found");
+	let end = source[start..].find("pub fn inherit_from").map_or(source.len(), |index| start + index);
+
+	let mut keys: Vec<String> = Vec::new();
+	let mut rest = &source[start..end];
+	while let Some(index) = rest.find("style_obj.get(\"") {
+		rest = &rest[index + "style_obj.get(\"".len()..];
+		let key = &rest[..rest.find('"').expect("unterminated style key")];
+		if !keys.iter().any(|known| known == key) {
+			keys.push(key.to_string());
+		}
+	}
+	keys
+}
+
+/// `export const NAME = [...] as const;`, wrapped like prettier would
+fn ts_const_array(name: &str, items: &[String]) -> String {
+	let quoted: Vec<String> = items.iter().map(|item| format!("\"{}\"", item)).collect();
+	let single = format!("export const {} = [{}] as const;\n", name, quoted.join(", "));
+	if single.len() <= TS_PRINT_WIDTH + 1 {
+		return single;
+	}
+	let mut output = format!("export const {} = [\n", name);
+	for item in &quoted {
+		output.push_str(&format!("    {},\n", item));
+	}
+	output.push_str("] as const;\n");
+	output
+}
+
+fn generate_protocol_typescript(
+	functions: &[FfiFunction],
+	element_kinds: &[String],
+	style_keys: &[String],
+) -> String {
+	let mut output = String::new();
+
+	// Header
+	output.push_str("/**\n");
+	output.push_str(" * Auto-generated protocol definitions - DO NOT EDIT\n");
+	output.push_str(" * Generated by: cargo run --bin codegen\n");
+	output.push_str(" * Source: rust/src/lib.rs, rust/src/element/mod.rs\n");
+	output.push_str(" */\n\n");
+	output.push_str("import { FFIType } from \"bun:ffi\";\n\n");
+
+	// FFI symbols, for dlopen
+	output.push_str("/** Symbols exported by the native library, for dlopen */\n");
+	output.push_str("export const FFI_SYMBOLS = {\n");
+	for function in functions {
+		let args: Vec<String> = function.args.iter().map(|arg| format!("FFIType.{}", arg)).collect();
+		let args = format!("[{}]", args.join(", "));
+		let returns = format!("FFIType.{}", function.returns);
+
+		let single = format!("    {}: {{ args: {}, returns: {} }},", function.name, args, returns);
+		if single.len() <= TS_PRINT_WIDTH {
+			output.push_str(&single);
+			output.push('\n');
+			continue;
+		}
+		output.push_str(&format!("    {}: {{\n", function.name));
+		if format!("        args: {},", args).len() <= TS_PRINT_WIDTH {
+			output.push_str(&format!("        args: {},\n", args));
+		} else {
+			output.push_str("        args: [\n");
+			for arg in &function.args {
+				output.push_str(&format!("            FFIType.{},\n", arg));
+			}
+			output.push_str("        ],\n");
+		}
+		output.push_str(&format!("        returns: {},\n", returns));
+		output.push_str("    },\n");
+	}
+	output.push_str("} as const;\n\n");
+
+	// Element kinds
+	output.push_str("/** Element types the renderer implements; others render as unknown */\n");
+	output.push_str(&ts_const_array("ELEMENT_KINDS", element_kinds));
+	output.push('\n');
+	output.push_str("export type ElementKind = (typeof ELEMENT_KINDS)[number];\n\n");
+
+	// Style keys
+	output.push_str("/** Keys of the element style object the renderer reads */\n");
+	output.push_str(&ts_const_array("STYLE_KEYS", style_keys));
+	output.push('\n');
+	output.push_str("export type StyleKey = (typeof STYLE_KEYS)[number];\n");
+
+	output
+}
+
+/// Write a generated file, or with `check` only report whether it is current
+/// Returns false if the file is out of date
+fn emit(path: &Path, content: &str, check: bool) -> bool {
+	if check {
+		let current = fs::read_to_string(path).unwrap_or_default() == content;
+		if !current {
+			println!("Out of date: {}", path.display());
+		}
+		return current;
+	}
+	if let Some(parent) = path.parent() {
+		fs::create_dir_all(parent).expect("Failed to create output directory");
+	}
+	fs::write(path, content).expect("Failed to write generated file");
+	println!("Generated: {}", path.display());
+	true
+}
+
 fn main() {
+	let check = std::env::args().any(|arg| arg == "--check");
+
 	// Get project root (assumes we're running from rust/ directory or project root)
 	let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| ".".to_string());
 	let rust_dir = Path::new(&manifest_dir);
 	let project_root = rust_dir.parent().unwrap_or(rust_dir);
 
+	let mut current = true;
+
 	// Generate TypeScript file
 	let ts_output = generate_typescript();
-	let ts_path = project_root.join("src/events/generated.ts");
-
-	if let Some(parent) = ts_path.parent() {
-		fs::create_dir_all(parent).expect("Failed to create events directory");
-	}
-
-	fs::write(&ts_path, &ts_output).expect("Failed to write TypeScript file");
-	println!("Generated: {}", ts_path.display());
+	current &= emit(&project_root.join("src/events/generated.ts"), &ts_output, check);
 
 	// Generate Rust file
 	let rust_output = generate_rust_event_types();
-	let rust_path = rust_dir.join("src/event_types.rs");
-
-	fs::write(&rust_path, &rust_output).expect("Failed to write Rust file");
-	println!("Generated: {}", rust_path.display());
+	current &= emit(&rust_dir.join("src/event_types.rs"), &rust_output, check);
+
+	// Generate protocol typings from the Rust sources
+	let lib_source =
+		fs::read_to_string(rust_dir.join("src/lib.rs")).expect("Failed to read src/lib.rs");
+	let element_source = fs::read_to_string(rust_dir.join("src/element/mod.rs"))
+		.expect("Failed to read src/element/mod.rs");
+	let protocol_output = generate_protocol_typescript(
+		&parse_ffi_functions(&lib_source),
+		&parse_element_kinds(&element_source),
+		&parse_style_keys(&element_source),
+	);
+	current &= emit(&project_root.join("src/core/generated.ts"), &protocol_output, check);
 
+	if check {
+		if !current {
+			println!("\nRun `cargo run --bin codegen` to regenerate.");
+			std::process::exit(1);
+		}
+		println!("Generated files are up to date.");
+		return;
+	}
 	println!("\nDone! Event type definitions and data structures are now synchronized.");
 }
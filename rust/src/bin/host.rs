@@ -0,0 +1,376 @@
+//! Standalone renderer process
+//!
+//! For hosts that can't load the native library, or that want a renderer crash
+//! to leave them running, this binary runs the renderer in its own process and
+//! mirrors the FFI surface over a stream: stdin/stdout, or a Unix socket with
+//! `--socket <path>` (a single connection).
+//!
+//! Every message is a little-endian u32 byte length followed by a JSON body.
+//! A request is `{"id", "call", "args"}`, where `call` is an FFI function name
+//! without the `gpui_` prefix and `args` an object of its arguments; it gets a
+//! response `{"id", "result"}` or `{"id", "error"}`. Events are not pushed:
+//! the client polls with `poll_events`, as over FFI. The process exits when
+//! the client disconnects.
+//!
+//! Run with: cargo run --bin gpui-host [-- --socket <path>]
+
+use std::{ffi::{CStr, CString, c_char}, io::{self, Read, Write}};
+
+use gpui_renderer::*;
+use serde_json::{Value, json};
+
+/// Largest message accepted, guards against a corrupt length prefix
+const MAX_MESSAGE_LEN: usize = 256 * 1024 * 1024;
+
+fn main() {
+	let args: Vec<String> = std::env::args().collect();
+	let served = match args.iter().position(|arg| arg == "--socket") {
+		Some(index) => match args.get(index + 1) {
+			Some(path) => serve_socket(path),
+			None => {
+				eprintln!("gpui-host: --socket needs a path");
+				std::process::exit(2);
+			}
+		},
+		None => serve_stdio(),
+	};
+	if let Err(e) = served {
+		eprintln!("gpui-host: {}", e);
+		std::process::exit(1);
+	}
+}
+
+#[cfg(unix)]
+fn serve_stdio() -> io::Result<()> {
+	use std::{fs::File, os::fd::FromRawFd};
+
+	// The library logs to stdout too: keep the protocol on a private copy of it
+	// and send everything else written to stdout to stderr
+	let output = unsafe {
+		let fd = libc::dup(libc::STDOUT_FILENO);
+		if fd < 0 || libc::dup2(libc::STDERR_FILENO, libc::STDOUT_FILENO) < 0 {
+			return Err(io::Error::last_os_error());
+		}
+		File::from_raw_fd(fd)
+	};
+	serve(io::stdin().lock(), output)
+}
+
+#[cfg(unix)]
+fn serve_socket(path: &str) -> io::Result<()> {
+	let _ = std::fs::remove_file(path);
+	let listener = std::os::unix::net::UnixListener::bind(path)?;
+	let (stream, _) = listener.accept()?;
+	serve(stream.try_clone()?, stream)
+}
+
+#[cfg(not(unix))]
+fn serve_stdio() -> io::Result<()> {
+	Err(io::Error::new(io::ErrorKind::Unsupported, "the stdio transport is Unix only"))
+}
+
+#[cfg(not(unix))]
+fn serve_socket(_path: &str) -> io::Result<()> {
+	Err(io::Error::new(io::ErrorKind::Unsupported, "the socket transport is Unix only"))
+}
+
+/// Answer requests in order until the client disconnects
+fn serve(mut input: impl Read, mut output: impl Write) -> io::Result<()> {
+	while let Some(request) = read_message(&mut input)? {
+		let response = match serde_json::from_slice::<Value>(&request) {
+			Ok(request) => {
+				let id = request.get("id").cloned().unwrap_or(Value::Null);
+				let call = request.get("call").and_then(Value::as_str).unwrap_or("");
+				let args = request.get("args").cloned().unwrap_or_else(|| json!({}));
+				match dispatch(call, &args) {
+					Ok(result) => json!({ "id": id, "result": result }),
+					Err(e) => json!({ "id": id, "error": e }),
+				}
+			}
+			Err(e) => json!({ "id": null, "error": format!("invalid request: {}", e) }),
+		};
+		write_message(&mut output, response.to_string().as_bytes())?;
+	}
+	Ok(())
+}
+
+/// Next message, None once the client has disconnected
+fn read_message(input: &mut impl Read) -> io::Result<Option<Vec<u8>>> {
+	let mut len = [0u8; 4];
+	match input.read_exact(&mut len) {
+		Ok(()) => {}
+		Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+		Err(e) => return Err(e),
+	}
+	let len = u32::from_le_bytes(len) as usize;
+	if len > MAX_MESSAGE_LEN {
+		return Err(io::Error::new(
+			io::ErrorKind::InvalidData,
+			format!("message of {} bytes is too large", len),
+		));
+	}
+	let mut body = vec![0; len];
+	input.read_exact(&mut body)?;
+	Ok(Some(body))
+}
+
+fn write_message(output: &mut impl Write, body: &[u8]) -> io::Result<()> {
+	output.write_all(&(body.len() as u32).to_le_bytes())?;
+	output.write_all(body)?;
+	output.flush()
+}
+
+/// Make one FFI call
+fn dispatch(call: &str, args: &Value) -> Result<Value, String> {
+	match call {
+		"init" => match args.get("options") {
+			Some(options) => {
+				let options = c_string(&options.to_string())?;
+				with_result(|result| gpui_init_with_options(options.as_ptr(), result))
+			}
+			None => with_result(|result| gpui_init(result)),
+		},
+		"is_ready" => Ok(gpui_is_ready().into()),
+		"shutdown" => take_json(gpui_shutdown()),
+		"create_window" => {
+			let options = json_arg(args, "options")?;
+			let mut result = WindowCreateResult::success(0);
+			gpui_create_window(options.as_ptr(), &mut result);
+			if result.status != 0 {
+				let message = error_message(result.error_msg);
+				gpui_free_window_result(result);
+				return Err(message);
+			}
+			Ok(result.window_id.into())
+		}
+		"render_frame" => {
+			let window_id = u64_arg(args, "windowId")?;
+			let global_id = u64_arg(args, "globalId")?;
+			let element_type = c_string(args.get("type").and_then(Value::as_str).unwrap_or("unknown"))?;
+			let text = args.get("text").and_then(Value::as_str).map(c_string).transpose()?;
+			let children: Vec<u64> = args
+				.get("children")
+				.and_then(Value::as_array)
+				.map(|children| children.iter().filter_map(Value::as_u64).collect())
+				.unwrap_or_default();
+			let child_count = children.len() as u64;
+			with_result(|result| {
+				gpui_render_frame(
+					u64_ptr(&window_id),
+					u64_ptr(&global_id),
+					element_type.as_ptr(),
+					text.as_ref().map_or(std::ptr::null(), |text| text.as_ptr()),
+					u64_ptr(&child_count),
+					children.as_ptr(),
+					result,
+				)
+			})
+		}
+		"trigger_render" => {
+			let window_id = u64_arg(args, "windowId")?;
+			with_result(|result| gpui_trigger_render(u64_ptr(&window_id), result))
+		}
+		"batch_update_elements" => {
+			let window_id = u64_arg(args, "windowId")?;
+			let count = args.get("elements").and_then(Value::as_array).map_or(0, Vec::len) as u64;
+			let elements = json_arg(args, "elements")?;
+			with_result(|result| {
+				gpui_batch_update_elements(u64_ptr(&window_id), u64_ptr(&count), elements.as_ptr(), result)
+			})
+		}
+		"patch_styles" => {
+			let window_id = u64_arg(args, "windowId")?;
+			let patches = json_arg(args, "patches")?;
+			with_result(|result| gpui_patch_styles(u64_ptr(&window_id), patches.as_ptr(), result))
+		}
+		"edit_text" => {
+			let window_id = u64_arg(args, "windowId")?;
+			let edits = json_arg(args, "edits")?;
+			with_result(|result| gpui_edit_text(u64_ptr(&window_id), edits.as_ptr(), result))
+		}
+		"release_elements" => {
+			let window_id = u64_arg(args, "windowId")?;
+			let ids = json_arg(args, "ids")?;
+			with_result(|result| gpui_release_elements(u64_ptr(&window_id), ids.as_ptr(), result))
+		}
+		"poll_events" => {
+			let window_id = u64_arg(args, "windowId")?;
+			take_json(gpui_poll_events(u64_ptr(&window_id)))
+		}
+		"get_input_value" => {
+			let window_id = u64_arg(args, "windowId")?;
+			let element_id = u64_arg(args, "elementId")?;
+			take_json(gpui_get_input_value(u64_ptr(&window_id), u64_ptr(&element_id)))
+		}
+		"push_sparkline" => {
+			let window_id = u64_arg(args, "windowId")?;
+			let element_id = u64_arg(args, "elementId")?;
+			let values = args.get("values").and_then(Value::as_array).ok_or("values is required")?;
+			let bytes: Vec<u8> =
+				values.iter().filter_map(Value::as_f64).flat_map(f64::to_le_bytes).collect();
+			let count = (bytes.len() / 8) as u32;
+			gpui_push_sparkline(u64_ptr(&window_id), u64_ptr(&element_id), bytes.as_ptr(), count);
+			Ok(Value::Null)
+		}
+		"find" => {
+			let window_id = u64_arg(args, "windowId")?;
+			let element_id = u64_arg(args, "elementId")?;
+			let query = c_string(args.get("query").and_then(Value::as_str).unwrap_or(""))?;
+			take_json(gpui_find(u64_ptr(&window_id), u64_ptr(&element_id), query.as_ptr()))
+		}
+		"find_next" => {
+			let window_id = u64_arg(args, "windowId")?;
+			let element_id = u64_arg(args, "elementId")?;
+			let backwards = args.get("backwards").and_then(Value::as_bool).unwrap_or(false);
+			take_json(gpui_find_next(u64_ptr(&window_id), u64_ptr(&element_id), backwards))
+		}
+		"get_layout_snapshot" => {
+			let window_id = u64_arg(args, "windowId")?;
+			take_json(gpui_get_layout_snapshot(u64_ptr(&window_id)))
+		}
+		"get_focus_state" => {
+			let window_id = u64_arg(args, "windowId")?;
+			take_json(gpui_get_focus_state(u64_ptr(&window_id)))
+		}
+		"get_resource_stats" => {
+			let window_id = u64_arg(args, "windowId")?;
+			take_json(gpui_get_resource_stats(u64_ptr(&window_id)))
+		}
+		"get_latency_stats" => {
+			let window_id = u64_arg(args, "windowId")?;
+			take_json(gpui_get_latency_stats(u64_ptr(&window_id)))
+		}
+		"get_clock" => take_json(gpui_get_clock()),
+		"format_shortcut" => {
+			let shortcut = c_string(args.get("shortcut").and_then(Value::as_str).unwrap_or(""))?;
+			Ok(take_string(gpui_format_shortcut(shortcut.as_ptr())).into())
+		}
+		"set_privacy_options" => {
+			let options = json_arg(args, "options")?;
+			with_result(|result| gpui_set_privacy_options(options.as_ptr(), result))
+		}
+		"set_font_fallbacks" => {
+			let families = json_arg(args, "families")?;
+			with_result(|result| gpui_set_font_fallbacks(families.as_ptr(), result))
+		}
+		"register_font" => {
+			let path = c_string(args.get("path").and_then(Value::as_str).unwrap_or(""))?;
+			with_result(|result| gpui_register_font(path.as_ptr(), result))
+		}
+		"set_key_repeat" => {
+			let options = json_arg(args, "options")?;
+			with_result(|result| gpui_set_key_repeat(options.as_ptr(), result))
+		}
+		"set_input_settings" => {
+			let options = json_arg(args, "options")?;
+			with_result(|result| gpui_set_input_settings(options.as_ptr(), result))
+		}
+		"set_watchdog" => {
+			let options = json_arg(args, "options")?;
+			with_result(|result| gpui_set_watchdog(options.as_ptr(), result))
+		}
+		"set_safe_area_insets" => {
+			let window_id = u64_arg(args, "windowId")?;
+			let insets = json_arg(args, "insets")?;
+			with_result(|result| gpui_set_safe_area_insets(u64_ptr(&window_id), insets.as_ptr(), result))
+		}
+		"set_event_mask" => {
+			let window_id = u64_arg(args, "windowId")?;
+			let types = json_arg(args, "types")?;
+			with_result(|result| gpui_set_event_mask(u64_ptr(&window_id), types.as_ptr(), result))
+		}
+		"set_hit_test_debug" => {
+			let window_id = u64_arg(args, "windowId")?;
+			gpui_set_hit_test_debug(u64_ptr(&window_id), bool_arg(args, "enabled"));
+			Ok(Value::Null)
+		}
+		"set_hud" => {
+			let window_id = u64_arg(args, "windowId")?;
+			gpui_set_hud(u64_ptr(&window_id), bool_arg(args, "enabled"));
+			Ok(Value::Null)
+		}
+		"set_max_fps" => {
+			let window_id = u64_arg(args, "windowId")?;
+			let fps = args.get("fps").and_then(Value::as_f64).unwrap_or(0.0) as f32;
+			gpui_set_max_fps(u64_ptr(&window_id), fps);
+			Ok(Value::Null)
+		}
+		"set_power_saver" => {
+			gpui_set_power_saver(bool_arg(args, "enabled"));
+			Ok(Value::Null)
+		}
+		"set_live_resize" => {
+			let window_id = u64_arg(args, "windowId")?;
+			let interval_ms = u32::try_from(u64_arg(args, "intervalMs")?).unwrap_or(u32::MAX);
+			gpui_set_live_resize(u64_ptr(&window_id), interval_ms);
+			Ok(Value::Null)
+		}
+		"set_paint_tracking" => {
+			let window_id = u64_arg(args, "windowId")?;
+			gpui_set_paint_tracking(u64_ptr(&window_id), bool_arg(args, "enabled"));
+			Ok(Value::Null)
+		}
+		"set_latency_tracing" => {
+			gpui_set_latency_tracing(bool_arg(args, "enabled"));
+			Ok(Value::Null)
+		}
+		_ => Err(format!("unknown call {:?}", call)),
+	}
+}
+
+/// Pointer to a u64 argument, as the FFI functions take them
+fn u64_ptr(value: &u64) -> *const u8 { value as *const u64 as *const u8 }
+
+fn u64_arg(args: &Value, name: &str) -> Result<u64, String> {
+	args.get(name).and_then(Value::as_u64).ok_or_else(|| format!("{} is required", name))
+}
+
+fn bool_arg(args: &Value, name: &str) -> bool {
+	args.get(name).and_then(Value::as_bool).unwrap_or(false)
+}
+
+/// An argument re-encoded as the JSON string the FFI function parses
+fn json_arg(args: &Value, name: &str) -> Result<CString, String> {
+	c_string(&args.get(name).unwrap_or(&Value::Null).to_string())
+}
+
+fn c_string(text: &str) -> Result<CString, String> {
+	CString::new(text).map_err(|_| "strings can't contain NUL".to_string())
+}
+
+/// Run an FFI call that reports through an FfiResult
+fn with_result(call: impl FnOnce(*mut FfiResult)) -> Result<Value, String> {
+	let mut result = FfiResult::success();
+	call(&mut result);
+	if result.status == 0 {
+		return Ok(Value::Null);
+	}
+	let message = error_message(result.error_msg);
+	gpui_free_result(result);
+	Err(message)
+}
+
+fn error_message(ptr: *const c_char) -> String {
+	if ptr.is_null() {
+		return "unknown error".to_string();
+	}
+	unsafe { CStr::from_ptr(ptr) }.to_string_lossy().into_owned()
+}
+
+/// Take ownership of a string returned over FFI (None for null)
+fn take_string(ptr: *mut c_char) -> Option<String> {
+	if ptr.is_null() {
+		return None;
+	}
+	let text = unsafe { CStr::from_ptr(ptr) }.to_string_lossy().into_owned();
+	gpui_free_event_string(ptr);
+	Some(text)
+}
+
+/// Take a JSON string returned over FFI (null for none)
+fn take_json(ptr: *mut c_char) -> Result<Value, String> {
+	match take_string(ptr) {
+		Some(text) => serde_json::from_str(&text).map_err(|e| e.to_string()),
+		None => Ok(Value::Null),
+	}
+}
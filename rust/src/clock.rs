@@ -0,0 +1,23 @@
+//! Monotonic clock for event timestamps
+//!
+//! Timestamps are microseconds since a process-wide epoch taken from
+//! `Instant`, so they never jump with wall-clock changes and are precise
+//! enough for gesture velocities. `gpui_get_clock` reports the current
+//! reading and the wall-clock time of the epoch, letting JS measure
+//! latencies and convert timestamps to dates.
+
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use lazy_static::lazy_static;
+
+lazy_static! {
+	static ref EPOCH: (Instant, SystemTime) = (Instant::now(), SystemTime::now());
+}
+
+/// Microseconds since the epoch
+pub fn now_us() -> u64 { EPOCH.0.elapsed().as_micros() as u64 }
+
+/// Wall-clock time of the epoch, in microseconds since the Unix epoch
+pub fn epoch_unix_us() -> u64 {
+	EPOCH.1.duration_since(UNIX_EPOCH).map(|d| d.as_micros() as u64).unwrap_or(0)
+}
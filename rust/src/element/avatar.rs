@@ -0,0 +1,226 @@
+//! Avatar element
+//!
+//! An `avatar` shows the image at `src` (a file path or an http(s) URL) clipped
+//! to a circle, or to `borderRadius` when set. While the image loads, when it
+//! fails and when there's no `src`, it shows the initials of `name` on a color
+//! picked from the name, so the same person always gets the same color; a
+//! background color overrides it. `status` adds a dot at the bottom right:
+//! "online", "away", "busy" or "offline".
+
+use std::{path::PathBuf, sync::Arc};
+
+use gpui::{AnyElement, App, Bounds, Corners, Element, ElementId, GlobalElementId, Hitbox, Hsla, InspectorElementId, IntoElement, LayoutId, Pixels, SharedString, StyledImage, Window, div, fill, img, point, prelude::*, px, rgb, size};
+
+use super::{ElementStyle, ReactElement, border, events::{EventHandlerFlags, insert_hitbox_if_needed, register_event_handlers}, fonts, layout};
+
+/// Size of the avatar when the style has none
+const DEFAULT_SIZE: f32 = 40.0;
+/// Initials backgrounds, picked by a hash of the name
+const PALETTE: [u32; 8] =
+	[0xef4444, 0xf97316, 0xeab308, 0x22c55e, 0x14b8a6, 0x3b82f6, 0x8b5cf6, 0xec4899];
+/// Status dot diameter, as a fraction of the avatar
+const STATUS_SIZE: f32 = 0.28;
+
+/// First letters of the first and last words of a name, uppercased
+fn initials(name: &str) -> String {
+	let words: Vec<&str> = name.split_whitespace().collect();
+	let first = |word: &&str| word.chars().next();
+	let letters: Vec<char> = match words.as_slice() {
+		[] => Vec::new(),
+		[only] => first(only).into_iter().collect(),
+		[head, .., last] => first(head).into_iter().chain(first(last)).collect(),
+	};
+	letters.into_iter().flat_map(char::to_uppercase).collect()
+}
+
+/// Background of the initials, stable for a name
+fn name_color(name: &str) -> Hsla {
+	// FNV-1a, so the color doesn't change between runs
+	let hash = name.bytes().fold(0x811c9dc5u32, |hash, b| (hash ^ b as u32).wrapping_mul(0x01000193));
+	rgb(PALETTE[hash as usize % PALETTE.len()]).into()
+}
+
+fn status_color(status: &str) -> Option<Hsla> {
+	let color = match status {
+		"online" => 0x22c55e,
+		"away" => 0xf59e0b,
+		"busy" => 0xef4444,
+		"offline" => 0x9ca3af,
+		_ => return None,
+	};
+	Some(rgb(color).into())
+}
+
+pub struct ReactAvatarElement {
+	element:      Arc<ReactElement>,
+	window_id:    u64,
+	parent_style: Option<ElementStyle>,
+	/// The image, or the initials without a `src`
+	child:        Option<AnyElement>,
+}
+
+pub struct AvatarLayoutState {}
+
+pub struct AvatarPrepaintState {
+	hitbox:      Option<Hitbox>,
+	event_flags: EventHandlerFlags,
+}
+
+impl ReactAvatarElement {
+	pub fn new(
+		element: Arc<ReactElement>,
+		window_id: u64,
+		parent_style: Option<ElementStyle>,
+	) -> Self {
+		Self { element, window_id, parent_style, child: None }
+	}
+
+	/// Corner radius of the image and initials: `borderRadius`, else a circle
+	fn radius(&self, side: f32) -> Pixels {
+		px(self.element.style.border_radius.unwrap_or(side / 2.0))
+	}
+}
+
+impl Element for ReactAvatarElement {
+	type PrepaintState = AvatarPrepaintState;
+	type RequestLayoutState = AvatarLayoutState;
+
+	fn id(&self) -> Option<ElementId> { Some(ElementId::Integer(self.element.global_id)) }
+
+	fn source_location(&self) -> Option<&'static std::panic::Location<'static>> { None }
+
+	fn request_layout(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		window: &mut Window,
+		cx: &mut App,
+	) -> (LayoutId, Self::RequestLayoutState) {
+		let es = &self.element.style;
+		let mut style = self.element.build_gpui_style(None);
+		if es.width.is_none() {
+			style.size.width = px(DEFAULT_SIZE).into();
+		}
+		if es.height.is_none() {
+			style.size.height = px(DEFAULT_SIZE).into();
+		}
+		// The child paints the background, clipped to the avatar's shape
+		style.background = None;
+
+		let side = es.width.unwrap_or(DEFAULT_SIZE).min(es.height.unwrap_or(DEFAULT_SIZE));
+		let radius = self.radius(side);
+		let effective = self.element.effective_style(self.parent_style.as_ref());
+		let name = es.name.clone().unwrap_or_default();
+		let background = es.bg_color.unwrap_or_else(|| name_color(&name));
+		let text_color = es.text_color.unwrap_or_else(|| rgb(0xffffff).into());
+		let font = fonts::resolve(&effective, window);
+		let initials_element = move || {
+			div()
+				.size_full()
+				.flex()
+				.items_center()
+				.justify_center()
+				.rounded(radius)
+				.bg(background)
+				.text_color(text_color)
+				.text_size(px(side * 0.4))
+				.font(font.clone())
+				.child(initials(&name))
+				.into_any_element()
+		};
+
+		let mut child = match es.src.as_deref().filter(|src| !src.is_empty()) {
+			Some(src) => {
+				let image = if src.starts_with("http://") || src.starts_with("https://") {
+					img(SharedString::from(src.to_string()))
+				} else {
+					img(PathBuf::from(src.strip_prefix("file://").unwrap_or(src)))
+				};
+				let initials_element = Arc::new(initials_element);
+				let loading = initials_element.clone();
+				image
+					.size_full()
+					.rounded(radius)
+					.with_loading(move || loading())
+					.with_fallback(move || initials_element())
+					.into_any_element()
+			}
+			None => initials_element(),
+		};
+		let child_layout_id = child.request_layout(window, cx);
+		self.child = Some(child);
+
+		let layout_id = window.request_layout(style, std::iter::once(child_layout_id), cx);
+		(layout_id, AvatarLayoutState {})
+	}
+
+	fn prepaint(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		bounds: Bounds<Pixels>,
+		_request_layout: &mut Self::RequestLayoutState,
+		window: &mut Window,
+		cx: &mut App,
+	) -> Self::PrepaintState {
+		layout::record_bounds(self.window_id, self.element.global_id, bounds);
+
+		if let Some(child) = self.child.as_mut() {
+			child.prepaint(window, cx);
+		}
+
+		let event_flags = EventHandlerFlags::from_handlers(
+			self.element.event_handlers.as_ref(),
+			self.element.style.tab_index,
+		);
+		let hitbox = insert_hitbox_if_needed(&event_flags, bounds, window);
+		AvatarPrepaintState { hitbox, event_flags }
+	}
+
+	fn paint(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		bounds: Bounds<Pixels>,
+		_request_layout: &mut Self::RequestLayoutState,
+		prepaint: &mut Self::PrepaintState,
+		window: &mut Window,
+		cx: &mut App,
+	) {
+		let element_style = &self.element.style;
+		let mut style = self.element.build_gpui_style(None);
+		style.background = None;
+		style.paint(bounds, window, cx, |window, cx| {
+			if let Some(child) = self.child.as_mut() {
+				child.paint(window, cx);
+			}
+		});
+		border::paint(element_style, bounds, window);
+		border::paint_outline(element_style, bounds, window);
+
+		// On the edge at the bottom right, where a circle's edge is at 45°
+		if let Some(color) = element_style.avatar_status.as_deref().and_then(status_color) {
+			let side = f32::from(bounds.size.width.min(bounds.size.height));
+			let radius = f32::from(self.radius(side)).min(side / 2.0);
+			let dot = (side * STATUS_SIZE).max(6.0);
+			let inset = radius * (1.0 - std::f32::consts::FRAC_1_SQRT_2);
+			let center = point(bounds.right() - px(inset), bounds.bottom() - px(inset));
+			let dot_bounds = Bounds::centered_at(center, size(px(dot), px(dot)));
+			window.paint_quad(fill(dot_bounds, color).corner_radii(Corners::all(px(dot / 2.0))));
+		}
+
+		register_event_handlers(
+			&prepaint.event_flags,
+			prepaint.hitbox.as_ref(),
+			self.window_id,
+			self.element.global_id,
+			window,
+		);
+	}
+}
+
+impl IntoElement for ReactAvatarElement {
+	type Element = Self;
+
+	fn into_element(self) -> Self::Element { self }
+}
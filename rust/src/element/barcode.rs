@@ -0,0 +1,182 @@
+//! 1D barcode encoders
+//!
+//! Each returns the modules of the symbol from the first bar to the last, dark
+//! = true, without quiet zone. Code 128 encodes printable ASCII (code set B),
+//! or pairs of digits (code set C) when the value is an even number of digits.
+//! EAN-13 takes 12 digits, or 13 with a correct check digit.
+
+/// Bar and space widths of the Code 128 symbols, by value; the last is Stop
+#[rustfmt::skip]
+const CODE_128: [&str; 107] = [
+	"212222", "222122", "222221", "121223", "121322", "131222", "122213", "122312", "132212", "221213",
+	"221312", "231212", "112232", "122132", "122231", "113222", "123122", "123221", "223211", "221132",
+	"221231", "213212", "223112", "312131", "311222", "321122", "321221", "312212", "322112", "322211",
+	"212123", "212321", "232121", "111323", "131123", "131321", "112313", "132113", "132311", "211313",
+	"231113", "231311", "112133", "112331", "132131", "113123", "113321", "133121", "313121", "211331",
+	"231131", "213113", "213311", "213131", "311123", "311321", "331121", "312113", "312311", "332111",
+	"314111", "221411", "431111", "111224", "111422", "121124", "121421", "141122", "141221", "112214",
+	"112412", "122114", "122411", "142112", "142211", "241211", "221114", "413111", "241112", "134111",
+	"111242", "121142", "121241", "114212", "124112", "124211", "411212", "421112", "421211", "212141",
+	"214121", "412121", "111143", "111341", "131141", "114113", "114311", "411113", "411311", "113141",
+	"114131", "311141", "411131", "211412", "211214", "211232", "2331112",
+];
+const START_B: usize = 104;
+const START_C: usize = 105;
+const STOP: usize = 106;
+
+/// EAN left-hand odd parity (L) codes; R codes are their complement and even
+/// parity (G) codes the reversed R codes
+const EAN_L: [u8; 10] = [0x0d, 0x19, 0x13, 0x3d, 0x23, 0x31, 0x2f, 0x3b, 0x37, 0x0b];
+/// Which of the six left digits use G codes, bit 5 first, by the first digit
+const EAN_PARITY: [u8; 10] = [0x00, 0x0b, 0x0d, 0x0e, 0x13, 0x19, 0x1c, 0x15, 0x16, 0x1a];
+
+/// Encode as Code 128, None if the value has characters outside printable ASCII
+pub fn code_128(value: &str) -> Option<Vec<bool>> {
+	if value.is_empty() {
+		return None;
+	}
+	let digits = value.len().is_multiple_of(2) && value.bytes().all(|b| b.is_ascii_digit());
+	let mut symbols = if digits {
+		let mut symbols = vec![START_C];
+		symbols.extend(
+			value.as_bytes().chunks(2).map(|pair| ((pair[0] - b'0') * 10 + (pair[1] - b'0')) as usize),
+		);
+		symbols
+	} else {
+		if !value.bytes().all(|b| (32..127).contains(&b)) {
+			return None;
+		}
+		let mut symbols = vec![START_B];
+		symbols.extend(value.bytes().map(|b| (b - 32) as usize));
+		symbols
+	};
+
+	let checksum =
+		symbols.iter().enumerate().map(|(i, symbol)| i.max(1) * symbol).sum::<usize>() % 103;
+	symbols.push(checksum);
+	symbols.push(STOP);
+
+	let mut modules = Vec::new();
+	for symbol in symbols {
+		for (i, width) in CODE_128[symbol].bytes().enumerate() {
+			// Widths alternate bar, space, starting with a bar
+			let dark = i % 2 == 0;
+			modules.extend(std::iter::repeat_n(dark, (width - b'0') as usize));
+		}
+	}
+	Some(modules)
+}
+
+/// Encode as EAN-13, None unless the value is 12 digits or 13 with a valid
+/// check digit
+pub fn ean_13(value: &str) -> Option<Vec<bool>> {
+	let digits: Vec<u8> = value.bytes().map(|b| b.wrapping_sub(b'0')).collect();
+	if !(12..=13).contains(&digits.len()) || digits.iter().any(|d| *d > 9) {
+		return None;
+	}
+	let sum: u32 =
+		digits[..12].iter().enumerate().map(|(i, d)| *d as u32 * if i % 2 == 0 { 1 } else { 3 }).sum();
+	let check = ((10 - sum % 10) % 10) as u8;
+	if digits.len() == 13 && digits[12] != check {
+		return None;
+	}
+
+	let mut modules = Vec::with_capacity(95);
+	let mut push = |bits: u8, count: usize| {
+		modules.extend((0..count).rev().map(|i| (bits >> i) & 1 != 0));
+	};
+	push(0b101, 3);
+	let parity = EAN_PARITY[digits[0] as usize];
+	for (i, digit) in digits[1..7].iter().enumerate() {
+		let l = EAN_L[*digit as usize];
+		let even = (parity >> (5 - i)) & 1 != 0;
+		push(if even { reverse_7(!l & 0x7f) } else { l }, 7);
+	}
+	push(0b01010, 5);
+	for digit in digits[7..12].iter().chain([&check]) {
+		push(!EAN_L[*digit as usize] & 0x7f, 7);
+	}
+	push(0b101, 3);
+	Some(modules)
+}
+
+fn reverse_7(bits: u8) -> u8 { bits.reverse_bits() >> 1 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn bits(modules: &[bool]) -> String {
+		modules.iter().map(|dark| if *dark { '1' } else { '0' }).collect()
+	}
+
+	/// Symbol values of a Code 128 barcode, read back from its bar widths
+	fn symbols(modules: &[bool]) -> Vec<usize> {
+		let mut widths = Vec::new();
+		let mut run = 1;
+		for i in 1..=modules.len() {
+			if i < modules.len() && modules[i] == modules[i - 1] {
+				run += 1;
+			} else {
+				widths.push(b'0' + run);
+				run = 1;
+			}
+		}
+		let (body, stop) = widths.split_at(widths.len() - 7);
+		let mut values: Vec<usize> = body
+			.chunks(6)
+			.map(|chunk| CODE_128.iter().position(|w| w.as_bytes() == chunk).unwrap())
+			.collect();
+		assert_eq!(stop, CODE_128[STOP].as_bytes());
+		values.push(STOP);
+		values
+	}
+
+	#[test]
+	fn code_128_set_b_checksum() {
+		let modules = code_128("PJJ123C").unwrap();
+		// 104 + 48 + 2*42 + 3*42 + 4*17 + 5*18 + 6*19 + 7*35 = 879, 879 % 103 = 55
+		assert_eq!(symbols(&modules), [START_B, 48, 42, 42, 17, 18, 19, 35, 55, STOP]);
+		assert_eq!(modules.len(), 9 * 11 + 13);
+		assert_eq!(bits(&modules[..11]), "11010010000");
+		assert_eq!(bits(&modules[modules.len() - 13..]), "1100011101011");
+	}
+
+	#[test]
+	fn code_128_set_c_checksum() {
+		// 105 + 12 + 2*34 + 3*56 = 353, 353 % 103 = 44
+		assert_eq!(symbols(&code_128("123456").unwrap()), [START_C, 12, 34, 56, 44, STOP]);
+		// An odd number of digits stays in code set B
+		assert_eq!(symbols(&code_128("12345").unwrap())[0], START_B);
+	}
+
+	#[test]
+	fn code_128_rejects() {
+		assert_eq!(code_128(""), None);
+		assert_eq!(code_128("café"), None);
+	}
+
+	#[test]
+	fn ean_13_check_digit() {
+		assert!(ean_13("4006381333931").is_some());
+		assert_eq!(ean_13("4006381333932"), None);
+		assert_eq!(ean_13("400638133393"), ean_13("4006381333931"));
+		assert_eq!(ean_13("40063813339"), None);
+		assert_eq!(ean_13("40063813339a"), None);
+	}
+
+	#[test]
+	fn ean_13_modules() {
+		// First digit 5 sets the left parity to LGGLLG
+		#[rustfmt::skip]
+		let expected = [
+			"101",
+			"0001011", "0100111", "0110011", "0010011", "0111101", "0011101",
+			"01010",
+			"1100110", "1101100", "1000010", "1011100", "1001110", "1000100",
+			"101",
+		]
+		.concat();
+		assert_eq!(bits(&ean_13("5901234123457").unwrap()), expected);
+	}
+}
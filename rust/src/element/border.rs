@@ -0,0 +1,212 @@
+//! Border styles beyond what gpui quads support
+//!
+//! A gpui quad paints all four sides of its border with one color and one
+//! style, either solid or dashed. Borders that are uniform are left to gpui,
+//! which also rounds their corners. Dotted borders and borders whose sides
+//! differ in style or color are painted here instead, one side at a time,
+//! after the element's content. Corners of these borders are square.
+//!
+//! Outlines are painted here too: outside the border box, on top of the
+//! content and without taking layout space, so focus rings never shift
+//! anything around.
+
+use gpui::{BorderStyle, Bounds, Corners, Edges, Hsla, PaintQuad, Pixels, Window, fill, point, px, rgb, size};
+
+use super::ElementStyle;
+
+/// Line style of one border side
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SideStyle {
+	None,
+	Solid,
+	Dashed,
+	Dotted,
+}
+
+impl SideStyle {
+	fn parse(value: &str) -> Option<Self> {
+		match value {
+			"none" | "hidden" => Some(SideStyle::None),
+			"solid" => Some(SideStyle::Solid),
+			"dashed" => Some(SideStyle::Dashed),
+			"dotted" => Some(SideStyle::Dotted),
+			_ => None,
+		}
+	}
+}
+
+/// Resolved style of each side, in top, right, bottom, left order
+/// Per-side styles override `borderStyle`, which defaults to solid
+pub fn side_styles(style: &ElementStyle) -> [SideStyle; 4] {
+	let base = style.border_style.as_deref().and_then(SideStyle::parse).unwrap_or(SideStyle::Solid);
+	[
+		&style.border_top_style,
+		&style.border_right_style,
+		&style.border_bottom_style,
+		&style.border_left_style,
+	]
+	.map(|side| side.as_deref().and_then(SideStyle::parse).unwrap_or(base))
+}
+
+/// Resolved color of each side, in top, right, bottom, left order
+/// Per-side colors fall back to `borderColor`, then gray
+pub fn side_colors(style: &ElementStyle) -> [Hsla; 4] {
+	[
+		style.border_top_color,
+		style.border_right_color,
+		style.border_bottom_color,
+		style.border_left_color,
+	]
+	.map(|color| color.or(style.border_color).unwrap_or_else(|| rgb(0x808080).into()))
+}
+
+/// Style and color of the sides that have a width, top first
+fn visible_sides(style: &ElementStyle) -> Vec<(SideStyle, Hsla)> {
+	let widths = [
+		style.border_top_width,
+		style.border_right_width,
+		style.border_bottom_width,
+		style.border_left_width,
+	];
+	side_styles(style)
+		.into_iter()
+		.zip(side_colors(style))
+		.zip(widths)
+		.filter(|((side, _), width)| *side != SideStyle::None && width.is_some_and(|w| w > 0.0))
+		.map(|(side, _)| side)
+		.collect()
+}
+
+/// Whether this element's border has to be painted by `paint` rather than gpui
+pub fn needs_custom_paint(style: &ElementStyle) -> bool {
+	let sides = visible_sides(style);
+	sides.iter().any(|(side, color)| *side == SideStyle::Dotted || (*side, *color) != sides[0])
+}
+
+/// Style and color of a border gpui paints, the same on every visible side
+pub fn uniform(style: &ElementStyle) -> (SideStyle, Hsla) {
+	visible_sides(style).first().copied().unwrap_or((side_styles(style)[0], side_colors(style)[0]))
+}
+
+/// Paint a border gpui can't, over an element's bounds
+pub fn paint(style: &ElementStyle, bounds: Bounds<Pixels>, window: &mut Window) {
+	if !needs_custom_paint(style) {
+		return;
+	}
+	let [top_style, right_style, bottom_style, left_style] = side_styles(style);
+	let width = |w: Option<f32>, side: SideStyle| {
+		if side == SideStyle::None { 0.0 } else { w.unwrap_or(0.0).max(0.0) }
+	};
+	let top = width(style.border_top_width, top_style);
+	let right = width(style.border_right_width, right_style);
+	let bottom = width(style.border_bottom_width, bottom_style);
+	let left = width(style.border_left_width, left_style);
+	let [top_color, right_color, bottom_color, left_color] = side_colors(style);
+
+	let x = f32::from(bounds.origin.x);
+	let y = f32::from(bounds.origin.y);
+	let w = f32::from(bounds.size.width);
+	let h = f32::from(bounds.size.height);
+
+	// Top and bottom run the full width, left and right fill in between
+	paint_side(window, top_style, top_color, (x, y), w, top, true);
+	paint_side(window, bottom_style, bottom_color, (x, y + h - bottom), w, bottom, true);
+	paint_side(window, left_style, left_color, (x, y + top), h - top - bottom, left, false);
+	paint_side(
+		window,
+		right_style,
+		right_color,
+		(x + w - right, y + top),
+		h - top - bottom,
+		right,
+		false,
+	);
+}
+
+/// Paint an element's outline around its bounds
+pub fn paint_outline(style: &ElementStyle, bounds: Bounds<Pixels>, window: &mut Window) {
+	let Some(width) = style.outline_width.filter(|w| *w > 0.0) else {
+		return;
+	};
+	let side = style.outline_style.as_deref().and_then(SideStyle::parse).unwrap_or(SideStyle::Solid);
+	let offset = style.outline_offset.unwrap_or(0.0);
+	let outer = bounds.dilate(px(offset + width));
+	let color = style.outline_color.unwrap_or_else(|| rgb(0x808080).into());
+
+	match side {
+		SideStyle::None => {}
+		SideStyle::Solid | SideStyle::Dashed => {
+			// Follow the border radius, grown by the distance to the outline
+			let radius = style.border_radius.map_or(0.0, |r| (r + offset + width).max(0.0));
+			let border_style =
+				if side == SideStyle::Dashed { BorderStyle::Dashed } else { BorderStyle::Solid };
+			window.paint_quad(PaintQuad {
+				bounds: outer,
+				corner_radii: Corners::all(px(radius)),
+				background: Hsla::transparent_black().into(),
+				border_widths: Edges::all(px(width)),
+				border_color: color,
+				border_style,
+			});
+		}
+		SideStyle::Dotted => {
+			let x = f32::from(outer.origin.x);
+			let y = f32::from(outer.origin.y);
+			let w = f32::from(outer.size.width);
+			let h = f32::from(outer.size.height);
+			paint_side(window, side, color, (x, y), w, width, true);
+			paint_side(window, side, color, (x, y + h - width), w, width, true);
+			paint_side(window, side, color, (x, y + width), h - 2.0 * width, width, false);
+			paint_side(window, side, color, (x + w - width, y + width), h - 2.0 * width, width, false);
+		}
+	}
+}
+
+/// Paint one side as a strip of `length` by `thickness` starting at `origin`
+fn paint_side(
+	window: &mut Window,
+	side: SideStyle,
+	color: Hsla,
+	origin: (f32, f32),
+	length: f32,
+	thickness: f32,
+	horizontal: bool,
+) {
+	if thickness <= 0.0 || length <= 0.0 {
+		return;
+	}
+	let rect = |offset: f32, extent: f32| {
+		let (dx, dy, width, height) =
+			if horizontal { (offset, 0.0, extent, thickness) } else { (0.0, offset, thickness, extent) };
+		Bounds {
+			origin: point(px(origin.0 + dx), px(origin.1 + dy)),
+			size:   size(px(width), px(height)),
+		}
+	};
+
+	match side {
+		SideStyle::None => {}
+		SideStyle::Solid => window.paint_quad(fill(rect(0.0, length), color)),
+		SideStyle::Dashed => {
+			// Dashes three times as long as the border is thick, with shorter gaps
+			let dash = (thickness * 3.0).max(2.0);
+			let gap = (thickness * 2.0).max(2.0);
+			let mut offset = 0.0;
+			while offset < length {
+				window.paint_quad(fill(rect(offset, dash.min(length - offset)), color));
+				offset += dash + gap;
+			}
+		}
+		SideStyle::Dotted => {
+			// Round dots one border-width across, spread evenly over the side
+			let count = ((length / (thickness * 2.0)).floor() as usize).max(1);
+			let step = length / count as f32;
+			for i in 0..count {
+				let offset = i as f32 * step + (step - thickness) / 2.0;
+				window.paint_quad(
+					fill(rect(offset, thickness), color).corner_radii(Corners::all(px(thickness / 2.0))),
+				);
+			}
+		}
+	}
+}
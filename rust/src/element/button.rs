@@ -0,0 +1,341 @@
+//! Button element
+//!
+//! A `button` lays out its children like a div, with a blue background, 6px
+//! corners and 6px/12px padding unless the style sets them. It shows the
+//! pointing hand cursor, darkens its background while hovered and more while
+//! pressed, and draws a focus ring while focused. Buttons are focusable
+//! without a `tabIndex`: Enter clicks a focused button at once, Space when it
+//! is released, so holding Space shows the pressed state first. A keyboard
+//! click is a `click` event at the button's center.
+//!
+//! A disabled button is dimmed and takes no clicks, presses or focus.
+
+use std::{collections::HashMap, sync::{Arc, Mutex}};
+
+use gpui::{AnyElement, App, BorderStyle, Bounds, Corners, CursorStyle, DispatchPhase, Edges, Element, ElementId, Fill, GlobalElementId, Hitbox, HitboxBehavior, Hsla, InspectorElementId, IntoElement, LayoutId, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent, PaintQuad, Pixels, Window, div, prelude::*, px, rgb};
+use lazy_static::lazy_static;
+
+use super::{ElementKind, ElementStyle, ReactElement, border, dispatcher, events::{EventHandlerFlags, insert_hitbox_if_needed, register_event_handlers}, focus, fonts, inert, layout};
+use crate::{event_types::types, global_state::GLOBAL_STATE, renderer::dispatch_event_to_js, sync::MutexExt};
+
+const DEFAULT_BG: u32 = 0x3b82f6;
+const DEFAULT_RADIUS: f32 = 6.0;
+const DEFAULT_PADDING_X: f32 = 12.0;
+const DEFAULT_PADDING_Y: f32 = 6.0;
+/// Black laid over the background while hovered and while pressed
+const HOVER_SHADE: f32 = 0.08;
+const PRESS_SHADE: f32 = 0.18;
+
+/// The button of a window held down by the pointer or Space
+#[derive(Clone, Copy)]
+struct Press {
+	element_id: u64,
+	by_key:     bool,
+}
+
+lazy_static! {
+	static ref PRESSED: Mutex<HashMap<u64, Press>> = Mutex::new(HashMap::new());
+}
+
+/// Drop all state of a closed window
+pub fn forget_window(window_id: u64) { PRESSED.lock_recover().remove(&window_id); }
+
+/// Drop the pressed state of a released element
+pub fn forget_element(window_id: u64, element_id: u64) {
+	PRESSED.lock_recover().retain(|id, press| (*id, press.element_id) != (window_id, element_id));
+}
+
+fn press_of(window_id: u64, element_id: u64) -> Option<Press> {
+	PRESSED.lock_recover().get(&window_id).copied().filter(|press| press.element_id == element_id)
+}
+
+/// Whether the element is a button that takes input
+fn is_enabled_button(window_id: u64, element_id: u64) -> bool {
+	let Some(window) = GLOBAL_STATE.get_window(window_id) else {
+		return false;
+	};
+	let element_map = window.state().element_map.lock_recover();
+	element_map.get(&element_id).is_some_and(|element| {
+		element.element_kind == ElementKind::Button && element.style.disabled != Some(true)
+	}) && !inert::is_inert(window_id, element_id)
+}
+
+/// Queue a click at the button's center, as Enter or Space do
+fn click(window_id: u64, element_id: u64) {
+	let Some(bounds) = layout::bounds_of(window_id, element_id) else {
+		return;
+	};
+	log::debug!("[Rust] button key click: window_id={}, element_id={}", window_id, element_id);
+	let data = dispatcher::mouse_data(bounds.center(), bounds, 0);
+	dispatch_event_to_js(window_id, element_id, types::CLICK, data);
+}
+
+/// Enter clicks a focused button, Space presses it
+/// Returns whether the button needs repainting
+pub fn key_down(window_id: u64, element_id: u64, key: &str, held: bool) -> bool {
+	if held || !matches!(key, "enter" | "space") || !is_enabled_button(window_id, element_id) {
+		return false;
+	}
+	if key == "enter" {
+		click(window_id, element_id);
+		return false;
+	}
+	PRESSED.lock_recover().insert(window_id, Press { element_id, by_key: true });
+	true
+}
+
+/// Releasing Space clicks the button it pressed
+/// Returns whether the button needs repainting
+pub fn key_up(window_id: u64, element_id: u64, key: &str) -> bool {
+	if key != "space" || press_of(window_id, element_id).is_none_or(|press| !press.by_key) {
+		return false;
+	}
+	PRESSED.lock_recover().remove(&window_id);
+	if is_enabled_button(window_id, element_id) {
+		click(window_id, element_id);
+	}
+	true
+}
+
+pub struct ReactButtonElement {
+	element:      Arc<ReactElement>,
+	window_id:    u64,
+	parent_style: Option<ElementStyle>,
+	children:     Vec<AnyElement>,
+}
+
+pub struct ButtonLayoutState {}
+
+pub struct ButtonPrepaintState {
+	hitbox:      Option<Hitbox>,
+	event_flags: EventHandlerFlags,
+}
+
+impl ReactButtonElement {
+	pub fn new(
+		element: Arc<ReactElement>,
+		window_id: u64,
+		parent_style: Option<ElementStyle>,
+	) -> Self {
+		Self { element, window_id, parent_style, children: Vec::new() }
+	}
+
+	fn is_enabled(&self) -> bool {
+		self.element.style.disabled != Some(true)
+			&& !inert::is_inert(self.window_id, self.element.global_id)
+	}
+
+	/// The element's style with the button's defaults filled in
+	fn gpui_style(&self) -> gpui::Style {
+		let es = &self.element.style;
+		let mut style = self.element.build_gpui_style(Some(DEFAULT_BG));
+		if es.border_radius.is_none() {
+			style.corner_radii = Corners::all(px(DEFAULT_RADIUS).into());
+		}
+		let padding = [es.padding_top, es.padding_right, es.padding_bottom, es.padding_left];
+		if padding.iter().all(Option::is_none) {
+			style.padding.top = px(DEFAULT_PADDING_Y).into();
+			style.padding.bottom = px(DEFAULT_PADDING_Y).into();
+			style.padding.left = px(DEFAULT_PADDING_X).into();
+			style.padding.right = px(DEFAULT_PADDING_X).into();
+		}
+		if !self.is_enabled() {
+			style.opacity = Some(style.opacity.unwrap_or(1.0) * 0.5);
+		}
+		style
+	}
+}
+
+impl Element for ReactButtonElement {
+	type PrepaintState = ButtonPrepaintState;
+	type RequestLayoutState = ButtonLayoutState;
+
+	fn id(&self) -> Option<ElementId> { Some(ElementId::Integer(self.element.global_id)) }
+
+	fn source_location(&self) -> Option<&'static std::panic::Location<'static>> { None }
+
+	fn request_layout(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		window: &mut Window,
+		cx: &mut App,
+	) -> (LayoutId, Self::RequestLayoutState) {
+		let style = self.gpui_style();
+		let inherited_style = self.element.effective_style(self.parent_style.as_ref());
+
+		self.children = self
+			.element
+			.children
+			.iter()
+			.map(|child| {
+				super::create_element(child.clone(), self.window_id, Some(inherited_style.clone()))
+					.into_any_element()
+			})
+			.collect();
+		if let Some(text) = self.element.text.as_ref().filter(|text| !text.is_empty()) {
+			let label = div()
+				.text_color(inherited_style.text_color.unwrap_or_else(|| rgb(0xffffff).into()))
+				.text_size(px(inherited_style.text_size.unwrap_or(14.0)))
+				.font(fonts::resolve(&inherited_style, window))
+				.child(text.clone());
+			self.children.push(label.into_any_element());
+		}
+
+		let child_layout_ids: Vec<LayoutId> =
+			self.children.iter_mut().map(|child| child.request_layout(window, cx)).collect();
+		let layout_id = window.request_layout(style, child_layout_ids, cx);
+		(layout_id, ButtonLayoutState {})
+	}
+
+	fn prepaint(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		bounds: Bounds<Pixels>,
+		_request_layout: &mut Self::RequestLayoutState,
+		window: &mut Window,
+		cx: &mut App,
+	) -> Self::PrepaintState {
+		layout::record_bounds(self.window_id, self.element.global_id, bounds);
+
+		for child in &mut self.children {
+			child.prepaint(window, cx);
+		}
+
+		let mut event_flags = EventHandlerFlags::from_handlers(
+			self.element.event_handlers.as_ref(),
+			self.element.style.tab_index,
+		);
+		if self.is_enabled() {
+			event_flags.tab_index = event_flags.tab_index.or(Some(0));
+		} else {
+			event_flags.has_click = false;
+			event_flags.has_double_click = false;
+			event_flags.has_mouse_down = false;
+			event_flags.has_mouse_up = false;
+			event_flags.has_long_press = false;
+			event_flags.tab_index = None;
+		}
+		// Always hit tested, for the cursor and the hover shade
+		let hitbox = insert_hitbox_if_needed(&event_flags, bounds, window)
+			.unwrap_or_else(|| window.insert_hitbox(bounds, HitboxBehavior::Normal));
+		ButtonPrepaintState { hitbox: Some(hitbox), event_flags }
+	}
+
+	fn paint(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		bounds: Bounds<Pixels>,
+		_request_layout: &mut Self::RequestLayoutState,
+		prepaint: &mut Self::PrepaintState,
+		window: &mut Window,
+		cx: &mut App,
+	) {
+		let element_style = &self.element.style;
+		let (window_id, element_id) = (self.window_id, self.element.global_id);
+		let enabled = self.is_enabled();
+		let hovered = prepaint.hitbox.as_ref().is_some_and(|hitbox| hitbox.is_hovered(window));
+		let focused = focus::is_focused(window_id, element_id);
+		// A pointer press shows only while the pointer is over the button, like
+		// native buttons; a Space press shows wherever the pointer is
+		let pressed = press_of(window_id, element_id).is_some_and(|press| press.by_key || hovered);
+
+		let mut style = self.gpui_style();
+		let shade = match (enabled, pressed, hovered) {
+			(false, ..) => 0.0,
+			(true, true, _) => PRESS_SHADE,
+			(true, false, true) => HOVER_SHADE,
+			(true, false, false) => 0.0,
+		};
+		if shade > 0.0 {
+			let background = element_style.bg_color.unwrap_or_else(|| rgb(DEFAULT_BG).into());
+			let black = Hsla { h: 0.0, s: 0.0, l: 0.0, a: shade };
+			style.background = Some(Fill::Color(background.blend(black).into()));
+		}
+
+		style.paint(bounds, window, cx, |window, cx| {
+			super::paint_children_with_clip(
+				&mut self.children,
+				bounds,
+				element_style.should_clip(),
+				window,
+				cx,
+				|child, window, cx| child.paint(window, cx),
+			);
+		});
+		border::paint(element_style, bounds, window);
+		border::paint_outline(element_style, bounds, window);
+
+		if focused && enabled {
+			let accent = element_style.accent_color.unwrap_or_else(|| rgb(DEFAULT_BG).into());
+			let radius = element_style.border_radius.unwrap_or(DEFAULT_RADIUS) + 2.0;
+			window.paint_quad(PaintQuad {
+				bounds:        bounds.dilate(px(2.0)),
+				corner_radii:  Corners::all(px(radius)),
+				background:    Hsla::transparent_black().into(),
+				border_widths: Edges::all(px(2.0)),
+				border_color:  Hsla { a: accent.a * 0.5, ..accent },
+				border_style:  BorderStyle::Solid,
+			});
+		}
+
+		if let Some(hitbox) = prepaint.hitbox.clone()
+			&& enabled
+		{
+			window.set_cursor_style(CursorStyle::PointingHand, &hitbox);
+			listen_for_press(window_id, element_id, hitbox, hovered, window);
+		}
+
+		register_event_handlers(
+			&prepaint.event_flags,
+			prepaint.hitbox.as_ref(),
+			window_id,
+			element_id,
+			window,
+		);
+	}
+}
+
+impl IntoElement for ReactButtonElement {
+	type Element = Self;
+
+	fn into_element(self) -> Self::Element { self }
+}
+
+/// Track the pointer press and hover that the button's shade shows
+fn listen_for_press(
+	window_id: u64,
+	element_id: u64,
+	hitbox: Hitbox,
+	hovered: bool,
+	window: &mut Window,
+) {
+	let move_hitbox = hitbox.clone();
+	window.on_mouse_event(move |event: &MouseDownEvent, phase, window, _| {
+		if phase == DispatchPhase::Bubble
+			&& event.button == MouseButton::Left
+			&& hitbox.is_hovered(window)
+		{
+			PRESSED.lock_recover().insert(window_id, Press { element_id, by_key: false });
+			window.refresh();
+		}
+	});
+	window.on_mouse_event(move |_: &MouseMoveEvent, phase, window, _| {
+		if phase == DispatchPhase::Bubble && move_hitbox.is_hovered(window) != hovered {
+			window.refresh();
+		}
+	});
+	window.on_mouse_event(move |event: &MouseUpEvent, phase, window, _| {
+		if phase != DispatchPhase::Bubble || event.button != MouseButton::Left {
+			return;
+		}
+		let mut pressed = PRESSED.lock_recover();
+		if pressed.get(&window_id).is_some_and(|press| press.element_id == element_id && !press.by_key)
+		{
+			pressed.remove(&window_id);
+			window.refresh();
+		}
+	});
+}
@@ -1,48 +1,253 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::{Arc, Mutex}};
 
-use gpui::{App, Background, BorderStyle, Bounds, Corners, Edges, Element, ElementId, GlobalElementId, Hitbox, Hsla, InspectorElementId, IntoElement, LayoutId, PaintQuad, Path, Pixels, Rgba, Size, Style, Window, point, px, Context};
-use serde::Deserialize;
-use crate::renderer::RootView;
-use super::{ElementStyle, ReactElement, events::{EventHandlerFlags, insert_hitbox_if_needed, register_event_handlers}};
+use gpui::{App, Background, BorderStyle, Bounds, Corners, Edges, Element, ElementId, GlobalElementId, Hitbox, Hsla, ImgResourceLoader, InspectorElementId, IntoElement, LayoutId, PaintQuad, Path, Pixels, RenderImage, Resource, SharedString, Size, Style, Window, fill, linear_color_stop, linear_gradient, point, px, Context};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Deserializer, de::Error as _};
+use crate::{renderer::RootView, sync::MutexExt};
+use super::{ElementStyle, ReactElement, color_from_json, events::{EventHandlerFlags, insert_hitbox_if_needed, register_event_handlers}, layout, text_cache};
 
 /// Draw command types matching TypeScript definitions
-#[derive(Debug, Deserialize)]
+/// Colors arrive packed as 0xRRGGBBAA, like style colors
+#[derive(Debug, Clone, Deserialize)]
 #[serde(tag = "type")]
 pub enum DrawCommand {
 	#[serde(rename = "clear")]
-	Clear { color: String },
+	Clear {
+		#[serde(deserialize_with = "color")]
+		color: Hsla,
+	},
+	/// Paint the canvas background back over a rect
+	#[serde(rename = "clearRect")]
+	ClearRect { x: f32, y: f32, width: f32, height: f32 },
 	#[serde(rename = "fillRect")]
-	FillRect { x: f32, y: f32, width: f32, height: f32, color: String },
+	FillRect {
+		x:      f32,
+		y:      f32,
+		width:  f32,
+		height: f32,
+		#[serde(deserialize_with = "color")]
+		color:  Hsla,
+	},
 	#[serde(rename = "circle")]
-	Circle { x: f32, y: f32, radius: f32, color: String },
+	Circle {
+		x:      f32,
+		y:      f32,
+		radius: f32,
+		#[serde(deserialize_with = "color")]
+		color:  Hsla,
+	},
 	#[serde(rename = "line")]
-	Line { x1: f32, y1: f32, x2: f32, y2: f32, width: f32, color: String },
+	Line {
+		x1:    f32,
+		y1:    f32,
+		x2:    f32,
+		y2:    f32,
+		width: f32,
+		#[serde(deserialize_with = "color")]
+		color: Hsla,
+	},
 	#[serde(rename = "text")]
-	Text { text: String, x: f32, y: f32, size: f32, color: String },
+	Text {
+		text:  String,
+		x:     f32,
+		y:     f32,
+		size:  f32,
+		#[serde(deserialize_with = "color")]
+		color: Hsla,
+	},
 	#[serde(rename = "path")]
-	Path { points: Vec<(f32, f32)>, width: f32, color: String },
+	Path {
+		points: Vec<(f32, f32)>,
+		width:  f32,
+		#[serde(deserialize_with = "color")]
+		color:  Hsla,
+	},
+	/// The image at `src` (a file path or an http(s) URL) scaled into a rect;
+	/// decoded images are kept in gpui's asset cache
+	#[serde(rename = "drawImage")]
+	DrawImage { src: String, x: f32, y: f32, width: f32, height: f32 },
+	/// A rect filled with a linear gradient, `angle` in CSS degrees (0 runs
+	/// to the top, 90 to the right, the default 180 to the bottom)
+	#[serde(rename = "gradientRect")]
+	GradientRect {
+		x:      f32,
+		y:      f32,
+		width:  f32,
+		height: f32,
+		stops:  Vec<GradientStop>,
+		angle:  Option<f32>,
+	},
 }
 
-/// Parse color string to GPUI Hsla
-/// Supports "#rrggbb" and "#rgb" formats
-fn parse_color(color: &str) -> Hsla {
-	let color = color.trim_start_matches('#');
-	let (r, g, b) = if color.len() == 6 {
-		(
-			u8::from_str_radix(&color[0..2], 16).unwrap_or(0),
-			u8::from_str_radix(&color[2..4], 16).unwrap_or(0),
-			u8::from_str_radix(&color[4..6], 16).unwrap_or(0),
-		)
-	} else if color.len() == 3 {
-		(
-			u8::from_str_radix(&color[0..1], 16).unwrap_or(0) * 17,
-			u8::from_str_radix(&color[1..2], 16).unwrap_or(0) * 17,
-			u8::from_str_radix(&color[2..3], 16).unwrap_or(0) * 17,
-		)
+#[derive(Debug, Clone, Deserialize)]
+pub struct GradientStop {
+	/// Position along the gradient, 0 to 1
+	offset: f32,
+	#[serde(deserialize_with = "color")]
+	color:  Hsla,
+}
+
+impl DrawCommand {
+	/// Area painted, as (left, top, right, bottom) in canvas coordinates; None
+	/// when it isn't known without shaping or covers the whole canvas
+	fn extent(&self) -> Option<(f32, f32, f32, f32)> {
+		let of_points = |points: &mut dyn Iterator<Item = (f32, f32)>| {
+			points.fold(None, |extent: Option<(f32, f32, f32, f32)>, (x, y)| {
+				Some(match extent {
+					Some((l, t, r, b)) => (l.min(x), t.min(y), r.max(x), b.max(y)),
+					None => (x, y, x, y),
+				})
+			})
+		};
+		match self {
+			DrawCommand::ClearRect { x, y, width, height }
+			| DrawCommand::FillRect { x, y, width, height, .. }
+			| DrawCommand::DrawImage { x, y, width, height, .. }
+			| DrawCommand::GradientRect { x, y, width, height, .. } => Some((*x, *y, x + width, y + height)),
+			DrawCommand::Circle { x, y, radius, .. } => {
+				Some((x - radius, y - radius, x + radius, y + radius))
+			}
+			DrawCommand::Line { x1, y1, x2, y2, .. } => {
+				of_points(&mut [(*x1, *y1), (*x2, *y2)].into_iter())
+			}
+			DrawCommand::Path { points, .. } => of_points(&mut points.iter().copied()),
+			DrawCommand::Clear { .. } | DrawCommand::Text { .. } => None,
+		}
+	}
+}
+
+fn color<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Hsla, D::Error> {
+	let value = serde_json::Value::deserialize(deserializer)?;
+	color_from_json(&value).ok_or_else(|| D::Error::custom(format!("expected a packed color, got {}", value)))
+}
+
+/// The decoded image at `src`, None while it loads or when it failed
+/// gpui renders the window again once a load completes
+fn image(src: &str, window: &mut Window, cx: &mut App) -> Option<Arc<RenderImage>> {
+	let resource = if src.starts_with("http://") || src.starts_with("https://") {
+		Resource::Uri(SharedString::from(src.to_string()).into())
 	} else {
-		(0, 0, 0)
+		Resource::Path(std::path::Path::new(src.strip_prefix("file://").unwrap_or(src)).into())
 	};
-	Hsla::from(Rgba { r: r as f32 / 255.0, g: g as f32 / 255.0, b: b as f32 / 255.0, a: 1.0 })
+	match window.use_asset::<ImgResourceLoader>(&resource, cx)? {
+		Ok(image) => Some(image),
+		Err(e) => {
+			// Failures stay cached, so this comes up on every paint
+			log::debug!("canvas: failed to load image {}: {}", src, e);
+			None
+		}
+	}
+}
+
+/// Paint a gradient with any number of stops
+/// gpui gradients have two stops, so the first pair is painted as is and each
+/// later stop as a coat rising from transparent at the previous stop to its
+/// color; exact for opaque stops, translucent ones stack where coats overlap
+fn paint_gradient(bounds: Bounds<Pixels>, stops: &[GradientStop], angle: f32, window: &mut Window) {
+	let mut stops = stops.to_vec();
+	stops.sort_by(|a, b| a.offset.total_cmp(&b.offset));
+	match stops.as_slice() {
+		[] => {}
+		[only] => window.paint_quad(fill(bounds, only.color)),
+		[first, second, rest @ ..] => {
+			let stop = |stop: &GradientStop| linear_color_stop(stop.color, stop.offset);
+			window.paint_quad(fill(bounds, linear_gradient(angle, stop(first), stop(second))));
+			let mut previous = second;
+			for next in rest {
+				let from = linear_color_stop(next.color.opacity(0.0), previous.offset);
+				window.paint_quad(fill(bounds, linear_gradient(angle, from, stop(next))));
+				previous = next;
+			}
+		}
+	}
+}
+
+/// Parsed draw commands of a canvas: the latest ones, reused until its style
+/// changes, or for a retained canvas everything drawn since the last clear
+struct Drawn {
+	/// Style the commands were last taken from
+	style:    Arc<ElementStyle>,
+	/// `canvasBatch` of the commands last added to a retained canvas
+	batch:    Option<u64>,
+	commands: Arc<Vec<DrawCommand>>,
+}
+
+lazy_static! {
+	static ref DRAWN: Mutex<HashMap<(u64, u64), Drawn>> = Mutex::new(HashMap::new());
+}
+
+/// Drop all state of a closed window
+pub fn forget_window(window_id: u64) { DRAWN.lock_recover().retain(|(id, _), _| *id != window_id); }
+
+/// Drop the commands of a released canvas
+pub fn forget_element(window_id: u64, element_id: u64) {
+	DRAWN.lock_recover().remove(&(window_id, element_id));
+}
+
+/// Add a command to a retained canvas, dropping earlier commands it paints
+/// over entirely
+fn retain(commands: &mut Vec<DrawCommand>, command: DrawCommand) {
+	match command {
+		DrawCommand::Clear { .. } => commands.clear(),
+		DrawCommand::ClearRect { x, y, width, height } => commands.retain(|earlier| {
+			!earlier
+				.extent()
+				.is_some_and(|(l, t, r, b)| l >= x && t >= y && r <= x + width && b <= y + height)
+		}),
+		_ => {}
+	}
+	commands.push(command);
+}
+
+/// Parse draw commands from a style
+/// draw_commands can be either a JSON string or an already parsed JSON array
+fn parse_draw_commands(style: &ElementStyle) -> Vec<DrawCommand> {
+	let parsed = match style.draw_commands.as_ref() {
+		Some(serde_json::Value::String(json)) => serde_json::from_str(json),
+		Some(value) => Vec::<DrawCommand>::deserialize(value),
+		None => return Vec::new(),
+	};
+	parsed.unwrap_or_else(|e| {
+		log::warn!("canvas: invalid draw commands: {}", e);
+		Vec::new()
+	})
+}
+
+/// Commands to paint this frame, parsing only when the style changed
+/// A retained canvas adds the style's commands to the ones it has whenever
+/// `canvasBatch` changes, instead of replacing them
+fn drawn_commands(window_id: u64, element: &ReactElement) -> Arc<Vec<DrawCommand>> {
+	let style = &element.style;
+	let mut all = DRAWN.lock_recover();
+	let key = (window_id, element.global_id);
+	if let Some(drawn) = all.get_mut(&key) {
+		if Arc::ptr_eq(&drawn.style, style) {
+			return drawn.commands.clone();
+		}
+		if style.canvas_retained == Some(true) {
+			drawn.style = style.clone();
+			if drawn.batch != style.canvas_batch {
+				drawn.batch = style.canvas_batch;
+				let commands = Arc::make_mut(&mut drawn.commands);
+				for command in parse_draw_commands(style) {
+					retain(commands, command);
+				}
+			}
+			return drawn.commands.clone();
+		}
+	}
+
+	let mut commands = Vec::new();
+	for command in parse_draw_commands(style) {
+		retain(&mut commands, command);
+	}
+	let commands = Arc::new(commands);
+	all.insert(key, Drawn {
+		style:    style.clone(),
+		batch:    style.canvas_batch,
+		commands: commands.clone(),
+	});
+	commands
 }
 
 pub struct ReactCanvasElement {
@@ -82,55 +287,41 @@ impl ReactCanvasElement {
 			));
 		}
 		if let Some(bg) = es.bg_color {
-			style.background = Some(gpui::Fill::Color(gpui::rgb(bg).into()));
+			style.background = Some(gpui::Fill::Color(bg.into()));
 		}
 		style.position = gpui::Position::Relative;
 		style
 	}
 
-	/// Parse draw commands from element style
-	fn parse_draw_commands(&self) -> Vec<DrawCommand> {
-		if let Some(ref draw_commands_json) = self.element.style.draw_commands {
-			// draw_commands can be either a JSON string or already parsed JSON array
-			let commands_value = if draw_commands_json.is_string() {
-				// It's a JSON string, parse it
-				if let Some(s) = draw_commands_json.as_str() {
-					serde_json::from_str::<serde_json::Value>(s).ok()
-				} else {
-					None
-				}
-			} else {
-				// Already a JSON value
-				Some(draw_commands_json.clone())
-			};
-
-			if let Some(value) = commands_value {
-				if let Ok(commands) = serde_json::from_value::<Vec<DrawCommand>>(value) {
-					return commands;
-				}
-			}
-		}
-		Vec::new()
-	}
-
 	/// Execute draw commands using GPUI paint APIs
-	fn execute_draw_commands(&self, bounds: Bounds<Pixels>, window: &mut Window) {
-		let commands = self.parse_draw_commands();
+	fn execute_draw_commands(&self, bounds: Bounds<Pixels>, window: &mut Window, cx: &mut App) {
+		let commands = drawn_commands(self.window_id, &self.element);
 		let origin = bounds.origin;
 
-		for cmd in commands {
-			match cmd {
+		for cmd in commands.iter() {
+			match *cmd {
 				DrawCommand::Clear { color } => {
 					let quad = PaintQuad {
 						bounds,
 						corner_radii: Corners::default(),
-						background: parse_color(&color).into(),
+						background: color.into(),
 						border_widths: Edges::default(),
 						border_color: Hsla::transparent_black(),
 						border_style: BorderStyle::default(),
 					};
 					window.paint_quad(quad);
 				}
+				DrawCommand::ClearRect { x, y, width, height } => {
+					// There are no pixels to erase: commands inside the rect were
+					// dropped (see retain), the background covers what is left
+					if let Some(bg) = self.element.style.bg_color {
+						let rect_bounds = Bounds {
+							origin: point(origin.x + px(x), origin.y + px(y)),
+							size:   Size { width: px(width), height: px(height) },
+						};
+						window.paint_quad(gpui::fill(rect_bounds, bg));
+					}
+				}
 				DrawCommand::FillRect { x, y, width, height, color } => {
 					let rect_bounds = Bounds {
 						origin: point(origin.x + px(x), origin.y + px(y)),
@@ -139,7 +330,7 @@ impl ReactCanvasElement {
 					let quad = PaintQuad {
 						bounds:        rect_bounds,
 						corner_radii:  Corners::default(),
-						background:    parse_color(&color).into(),
+						background:    color.into(),
 						border_widths: Edges::default(),
 						border_color:  Hsla::transparent_black(),
 						border_style:  BorderStyle::default(),
@@ -162,7 +353,7 @@ impl ReactCanvasElement {
 							bottom_left:  corner_radius,
 							bottom_right: corner_radius,
 						},
-						background:    parse_color(&color).into(),
+						background:    color.into(),
 						border_widths: Edges::default(),
 						border_color:  Hsla::transparent_black(),
 						border_style:  BorderStyle::default(),
@@ -175,23 +366,49 @@ impl ReactCanvasElement {
 					let end = point(origin.x + px(x2), origin.y + px(y2));
 					let mut path = Path::new(start);
 					path.line_to(end);
-					window.paint_path(path, parse_color(&color));
+					window.paint_path(path, color);
 				}
-				DrawCommand::Text { text: _, x: _, y: _, size: _, color: _ } => {
-					// Text rendering requires more complex setup with fonts
-					// For now, skip text commands - they can be rendered via child elements
-					log::debug!("Text draw command not yet implemented in canvas");
+				DrawCommand::Text { ref text, x, y, size, color } => {
+					// (x, y) is the top-left of the line box; shaping goes through the
+					// shared cache since commands are replayed every frame
+					let font = window.text_style().font();
+					let font_size = px(size);
+					let line = text_cache::shape_line(window, text.clone(), &font, font_size, color);
+					let line_height = font_size * 1.25;
+					let line_origin = point(origin.x + px(x), origin.y + px(y));
+					if let Err(e) = line.paint(line_origin, line_height, window, cx) {
+						log::warn!("canvas: failed to paint text: {}", e);
+					}
 				}
-				DrawCommand::Path { points, width: _, color } => {
+				DrawCommand::Path { ref points, width: _, color } => {
 					if points.len() >= 2 {
 						let start = point(origin.x + px(points[0].0), origin.y + px(points[0].1));
 						let mut path = Path::new(start);
 						for (px_val, py_val) in points.iter().skip(1) {
 							path.line_to(point(origin.x + px(*px_val), origin.y + px(*py_val)));
 						}
-						window.paint_path(path, parse_color(&color));
+						window.paint_path(path, color);
 					}
 				}
+				DrawCommand::DrawImage { ref src, x, y, width, height } => {
+					let Some(image) = image(src, window, cx) else {
+						continue;
+					};
+					let rect_bounds = Bounds {
+						origin: point(origin.x + px(x), origin.y + px(y)),
+						size:   Size { width: px(width), height: px(height) },
+					};
+					if let Err(e) = window.paint_image(rect_bounds, Corners::default(), image, 0, false) {
+						log::warn!("canvas: failed to paint image {}: {}", src, e);
+					}
+				}
+				DrawCommand::GradientRect { x, y, width, height, ref stops, angle } => {
+					let rect_bounds = Bounds {
+						origin: point(origin.x + px(x), origin.y + px(y)),
+						size:   Size { width: px(width), height: px(height) },
+					};
+					paint_gradient(rect_bounds, stops, angle.unwrap_or(180.0), window);
+				}
 			}
 		}
 	}
@@ -227,6 +444,8 @@ impl Element for ReactCanvasElement {
 		window: &mut Window,
 		_cx: &mut App,
 	) -> Self::PrepaintState {
+		layout::record_bounds(self.window_id, self.element.global_id, bounds);
+
 		let event_flags = EventHandlerFlags::from_handlers(
 			self.element.event_handlers.as_ref(),
 			self.element.style.tab_index,
@@ -243,18 +462,17 @@ impl Element for ReactCanvasElement {
 		_request_layout: &mut Self::RequestLayoutState,
 		prepaint: &mut Self::PrepaintState,
 		window: &mut Window,
-		_cx: &mut App,
+		cx: &mut App,
 	) {
 		let element_id = self.element.global_id;
 		let window_id = self.window_id;
 
 		// Paint background first if specified
 		if let Some(bg) = self.element.style.bg_color {
-			let bg_color = gpui::rgb(bg);
 			let quad = PaintQuad {
 				bounds,
 				corner_radii: Corners::default(),
-				background: Hsla::from(bg_color).into(),
+				background: bg.into(),
 				border_widths: Edges::default(),
 				border_color: Hsla::transparent_black(),
 				border_style: BorderStyle::default(),
@@ -263,7 +481,7 @@ impl Element for ReactCanvasElement {
 		}
 
 		// Execute draw commands
-		self.execute_draw_commands(bounds, window);
+		self.execute_draw_commands(bounds, window, cx);
 
 		// Register event handlers
 		register_event_handlers(
@@ -281,3 +499,33 @@ impl IntoElement for ReactCanvasElement {
 
 	fn into_element(self) -> Self::Element { self }
 }
+
+#[cfg(test)]
+mod tests {
+	use gpui::rgba;
+	use serde_json::json;
+
+	use super::*;
+
+	#[test]
+	fn colors_are_read_packed() {
+		let commands: Vec<DrawCommand> = serde_json::from_value(json!([
+			{ "type": "fillRect", "x": 0, "y": 0, "width": 4, "height": 4, "color": 0xffa500ffu32 },
+			{ "type": "gradientRect", "x": 0, "y": 0, "width": 4, "height": 4, "stops": [
+				{ "offset": 0, "color": 0xffffffffu32 },
+				{ "offset": 1, "color": 0x0000ff80u32 },
+			] },
+		]))
+		.unwrap();
+		let DrawCommand::FillRect { color, .. } = &commands[0] else { panic!("not a fillRect") };
+		assert_eq!(*color, Hsla::from(rgba(0xffa500ff)));
+		let DrawCommand::GradientRect { stops, .. } = &commands[1] else { panic!("not a gradientRect") };
+		assert_eq!(stops[1].color, Hsla::from(rgba(0x0000ff80)));
+	}
+
+	#[test]
+	fn unpacked_colors_are_rejected() {
+		let parsed = serde_json::from_value::<Vec<DrawCommand>>(json!([{ "type": "clear", "color": "red" }]));
+		assert!(parsed.is_err());
+	}
+}
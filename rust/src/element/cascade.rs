@@ -0,0 +1,154 @@
+//! Style cascade: which style wins when several apply to one element
+//!
+//! An element's style props arrive as one JSON object carrying up to three
+//! layers, merged property by property, later layers winning:
+//!
+//! | Layer       | Source                                             |
+//! |-------------|----------------------------------------------------|
+//! | `Base`      | the element's style props                          |
+//! | `Theme`     | theme styles (no source yet)                       |
+//! | `State`     | Rust-applied state styles: `hoverStyle`,           |
+//! |             | `focusStyle` and `activeStyle`                     |
+//! | `Important` | style props marked `!important` (`importantStyle`) |
+//!
+//! So a hover style beats the element's own styles, and an `!important`
+//! style prop beats the hover style. Each state style is resolved on its
+//! own; style_variants picks the one to render. Properties no layer sets keep their
+//! defaults; inheritance from the parent happens after the cascade.
+
+use serde_json::{Map, Value};
+
+/// Cascade layers, lowest precedence first
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Layer {
+	Base,
+	Theme,
+	State,
+	Important,
+}
+
+/// Keys of a style object that hold other layers rather than properties
+const LAYER_KEYS: [&str; 4] = ["hoverStyle", "focusStyle", "activeStyle", "importantStyle"];
+
+/// Merge layers into one style object, in precedence order whatever the
+/// order given
+pub fn merge<'a>(
+	layers: impl IntoIterator<Item = (Layer, &'a Map<String, Value>)>,
+) -> Map<String, Value> {
+	let mut layers: Vec<_> = layers.into_iter().collect();
+	// Stable, so layers of one kind apply in the order given
+	layers.sort_by_key(|(layer, _)| *layer);
+	let mut merged = Map::new();
+	for (_, properties) in layers {
+		for (key, value) in properties {
+			if !LAYER_KEYS.contains(&key.as_str()) {
+				merged.insert(key.clone(), value.clone());
+			}
+		}
+	}
+	merged
+}
+
+/// Style of an element with its `!important` props applied over the base
+/// With `state`, the key of a state style such as `hoverStyle`, that state
+/// style is applied in between; None if the element has no such style.
+pub fn resolve(style_obj: &Value, state: Option<&str>) -> Option<Value> {
+	let base = style_obj.as_object()?;
+	let mut layers = vec![(Layer::Base, base)];
+	if let Some(state) = state {
+		layers.push((Layer::State, style_obj.get(state)?.as_object()?));
+	}
+	if let Some(important) = style_obj.get("importantStyle").and_then(Value::as_object) {
+		layers.push((Layer::Important, important));
+	}
+	Some(Value::Object(merge(layers)))
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json::json;
+
+	use super::*;
+	use crate::element::ElementStyle;
+
+	fn object(value: Value) -> Map<String, Value> { value.as_object().cloned().unwrap() }
+
+	#[test]
+	fn later_layers_win() {
+		let base = object(json!({ "color": "base", "width": 1, "height": 1, "opacity": 1 }));
+		let theme = object(json!({ "color": "theme", "width": 2, "height": 2 }));
+		let state = object(json!({ "color": "state", "width": 3 }));
+		let important = object(json!({ "color": "important" }));
+		// Given out of order, applied in precedence order
+		let merged = merge([
+			(Layer::Important, &important),
+			(Layer::State, &state),
+			(Layer::Base, &base),
+			(Layer::Theme, &theme),
+		]);
+		assert_eq!(
+			Value::Object(merged),
+			json!({ "color": "important", "width": 3, "height": 2, "opacity": 1 })
+		);
+	}
+
+	#[test]
+	fn later_source_wins_within_a_layer() {
+		let first = object(json!({ "color": "first", "width": 1 }));
+		let second = object(json!({ "color": "second" }));
+		let merged = merge([(Layer::Theme, &first), (Layer::Theme, &second)]);
+		assert_eq!(Value::Object(merged), json!({ "color": "second", "width": 1 }));
+	}
+
+	#[test]
+	fn unset_properties_keep_defaults() {
+		let base = object(json!({ "width": 1 }));
+		let merged = merge([(Layer::Base, &base)]);
+		assert!(!merged.contains_key("color"));
+		assert!(merge([]).is_empty());
+	}
+
+	#[test]
+	fn layer_keys_are_not_properties() {
+		let base = object(json!({ "width": 1, "hoverStyle": { "width": 2 }, "importantStyle": {} }));
+		assert_eq!(Value::Object(merge([(Layer::Base, &base)])), json!({ "width": 1 }));
+	}
+
+	#[test]
+	fn resolve_applies_state_between_base_and_important() {
+		let style = json!({
+			"color": "base",
+			"width": 1,
+			"height": 1,
+			"hoverStyle": { "color": "hover", "width": 2 },
+			"importantStyle": { "color": "important" },
+		});
+		assert_eq!(
+			resolve(&style, Some("hoverStyle")),
+			Some(json!({ "color": "important", "width": 2, "height": 1 }))
+		);
+		assert_eq!(
+			resolve(&style, None),
+			Some(json!({ "color": "important", "width": 1, "height": 1 }))
+		);
+	}
+
+	#[test]
+	fn resolve_without_the_state_style() {
+		let style = json!({ "color": "base" });
+		assert_eq!(resolve(&style, Some("hoverStyle")), None);
+		assert_eq!(resolve(&json!("not a style"), None), None);
+	}
+
+	#[test]
+	fn cascaded_values_beat_inherited_ones() {
+		let parent =
+			ElementStyle { text_size: Some(20.0), line_height: Some(30.0), ..Default::default() };
+		let mut child = ElementStyle { text_size: Some(12.0), ..Default::default() };
+		child.inherit_from(&parent);
+		assert_eq!(child.text_size, Some(12.0));
+		assert_eq!(child.line_height, Some(30.0));
+		// Nothing to inherit either: the default stays
+		assert_eq!(child.font_weight, None);
+	}
+}
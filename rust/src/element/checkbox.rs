@@ -0,0 +1,312 @@
+//! Checkbox and radio elements
+//!
+//! Both paint a native-looking control (a check square or a radio circle)
+//! sized 16px unless the style says otherwise, are focusable without a
+//! `tabIndex`, and toggle on a left click and on Space while focused. A
+//! toggle queues `input` and `change` events whose `checked` is the new state.
+//! A checked radio stays checked; checking one unchecks the other radios of
+//! its window with the same `name`.
+//!
+//! The new state shows right away, before JS answers: it overrides the
+//! `checked` prop until the prop changes, which a controlled element does by
+//! echoing the new state. Disabled controls don't toggle or take focus.
+
+use std::{collections::HashMap, sync::{Arc, Mutex}};
+
+use gpui::{App, BorderStyle, Bounds, Corners, Edges, Element, ElementId, GlobalElementId, Hitbox, Hsla, InspectorElementId, IntoElement, LayoutId, PaintQuad, PathBuilder, Pixels, Window, fill, point, px, rgb, size};
+use lazy_static::lazy_static;
+
+use super::{ElementKind, ElementStyle, ReactElement, border, events::{EventHandlerFlags, insert_hitbox_if_needed, register_event_handlers}, focus, layout, pixel_snap};
+use crate::{event_types::{EventData, InputEventData, types}, global_state::GLOBAL_STATE, renderer::dispatch_event_to_js, sync::MutexExt};
+
+/// Size of the control when the style has none
+const DEFAULT_SIZE: f32 = 16.0;
+/// Fill of a checked control when the style has no `accentColor`
+const DEFAULT_ACCENT: u32 = 0x3b82f6;
+
+/// State toggled in Rust that JS hasn't confirmed yet
+struct Toggled {
+	/// `checked` prop at the time of the toggle
+	prop:    Option<bool>,
+	checked: bool,
+}
+
+lazy_static! {
+	static ref TOGGLED: Mutex<HashMap<(u64, u64), Toggled>> = Mutex::new(HashMap::new());
+}
+
+/// Drop all state of a closed window
+pub fn forget_window(window_id: u64) {
+	TOGGLED.lock_recover().retain(|(id, _), _| *id != window_id);
+}
+
+/// Drop the toggled state of a released element
+pub fn forget_element(window_id: u64, element_id: u64) {
+	TOGGLED.lock_recover().remove(&(window_id, element_id));
+}
+
+/// Whether a checkbox or radio is checked, toggled state first
+pub fn is_checked(window_id: u64, element_id: u64, style: &ElementStyle) -> bool {
+	let toggled = TOGGLED.lock_recover();
+	match toggled.get(&(window_id, element_id)) {
+		Some(toggled) if toggled.prop == style.checked => toggled.checked,
+		_ => style.checked.unwrap_or(false),
+	}
+}
+
+/// Toggle a checkbox or check a radio, as a click or Space would
+/// Returns whether the element changed
+pub fn activate(window_id: u64, element_id: u64) -> bool {
+	let Some(window) = GLOBAL_STATE.get_window(window_id) else {
+		return false;
+	};
+	let (checked, value, prop, others) = {
+		let element_map = window.state().element_map.lock_recover();
+		let Some(element) = element_map.get(&element_id) else {
+			return false;
+		};
+		let radio = match element.element_kind {
+			ElementKind::Checkbox => false,
+			ElementKind::Radio => true,
+			_ => return false,
+		};
+		let style = &element.style;
+		let current = is_checked(window_id, element_id, style);
+		if style.disabled == Some(true) || (radio && current) {
+			return false;
+		}
+
+		// Other radios of the group, unchecked without events like native ones
+		let others: Vec<(u64, Option<bool>)> = match style.name.as_deref() {
+			Some(name) if radio => element_map
+				.values()
+				.filter(|other| {
+					other.global_id != element_id
+						&& other.element_kind == ElementKind::Radio
+						&& other.style.name.as_deref() == Some(name)
+				})
+				.map(|other| (other.global_id, other.style.checked))
+				.collect(),
+			_ => Vec::new(),
+		};
+		(!current, style.value.clone().unwrap_or_else(|| "on".to_string()), style.checked, others)
+	};
+
+	{
+		let mut toggled = TOGGLED.lock_recover();
+		toggled.insert((window_id, element_id), Toggled { prop, checked });
+		for (other_id, prop) in others {
+			toggled.insert((window_id, other_id), Toggled { prop, checked: false });
+		}
+	}
+
+	log::debug!(
+		"[Rust] toggle: window_id={}, element_id={}, checked={}",
+		window_id,
+		element_id,
+		checked
+	);
+	for event_type in [types::INPUT, types::CHANGE] {
+		dispatch_event_to_js(
+			window_id,
+			element_id,
+			event_type,
+			EventData::Input(InputEventData {
+				value:        value.clone(),
+				data:         None,
+				input_type:   String::new(),
+				is_composing: false,
+				checked:      Some(checked),
+			}),
+		);
+	}
+	true
+}
+
+pub struct ReactCheckboxElement {
+	element:      Arc<ReactElement>,
+	window_id:    u64,
+	parent_style: Option<ElementStyle>,
+}
+
+pub struct CheckboxLayoutState {}
+
+pub struct CheckboxPrepaintState {
+	hitbox:      Option<Hitbox>,
+	event_flags: EventHandlerFlags,
+}
+
+impl ReactCheckboxElement {
+	pub fn new(
+		element: Arc<ReactElement>,
+		window_id: u64,
+		parent_style: Option<ElementStyle>,
+	) -> Self {
+		Self { element, window_id, parent_style }
+	}
+
+	fn is_radio(&self) -> bool { self.element.element_kind == ElementKind::Radio }
+}
+
+impl Element for ReactCheckboxElement {
+	type PrepaintState = CheckboxPrepaintState;
+	type RequestLayoutState = CheckboxLayoutState;
+
+	fn id(&self) -> Option<ElementId> { Some(ElementId::Integer(self.element.global_id)) }
+
+	fn source_location(&self) -> Option<&'static std::panic::Location<'static>> { None }
+
+	fn request_layout(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		window: &mut Window,
+		cx: &mut App,
+	) -> (LayoutId, Self::RequestLayoutState) {
+		let mut style = self.element.build_gpui_style(None);
+		if self.element.style.width.is_none() {
+			style.size.width = px(DEFAULT_SIZE).into();
+		}
+		if self.element.style.height.is_none() {
+			style.size.height = px(DEFAULT_SIZE).into();
+		}
+		style.flex_shrink = 0.0;
+		let layout_id = window.request_layout(style, std::iter::empty(), cx);
+		(layout_id, CheckboxLayoutState {})
+	}
+
+	fn prepaint(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		bounds: Bounds<Pixels>,
+		_request_layout: &mut Self::RequestLayoutState,
+		window: &mut Window,
+		_cx: &mut App,
+	) -> Self::PrepaintState {
+		layout::record_bounds(self.window_id, self.element.global_id, bounds);
+
+		let style = &self.element.style;
+		let mut event_flags =
+			EventHandlerFlags::from_handlers(self.element.event_handlers.as_ref(), style.tab_index);
+		if style.disabled != Some(true) {
+			event_flags.toggles = true;
+			event_flags.tab_index = event_flags.tab_index.or(Some(0));
+		}
+		let hitbox = insert_hitbox_if_needed(&event_flags, bounds, window);
+		CheckboxPrepaintState { hitbox, event_flags }
+	}
+
+	fn paint(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		bounds: Bounds<Pixels>,
+		_request_layout: &mut Self::RequestLayoutState,
+		prepaint: &mut Self::PrepaintState,
+		window: &mut Window,
+		cx: &mut App,
+	) {
+		let mut style = self.element.build_gpui_style(None);
+		let crisp = pixel_snap::is_crisp(&self.element.style, self.parent_style.as_ref());
+		let (bounds, snapped) =
+			pixel_snap::snap(crisp, bounds, &mut style, &self.element.style, window);
+		let element_style = snapped.as_ref().unwrap_or(&self.element.style);
+		// The background fills the control's shape, not the whole box
+		style.background = None;
+
+		let checked = is_checked(self.window_id, self.element.global_id, &self.element.style);
+		let focused = focus::is_focused(self.window_id, self.element.global_id);
+		let radio = self.is_radio();
+		style.paint(bounds, window, cx, |window, _| {
+			paint_control(element_style, bounds, radio, checked, focused, window);
+		});
+		border::paint(element_style, bounds, window);
+		border::paint_outline(element_style, bounds, window);
+
+		register_event_handlers(
+			&prepaint.event_flags,
+			prepaint.hitbox.as_ref(),
+			self.window_id,
+			self.element.global_id,
+			window,
+		);
+	}
+}
+
+impl IntoElement for ReactCheckboxElement {
+	type Element = Self;
+
+	fn into_element(self) -> Self::Element { self }
+}
+
+/// Paint the square or circle, its check mark or dot, and the focus ring
+fn paint_control(
+	style: &ElementStyle,
+	bounds: Bounds<Pixels>,
+	radio: bool,
+	checked: bool,
+	focused: bool,
+	window: &mut Window,
+) {
+	let mut accent = style.accent_color.unwrap_or_else(|| rgb(DEFAULT_ACCENT).into());
+	let mut frame = style.border_color.unwrap_or_else(|| rgb(0x808080).into());
+	let mut background = style.bg_color.unwrap_or_else(Hsla::transparent_black);
+	let mut mark: Hsla = rgb(0xffffff).into();
+	if style.disabled == Some(true) {
+		for color in [&mut accent, &mut frame, &mut background, &mut mark] {
+			color.a *= 0.5;
+		}
+	}
+	let side = f32::from(bounds.size.width.min(bounds.size.height));
+	let radius = if radio { side / 2.0 } else { side * 0.2 };
+	let control = Bounds::centered_at(bounds.center(), size(px(side), px(side)));
+
+	if focused {
+		let ring = Hsla { a: accent.a * 0.5, ..accent };
+		let outer = control.dilate(px(2.0));
+		window.paint_quad(framed(outer, radius + 2.0, Hsla::transparent_black(), 2.0, ring));
+	}
+
+	match (radio, checked) {
+		(false, true) => {
+			window.paint_quad(fill(control, accent).corner_radii(Corners::all(px(radius))));
+			// Check mark from the left middle, down to the bottom third, up to the top
+			// right
+			let at =
+				|x: f32, y: f32| point(control.origin.x + px(side * x), control.origin.y + px(side * y));
+			let mut path = PathBuilder::stroke(px((side * 0.125).max(1.5)));
+			path.move_to(at(0.25, 0.52));
+			path.line_to(at(0.43, 0.7));
+			path.line_to(at(0.76, 0.32));
+			match path.build() {
+				Ok(path) => window.paint_path(path, mark),
+				Err(e) => log::warn!("checkbox: failed to build check mark: {}", e),
+			}
+		}
+		(true, true) => {
+			window.paint_quad(framed(control, radius, background, 1.0, accent));
+			let dot = Bounds::centered_at(control.center(), size(px(side / 2.0), px(side / 2.0)));
+			window.paint_quad(fill(dot, accent).corner_radii(Corners::all(px(side / 4.0))));
+		}
+		(_, false) => window.paint_quad(framed(control, radius, background, 1.0, frame)),
+	}
+}
+
+/// Rounded box with a solid border
+fn framed(
+	bounds: Bounds<Pixels>,
+	radius: f32,
+	background: Hsla,
+	width: f32,
+	color: Hsla,
+) -> PaintQuad {
+	PaintQuad {
+		bounds,
+		corner_radii: Corners::all(px(radius)),
+		background: background.into(),
+		border_widths: Edges::all(px(width)),
+		border_color: color,
+		border_style: BorderStyle::Solid,
+	}
+}
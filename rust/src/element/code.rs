@@ -0,0 +1,240 @@
+//! Code element
+//!
+//! A `code` shows `text` in a monospaced font, syntax highlighted for its
+//! `language` (rust, typescript/javascript, python, go, c/cpp, shell, json) by
+//! the highlight module. The whole block is a single styled text rather than an
+//! element per token, and its highlights are kept per element until the text or
+//! language changes. Lines don't wrap; tabs become `tabSize` spaces (4 by
+//! default).
+//!
+//! Token colors come from a dark palette, or a light one on a light
+//! background; `textColor` replaces the color of plain text and `fontFamily`
+//! the platform's monospaced font.
+
+use std::{collections::HashMap, ops::Range, sync::{Arc, Mutex}};
+
+use gpui::{AnyElement, App, Bounds, Element, ElementId, FontStyle, GlobalElementId, HighlightStyle, Hitbox, Hsla, InspectorElementId, IntoElement, LayoutId, Pixels, StyledText, Window, div, prelude::*, px, rgb};
+use lazy_static::lazy_static;
+
+use super::{ElementStyle, ReactElement, border, events::{EventHandlerFlags, insert_hitbox_if_needed, register_event_handlers}, fonts, highlight::{self, Token}, layout};
+use crate::sync::MutexExt;
+
+#[cfg(target_os = "macos")]
+const MONO_FAMILY: &str = "Menlo";
+#[cfg(target_os = "windows")]
+const MONO_FAMILY: &str = "Consolas";
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const MONO_FAMILY: &str = "DejaVu Sans Mono";
+
+const DEFAULT_TEXT_SIZE: f32 = 13.0;
+const DEFAULT_TAB_SIZE: usize = 4;
+
+/// Colors of plain text, comments, strings, numbers, keywords, literals,
+/// types and functions
+type Palette = [u32; 8];
+const DARK: Palette =
+	[0xabb2bf, 0x7f848e, 0x98c379, 0xd19a66, 0xc678dd, 0xd19a66, 0xe5c07b, 0x61afef];
+const LIGHT: Palette =
+	[0x383a42, 0xa0a1a7, 0x50a14f, 0x986801, 0xa626a4, 0x986801, 0xc18401, 0x4078f2];
+
+/// Byte ranges of the text and the token each one is
+type Highlights = Arc<Vec<(Range<usize>, Token)>>;
+
+/// Highlights of an element's text, reused until text or language change
+struct Cached {
+	language:   String,
+	text:       String,
+	/// The text with tabs expanded, which the highlights index into
+	expanded:   Arc<str>,
+	highlights: Highlights,
+}
+
+lazy_static! {
+	static ref HIGHLIGHTS: Mutex<HashMap<(u64, u64), Cached>> = Mutex::new(HashMap::new());
+}
+
+/// Drop all state of a closed window
+pub fn forget_window(window_id: u64) {
+	HIGHLIGHTS.lock_recover().retain(|(id, _), _| *id != window_id);
+}
+
+/// Drop the cached highlights of a released element
+pub fn forget_element(window_id: u64, element_id: u64) {
+	HIGHLIGHTS.lock_recover().remove(&(window_id, element_id));
+}
+
+/// Expanded text and highlights of the element, highlighting again only when
+/// its text or language changed
+fn highlights(window_id: u64, element: &ReactElement) -> (Arc<str>, Highlights) {
+	let style = &element.style;
+	let text = style.code_text.as_deref().unwrap_or_default();
+	let language = style.code_language.as_deref().unwrap_or_default();
+
+	let mut all = HIGHLIGHTS.lock_recover();
+	if let Some(cached) = all.get(&(window_id, element.global_id))
+		&& cached.text == text
+		&& cached.language == language
+	{
+		return (cached.expanded.clone(), cached.highlights.clone());
+	}
+
+	let tab = " ".repeat(style.tab_size.unwrap_or(DEFAULT_TAB_SIZE));
+	let expanded: Arc<str> = text.replace('\t', &tab).into();
+	let highlights = Arc::new(highlight::highlight(language, &expanded));
+	let cached = Cached {
+		language:   language.to_string(),
+		text:       text.to_string(),
+		expanded:   expanded.clone(),
+		highlights: highlights.clone(),
+	};
+	all.insert((window_id, element.global_id), cached);
+	(expanded, highlights)
+}
+
+fn token_style(palette: &Palette, token: Token) -> HighlightStyle {
+	let index = match token {
+		Token::Comment => 1,
+		Token::String => 2,
+		Token::Number => 3,
+		Token::Keyword => 4,
+		Token::Literal => 5,
+		Token::Type => 6,
+		Token::Function => 7,
+	};
+	HighlightStyle {
+		color: Some(rgb(palette[index]).into()),
+		font_style: (token == Token::Comment).then_some(FontStyle::Italic),
+		..Default::default()
+	}
+}
+
+pub struct ReactCodeElement {
+	element:      Arc<ReactElement>,
+	window_id:    u64,
+	parent_style: Option<ElementStyle>,
+	child:        Option<AnyElement>,
+}
+
+pub struct CodeLayoutState {}
+
+pub struct CodePrepaintState {
+	hitbox:      Option<Hitbox>,
+	event_flags: EventHandlerFlags,
+}
+
+impl ReactCodeElement {
+	pub fn new(
+		element: Arc<ReactElement>,
+		window_id: u64,
+		parent_style: Option<ElementStyle>,
+	) -> Self {
+		Self { element, window_id, parent_style, child: None }
+	}
+}
+
+impl Element for ReactCodeElement {
+	type PrepaintState = CodePrepaintState;
+	type RequestLayoutState = CodeLayoutState;
+
+	fn id(&self) -> Option<ElementId> { Some(ElementId::Integer(self.element.global_id)) }
+
+	fn source_location(&self) -> Option<&'static std::panic::Location<'static>> { None }
+
+	fn request_layout(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		window: &mut Window,
+		cx: &mut App,
+	) -> (LayoutId, Self::RequestLayoutState) {
+		let es = &self.element.style;
+		let style = self.element.build_gpui_style(None);
+		let effective = self.element.effective_style(self.parent_style.as_ref());
+
+		let palette = match es.bg_color {
+			Some(background) if background.l > 0.5 => &LIGHT,
+			_ => &DARK,
+		};
+		let text_color: Hsla = effective.text_color.unwrap_or_else(|| rgb(palette[0]).into());
+		let mut font = fonts::resolve(&effective, window);
+		// An inherited family is meant for prose, only the element's own counts
+		if es.font_family.is_none() {
+			font.family = MONO_FAMILY.into();
+		}
+
+		let (text, highlights) = highlights(self.window_id, &self.element);
+		let text = StyledText::new(text.to_string()).with_highlights(
+			highlights.iter().map(|(range, token)| (range.clone(), token_style(palette, *token))),
+		);
+		let mut child = div()
+			.whitespace_nowrap()
+			.text_color(text_color)
+			.text_size(px(effective.text_size.unwrap_or(DEFAULT_TEXT_SIZE)))
+			.font(font)
+			.child(text)
+			.into_any_element();
+		let child_layout_id = child.request_layout(window, cx);
+		self.child = Some(child);
+
+		let layout_id = window.request_layout(style, std::iter::once(child_layout_id), cx);
+		(layout_id, CodeLayoutState {})
+	}
+
+	fn prepaint(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		bounds: Bounds<Pixels>,
+		_request_layout: &mut Self::RequestLayoutState,
+		window: &mut Window,
+		cx: &mut App,
+	) -> Self::PrepaintState {
+		layout::record_bounds(self.window_id, self.element.global_id, bounds);
+
+		if let Some(child) = self.child.as_mut() {
+			child.prepaint(window, cx);
+		}
+
+		let event_flags = EventHandlerFlags::from_handlers(
+			self.element.event_handlers.as_ref(),
+			self.element.style.tab_index,
+		);
+		let hitbox = insert_hitbox_if_needed(&event_flags, bounds, window);
+		CodePrepaintState { hitbox, event_flags }
+	}
+
+	fn paint(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		bounds: Bounds<Pixels>,
+		_request_layout: &mut Self::RequestLayoutState,
+		prepaint: &mut Self::PrepaintState,
+		window: &mut Window,
+		cx: &mut App,
+	) {
+		let element_style = &self.element.style;
+		let style = self.element.build_gpui_style(None);
+		style.paint(bounds, window, cx, |window, cx| {
+			if let Some(child) = self.child.as_mut() {
+				child.paint(window, cx);
+			}
+		});
+		border::paint(element_style, bounds, window);
+		border::paint_outline(element_style, bounds, window);
+
+		register_event_handlers(
+			&prepaint.event_flags,
+			prepaint.hitbox.as_ref(),
+			self.window_id,
+			self.element.global_id,
+			window,
+		);
+	}
+}
+
+impl IntoElement for ReactCodeElement {
+	type Element = Self;
+
+	fn into_element(self) -> Self::Element { self }
+}
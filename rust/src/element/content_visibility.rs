@@ -0,0 +1,84 @@
+//! `contentVisibility: "auto"`: skip layout and paint of offscreen subtrees
+//!
+//! A div whose bounds ended up far outside the visible area (its content
+//! mask: the window, or the nearest clipping ancestor) is laid out without
+//! children on the next frame, as a placeholder that keeps its last measured
+//! size. When the placeholder comes back within range the subtree is built
+//! again on the following frame. Elements with `containIntrinsicWidth` /
+//! `containIntrinsicHeight` start out as placeholders of that size instead of
+//! being measured once.
+
+use std::{collections::HashMap, sync::Mutex};
+
+use gpui::{Bounds, Pixels, Size, Window, px};
+use lazy_static::lazy_static;
+
+use super::ElementStyle;
+use crate::sync::MutexExt;
+
+/// How far outside the visible area content stays rendered, in viewport heights
+const MARGIN_VIEWPORTS: f32 = 1.0;
+
+#[derive(Clone, Copy)]
+enum State {
+	Rendered,
+	Skipped(Size<Pixels>),
+}
+
+lazy_static! {
+	static ref STATES: Mutex<HashMap<(u64, u64), State>> = Mutex::new(HashMap::new());
+}
+
+/// Drop all state of a closed window
+pub fn forget_window(window_id: u64) {
+	STATES.lock_recover().retain(|(window, _), _| *window != window_id);
+}
+
+/// Drop the state of a released element
+pub fn forget_element(window_id: u64, element_id: u64) {
+	STATES.lock_recover().remove(&(window_id, element_id));
+}
+
+pub fn is_auto(style: &ElementStyle) -> bool { style.content_visibility.as_deref() == Some("auto") }
+
+/// Size to lay the element out at if its subtree is skipped this frame
+pub fn placeholder_size(
+	window_id: u64,
+	element_id: u64,
+	style: &ElementStyle,
+) -> Option<Size<Pixels>> {
+	if !is_auto(style) {
+		return None;
+	}
+	match STATES.lock_recover().get(&(window_id, element_id)) {
+		Some(State::Skipped(size)) => Some(*size),
+		Some(State::Rendered) => None,
+		None => {
+			if style.contain_intrinsic_width.is_none() && style.contain_intrinsic_height.is_none() {
+				return None;
+			}
+			Some(Size {
+				width:  px(style.contain_intrinsic_width.unwrap_or(0.0)),
+				height: px(style.contain_intrinsic_height.unwrap_or(0.0)),
+			})
+		}
+	}
+}
+
+/// Record where the element landed this frame, `skipped` if it was laid out
+/// as a placeholder. Returns true if the subtree has to be rebuilt, which
+/// takes another frame
+pub fn update(
+	window_id: u64,
+	element_id: u64,
+	bounds: Bounds<Pixels>,
+	skipped: bool,
+	window: &Window,
+) -> bool {
+	let margin = window.viewport_size().height * MARGIN_VIEWPORTS;
+	let near = window.content_mask().bounds.dilate(margin).intersects(&bounds);
+
+	let state = if near { State::Rendered } else { State::Skipped(bounds.size) };
+	STATES.lock_recover().insert((window_id, element_id), state);
+	near && skipped
+}
@@ -0,0 +1,195 @@
+//! Color contrast audit (debug builds)
+//!
+//! The first frame painted after each commit measures the contrast of every
+//! visible text against the background painted behind it: the backgrounds of
+//! its ancestors, blended from the nearest opaque one up. Text below WCAG AA
+//! (4.5:1, or 3:1 for large text: 24px, or 18.66px bold) is logged and
+//! queued as a window-level `contrastwarning` event, once until its colors
+//! change. Text with no opaque background behind it isn't measured, since
+//! whatever shows through the window is unknown.
+//!
+//! Release builds skip the audit.
+
+use std::{collections::HashMap, sync::Mutex};
+
+use gpui::{Hsla, Rgba, rgb};
+use lazy_static::lazy_static;
+use serde_json::json;
+
+use super::{ElementStyle, ReactElement};
+use crate::{global_state::GLOBAL_STATE, renderer::dispatch_window_event, sync::MutexExt};
+
+/// Minimum contrast ratio of normal and large text (WCAG 2 AA)
+const NORMAL_MINIMUM: f32 = 4.5;
+const LARGE_MINIMUM: f32 = 3.0;
+
+/// Failing text of one window
+struct Audit {
+	/// Tree generation last audited
+	generation: u64,
+	/// Colors of each failing text element, to warn once per color pair
+	failing:    HashMap<u64, (Rgba, Rgba)>,
+}
+
+lazy_static! {
+	static ref WINDOWS: Mutex<HashMap<u64, Audit>> = Mutex::new(HashMap::new());
+}
+
+/// Drop all state of a closed window
+pub fn forget_window(window_id: u64) { WINDOWS.lock_recover().remove(&window_id); }
+
+/// Text below the minimum contrast
+struct Failure {
+	element_id: u64,
+	color:      Rgba,
+	background: Rgba,
+	ratio:      f32,
+	minimum:    f32,
+}
+
+/// Audit the window's text if a commit landed since the last audit
+/// Called after the window's element tree painted
+pub fn after_paint(window_id: u64) {
+	if !cfg!(debug_assertions) {
+		return;
+	}
+	let Some(window) = GLOBAL_STATE.get_window(window_id) else {
+		return;
+	};
+	let generation = window.state().get_tree_generation();
+	let audited = WINDOWS.lock_recover().get(&window_id).map(|audit| audit.generation);
+	if audited == Some(generation) {
+		return;
+	}
+	let Some(root) = window.state().element_tree.lock_recover().clone() else {
+		return;
+	};
+
+	let mut failures = Vec::new();
+	audit(&root, None, &mut Vec::new(), &mut failures);
+
+	let mut new_failures = Vec::new();
+	{
+		let mut windows = WINDOWS.lock_recover();
+		let audit =
+			windows.entry(window_id).or_insert_with(|| Audit { generation, failing: HashMap::new() });
+		audit.generation = generation;
+		let mut failing = HashMap::with_capacity(failures.len());
+		for failure in failures {
+			let colors = (failure.color, failure.background);
+			failing.insert(failure.element_id, colors);
+			if audit.failing.get(&failure.element_id) != Some(&colors) {
+				new_failures.push(failure);
+			}
+		}
+		audit.failing = failing;
+	}
+
+	for failure in new_failures {
+		let (color, background) = (hex(failure.color), hex(failure.background));
+		log::warn!(
+			"[Rust] low contrast: window_id={}, element_id={}, ratio={:.2} < {}, color={}, background={}",
+			window_id,
+			failure.element_id,
+			failure.ratio,
+			failure.minimum,
+			color,
+			background
+		);
+		dispatch_window_event(
+			window_id,
+			"contrastwarning",
+			json!({
+				"targetId": failure.element_id,
+				"ratio": (failure.ratio * 100.0).round() / 100.0,
+				"minimum": failure.minimum,
+				"color": color,
+				"background": background,
+			}),
+		);
+	}
+}
+
+/// Measure the visible text of a subtree
+/// `backgrounds` holds the background colors of the ancestors, outermost first
+fn audit(
+	element: &ReactElement,
+	parent: Option<&ElementStyle>,
+	backgrounds: &mut Vec<Rgba>,
+	failures: &mut Vec<Failure>,
+) {
+	let style = element.effective_style(parent);
+	if style.display.as_deref() == Some("none") {
+		return;
+	}
+	let background = style.bg_color.filter(|color| color.a > 0.0);
+	if let Some(color) = background {
+		backgrounds.push(color.into());
+	}
+
+	if style.visibility.as_deref() != Some("hidden")
+		&& element.text.as_deref().is_some_and(|text| !text.trim().is_empty())
+		&& let Some(background) = resolve(backgrounds)
+	{
+		let color = blend(style.text_color.unwrap_or_else(|| rgb(0xffffff).into()), background);
+		let ratio = ratio(color, background);
+		let minimum = if is_large(&style) { LARGE_MINIMUM } else { NORMAL_MINIMUM };
+		if ratio < minimum {
+			failures.push(Failure { element_id: element.global_id, color, background, ratio, minimum });
+		}
+	}
+
+	for child in &element.children {
+		audit(child, Some(&style), backgrounds, failures);
+	}
+	if background.is_some() {
+		backgrounds.pop();
+	}
+}
+
+/// Background behind the top of the stack, None if nothing opaque is below it
+fn resolve(backgrounds: &[Rgba]) -> Option<Rgba> {
+	let base = backgrounds.iter().rposition(|color| color.a >= 1.0)?;
+	let mut background = backgrounds[base];
+	for color in &backgrounds[base + 1..] {
+		background = blend((*color).into(), background);
+	}
+	Some(background)
+}
+
+/// `color` painted over the opaque `background`
+fn blend(color: Hsla, background: Rgba) -> Rgba {
+	let color = Rgba::from(color);
+	let mix = |front: f32, back: f32| front * color.a + back * (1.0 - color.a);
+	Rgba {
+		r: mix(color.r, background.r),
+		g: mix(color.g, background.g),
+		b: mix(color.b, background.b),
+		a: 1.0,
+	}
+}
+
+/// WCAG contrast ratio of two opaque colors, from 1 to 21
+fn ratio(a: Rgba, b: Rgba) -> f32 {
+	let (a, b) = (luminance(a), luminance(b));
+	(a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+/// WCAG relative luminance of an sRGB color
+fn luminance(color: Rgba) -> f32 {
+	let linear = |channel: f32| {
+		if channel <= 0.04045 { channel / 12.92 } else { ((channel + 0.055) / 1.055).powf(2.4) }
+	};
+	0.2126 * linear(color.r) + 0.7152 * linear(color.g) + 0.0722 * linear(color.b)
+}
+
+/// Large text: 24px, or 18.66px (14pt) and bold
+fn is_large(style: &ElementStyle) -> bool {
+	let size = style.text_size.unwrap_or(14.0);
+	size >= 24.0 || (size >= 18.66 && style.font_weight.unwrap_or(400) >= 700)
+}
+
+fn hex(color: Rgba) -> String {
+	let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+	format!("#{:02x}{:02x}{:02x}", channel(color.r), channel(color.g), channel(color.b))
+}
@@ -0,0 +1,462 @@
+//! Central per-window mouse event dispatcher
+//!
+//! Interactive elements used to install their own mouse closures on every
+//! paint. Instead, each element now records a persistent entry (hitbox and
+//! handler flags, keyed by element id) in its window's handler table while it
+//! paints, and the root view installs a single set of window-level listeners
+//! per frame that walks those entries when an event arrives.
+//!
+//! Wheel events go to every element under the pointer, innermost first,
+//! until one whose `eventOptions` has `onWheel: { preventScroll: true }`
+//! consumes them: a zoomable canvas inside a scrolling document.
+//!
+//! Long presses are recognized here too: a left-button press on an element
+//! with `onLongPress` starts a timer, which is cancelled by releasing the
+//! button or moving the pointer past the drag distance. So are double clicks:
+//! a second left click within the double-click time and drag distance of the
+//! first sends `dblclick` (see input_settings).
+//!
+//! Hover and press state of elements with state styles is kept here as
+//! well: moves flip their hover state, a left press makes the innermost one
+//! under the pointer active until release, and either change refreshes the
+//! window so it renders the other variant (see style_variants).
+
+use std::{collections::HashMap, sync::{Mutex, atomic::{AtomicU64, Ordering}}, time::{Duration, Instant}};
+
+use gpui::{App, Bounds, DispatchPhase, Hitbox, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent, Pixels, Point, ScrollWheelEvent, Window};
+use lazy_static::lazy_static;
+
+use super::{checkbox, events::EventHandlerFlags, focus, hit_test, hover::get_hover_state, input_settings, style_variants};
+use crate::{event_types::{EventData, FocusEventData, MouseEventData, ScrollEventData, props, types}, global_state::GLOBAL_STATE, renderer::dispatch_event_to_js, sync::MutexExt};
+
+/// Handler entry for one interactive element, reused across frames
+struct HandlerEntry {
+	hitbox: Hitbox,
+	flags:  EventHandlerFlags,
+	/// Frame in which the element last painted
+	frame:  u64,
+}
+
+/// Handler table for a single window
+#[derive(Default)]
+struct WindowHandlers {
+	frame:   u64,
+	entries: HashMap<u64, HandlerEntry>,
+	/// Element ids recorded this frame, innermost elements first
+	order:   Vec<u64>,
+}
+
+/// How long the button must be held before `longpress` fires
+const LONG_PRESS_DELAY: Duration = Duration::from_millis(500);
+
+/// A press on an element with `onLongPress`, at most one per window
+struct Press {
+	element_id: u64,
+	origin:     Point<Pixels>,
+	bounds:     Bounds<Pixels>,
+	/// Identifies the press the timer was started for
+	serial:     u64,
+	fired:      bool,
+}
+
+static NEXT_PRESS: AtomicU64 = AtomicU64::new(1);
+
+/// The last left click of a window, which a second one can make a double click
+struct LastClick {
+	at:       Instant,
+	position: Point<Pixels>,
+}
+
+lazy_static! {
+	static ref HANDLERS: Mutex<HashMap<u64, WindowHandlers>> = Mutex::new(HashMap::new());
+	static ref PRESSES: Mutex<HashMap<u64, Press>> = Mutex::new(HashMap::new());
+	static ref LAST_CLICKS: Mutex<HashMap<u64, LastClick>> = Mutex::new(HashMap::new());
+}
+
+/// Drop all state of a closed window
+pub fn forget_window(window_id: u64) {
+	HANDLERS.lock_recover().remove(&window_id);
+	PRESSES.lock_recover().remove(&window_id);
+	LAST_CLICKS.lock_recover().remove(&window_id);
+}
+
+/// Start a new frame for a window
+/// Entries that weren't painted during the previous frame are dropped
+pub fn begin_frame(window_id: u64) {
+	let mut windows = HANDLERS.lock_recover();
+	let handlers = windows.entry(window_id).or_default();
+	let previous = handlers.frame;
+	handlers.entries.retain(|_, entry| entry.frame == previous);
+	handlers.frame += 1;
+	handlers.order.clear();
+}
+
+/// Record (or refresh) the handler entry for an element painted this frame
+pub fn record(window_id: u64, element_id: u64, hitbox: &Hitbox, flags: &EventHandlerFlags) {
+	let mut windows = HANDLERS.lock_recover();
+	let handlers = windows.entry(window_id).or_default();
+	let frame = handlers.frame;
+
+	match handlers.entries.get_mut(&element_id) {
+		Some(entry) => {
+			entry.hitbox = hitbox.clone();
+			entry.flags = *flags;
+			entry.frame = frame;
+		}
+		None => {
+			handlers.entries.insert(element_id, HandlerEntry {
+				hitbox: hitbox.clone(),
+				flags: *flags,
+				frame,
+			});
+		}
+	}
+	handlers.order.push(element_id);
+}
+
+/// Copy out the entries painted in the current frame, innermost first
+/// The table lock is released before any event is dispatched
+fn snapshot(window_id: u64) -> Vec<(u64, Hitbox, EventHandlerFlags)> {
+	let windows = HANDLERS.lock_recover();
+	let Some(handlers) = windows.get(&window_id) else {
+		return Vec::new();
+	};
+	handlers
+		.order
+		.iter()
+		.filter_map(|id| {
+			handlers
+				.entries
+				.get(id)
+				.filter(|entry| entry.frame == handlers.frame)
+				.map(|entry| (*id, entry.hitbox.clone(), entry.flags))
+		})
+		.collect()
+}
+
+/// Install the window-level mouse listeners for this frame
+/// Called once per frame by the root view, after the element tree painted
+pub fn install_window_listeners(window_id: u64, window: &mut Window) {
+	window.on_mouse_event(move |event: &MouseDownEvent, phase, window, cx| {
+		if phase != DispatchPhase::Bubble {
+			return;
+		}
+		let mut focus_handled = false;
+		let mut press_started = false;
+		let mut activated = false;
+		for (element_id, hitbox, flags) in snapshot(window_id) {
+			if !hitbox.is_hovered(window) {
+				continue;
+			}
+			// Only the innermost element with state styles becomes active
+			if flags.state_styles && !activated && event.button == MouseButton::Left {
+				activated = true;
+				if style_variants::set_active(window_id, Some(element_id)) {
+					window.refresh();
+				}
+			}
+			if flags.has_mouse_down {
+				log::debug!("[Rust] onMouseDown: window_id={}, element_id={}", window_id, element_id);
+				let data = mouse_data(event.position, hitbox.bounds, mouse_button_to_u8(event.button));
+				dispatch_event_to_js(window_id, element_id, types::MOUSEDOWN, data);
+			}
+			// Only the innermost long-press target under the mouse is tracked
+			if flags.has_long_press && !press_started && event.button == MouseButton::Left {
+				press_started = true;
+				start_long_press(window_id, element_id, event.position, hitbox.bounds, cx);
+			}
+			// Only the innermost focusable element under the mouse takes focus
+			if flags.is_focusable() && !focus_handled {
+				focus_handled = true;
+				focus_on_click(window_id, element_id, &flags);
+				// Focus rings are painted from the focus state
+				window.refresh();
+			}
+		}
+	});
+
+	window.on_mouse_event(move |event: &MouseUpEvent, phase, window, _cx| {
+		if phase != DispatchPhase::Bubble {
+			return;
+		}
+		let long_pressed = end_long_press(window_id);
+		if event.button == MouseButton::Left && style_variants::set_active(window_id, None) {
+			window.refresh();
+		}
+		let double_click =
+			event.button == MouseButton::Left && is_double_click(window_id, event.position);
+		let handlers = snapshot(window_id);
+		let mut received = Vec::new();
+		for (element_id, hitbox, flags) in &handlers {
+			let element_id = *element_id;
+			if !(flags.has_mouse_up || flags.has_click || flags.has_double_click || flags.toggles)
+				|| !hitbox.is_hovered(window)
+			{
+				continue;
+			}
+			let data = mouse_data(event.position, hitbox.bounds, mouse_button_to_u8(event.button));
+			if flags.has_mouse_up {
+				log::debug!("[Rust] onMouseUp: window_id={}, element_id={}", window_id, element_id);
+				dispatch_event_to_js(window_id, element_id, types::MOUSEUP, data.clone());
+				received.push((element_id, types::MOUSEUP));
+			}
+			// Click only fires for the left button, and not after a long press
+			if flags.has_click && event.button == MouseButton::Left && long_pressed != Some(element_id) {
+				log::info!("[Rust] onClick: window_id={}, element_id={}", window_id, element_id);
+				dispatch_event_to_js(window_id, element_id, types::CLICK, data.clone());
+				received.push((element_id, types::CLICK));
+			}
+			if flags.has_double_click && double_click && long_pressed != Some(element_id) {
+				log::info!("[Rust] onDoubleClick: window_id={}, element_id={}", window_id, element_id);
+				dispatch_event_to_js(window_id, element_id, types::DBLCLICK, data);
+				received.push((element_id, types::DBLCLICK));
+			}
+			// Checkboxes and radios toggle after the click, like native ones
+			if flags.toggles
+				&& event.button == MouseButton::Left
+				&& long_pressed != Some(element_id)
+				&& checkbox::activate(window_id, element_id)
+			{
+				window.refresh();
+			}
+		}
+
+		if hit_test::is_enabled(window_id) {
+			let button = mouse_button_to_u8(event.button);
+			hit_test::report(window_id, event.position, button, &handlers, &received, window);
+		}
+	});
+
+	window.on_mouse_event(move |event: &MouseMoveEvent, phase, window, _cx| {
+		if phase != DispatchPhase::Bubble {
+			return;
+		}
+		cancel_long_press_if_moved(window_id, event.position);
+		for (element_id, hitbox, flags) in snapshot(window_id) {
+			let is_hovered = hitbox.is_hovered(window);
+
+			if flags.has_mouse_move && is_hovered {
+				log::trace!("[Rust] onMouseMove: window_id={}, element_id={}", window_id, element_id);
+				let data = mouse_data(event.position, hitbox.bounds, 0);
+				dispatch_event_to_js(window_id, element_id, types::MOUSEMOVE, data);
+			}
+
+			if flags.has_mouse_enter || flags.has_mouse_leave {
+				update_hover(window_id, element_id, &flags, is_hovered, event.position, hitbox.bounds);
+			}
+
+			if flags.state_styles && style_variants::set_hovered(window_id, element_id, is_hovered) {
+				window.refresh();
+			}
+		}
+	});
+
+	window.on_mouse_event(move |event: &ScrollWheelEvent, phase, window, cx| {
+		if phase != DispatchPhase::Bubble {
+			return;
+		}
+		let (delta_x, delta_y, delta_mode): (f32, f32, u8) = match &event.delta {
+			gpui::ScrollDelta::Pixels(point) => (point.x.into(), point.y.into(), 0),
+			gpui::ScrollDelta::Lines(point) => (point.x, point.y, 1),
+		};
+		let (delta_x, delta_y) = input_settings::scroll_delta(delta_x, delta_y);
+
+		for (element_id, hitbox, flags) in snapshot(window_id) {
+			if !flags.has_any_scroll_handler() || !hitbox.is_hovered(window) {
+				continue;
+			}
+			let data = EventData::Scroll(ScrollEventData { delta_x, delta_y, delta_mode });
+			if flags.has_scroll {
+				log::debug!("[Rust] onScroll: window_id={}, element_id={}", window_id, element_id);
+				dispatch_event_to_js(window_id, element_id, types::SCROLL, data.clone());
+			}
+			if flags.has_wheel {
+				log::debug!("[Rust] onWheel: window_id={}, element_id={}", window_id, element_id);
+				dispatch_event_to_js(window_id, element_id, types::WHEEL, data);
+				// Listeners registered earlier (document scrolling) run after this one
+				if prevents_scroll(window_id, element_id) {
+					cx.stop_propagation();
+					break;
+				}
+			}
+		}
+	});
+}
+
+/// Whether an element consumes wheel events, set with
+/// `eventOptions: { onWheel: { preventScroll: true } }`
+fn prevents_scroll(window_id: u64, element_id: u64) -> bool {
+	let Some(window) = GLOBAL_STATE.get_window(window_id) else {
+		return false;
+	};
+	let element_map = window.state().element_map.lock_recover();
+	element_map
+		.get(&element_id)
+		.and_then(|element| {
+			element.style.event_options.as_ref()?.get(props::ON_WHEEL)?.get("preventScroll")?.as_bool()
+		})
+		.unwrap_or(false)
+}
+
+/// Track hover transitions and dispatch mouseenter/mouseleave
+fn update_hover(
+	window_id: u64,
+	element_id: u64,
+	flags: &EventHandlerFlags,
+	is_hovered: bool,
+	position: Point<Pixels>,
+	bounds: Bounds<Pixels>,
+) {
+	let mut state = get_hover_state().lock_recover();
+	let was_hovered = state.is_hovered(element_id);
+
+	if is_hovered && !was_hovered {
+		state.set_hovered(element_id);
+		if flags.has_mouse_enter {
+			log::debug!("[Rust] onMouseEnter: window_id={}, element_id={}", window_id, element_id);
+			dispatch_event_to_js(
+				window_id,
+				element_id,
+				types::MOUSEENTER,
+				mouse_data(position, bounds, 0),
+			);
+		}
+	} else if !is_hovered && was_hovered {
+		state.set_not_hovered(element_id);
+		if flags.has_mouse_leave {
+			log::debug!("[Rust] onMouseLeave: window_id={}, element_id={}", window_id, element_id);
+			dispatch_event_to_js(
+				window_id,
+				element_id,
+				types::MOUSELEAVE,
+				mouse_data(position, bounds, 0),
+			);
+		}
+	}
+}
+
+/// Remember a press and fire `longpress` if it is still held after the delay
+fn start_long_press(
+	window_id: u64,
+	element_id: u64,
+	origin: Point<Pixels>,
+	bounds: Bounds<Pixels>,
+	cx: &mut App,
+) {
+	let serial = NEXT_PRESS.fetch_add(1, Ordering::Relaxed);
+	PRESSES.lock_recover().insert(window_id, Press {
+		element_id,
+		origin,
+		bounds,
+		serial,
+		fired: false,
+	});
+
+	cx.spawn(async move |cx| {
+		cx.background_executor().timer(LONG_PRESS_DELAY).await;
+
+		let mut presses = PRESSES.lock_recover();
+		let Some(press) = presses.get_mut(&window_id).filter(|p| p.serial == serial && !p.fired) else {
+			return;
+		};
+		press.fired = true;
+		let (element_id, data) = (press.element_id, mouse_data(press.origin, press.bounds, 0));
+		drop(presses);
+
+		log::debug!("[Rust] onLongPress: window_id={}, element_id={}", window_id, element_id);
+		dispatch_event_to_js(window_id, element_id, types::LONGPRESS, data);
+	})
+	.detach();
+}
+
+/// Drop a pending long press once the pointer strays from where it went down
+fn cancel_long_press_if_moved(window_id: u64, position: Point<Pixels>) {
+	let mut presses = PRESSES.lock_recover();
+	let moved = presses.get(&window_id).is_some_and(|press| {
+		let dx: f32 = (position.x - press.origin.x).into();
+		let dy: f32 = (position.y - press.origin.y).into();
+		!press.fired && dx.hypot(dy) > input_settings::drag_distance()
+	});
+	if moved {
+		presses.remove(&window_id);
+	}
+}
+
+/// Record a left click, returning whether it completes a double click
+/// A third click starts over, so triple clicks don't fire `dblclick` twice
+fn is_double_click(window_id: u64, position: Point<Pixels>) -> bool {
+	let mut clicks = LAST_CLICKS.lock_recover();
+	let double = clicks.get(&window_id).is_some_and(|last| {
+		let dx: f32 = (position.x - last.position.x).into();
+		let dy: f32 = (position.y - last.position.y).into();
+		last.at.elapsed() <= input_settings::double_click_time()
+			&& dx.hypot(dy) <= input_settings::drag_distance()
+	});
+	if double {
+		clicks.remove(&window_id);
+	} else {
+		clicks.insert(window_id, LastClick { at: Instant::now(), position });
+	}
+	double
+}
+
+/// Finish the window's press on button release
+/// Returns the element that received `longpress`, so its click is suppressed
+fn end_long_press(window_id: u64) -> Option<u64> {
+	PRESSES
+		.lock_recover()
+		.remove(&window_id)
+		.filter(|press| press.fired)
+		.map(|press| press.element_id)
+}
+
+/// Move focus to a clicked focusable element and dispatch blur/focus
+fn focus_on_click(window_id: u64, element_id: u64, flags: &EventHandlerFlags) {
+	let (blur_id, focus_id) = focus::set_focus(window_id, element_id);
+
+	if let Some(blur_element_id) = blur_id
+		&& blur_element_id != element_id
+	{
+		log::debug!("[Rust] onBlur: window_id={}, element_id={}", window_id, blur_element_id);
+		dispatch_event_to_js(
+			window_id,
+			blur_element_id,
+			types::BLUR,
+			EventData::Focus(FocusEventData { related_target: Some(element_id) }),
+		);
+	}
+
+	if let Some(focus_element_id) = focus_id
+		&& flags.has_focus
+		&& blur_id != Some(element_id)
+	{
+		log::debug!("[Rust] onFocus: window_id={}, element_id={}", window_id, focus_element_id);
+		dispatch_event_to_js(
+			window_id,
+			focus_element_id,
+			types::FOCUS,
+			EventData::Focus(FocusEventData { related_target: blur_id }),
+		);
+	}
+}
+
+/// Build mouse event data relative to an element's bounds
+pub fn mouse_data(position: Point<Pixels>, bounds: Bounds<Pixels>, button: u8) -> EventData {
+	EventData::Mouse(MouseEventData {
+		client_x: position.x.into(),
+		client_y: position.y.into(),
+		offset_x: (position.x - bounds.origin.x).into(),
+		offset_y: (position.y - bounds.origin.y).into(),
+		button,
+	})
+}
+
+/// Convert GPUI MouseButton to u8 (0=left, 1=middle, 2=right)
+pub fn mouse_button_to_u8(button: MouseButton) -> u8 {
+	match button {
+		MouseButton::Left => 0,
+		MouseButton::Middle => 1,
+		MouseButton::Right => 2,
+		MouseButton::Navigate(_) => 3,
+	}
+}
@@ -1,8 +1,8 @@
 use std::sync::Arc;
 
-use gpui::{AnyElement, App, Bounds, Element, ElementId, GlobalElementId, Hitbox, InspectorElementId, IntoElement, LayoutId, Pixels, Window, div, prelude::*, px, rgb};
+use gpui::{AnyElement, App, Bounds, Element, ElementId, GlobalElementId, Hitbox, InspectorElementId, IntoElement, LayoutId, Pixels, Window, px, rgb};
 use crate::renderer::RootView;
-use super::{ElementStyle, ReactElement, events::{EventHandlerFlags, insert_hitbox_if_needed, register_event_handlers}};
+use super::{ElementStyle, ReactElement, border, content_visibility, events::{EventHandlerFlags, insert_hitbox_if_needed, register_event_handlers}, fonts, inert, layout, pixel_snap, style_variants, text_cache};
 
 /// A React element that implements GPUI's Element trait directly
 pub struct ReactDivElement {
@@ -15,6 +15,8 @@ pub struct ReactDivElement {
 /// State returned from request_layout, containing child layout IDs
 pub struct DivLayoutState {
 	child_layout_ids: Vec<LayoutId>,
+	/// Laid out as a `contentVisibility: "auto"` placeholder, without children
+	skipped:          bool,
 }
 
 /// State returned from prepaint
@@ -48,8 +50,26 @@ impl Element for ReactDivElement {
 		window: &mut Window,
 		cx: &mut App,
 	) -> (LayoutId, Self::RequestLayoutState) {
-		let style = self.element.build_gpui_style(None);
-		let inherited_style = self.element.effective_style(self.parent_style.as_ref());
+		let state = style_variants::current_state(self.window_id, &self.element);
+		let variant = state.and_then(|state| style_variants::variant_style(self.window_id, &self.element, state));
+		let mut style = variant.unwrap_or_else(|| self.element.build_gpui_style(None));
+
+		// Offscreen content-visibility subtrees keep their size but aren't built
+		if let Some(size) = content_visibility::placeholder_size(
+			self.window_id,
+			self.element.global_id,
+			&self.element.style,
+		) {
+			self.children.clear();
+			style.size.width = size.width.into();
+			style.size.height = size.height.into();
+			style.flex_shrink = 0.0;
+			let layout_id = window.request_layout(style, [], cx);
+			return (layout_id, DivLayoutState { child_layout_ids: Vec::new(), skipped: true });
+		}
+
+		let inherited_style =
+			style_variants::effective_style(self.window_id, &self.element, self.parent_style.as_ref());
 
 		// Build child elements with inherited style
 		self.children = self
@@ -62,16 +82,21 @@ impl Element for ReactDivElement {
 			})
 			.collect();
 
-		// If element has text content, add it as a child using GPUI's text element
+		// If element has text content, add it as a child, shaped through the text cache
 		if let Some(ref text) = self.element.text {
 			if !text.is_empty() {
 				// Use inherited text styles
-				let text_color = inherited_style.text_color.unwrap_or(0xffffff);
+				let text_color = inherited_style.text_color.unwrap_or_else(|| rgb(0xffffff).into());
 				let text_size = inherited_style.text_size.unwrap_or(14.0);
 
-				let text_element =
-					div().text_color(rgb(text_color)).text_size(px(text_size)).child(text.clone());
-				self.children.push(text_element.into_any_element());
+				let font = fonts::resolve(&inherited_style, window);
+				self.children.push(text_cache::text_element(
+					text.clone().into(),
+					font,
+					px(text_size),
+					text_color,
+					window,
+				));
 			}
 		}
 
@@ -82,7 +107,7 @@ impl Element for ReactDivElement {
 		// Request our own layout
 		let layout_id = window.request_layout(style, child_layout_ids.iter().copied(), cx);
 
-		(layout_id, DivLayoutState { child_layout_ids })
+		(layout_id, DivLayoutState { child_layout_ids, skipped: false })
 	}
 
 	fn prepaint(
@@ -90,20 +115,36 @@ impl Element for ReactDivElement {
 		_id: Option<&GlobalElementId>,
 		_inspector_id: Option<&InspectorElementId>,
 		bounds: Bounds<Pixels>,
-		_request_layout: &mut Self::RequestLayoutState,
+		request_layout: &mut Self::RequestLayoutState,
 		window: &mut Window,
 		cx: &mut App,
 	) -> Self::PrepaintState {
+		layout::record_bounds(self.window_id, self.element.global_id, bounds);
+
+		if content_visibility::is_auto(&self.element.style)
+			&& content_visibility::update(
+				self.window_id,
+				self.element.global_id,
+				bounds,
+				request_layout.skipped,
+				window,
+			) {
+			window.request_animation_frame();
+		}
+
 		// Prepaint children
 		for child in &mut self.children {
 			child.prepaint(window, cx);
 		}
 
 		// Check event handlers and insert hitbox if needed
-		let event_flags = EventHandlerFlags::from_handlers(
+		let mut event_flags = EventHandlerFlags::from_handlers(
 			self.element.event_handlers.as_ref(),
 			self.element.style.tab_index,
 		);
+		// The dispatcher tracks hover and presses of elements with state styles
+		event_flags.state_styles = style_variants::has_state_styles(&self.element.style)
+			&& !inert::is_inert(self.window_id, self.element.global_id);
 		let hitbox = insert_hitbox_if_needed(&event_flags, bounds, window);
 
 		DivPrepaintState { hitbox, event_flags }
@@ -119,7 +160,22 @@ impl Element for ReactDivElement {
 		window: &mut Window,
 		cx: &mut App,
 	) {
-		let style = self.element.build_gpui_style(None);
+		// Layout under a still pointer can move the edge too; the dispatcher
+		// only sees moves
+		if prepaint.event_flags.state_styles
+			&& let Some(hitbox) = &prepaint.hitbox
+			&& style_variants::set_hovered(self.window_id, self.element.global_id, hitbox.is_hovered(window))
+		{
+			window.refresh();
+		}
+		let state = style_variants::current_state(self.window_id, &self.element);
+		let variant = state.and_then(|state| style_variants::variant_style(self.window_id, &self.element, state));
+		let mut style = variant.unwrap_or_else(|| self.element.build_gpui_style(None));
+		let element_style =
+			state.and_then(|state| state.style(&self.element.style)).unwrap_or(&self.element.style);
+		let crisp = pixel_snap::is_crisp(element_style, self.parent_style.as_ref());
+		let (bounds, snapped) = pixel_snap::snap(crisp, bounds, &mut style, element_style, window);
+		let element_style = snapped.as_ref().unwrap_or(element_style);
 
 		// Paint background and children
 		style.paint(bounds, window, cx, |window, cx| {
@@ -133,6 +189,8 @@ impl Element for ReactDivElement {
 				|child, window, cx| child.paint(window, cx),
 			);
 		});
+		border::paint(element_style, bounds, window);
+		border::paint_outline(element_style, bounds, window);
 
 		// Register event handlers using shared module
 		register_event_handlers(
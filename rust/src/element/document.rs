@@ -0,0 +1,540 @@
+//! Read-only long text viewer ("document")
+//!
+//! Text is kept in a rope per element so only the lines inside the viewport
+//! are sliced, shaped and painted. The element scrolls itself with the wheel
+//! and supports find-in-page: `gpui_find` sets a query and highlights every
+//! match, `gpui_find_next` moves the current match and scrolls it into view.
+//!
+//! `textarea` elements and `multiLine` inputs are painted the same way. Where
+//! their style leaves the height or width auto they are `rows` lines tall (2
+//! by default) and `cols` advances of "0" wide (20 by default), as in HTML.
+//! Any of them paints a gutter of line numbers left of the text with
+//! `lineNumbers`. Textareas show their `value` but don't edit it: a caret,
+//! selection and key input need the editor core the input element doesn't
+//! have yet.
+
+use std::{collections::{HashMap, hash_map::{DefaultHasher, Entry}}, hash::{Hash, Hasher}, ops::Range, sync::{Arc, Mutex}};
+
+use gpui::{App, Bounds, ContentMask, DispatchPhase, Element, ElementId, Font, GlobalElementId, Hitbox, HitboxBehavior, Hsla, InspectorElementId, IntoElement, LayoutId, Length, Pixels, ScrollWheelEvent, Style, Window, fill, point, px, rgb, size};
+use lazy_static::lazy_static;
+use ropey::{LineType, Rope};
+
+use super::{ElementKind, ElementStyle, ReactElement, border, events::{EventHandlerFlags, register_event_handlers}, fonts, inert, input_settings, layout, pixel_snap, text_cache};
+use crate::{global_state::GLOBAL_STATE, sync::MutexExt};
+
+/// Visible lines of a textarea without `rows`, as in HTML
+const DEFAULT_ROWS: usize = 2;
+/// Width of a textarea without `cols`, in advances of "0"
+const DEFAULT_COLS: usize = 20;
+
+/// Result of a find operation, serialized back to JS
+pub struct FindResult {
+	pub count:   usize,
+	/// Index of the current match, if any
+	pub current: Option<usize>,
+}
+
+/// Per-element document state that survives across frames
+struct DocumentState {
+	/// Element the rope was built from, held so its identity can't be reused
+	source:         Arc<ReactElement>,
+	source_hash:    u64,
+	rope:           Rope,
+	scroll_top:     f32,
+	query:          String,
+	/// Byte ranges of all matches of `query`
+	matches:        Vec<Range<usize>>,
+	current:        Option<usize>,
+	/// Scroll the current match into view on the next paint
+	reveal_current: bool,
+}
+
+impl DocumentState {
+	fn new(element: &Arc<ReactElement>) -> Self {
+		let text = document_text(element);
+		Self {
+			source:         element.clone(),
+			source_hash:    hash_text(text),
+			rope:           Rope::from_str(text),
+			scroll_top:     0.0,
+			query:          String::new(),
+			matches:        Vec::new(),
+			current:        None,
+			reveal_current: false,
+		}
+	}
+
+	/// Rebuild the rope if the element's text changed, keeping scroll and query
+	fn sync(&mut self, element: &Arc<ReactElement>) {
+		if Arc::ptr_eq(&self.source, element) {
+			return;
+		}
+		let text = document_text(element);
+		let hash = hash_text(text);
+		if hash != self.source_hash {
+			self.rope = Rope::from_str(text);
+			self.source_hash = hash;
+			let query = std::mem::take(&mut self.query);
+			self.search(&query);
+		}
+		self.source = element.clone();
+	}
+
+	/// Recompute matches for a query (ASCII case-insensitive)
+	fn search(&mut self, query: &str) {
+		self.query = query.to_string();
+		self.matches.clear();
+		self.current = None;
+		if query.is_empty() {
+			return;
+		}
+
+		// ASCII lowercasing keeps byte offsets identical to the source
+		let haystack = self.rope.to_string().to_ascii_lowercase();
+		let needle = query.to_ascii_lowercase();
+		let mut start = 0;
+		while let Some(pos) = haystack[start..].find(&needle) {
+			let begin = start + pos;
+			self.matches.push(begin..begin + needle.len());
+			start = begin + needle.len();
+		}
+
+		if !self.matches.is_empty() {
+			self.current = Some(0);
+			self.reveal_current = true;
+		}
+	}
+
+	fn step(&mut self, forward: bool) {
+		let count = self.matches.len();
+		if count == 0 {
+			return;
+		}
+		self.current = Some(match self.current {
+			Some(i) if forward => (i + 1) % count,
+			Some(i) => (i + count - 1) % count,
+			None => 0,
+		});
+		self.reveal_current = true;
+	}
+
+	fn result(&self) -> FindResult { FindResult { count: self.matches.len(), current: self.current } }
+}
+
+fn document_text(element: &ReactElement) -> &str { element.style.value.as_deref().unwrap_or("") }
+
+fn hash_text(text: &str) -> u64 {
+	let mut hasher = DefaultHasher::new();
+	text.hash(&mut hasher);
+	hasher.finish()
+}
+
+lazy_static! {
+	/// Document state keyed by (window_id, element_id)
+	static ref DOCUMENTS: Mutex<HashMap<(u64, u64), DocumentState>> = Mutex::new(HashMap::new());
+}
+
+/// Drop all state of a closed window
+pub fn forget_window(window_id: u64) {
+	DOCUMENTS.lock_recover().retain(|(window, _), _| *window != window_id);
+}
+
+/// Drop the state of a released element
+pub fn forget_element(window_id: u64, element_id: u64) {
+	DOCUMENTS.lock_recover().remove(&(window_id, element_id));
+}
+
+/// Run `f` on a document's state, creating it from the element's current text
+/// if the document hasn't been painted yet
+fn with_document<R>(
+	window_id: u64,
+	element_id: u64,
+	f: impl FnOnce(&mut DocumentState) -> R,
+) -> Option<R> {
+	let mut documents = DOCUMENTS.lock_recover();
+	let document = match documents.entry((window_id, element_id)) {
+		Entry::Occupied(entry) => entry.into_mut(),
+		Entry::Vacant(entry) => {
+			let window = GLOBAL_STATE.get_window(window_id)?;
+			let element_map = window.state().element_map.lock_recover();
+			entry.insert(DocumentState::new(element_map.get(&element_id)?))
+		}
+	};
+	Some(f(document))
+}
+
+/// Set the search query for a document element
+pub fn find(window_id: u64, element_id: u64, query: &str) -> Option<FindResult> {
+	with_document(window_id, element_id, |doc| {
+		doc.search(query);
+		doc.result()
+	})
+}
+
+/// Move to the next (or previous) match of the current query
+pub fn find_step(window_id: u64, element_id: u64, forward: bool) -> Option<FindResult> {
+	with_document(window_id, element_id, |doc| {
+		doc.step(forward);
+		doc.result()
+	})
+}
+
+/// Current search state of a document, without changing it
+pub fn result(window_id: u64, element_id: u64) -> Option<FindResult> {
+	let documents = DOCUMENTS.lock_recover();
+	documents.get(&(window_id, element_id)).map(|doc| doc.result())
+}
+
+/// Ids of the document elements painted so far in a window
+pub fn element_ids(window_id: u64) -> Vec<u64> {
+	let documents = DOCUMENTS.lock_recover();
+	documents.keys().filter(|(w, _)| *w == window_id).map(|(_, id)| *id).collect()
+}
+
+/// Bounds inside the element's padding
+fn content_bounds(bounds: Bounds<Pixels>, style: &ElementStyle) -> Bounds<Pixels> {
+	let top = px(style.padding_top.unwrap_or(0.0));
+	let right = px(style.padding_right.unwrap_or(0.0));
+	let bottom = px(style.padding_bottom.unwrap_or(0.0));
+	let left = px(style.padding_left.unwrap_or(0.0));
+	Bounds {
+		origin: point(bounds.origin.x + left, bounds.origin.y + top),
+		size:   size(
+			(bounds.size.width - left - right).max(px(0.0)),
+			(bounds.size.height - top - bottom).max(px(0.0)),
+		),
+	}
+}
+
+/// Font and metrics the lines are shaped with
+struct TextMetrics {
+	text_size:   f32,
+	line_height: f32,
+	color:       Hsla,
+	font:        Font,
+}
+
+impl TextMetrics {
+	fn new(effective: &ElementStyle, window: &Window) -> Self {
+		let text_size = effective.text_size.unwrap_or(14.0);
+		Self {
+			text_size,
+			line_height: effective.line_height.unwrap_or(text_size * 1.4),
+			color: effective.text_color.unwrap_or_else(|| rgb(0xffffff).into()),
+			font: fonts::resolve(effective, window),
+		}
+	}
+
+	/// Advance of "0", the unit of `cols` and of gutter digits
+	fn char_width(&self, window: &Window) -> Pixels {
+		text_cache::shape_line(window, "0", &self.font, px(self.text_size), self.color).width
+	}
+}
+
+/// Width of the line-number gutter: the digits of the last line number (at
+/// least two) with an advance of space on either side
+fn gutter_width(line_count: usize, char_width: Pixels) -> Pixels {
+	let digits = line_count.max(1).ilog10() as usize + 1;
+	char_width * (digits.max(2) + 2) as f32
+}
+
+/// One visible line, ready to shape and paint
+struct VisibleLine {
+	index:      usize,
+	text:       String,
+	/// Highlighted byte ranges within the line, with "is current" flag
+	highlights: Vec<(Range<usize>, bool)>,
+}
+
+pub struct ReactDocumentElement {
+	element:      Arc<ReactElement>,
+	window_id:    u64,
+	parent_style: Option<ElementStyle>,
+}
+
+pub struct DocumentLayoutState {}
+
+pub struct DocumentPrepaintState {
+	hitbox:      Hitbox,
+	event_flags: EventHandlerFlags,
+}
+
+impl ReactDocumentElement {
+	pub fn new(
+		element: Arc<ReactElement>,
+		window_id: u64,
+		parent_style: Option<ElementStyle>,
+	) -> Self {
+		Self { element, window_id, parent_style }
+	}
+
+	/// This element's document state, in sync with its text
+	fn synced<'a>(&self, documents: &'a mut HashMap<(u64, u64), DocumentState>) -> &'a mut DocumentState {
+		let key = (self.window_id, self.element.global_id);
+		let doc = documents.entry(key).or_insert_with(|| DocumentState::new(&self.element));
+		doc.sync(&self.element);
+		doc
+	}
+
+	/// Whether this is a textarea, or an input painted as one
+	fn is_textarea(&self) -> bool {
+		match self.element.element_kind {
+			ElementKind::TextArea => true,
+			ElementKind::Input => self.element.style.multi_line == Some(true),
+			_ => false,
+		}
+	}
+
+	/// Size a textarea to `rows` lines of `cols` advances where its style
+	/// leaves the height or width auto
+	fn size_from_rows_and_cols(&self, style: &mut Style, window: &Window) {
+		let es = &self.element.style;
+		let metrics = TextMetrics::new(&self.element.effective_style(self.parent_style.as_ref()), window);
+		if style.size.height == Length::Auto {
+			let rows = es.rows.unwrap_or(DEFAULT_ROWS) as f32;
+			let padding = es.padding_top.unwrap_or(0.0) + es.padding_bottom.unwrap_or(0.0);
+			style.size.height = px(rows * metrics.line_height + padding).into();
+		}
+		if style.size.width == Length::Auto {
+			let char_width = metrics.char_width(window);
+			let gutter = if es.line_numbers == Some(true) {
+				let line_count = self.synced(&mut DOCUMENTS.lock_recover()).rope.len_lines(LineType::LF);
+				gutter_width(line_count, char_width)
+			} else {
+				px(0.0)
+			};
+			let padding = px(es.padding_left.unwrap_or(0.0) + es.padding_right.unwrap_or(0.0));
+			let cols = es.cols.unwrap_or(DEFAULT_COLS) as f32;
+			style.size.width = (char_width * cols + gutter + padding).into();
+		}
+	}
+
+	/// Collect the lines intersecting the viewport, clamping scroll first
+	/// Returns the lines, the scroll offset and the document's line count
+	fn visible_lines(&self, viewport_height: f32, line_height: f32) -> (Vec<VisibleLine>, f32, usize) {
+		let mut documents = DOCUMENTS.lock_recover();
+		let doc = self.synced(&mut documents);
+
+		let line_count = doc.rope.len_lines(LineType::LF);
+
+		// Bring the current match into the middle of the viewport
+		if doc.reveal_current {
+			doc.reveal_current = false;
+			if let Some(range) = doc.current.and_then(|i| doc.matches.get(i)) {
+				let line = doc.rope.byte_to_line_idx(range.start, LineType::LF);
+				doc.scroll_top = line as f32 * line_height - (viewport_height - line_height) / 2.0;
+			}
+		}
+		let max_scroll = (line_count as f32 * line_height - viewport_height).max(0.0);
+		doc.scroll_top = doc.scroll_top.clamp(0.0, max_scroll);
+
+		let first = (doc.scroll_top / line_height).floor() as usize;
+		let last = (((doc.scroll_top + viewport_height) / line_height).ceil() as usize).min(line_count);
+
+		let mut lines = Vec::with_capacity(last.saturating_sub(first));
+		for index in first..last {
+			let line_start = doc.rope.line_to_byte_idx(index, LineType::LF);
+			let text = doc.rope.line(index, LineType::LF).to_string();
+			let text = text.trim_end_matches(['\n', '\r']).to_string();
+			let line_end = line_start + text.len();
+
+			let highlights = doc
+				.matches
+				.iter()
+				.enumerate()
+				.filter(|(_, m)| m.start < line_end && m.end > line_start)
+				.map(|(i, m)| {
+					let start = m.start.max(line_start) - line_start;
+					let end = m.end.min(line_end) - line_start;
+					(start..end, doc.current == Some(i))
+				})
+				.collect();
+
+			lines.push(VisibleLine { index, text, highlights });
+		}
+
+		(lines, doc.scroll_top, line_count)
+	}
+}
+
+impl Element for ReactDocumentElement {
+	type PrepaintState = DocumentPrepaintState;
+	type RequestLayoutState = DocumentLayoutState;
+
+	fn id(&self) -> Option<ElementId> { Some(ElementId::Integer(self.element.global_id)) }
+
+	fn source_location(&self) -> Option<&'static std::panic::Location<'static>> { None }
+
+	fn request_layout(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		window: &mut Window,
+		cx: &mut App,
+	) -> (LayoutId, Self::RequestLayoutState) {
+		let mut style = self.element.build_gpui_style(None);
+		if self.is_textarea() {
+			self.size_from_rows_and_cols(&mut style, window);
+		}
+		let layout_id = window.request_layout(style, std::iter::empty(), cx);
+		(layout_id, DocumentLayoutState {})
+	}
+
+	fn prepaint(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		bounds: Bounds<Pixels>,
+		_request_layout: &mut Self::RequestLayoutState,
+		window: &mut Window,
+		_cx: &mut App,
+	) -> Self::PrepaintState {
+		layout::record_bounds(self.window_id, self.element.global_id, bounds);
+
+		let event_flags = EventHandlerFlags::from_handlers(
+			self.element.event_handlers.as_ref(),
+			self.element.style.tab_index,
+		);
+		// Always hit-testable: the document handles its own wheel scrolling
+		let hitbox = window.insert_hitbox(bounds, HitboxBehavior::Normal);
+		DocumentPrepaintState { hitbox, event_flags }
+	}
+
+	fn paint(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		bounds: Bounds<Pixels>,
+		_request_layout: &mut Self::RequestLayoutState,
+		prepaint: &mut Self::PrepaintState,
+		window: &mut Window,
+		cx: &mut App,
+	) {
+		let effective = self.element.effective_style(self.parent_style.as_ref());
+		let mut style = self.element.build_gpui_style(None);
+		let crisp = pixel_snap::is_crisp(&effective, None);
+		let (bounds, snapped) =
+			pixel_snap::snap(crisp, bounds, &mut style, &self.element.style, window);
+		let element_style = snapped.as_ref().unwrap_or(&self.element.style);
+
+		let metrics = TextMetrics::new(&effective, window);
+		let line_height = metrics.line_height;
+
+		let content = content_bounds(bounds, &self.element.style);
+		let viewport_height: f32 = content.size.height.into();
+		let (lines, scroll_top, line_count) = self.visible_lines(viewport_height, line_height);
+
+		// Line numbers are right-aligned an advance short of the text
+		let gutter = (self.element.style.line_numbers == Some(true)).then(|| {
+			let char_width = metrics.char_width(window);
+			(gutter_width(line_count, char_width), char_width)
+		});
+		let gutter_color = Hsla { a: metrics.color.a * 0.5, ..metrics.color };
+		let text_bounds = match gutter {
+			Some((width, _)) => Bounds {
+				origin: point(content.origin.x + width, content.origin.y),
+				size:   size((content.size.width - width).max(px(0.0)), content.size.height),
+			},
+			None => content,
+		};
+
+		let match_color = Hsla { h: 0.14, s: 0.9, l: 0.5, a: 0.35 };
+		let current_color = Hsla { h: 0.08, s: 0.95, l: 0.5, a: 0.7 };
+
+		style.paint(bounds, window, cx, |window, cx| {
+			window.with_content_mask(Some(ContentMask { bounds: content }), |window| {
+				for line in &lines {
+					let y = content.origin.y + px(line.index as f32 * line_height - scroll_top);
+
+					if let Some((width, char_width)) = gutter {
+						let number = text_cache::shape_line(
+							window,
+							(line.index + 1).to_string(),
+							&metrics.font,
+							px(metrics.text_size),
+							gutter_color,
+						);
+						let origin = point(content.origin.x + width - char_width - number.width, y);
+						if let Err(e) = number.paint(origin, px(line_height), window, cx) {
+							log::warn!("document: failed to paint line number {}: {}", line.index + 1, e);
+						}
+					}
+
+					let origin = point(text_bounds.origin.x, y);
+					let shaped = text_cache::shape_line(
+						window,
+						line.text.clone(),
+						&metrics.font,
+						px(metrics.text_size),
+						metrics.color,
+					);
+					window.with_content_mask(Some(ContentMask { bounds: text_bounds }), |window| {
+						for (range, is_current) in &line.highlights {
+							let x0 = shaped.x_for_index(range.start);
+							let x1 = shaped.x_for_index(range.end);
+							let highlight = Bounds {
+								origin: point(origin.x + x0, origin.y),
+								size:   size(x1 - x0, px(line_height)),
+							};
+							window.paint_quad(fill(
+								highlight,
+								if *is_current { current_color } else { match_color },
+							));
+						}
+
+						if let Err(e) = shaped.paint(origin, px(line_height), window, cx) {
+							log::warn!("document: failed to paint line {}: {}", line.index, e);
+						}
+					});
+				}
+			});
+		});
+		border::paint(element_style, bounds, window);
+		border::paint_outline(element_style, bounds, window);
+
+		// Wheel scrolling moves the document's own viewport
+		let hitbox = prepaint.hitbox.clone();
+		let key = (self.window_id, self.element.global_id);
+		window.on_mouse_event(move |event: &ScrollWheelEvent, phase, window, _cx| {
+			if phase == DispatchPhase::Bubble
+				&& hitbox.is_hovered(window)
+				&& !inert::is_inert(key.0, key.1)
+			{
+				let delta = event.delta.pixel_delta(px(line_height));
+				let (_, delta_y) = input_settings::scroll_delta(0.0, delta.y.into());
+				if let Some(doc) = DOCUMENTS.lock_recover().get_mut(&key) {
+					// Clamped against the content height on the next paint
+					doc.scroll_top -= delta_y;
+				}
+				window.refresh();
+			}
+		});
+
+		register_event_handlers(
+			&prepaint.event_flags,
+			Some(&prepaint.hitbox),
+			self.window_id,
+			self.element.global_id,
+			window,
+		);
+	}
+}
+
+impl IntoElement for ReactDocumentElement {
+	type Element = Self;
+
+	fn into_element(self) -> Self::Element { self }
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn gutter_fits_the_last_line_number() {
+		assert_eq!(gutter_width(0, px(6.0)), px(24.0));
+		assert_eq!(gutter_width(99, px(6.0)), px(24.0));
+		assert_eq!(gutter_width(100, px(6.0)), px(30.0));
+		assert_eq!(gutter_width(12_345, px(6.0)), px(42.0));
+	}
+}
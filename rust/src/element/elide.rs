@@ -0,0 +1,156 @@
+//! Single-line text eliding
+//!
+//! With `elideMode` ("start", "middle" or "end", inherited) a text element
+//! stays on one line and, when the line is wider than the element, gives up
+//! characters to a "…" until it fits: at its start, in its middle or at its
+//! end. Middle eliding keeps a file extension with the end of the text, so
+//! "very-long-file-name.rs" turns into "very-…-name.rs" and never loses
+//! its ".rs". Fits are measured on shaped lines (through the text cache), so
+//! they are exact for the element's font. The element lays out at the width
+//! of its whole line and may shrink to nothing, e.g. as a tab label or a
+//! breadcrumb in a row that runs out of room.
+
+use gpui::{Font, Hsla, Pixels, ShapedLine, Window};
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::{ElementStyle, text_cache};
+
+const ELLIPSIS: &str = "…";
+/// Longest extension kept whole, dot included; a longer tail after the last
+/// dot is more likely prose than an extension
+const MAX_EXTENSION: usize = 10;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ElideMode {
+	Start,
+	Middle,
+	End,
+}
+
+pub fn mode(style: &ElementStyle) -> Option<ElideMode> {
+	match style.elide_mode.as_deref() {
+		Some("start") => Some(ElideMode::Start),
+		Some("middle") => Some(ElideMode::Middle),
+		Some("end") => Some(ElideMode::End),
+		_ => None,
+	}
+}
+
+/// Height of a line of text at `font_size`, as gpui lays out text in a div
+pub fn line_height(font_size: Pixels, window: &Window) -> Pixels {
+	let mut text_style = window.text_style();
+	text_style.font_size = font_size.into();
+	text_style.line_height_in_pixels(window.rem_size())
+}
+
+/// `text` shaped on one line, elided to fit in `width` if it doesn't
+pub fn fit(
+	text: &str,
+	mode: ElideMode,
+	width: Pixels,
+	font: &Font,
+	font_size: Pixels,
+	color: Hsla,
+	window: &Window,
+) -> ShapedLine {
+	let shape = |text: String| text_cache::shape_line(window, text, font, font_size, color);
+	let line = shape(text.to_string());
+	if line.width <= width {
+		return line;
+	}
+
+	// Widths grow with the number of characters kept: find the most that fit
+	let graphemes: Vec<&str> = text.graphemes(true).collect();
+	let extension = extension_len(&graphemes);
+	let (mut fits, mut too_many) = (0, graphemes.len());
+	while too_many - fits > 1 {
+		let keep = (fits + too_many) / 2;
+		if shape(elide(&graphemes, mode, keep, extension)).width <= width {
+			fits = keep;
+		} else {
+			too_many = keep;
+		}
+	}
+	shape(elide(&graphemes, mode, fits, extension))
+}
+
+/// The text with all but `keep` characters replaced by the ellipsis
+fn elide(graphemes: &[&str], mode: ElideMode, keep: usize, extension: usize) -> String {
+	let (head, tail) = match mode {
+		ElideMode::Start => (0, keep),
+		ElideMode::End => (keep, 0),
+		// The extension goes to the tail, the rest is shared evenly
+		ElideMode::Middle => {
+			let tail = if keep > extension { extension + (keep - extension).div_ceil(2) } else { keep };
+			(keep - tail, tail)
+		}
+	};
+	let mut elided = graphemes[..head].concat();
+	elided.push_str(ELLIPSIS);
+	elided.push_str(&graphemes[graphemes.len() - tail..].concat());
+	elided
+}
+
+/// Characters of the file extension, dot included; 0 for none or for a
+/// leading dot, as in ".gitignore"
+fn extension_len(graphemes: &[&str]) -> usize {
+	match graphemes.iter().rposition(|g| *g == ".") {
+		Some(dot) if dot > 0 && graphemes.len() - dot <= MAX_EXTENSION => graphemes.len() - dot,
+		_ => 0,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use gpui::{TestAppContext, black, font, px};
+
+	use super::*;
+
+	/// `text` fitted in `width` by gpui's test text system, where every
+	/// character of a 10px font is 6px wide (12px outside the BMP)
+	fn fitted(cx: &mut TestAppContext, text: &str, mode: ElideMode, width: f32) -> String {
+		let cx = cx.add_empty_window();
+		cx.update(|window, _| {
+			let line = fit(text, mode, px(width), &font("Helvetica"), px(10.0), black(), window);
+			assert!(line.width <= px(width) || line.text.as_ref() == ELLIPSIS);
+			line.text.to_string()
+		})
+	}
+
+	#[gpui::test]
+	fn name_without_extension(cx: &mut TestAppContext) {
+		assert_eq!(fitted(cx, "abcdefghij", ElideMode::Middle, 36.0), "ab…hij");
+		assert_eq!(fitted(cx, "abcdefghij", ElideMode::Start, 36.0), "…fghij");
+		assert_eq!(fitted(cx, "abcdefghij", ElideMode::End, 36.0), "abcde…");
+		assert_eq!(fitted(cx, "abcdefghij", ElideMode::Middle, 60.0), "abcdefghij");
+	}
+
+	#[gpui::test]
+	fn dotfiles(cx: &mut TestAppContext) {
+		// The leading dot is not an extension
+		assert_eq!(extension_len(&".gitignore".graphemes(true).collect::<Vec<_>>()), 0);
+		assert_eq!(fitted(cx, ".gitignore", ElideMode::Middle, 36.0), ".g…ore");
+		// But a dotfile can still have one
+		assert_eq!(fitted(cx, ".eslintrc.json", ElideMode::Middle, 48.0), ".…c.json");
+	}
+
+	#[gpui::test]
+	fn extension_wider_than_the_width(cx: &mut TestAppContext) {
+		// Only the end of ".markdown" fits
+		assert_eq!(fitted(cx, "report.markdown", ElideMode::Middle, 30.0), "…down");
+		assert_eq!(fitted(cx, "report.markdown", ElideMode::Middle, 6.0), "…");
+		// No room even for the ellipsis
+		assert_eq!(fitted(cx, "report.markdown", ElideMode::Middle, 0.0), "…");
+	}
+
+	#[gpui::test]
+	fn multi_byte_characters_at_the_cut(cx: &mut TestAppContext) {
+		assert_eq!(fitted(cx, "héllo wörld", ElideMode::End, 30.0), "héll…");
+		assert_eq!(fitted(cx, "日本語のファイル名.txt", ElideMode::Middle, 60.0), "日本…イル名.txt");
+		// A combining accent goes with its letter
+		assert_eq!(fitted(cx, "cafe\u{301}s", ElideMode::End, 30.0), "caf…");
+		// As does the second half of a surrogate pair
+		assert_eq!(fitted(cx, "ab\u{1F600}cd", ElideMode::End, 30.0), "ab\u{1F600}…");
+		assert_eq!(fitted(cx, "ab\u{1F600}cd", ElideMode::End, 24.0), "ab…");
+	}
+}
@@ -0,0 +1,302 @@
+//! Built-in find-in-window overlay (Ctrl+F / Cmd+F)
+//!
+//! Enabled per window with the `findOverlay` window option. While the overlay
+//! is open, text elements record their text and bounds as they paint; the
+//! overlay (painted last, on top of the tree) searches that index, highlights
+//! every match and draws a small query box. Typing edits the query, Enter and
+//! Shift+Enter move between matches and Escape closes the overlay. Document
+//! elements receive the query too and scroll their own viewport. Results are
+//! reported to JS as a window-level `findresult` event.
+
+use std::{collections::{HashMap, HashSet}, sync::Mutex};
+
+use gpui::{App, Bounds, Corners, DispatchPhase, Font, FontWeight, Hsla, KeyDownEvent, Pixels, Window, fill, point, px, size};
+use lazy_static::lazy_static;
+
+use super::{document, inert, text_cache};
+use crate::{renderer::dispatch_window_event, sync::MutexExt};
+
+/// Text painted by one element this frame
+struct TextEntry {
+	element_id: u64,
+	text:       String,
+	bounds:     Bounds<Pixels>,
+	font_size:  f32,
+	font:       Font,
+}
+
+/// A single match, in window coordinates
+struct Match {
+	element_id: u64,
+	bounds:     Bounds<Pixels>,
+}
+
+#[derive(Default)]
+struct FindState {
+	open:           bool,
+	query:          String,
+	current:        usize,
+	/// Match count of the last search, for wrapping navigation
+	count:          usize,
+	/// Text elements painted this frame, in paint order
+	index:          Vec<TextEntry>,
+	/// Send a `findresult` event after the next search
+	report_pending: bool,
+}
+
+lazy_static! {
+	/// Windows created with `findOverlay: true`
+	static ref ENABLED: Mutex<HashSet<u64>> = Mutex::new(HashSet::new());
+	static ref FIND: Mutex<HashMap<u64, FindState>> = Mutex::new(HashMap::new());
+}
+
+/// Drop all state of a closed window
+pub fn forget_window(window_id: u64) {
+	ENABLED.lock_recover().remove(&window_id);
+	FIND.lock_recover().remove(&window_id);
+}
+
+/// Enable the overlay for a window
+pub fn enable(window_id: u64) { ENABLED.lock_recover().insert(window_id); }
+
+pub fn is_enabled(window_id: u64) -> bool { ENABLED.lock_recover().contains(&window_id) }
+
+/// Whether the overlay is currently showing (and indexing text)
+pub fn is_open(window_id: u64) -> bool {
+	FIND.lock_recover().get(&window_id).is_some_and(|state| state.open)
+}
+
+/// Clear the text index before the element tree paints
+pub fn begin_frame(window_id: u64) {
+	if let Some(state) = FIND.lock_recover().get_mut(&window_id) {
+		state.index.clear();
+	}
+}
+
+/// Record a painted text element
+pub fn record_text(
+	window_id: u64,
+	element_id: u64,
+	text: &str,
+	bounds: Bounds<Pixels>,
+	font_size: f32,
+	font: Font,
+) {
+	// Like browsers, find-in-page skips inert content
+	if inert::is_inert(window_id, element_id) {
+		return;
+	}
+	let mut find = FIND.lock_recover();
+	let Some(state) = find.get_mut(&window_id) else {
+		return;
+	};
+	if !text.is_empty() {
+		state.index.push(TextEntry { element_id, text: text.to_string(), bounds, font_size, font });
+	}
+}
+
+/// Handle a key press. Returns true if the overlay consumed it.
+fn handle_key(window_id: u64, event: &KeyDownEvent) -> bool {
+	let keystroke = &event.keystroke;
+	let mut find = FIND.lock_recover();
+	let state = find.entry(window_id).or_default();
+
+	if keystroke.key == "f" && keystroke.modifiers.secondary() {
+		state.open = true;
+		state.report_pending = true;
+		return true;
+	}
+	if !state.open {
+		return false;
+	}
+
+	match keystroke.key.as_str() {
+		"escape" => {
+			state.open = false;
+			state.query.clear();
+			state.index.clear();
+			update_documents(window_id, "");
+		}
+		"enter" => {
+			let forward = !keystroke.modifiers.shift;
+			if state.count > 0 {
+				state.current = if forward {
+					(state.current + 1) % state.count
+				} else {
+					(state.current + state.count - 1) % state.count
+				};
+			}
+			for element_id in document::element_ids(window_id) {
+				document::find_step(window_id, element_id, forward);
+			}
+		}
+		"backspace" => {
+			state.query.pop();
+			state.current = 0;
+			update_documents(window_id, &state.query);
+		}
+		_ => {
+			// Keys the overlay doesn't handle, shortcuts included, reach the app
+			let Some(ch) = keystroke.key_char.as_ref() else {
+				return false;
+			};
+			if keystroke.modifiers.control || keystroke.modifiers.platform {
+				return false;
+			}
+			state.query.push_str(ch);
+			state.current = 0;
+			update_documents(window_id, &state.query);
+		}
+	}
+	state.report_pending = true;
+	true
+}
+
+/// Forward the query to every document element in the window
+fn update_documents(window_id: u64, query: &str) {
+	for element_id in document::element_ids(window_id) {
+		document::find(window_id, element_id, query);
+	}
+}
+
+/// Find all matches of `query` in the indexed text
+fn search(index: &[TextEntry], query: &str, window: &Window) -> Vec<Match> {
+	let mut matches = Vec::new();
+	if query.is_empty() {
+		return matches;
+	}
+	let needle = query.to_ascii_lowercase();
+
+	for entry in index {
+		let haystack = entry.text.to_ascii_lowercase();
+		let mut ranges = Vec::new();
+		let mut start = 0;
+		while let Some(pos) = haystack[start..].find(&needle) {
+			ranges.push(start + pos..start + pos + needle.len());
+			start += pos + needle.len();
+		}
+		if ranges.is_empty() {
+			continue;
+		}
+
+		// Wrapped text is measured as a single line and clamped to the element
+		let line = text_cache::shape_line(
+			window,
+			entry.text.clone(),
+			&entry.font,
+			px(entry.font_size),
+			Hsla::default(),
+		);
+		let line_height = px(entry.font_size * 1.25).min(entry.bounds.size.height);
+		for range in ranges {
+			let x0 = line.x_for_index(range.start).min(entry.bounds.size.width);
+			let x1 = line.x_for_index(range.end).min(entry.bounds.size.width);
+			matches.push(Match {
+				element_id: entry.element_id,
+				bounds:     Bounds {
+					origin: point(entry.bounds.origin.x + x0, entry.bounds.origin.y),
+					size:   size(x1 - x0, line_height),
+				},
+			});
+		}
+	}
+	matches
+}
+
+/// Paint highlights and the query box, and install the key listener
+/// Called once per frame by the root view, after the element tree painted
+pub fn paint(window_id: u64, window: &mut Window, cx: &mut App) {
+	window.on_key_event(move |event: &KeyDownEvent, phase, window, cx| {
+		if phase == DispatchPhase::Capture && handle_key(window_id, event) {
+			cx.stop_propagation();
+			window.refresh();
+		}
+	});
+
+	let mut find = FIND.lock_recover();
+	let Some(state) = find.get_mut(&window_id) else {
+		return;
+	};
+
+	let matches = if state.open { search(&state.index, &state.query, window) } else { Vec::new() };
+	state.count = matches.len();
+	if state.current >= state.count {
+		state.current = 0;
+	}
+
+	if state.open {
+		let match_color = Hsla { h: 0.14, s: 0.9, l: 0.5, a: 0.35 };
+		let current_color = Hsla { h: 0.08, s: 0.95, l: 0.5, a: 0.6 };
+		for (i, m) in matches.iter().enumerate() {
+			let color = if i == state.current { current_color } else { match_color };
+			window.paint_quad(fill(m.bounds, color));
+		}
+		paint_query_box(state, matches.len(), window, cx);
+	}
+
+	if state.report_pending {
+		state.report_pending = false;
+		let positions: Vec<serde_json::Value> = matches
+			.iter()
+			.map(|m| {
+				serde_json::json!({
+					"elementId": m.element_id,
+					"x": f32::from(m.bounds.origin.x),
+					"y": f32::from(m.bounds.origin.y),
+					"width": f32::from(m.bounds.size.width),
+					"height": f32::from(m.bounds.size.height),
+				})
+			})
+			.collect();
+		let documents: Vec<serde_json::Value> = document::element_ids(window_id)
+			.into_iter()
+			.filter_map(|id| {
+				let found = document::result(window_id, id)?;
+				Some(serde_json::json!({ "elementId": id, "count": found.count, "current": found.current }))
+			})
+			.collect();
+		dispatch_window_event(
+			window_id,
+			"findresult",
+			serde_json::json!({
+				"open": state.open,
+				"query": state.query,
+				"count": matches.len(),
+				"current": if matches.is_empty() { None } else { Some(state.current) },
+				"matches": positions,
+				"documents": documents,
+			}),
+		);
+	}
+}
+
+/// Draw the query box in the top-right corner of the window
+fn paint_query_box(state: &FindState, count: usize, window: &mut Window, cx: &mut App) {
+	let font_size = px(13.0);
+	let label = if state.query.is_empty() {
+		"Find…".to_string()
+	} else if count == 0 {
+		format!("{}   no results", state.query)
+	} else {
+		format!("{}   {}/{}", state.query, state.current + 1, count)
+	};
+
+	let mut font = window.text_style().font();
+	font.weight = FontWeight::NORMAL;
+	let text_color = Hsla { h: 0.0, s: 0.0, l: 0.92, a: 1.0 };
+	let line = text_cache::shape_line(window, label, &font, font_size, text_color);
+
+	let viewport = window.viewport_size();
+	let width = (line.width + px(24.0)).max(px(200.0));
+	let height = px(30.0);
+	let origin = point(viewport.width - width - px(12.0), px(12.0));
+	let background = Bounds { origin, size: size(width, height) };
+
+	window.paint_quad(
+		fill(background, Hsla { h: 0.0, s: 0.0, l: 0.12, a: 0.95 }).corner_radii(Corners::all(px(6.0))),
+	);
+	let text_origin = point(origin.x + px(12.0), origin.y + (height - font_size * 1.25) / 2.0);
+	if let Err(e) = line.paint(text_origin, font_size * 1.25, window, cx) {
+		log::warn!("find overlay: failed to paint query: {}", e);
+	}
+}
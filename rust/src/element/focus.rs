@@ -7,10 +7,18 @@
 //! Note: This is a simplified implementation. GPUI has a more sophisticated
 //! focus system with FocusHandle, but integrating it with custom Element
 //! implementations requires a different approach.
+//!
+//! The elements of an overlay form a focus group, the overlay's id; the main
+//! tree's have none. `snapshot` reports groups with the Tab order, for JS focus
+//! utilities and tests.
 
 use std::{collections::HashMap, sync::{Arc, Mutex}};
 
 use lazy_static::lazy_static;
+use serde_json::{Value, json};
+
+use super::{ElementKind, ReactElement};
+use crate::sync::MutexExt;
 
 /// Focus state for a single window
 pub struct WindowFocusState {
@@ -52,21 +60,19 @@ impl WindowFocusState {
 	/// Unregister an element from tab order
 	pub fn unregister_tab_index(&mut self, element_id: u64) { self.tab_order.remove(&element_id); }
 
-	/// Get the next focusable element in tab order (Tab key navigation)
-	pub fn get_next_focusable(&self) -> Option<u64> {
-		if self.tab_order.is_empty() {
-			return None;
-		}
-
-		// Get all elements sorted by tab index, then by element_id for stable ordering
-		let mut sorted: Vec<_> = self
-            .tab_order
-            .iter()
-            .filter(|(_, idx)| **idx >= 0) // Only positive tab indices participate in tab navigation
-            .collect();
+	/// Elements that take part in Tab navigation, in Tab order
+	pub fn tab_sequence(&self) -> Vec<u64> {
+		// Only positive tab indices participate in tab navigation
+		let mut sorted: Vec<(u64, i32)> =
+			self.tab_order.iter().filter(|(_, idx)| **idx >= 0).map(|(id, idx)| (*id, *idx)).collect();
 		// Sort by (tab_index, element_id) for stable ordering
 		sorted.sort_by(|(id_a, idx_a), (id_b, idx_b)| idx_a.cmp(idx_b).then_with(|| id_a.cmp(id_b)));
+		sorted.into_iter().map(|(id, _)| id).collect()
+	}
 
+	/// Get the next focusable element in tab order (Tab key navigation)
+	pub fn get_next_focusable(&self) -> Option<u64> {
+		let sorted = self.tab_sequence();
 		if sorted.is_empty() {
 			return None;
 		}
@@ -74,52 +80,45 @@ impl WindowFocusState {
 		match self.focused_element {
 			Some(current_id) => {
 				// Find current element's position
-				let current_pos = sorted.iter().position(|(id, _)| **id == current_id);
+				let current_pos = sorted.iter().position(|id| *id == current_id);
 				match current_pos {
 					Some(pos) => {
 						// Move to next element, wrap around
 						let next_pos = (pos + 1) % sorted.len();
-						Some(*sorted[next_pos].0)
+						Some(sorted[next_pos])
 					}
 					None => {
 						// Current element not in tab order, start from beginning
-						Some(*sorted[0].0)
+						Some(sorted[0])
 					}
 				}
 			}
 			None => {
 				// No current focus, start from first element
-				Some(*sorted[0].0)
+				Some(sorted[0])
 			}
 		}
 	}
 
 	/// Get the previous focusable element in tab order (Shift+Tab navigation)
 	pub fn get_prev_focusable(&self) -> Option<u64> {
-		if self.tab_order.is_empty() {
-			return None;
-		}
-
-		let mut sorted: Vec<_> = self.tab_order.iter().filter(|(_, idx)| **idx >= 0).collect();
-		// Sort by (tab_index, element_id) for stable ordering
-		sorted.sort_by(|(id_a, idx_a), (id_b, idx_b)| idx_a.cmp(idx_b).then_with(|| id_a.cmp(id_b)));
-
+		let sorted = self.tab_sequence();
 		if sorted.is_empty() {
 			return None;
 		}
 
 		match self.focused_element {
 			Some(current_id) => {
-				let current_pos = sorted.iter().position(|(id, _)| **id == current_id);
+				let current_pos = sorted.iter().position(|id| *id == current_id);
 				match current_pos {
 					Some(pos) => {
 						let prev_pos = if pos == 0 { sorted.len() - 1 } else { pos - 1 };
-						Some(*sorted[prev_pos].0)
+						Some(sorted[prev_pos])
 					}
-					None => Some(*sorted[sorted.len() - 1].0),
+					None => Some(sorted[sorted.len() - 1]),
 				}
 			}
-			None => Some(*sorted[sorted.len() - 1].0),
+			None => Some(sorted[sorted.len() - 1]),
 		}
 	}
 
@@ -170,69 +169,62 @@ pub fn get_focus_manager() -> &'static Arc<Mutex<FocusManager>> { &FOCUS_MANAGER
 /// Set focus to an element. Returns (blur_element_id, focus_element_id) for
 /// event dispatch.
 pub fn set_focus(window_id: u64, element_id: u64) -> (Option<u64>, Option<u64>) {
-	if let Ok(mut manager) = FOCUS_MANAGER.lock() {
-		let state = manager.get_window_state(window_id);
-		state.set_focus(element_id)
-	} else {
-		(None, None)
-	}
+	let mut manager = FOCUS_MANAGER.lock_recover();
+	let state = manager.get_window_state(window_id);
+	state.set_focus(element_id)
 }
 
 /// Clear focus for a window. Returns the previously focused element (if any).
 pub fn clear_focus(window_id: u64) -> Option<u64> {
-	if let Ok(mut manager) = FOCUS_MANAGER.lock() {
-		let state = manager.get_window_state(window_id);
-		state.clear_focus()
-	} else {
-		None
-	}
+	let mut manager = FOCUS_MANAGER.lock_recover();
+	let state = manager.get_window_state(window_id);
+	state.clear_focus()
 }
 
 /// Check if a specific element is focused
 pub fn is_focused(window_id: u64, element_id: u64) -> bool {
-	if let Ok(mut manager) = FOCUS_MANAGER.lock() {
-		let state = manager.get_window_state(window_id);
-		state.is_focused(element_id)
-	} else {
-		false
+	let mut manager = FOCUS_MANAGER.lock_recover();
+	let state = manager.get_window_state(window_id);
+	state.is_focused(element_id)
+}
+
+/// Forget a released element: unfocus it and drop its tab index
+pub fn forget_element(window_id: u64, element_id: u64) {
+	let mut manager = FOCUS_MANAGER.lock_recover();
+	let state = manager.get_window_state(window_id);
+	if state.is_focused(element_id) {
+		state.clear_focus();
 	}
+	state.unregister_tab_index(element_id);
 }
 
 /// Get the currently focused element for a window
 pub fn get_focused(window_id: u64) -> Option<u64> {
-	if let Ok(mut manager) = FOCUS_MANAGER.lock() {
-		let state = manager.get_window_state(window_id);
-		state.get_focused()
-	} else {
-		None
-	}
+	let mut manager = FOCUS_MANAGER.lock_recover();
+	let state = manager.get_window_state(window_id);
+	state.get_focused()
 }
 
 /// Register an element's tab index
 pub fn register_tab_index(window_id: u64, element_id: u64, tab_index: i32) {
-	if let Ok(mut manager) = FOCUS_MANAGER.lock() {
-		let state = manager.get_window_state(window_id);
-		state.register_tab_index(element_id, tab_index);
-	}
+	let mut manager = FOCUS_MANAGER.lock_recover();
+	let state = manager.get_window_state(window_id);
+	state.register_tab_index(element_id, tab_index);
 }
 
 /// Unregister an element from tab order
 pub fn unregister_tab_index(window_id: u64, element_id: u64) {
-	if let Ok(mut manager) = FOCUS_MANAGER.lock() {
-		let state = manager.get_window_state(window_id);
-		state.unregister_tab_index(element_id);
-	}
+	let mut manager = FOCUS_MANAGER.lock_recover();
+	let state = manager.get_window_state(window_id);
+	state.unregister_tab_index(element_id);
 }
 
 /// Focus the next element in tab order
 pub fn focus_next(window_id: u64) -> (Option<u64>, Option<u64>) {
-	if let Ok(mut manager) = FOCUS_MANAGER.lock() {
-		let state = manager.get_window_state(window_id);
-		if let Some(next_id) = state.get_next_focusable() {
-			state.set_focus(next_id)
-		} else {
-			(None, None)
-		}
+	let mut manager = FOCUS_MANAGER.lock_recover();
+	let state = manager.get_window_state(window_id);
+	if let Some(next_id) = state.get_next_focusable() {
+		state.set_focus(next_id)
 	} else {
 		(None, None)
 	}
@@ -240,14 +232,63 @@ pub fn focus_next(window_id: u64) -> (Option<u64>, Option<u64>) {
 
 /// Focus the previous element in tab order
 pub fn focus_prev(window_id: u64) -> (Option<u64>, Option<u64>) {
-	if let Ok(mut manager) = FOCUS_MANAGER.lock() {
-		let state = manager.get_window_state(window_id);
-		if let Some(prev_id) = state.get_prev_focusable() {
-			state.set_focus(prev_id)
-		} else {
-			(None, None)
-		}
+	let mut manager = FOCUS_MANAGER.lock_recover();
+	let state = manager.get_window_state(window_id);
+	if let Some(prev_id) = state.get_prev_focusable() {
+		state.set_focus(prev_id)
 	} else {
 		(None, None)
 	}
 }
+
+/// Focus state of a window, as of its last painted frame: the focused
+/// element, the Tab order, and the tab index and focus group of every element
+/// with a tab index, in Tab order then (for negative indices) by id
+pub fn snapshot(window_id: u64, root: &ReactElement) -> Value {
+	let mut tree = HashMap::new();
+	collect_groups(root, None, &mut tree);
+
+	let mut manager = FOCUS_MANAGER.lock_recover();
+	let state = manager.get_window_state(window_id);
+	let tab_order = state.tab_sequence();
+	let mut programmatic: Vec<u64> =
+		state.tab_order.iter().filter(|(_, idx)| **idx < 0).map(|(id, _)| *id).collect();
+	programmatic.sort_unstable();
+
+	let elements: Vec<Value> = tab_order
+		.iter()
+		.chain(&programmatic)
+		.map(|id| {
+			let (element_type, group) = match tree.get(id) {
+				Some((element_type, group)) => (Some(*element_type), *group),
+				None => (None, None),
+			};
+			json!({
+				"id": id,
+				"type": element_type,
+				"tabIndex": state.tab_order.get(id),
+				"group": group,
+			})
+		})
+		.collect();
+	json!({
+		"windowId": window_id,
+		"focused": state.get_focused(),
+		"tabOrder": tab_order,
+		"elements": elements,
+	})
+}
+
+/// Map every element of the tree to its type and enclosing overlay
+fn collect_groups<'a>(
+	element: &'a ReactElement,
+	group: Option<u64>,
+	tree: &mut HashMap<u64, (&'a str, Option<u64>)>,
+) {
+	tree.insert(element.global_id, (element.element_type.as_str(), group));
+	let group =
+		if element.element_kind == ElementKind::Overlay { Some(element.global_id) } else { group };
+	for child in &element.children {
+		collect_groups(child, group, tree);
+	}
+}
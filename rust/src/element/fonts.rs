@@ -0,0 +1,88 @@
+//! Font resolution shared by every element that renders text
+//!
+//! `fontFamily` takes a CSS-style list ("Inter, Noto Sans CJK JP, Noto Color
+//! Emoji"): the first family is used and the rest are tried in order for
+//! glyphs it lacks. The app-wide chain set with `gpui_set_font_fallbacks` is
+//! appended to every list, so mixed-script text finds a CJK or emoji font
+//! without each element naming one.
+//!
+//! `fontFeatureSettings` uses the CSS syntax too (`"liga" 0, "tnum"`) and
+//! becomes the font's OpenType features, so code fonts can turn ligatures off
+//! and tables can use tabular numbers.
+
+use std::sync::{Arc, RwLock};
+
+use gpui::{Font, FontFallbacks, FontFeatures, FontWeight, Window};
+use lazy_static::lazy_static;
+
+use super::ElementStyle;
+use crate::sync::RwLockExt;
+
+lazy_static! {
+	static ref DEFAULT_FALLBACKS: RwLock<Vec<String>> = RwLock::new(Vec::new());
+}
+
+/// Replace the fallback chain appended to every element's font
+pub fn set_default_fallbacks(families: Vec<String>) {
+	*DEFAULT_FALLBACKS.write_recover() = families;
+}
+
+/// Font for an element's text, starting from the window's text style
+pub fn resolve(style: &ElementStyle, window: &Window) -> Font {
+	let mut font = window.text_style().font();
+	let mut families = style.font_family.as_deref().map(parse_families).unwrap_or_default();
+	if !families.is_empty() {
+		font.family = families.remove(0).into();
+	}
+	if let Some(weight) = style.font_weight {
+		font.weight = FontWeight(weight as f32);
+	}
+	if let Some(settings) = style.font_feature_settings.as_deref() {
+		font.features = FontFeatures(Arc::new(parse_features(settings)));
+	}
+
+	for family in DEFAULT_FALLBACKS.read_recover().iter() {
+		if !families.contains(family) && *family != font.family.as_ref() {
+			families.push(family.clone());
+		}
+	}
+	if !families.is_empty() {
+		font.fallbacks = Some(FontFallbacks::from_fonts(families));
+	}
+	font
+}
+
+/// Split a CSS font-family list, dropping quotes around names
+fn parse_families(list: &str) -> Vec<String> {
+	list
+		.split(',')
+		.map(|family| family.trim().trim_matches(|c| c == '"' || c == '\'').trim())
+		.filter(|family| !family.is_empty())
+		.map(str::to_string)
+		.collect()
+}
+
+/// Parse CSS font-feature-settings into (tag, value) pairs
+/// A tag alone or with `on` is 1, `off` is 0; "normal" and malformed entries
+/// are skipped
+fn parse_features(settings: &str) -> Vec<(String, u32)> {
+	settings
+		.split(',')
+		.filter_map(|entry| {
+			let entry = entry.trim();
+			let quote = entry.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+			let rest = &entry[1..];
+			let end = rest.find(quote)?;
+			let tag = &rest[..end];
+			if tag.len() != 4 || !tag.is_ascii() {
+				return None;
+			}
+			let value = match rest[end + 1..].trim() {
+				"" | "on" => 1,
+				"off" => 0,
+				number => number.parse().ok()?,
+			};
+			Some((tag.to_string(), value))
+		})
+		.collect()
+}
@@ -0,0 +1,272 @@
+//! Gauge element
+//!
+//! Paints `value` between `min` and `max` (default 0 and 100) as an arc: the
+//! track in the background color and the part up to the value in
+//! `accentColor`. The arc sweeps `sweep` degrees clockwise, 270 by default and
+//! centered on the bottom; 360 makes a circular progress ring. `ticks` adds
+//! that many evenly spaced tick marks from end to end, and `needle` a needle
+//! pointing at the value.
+//!
+//! When `value` changes the arc and needle ease to the new value in Rust, so
+//! React renders once per value rather than once per frame.
+
+use std::{collections::HashMap, f32::consts::PI, sync::{Arc, Mutex}};
+
+use gpui::{App, Bounds, Corners, Element, ElementId, GlobalElementId, Hitbox, Hsla, InspectorElementId, IntoElement, LayoutId, PathBuilder, Pixels, Point, Window, fill, point, px, rgb, size};
+use lazy_static::lazy_static;
+
+use super::{ElementStyle, ReactElement, border, events::{EventHandlerFlags, insert_hitbox_if_needed, register_event_handlers}, layout, pacing, pixel_snap};
+use crate::{clock, sync::MutexExt};
+
+/// Size of the gauge when the style has none
+const DEFAULT_SIZE: f32 = 96.0;
+/// Arc and needle when the style has no `accentColor`
+const DEFAULT_ACCENT: u32 = 0x3b82f6;
+const DEFAULT_SWEEP: f32 = 270.0;
+/// Time to ease to a new value
+const TRANSITION_US: u64 = 300_000;
+/// Longest straight segment of an arc, in degrees
+const SEGMENT_DEGREES: f32 = 3.0;
+
+/// Value being shown, easing from `from` to `to`
+struct Shown {
+	from:    f64,
+	to:      f64,
+	started: u64,
+}
+
+lazy_static! {
+	static ref SHOWN: Mutex<HashMap<(u64, u64), Shown>> = Mutex::new(HashMap::new());
+}
+
+/// Drop all state of a closed window
+pub fn forget_window(window_id: u64) { SHOWN.lock_recover().retain(|(id, _), _| *id != window_id); }
+
+/// Drop the shown value of a released element
+pub fn forget_element(window_id: u64, element_id: u64) {
+	SHOWN.lock_recover().remove(&(window_id, element_id));
+}
+
+/// Value to paint now for a gauge whose value is `target`, and whether it's
+/// still easing towards it
+fn shown_value(window_id: u64, element_id: u64, target: f64) -> (f64, bool) {
+	let now = clock::now_us();
+	let mut values = SHOWN.lock_recover();
+	let shown = values.entry((window_id, element_id)).or_insert(Shown {
+		from:    target,
+		to:      target,
+		started: 0,
+	});
+	let at = |shown: &Shown| {
+		let t = (now.saturating_sub(shown.started) as f64 / TRANSITION_US as f64).min(1.0);
+		// Ease out: fast at first, settling on the value
+		let eased = 1.0 - (1.0 - t).powi(3);
+		(shown.from + (shown.to - shown.from) * eased, t < 1.0 && shown.from != shown.to)
+	};
+	if shown.to != target {
+		let (current, _) = at(shown);
+		*shown = Shown { from: current, to: target, started: now };
+	}
+	at(shown)
+}
+
+pub struct ReactGaugeElement {
+	element:      Arc<ReactElement>,
+	window_id:    u64,
+	parent_style: Option<ElementStyle>,
+}
+
+pub struct GaugeLayoutState {}
+
+pub struct GaugePrepaintState {
+	hitbox:      Option<Hitbox>,
+	event_flags: EventHandlerFlags,
+}
+
+impl ReactGaugeElement {
+	pub fn new(
+		element: Arc<ReactElement>,
+		window_id: u64,
+		parent_style: Option<ElementStyle>,
+	) -> Self {
+		Self { element, window_id, parent_style }
+	}
+}
+
+impl Element for ReactGaugeElement {
+	type PrepaintState = GaugePrepaintState;
+	type RequestLayoutState = GaugeLayoutState;
+
+	fn id(&self) -> Option<ElementId> { Some(ElementId::Integer(self.element.global_id)) }
+
+	fn source_location(&self) -> Option<&'static std::panic::Location<'static>> { None }
+
+	fn request_layout(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		window: &mut Window,
+		cx: &mut App,
+	) -> (LayoutId, Self::RequestLayoutState) {
+		let mut style = self.element.build_gpui_style(None);
+		if self.element.style.width.is_none() {
+			style.size.width = px(DEFAULT_SIZE).into();
+		}
+		if self.element.style.height.is_none() {
+			style.size.height = px(DEFAULT_SIZE).into();
+		}
+		let layout_id = window.request_layout(style, std::iter::empty(), cx);
+		(layout_id, GaugeLayoutState {})
+	}
+
+	fn prepaint(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		bounds: Bounds<Pixels>,
+		_request_layout: &mut Self::RequestLayoutState,
+		window: &mut Window,
+		_cx: &mut App,
+	) -> Self::PrepaintState {
+		layout::record_bounds(self.window_id, self.element.global_id, bounds);
+
+		let event_flags = EventHandlerFlags::from_handlers(
+			self.element.event_handlers.as_ref(),
+			self.element.style.tab_index,
+		);
+		let hitbox = insert_hitbox_if_needed(&event_flags, bounds, window);
+		GaugePrepaintState { hitbox, event_flags }
+	}
+
+	fn paint(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		bounds: Bounds<Pixels>,
+		_request_layout: &mut Self::RequestLayoutState,
+		prepaint: &mut Self::PrepaintState,
+		window: &mut Window,
+		cx: &mut App,
+	) {
+		let mut style = self.element.build_gpui_style(None);
+		let crisp = pixel_snap::is_crisp(&self.element.style, self.parent_style.as_ref());
+		let (bounds, snapped) =
+			pixel_snap::snap(crisp, bounds, &mut style, &self.element.style, window);
+		let element_style = snapped.as_ref().unwrap_or(&self.element.style);
+		// The background is the arc's track
+		style.background = None;
+
+		let (window_id, element_id) = (self.window_id, self.element.global_id);
+		let min = element_style.min.unwrap_or(0.0);
+		let max = element_style.max.unwrap_or(100.0).max(min);
+		let target = element_style
+			.value
+			.as_deref()
+			.and_then(|value| value.trim().parse::<f64>().ok())
+			.unwrap_or(min)
+			.clamp(min, max);
+		let (value, easing) = shown_value(window_id, element_id, target);
+		let fraction = if max > min { ((value - min) / (max - min)) as f32 } else { 0.0 };
+
+		style.paint(bounds, window, cx, |window, _| {
+			paint_gauge(element_style, bounds, fraction, window);
+		});
+		border::paint(element_style, bounds, window);
+		border::paint_outline(element_style, bounds, window);
+
+		if easing {
+			pacing::request_frame(window_id, window, cx);
+		}
+
+		register_event_handlers(
+			&prepaint.event_flags,
+			prepaint.hitbox.as_ref(),
+			window_id,
+			element_id,
+			window,
+		);
+	}
+}
+
+impl IntoElement for ReactGaugeElement {
+	type Element = Self;
+
+	fn into_element(self) -> Self::Element { self }
+}
+
+/// Paint the track, the value arc, the tick marks and the needle
+fn paint_gauge(style: &ElementStyle, bounds: Bounds<Pixels>, fraction: f32, window: &mut Window) {
+	let accent = style.accent_color.unwrap_or_else(|| rgb(DEFAULT_ACCENT).into());
+	let track = style.bg_color.unwrap_or_else(|| rgb(0x808080).into());
+	let side = f32::from(bounds.size.width.min(bounds.size.height));
+	let thickness = (side * 0.1).max(2.0);
+	let radius = (side - thickness) / 2.0;
+	let center = bounds.center();
+
+	// Angles in degrees, clockwise from the right; the gap sits at the bottom
+	let sweep = style.gauge_sweep.unwrap_or(DEFAULT_SWEEP).clamp(1.0, 360.0);
+	let start = 90.0 + (360.0 - sweep) / 2.0;
+	let at = |degrees: f32, distance: f32| {
+		let radians = degrees * PI / 180.0;
+		point(center.x + px(distance * radians.cos()), center.y + px(distance * radians.sin()))
+	};
+
+	paint_arc(start, sweep, radius, thickness, track, &at, window);
+	let filled = sweep * fraction.clamp(0.0, 1.0);
+	if filled > 0.0 {
+		paint_arc(start, filled, radius, thickness, accent, &at, window);
+	}
+
+	let ticks = style.gauge_ticks.unwrap_or(0);
+	if ticks > 0 {
+		let tick_color = style.text_color.unwrap_or(track);
+		// A full ring's last tick would land on its first
+		let spaces = if sweep >= 360.0 { ticks } else { ticks.saturating_sub(1).max(1) };
+		for i in 0..ticks {
+			let degrees = start + sweep * i as f32 / spaces as f32;
+			let outer = radius - thickness;
+			let tick = [at(degrees, outer), at(degrees, outer - thickness)];
+			paint_line(&tick, (thickness / 4.0).max(1.0), tick_color, window);
+		}
+	}
+
+	if style.gauge_needle == Some(true) {
+		let degrees = start + filled;
+		let tip = at(degrees, radius - thickness * 1.5);
+		paint_line(&[center, tip], (thickness / 3.0).max(1.5), accent, window);
+		let hub = thickness * 0.75;
+		let hub_bounds = Bounds::centered_at(center, size(px(hub * 2.0), px(hub * 2.0)));
+		window.paint_quad(fill(hub_bounds, accent).corner_radii(Corners::all(px(hub))));
+	}
+}
+
+/// Stroke an arc of `sweep` degrees from `start`, as short straight segments
+fn paint_arc(
+	start: f32,
+	sweep: f32,
+	radius: f32,
+	thickness: f32,
+	color: Hsla,
+	at: &impl Fn(f32, f32) -> Point<Pixels>,
+	window: &mut Window,
+) {
+	let segments = (sweep / SEGMENT_DEGREES).ceil().max(1.0) as usize;
+	let points: Vec<_> =
+		(0..=segments).map(|i| at(start + sweep * i as f32 / segments as f32, radius)).collect();
+	paint_line(&points, thickness, color, window);
+}
+
+fn paint_line(points: &[Point<Pixels>], width: f32, color: Hsla, window: &mut Window) {
+	let Some((first, rest)) = points.split_first() else {
+		return;
+	};
+	let mut path = PathBuilder::stroke(px(width));
+	path.move_to(*first);
+	for point in rest {
+		path.line_to(*point);
+	}
+	match path.build() {
+		Ok(path) => window.paint_path(path, color),
+		Err(e) => log::warn!("gauge: failed to build path: {}", e),
+	}
+}
@@ -0,0 +1,274 @@
+//! Syntax highlighter for the code element
+//!
+//! A single-pass scanner driven by a small table per language: comments,
+//! strings, numbers, keywords, literals, type names (builtin or capitalized)
+//! and calls. It doesn't parse, so it can't tell every context apart, but it
+//! is fast enough to rerun on every change of a few thousand lines and needs
+//! no grammar files. Languages it doesn't know get no highlights.
+
+use std::ops::Range;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Token {
+	Comment,
+	String,
+	Number,
+	Keyword,
+	/// true, false, null and the like
+	Literal,
+	Type,
+	/// A name followed by `(`, or a Rust macro
+	Function,
+}
+
+struct Syntax {
+	keywords:      &'static [&'static str],
+	literals:      &'static [&'static str],
+	types:         &'static [&'static str],
+	line_comments: &'static [&'static str],
+	block_comment: Option<(&'static str, &'static str)>,
+	/// Quotes that open a string ending on the same line
+	quotes:        &'static [char],
+	/// Quotes whose strings may span lines
+	multiline:     &'static [&'static str],
+	/// `'` opens a char literal only when it closes right after, as in Rust
+	lifetimes:     bool,
+}
+
+#[rustfmt::skip]
+const RUST: Syntax = Syntax {
+	keywords:      &[
+		"as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
+		"extern", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut",
+		"pub", "ref", "return", "self", "Self", "static", "struct", "super", "trait", "type",
+		"unsafe", "use", "where", "while",
+	],
+	literals:      &["true", "false", "None", "Some", "Ok", "Err"],
+	types:         &[
+		"bool", "char", "str", "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32",
+		"i64", "i128", "isize", "f32", "f64",
+	],
+	line_comments: &["//"],
+	block_comment: Some(("/*", "*/")),
+	quotes:        &['"', '\''],
+	multiline:     &[],
+	lifetimes:     true,
+};
+
+#[rustfmt::skip]
+const TYPESCRIPT: Syntax = Syntax {
+	keywords:      &[
+		"abstract", "as", "async", "await", "break", "case", "catch", "class", "const",
+		"continue", "debugger", "default", "delete", "do", "else", "enum", "export", "extends",
+		"finally", "for", "from", "function", "if", "implements", "import", "in", "instanceof",
+		"interface", "keyof", "let", "new", "of", "private", "protected", "public", "readonly",
+		"return", "static", "super", "switch", "this", "throw", "try", "type", "typeof", "var",
+		"void", "while", "yield",
+	],
+	literals:      &["true", "false", "null", "undefined", "NaN", "Infinity"],
+	types:         &["any", "bigint", "boolean", "never", "number", "object", "string", "unknown"],
+	line_comments: &["//"],
+	block_comment: Some(("/*", "*/")),
+	quotes:        &['"', '\''],
+	multiline:     &["`"],
+	lifetimes:     false,
+};
+
+#[rustfmt::skip]
+const PYTHON: Syntax = Syntax {
+	keywords:      &[
+		"and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del",
+		"elif", "else", "except", "finally", "for", "from", "global", "if", "import", "in", "is",
+		"lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while", "with",
+		"yield",
+	],
+	literals:      &["True", "False", "None", "self"],
+	types:         &["bool", "bytes", "dict", "float", "int", "list", "set", "str", "tuple"],
+	line_comments: &["#"],
+	block_comment: None,
+	quotes:        &['"', '\''],
+	multiline:     &["\"\"\"", "'''"],
+	lifetimes:     false,
+};
+
+#[rustfmt::skip]
+const GO: Syntax = Syntax {
+	keywords:      &[
+		"break", "case", "chan", "const", "continue", "default", "defer", "else", "fallthrough",
+		"for", "func", "go", "goto", "if", "import", "interface", "map", "package", "range",
+		"return", "select", "struct", "switch", "type", "var",
+	],
+	literals:      &["true", "false", "nil", "iota"],
+	types:         &[
+		"bool", "byte", "error", "float32", "float64", "int", "int8", "int16", "int32", "int64",
+		"rune", "string", "uint", "uint8", "uint16", "uint32", "uint64", "uintptr", "any",
+	],
+	line_comments: &["//"],
+	block_comment: Some(("/*", "*/")),
+	quotes:        &['"', '\''],
+	multiline:     &["`"],
+	lifetimes:     false,
+};
+
+#[rustfmt::skip]
+const C: Syntax = Syntax {
+	keywords:      &[
+		"auto", "break", "case", "class", "const", "constexpr", "continue", "default", "delete",
+		"do", "else", "enum", "explicit", "extern", "for", "friend", "goto", "if", "inline",
+		"namespace", "new", "operator", "private", "protected", "public", "return", "sizeof",
+		"static", "struct", "switch", "template", "this", "throw", "try", "catch", "typedef",
+		"typename", "union", "using", "virtual", "volatile", "while", "#include", "#define",
+		"#if", "#ifdef", "#ifndef", "#else", "#endif", "#pragma",
+	],
+	literals:      &["true", "false", "NULL", "nullptr"],
+	types:         &[
+		"bool", "char", "double", "float", "int", "long", "short", "signed", "unsigned", "void",
+		"size_t", "int8_t", "int16_t", "int32_t", "int64_t", "uint8_t", "uint16_t", "uint32_t",
+		"uint64_t",
+	],
+	line_comments: &["//"],
+	block_comment: Some(("/*", "*/")),
+	quotes:        &['"', '\''],
+	multiline:     &[],
+	lifetimes:     false,
+};
+
+#[rustfmt::skip]
+const SHELL: Syntax = Syntax {
+	keywords:      &[
+		"case", "do", "done", "elif", "else", "esac", "export", "fi", "for", "function", "if",
+		"in", "local", "return", "then", "until", "while",
+	],
+	literals:      &["true", "false"],
+	types:         &[],
+	line_comments: &["#"],
+	block_comment: None,
+	quotes:        &[],
+	multiline:     &["\"", "'"],
+	lifetimes:     false,
+};
+
+#[rustfmt::skip]
+const JSON: Syntax = Syntax {
+	keywords:      &[],
+	literals:      &["true", "false", "null"],
+	types:         &[],
+	line_comments: &[],
+	block_comment: None,
+	quotes:        &['"'],
+	multiline:     &[],
+	lifetimes:     false,
+};
+
+fn syntax(language: &str) -> Option<&'static Syntax> {
+	let syntax = match language.to_ascii_lowercase().as_str() {
+		"rust" | "rs" => &RUST,
+		"typescript" | "ts" | "tsx" | "javascript" | "js" | "jsx" | "mjs" => &TYPESCRIPT,
+		"python" | "py" => &PYTHON,
+		"go" | "golang" => &GO,
+		"c" | "h" | "cpp" | "c++" | "cc" | "hpp" => &C,
+		"bash" | "sh" | "shell" | "zsh" => &SHELL,
+		"json" => &JSON,
+		_ => return None,
+	};
+	Some(syntax)
+}
+
+/// Byte ranges of the tokens in `text`, in order; text between them is plain
+pub fn highlight(language: &str, text: &str) -> Vec<(Range<usize>, Token)> {
+	let Some(syntax) = syntax(language) else {
+		return Vec::new();
+	};
+	let bytes = text.as_bytes();
+	let mut tokens = Vec::new();
+	let mut i = 0;
+	while i < bytes.len() {
+		let rest = &text[i..];
+		let c = rest.chars().next().unwrap_or_default();
+		let start = i;
+
+		if syntax.line_comments.iter().any(|open| rest.starts_with(open)) {
+			i += rest.find('\n').unwrap_or(rest.len());
+			tokens.push((start..i, Token::Comment));
+		} else if let Some((open, close)) = syntax.block_comment
+			&& rest.starts_with(open)
+		{
+			i += rest[open.len()..].find(close).map_or(rest.len(), |end| open.len() + end + close.len());
+			tokens.push((start..i, Token::Comment));
+		} else if let Some(quote) = syntax.multiline.iter().find(|quote| rest.starts_with(**quote)) {
+			i += string_end(rest, quote, true);
+			tokens.push((start..i, Token::String));
+		} else if syntax.quotes.contains(&c) {
+			if syntax.lifetimes && c == '\'' && !is_char_literal(rest) {
+				// A lifetime or label: leave the quote plain, the name follows
+				i += 1;
+				continue;
+			}
+			let mut quote = [0u8; 4];
+			i += string_end(rest, c.encode_utf8(&mut quote), false);
+			tokens.push((start..i, Token::String));
+		} else if c.is_ascii_digit() {
+			i += rest
+				.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '.'))
+				.unwrap_or(rest.len());
+			tokens.push((start..i, Token::Number));
+		} else if c.is_alphabetic()
+			|| c == '_'
+			|| (c == '#' && syntax.keywords.iter().any(|k| k.starts_with('#')))
+		{
+			let len = rest[c.len_utf8()..]
+				.find(|c: char| !(c.is_alphanumeric() || c == '_'))
+				.map_or(rest.len(), |len| len + c.len_utf8());
+			let word = &rest[..len];
+			i += len;
+			let after = rest[len..].trim_start_matches([' ', '\t']);
+			let token = if syntax.keywords.contains(&word) {
+				Some(Token::Keyword)
+			} else if syntax.literals.contains(&word) {
+				Some(Token::Literal)
+			} else if syntax.types.contains(&word) {
+				Some(Token::Type)
+			} else if after.starts_with('(') || (syntax.lifetimes && after.starts_with('!')) {
+				Some(Token::Function)
+			} else if word.starts_with(|c: char| c.is_uppercase()) {
+				Some(Token::Type)
+			} else {
+				None
+			};
+			if let Some(token) = token {
+				tokens.push((start..i, token));
+			}
+		} else {
+			i += c.len_utf8();
+		}
+	}
+	tokens
+}
+
+/// Length of the string opening `rest` with `quote`, quotes included; an
+/// unterminated string runs to the end of the line, or of the text when it
+/// may span lines
+fn string_end(rest: &str, quote: &str, multiline: bool) -> usize {
+	let mut chars = rest.char_indices().skip(quote.chars().count());
+	while let Some((at, c)) = chars.next() {
+		match c {
+			'\\' => {
+				chars.next();
+			}
+			'\n' if !multiline => return at,
+			_ if rest[at..].starts_with(quote) => return at + quote.len(),
+			_ => {}
+		}
+	}
+	rest.len()
+}
+
+/// `'x'` or `'\n'`, as opposed to a lifetime `'a`
+fn is_char_literal(rest: &str) -> bool {
+	let mut chars = rest.chars().skip(1);
+	match chars.next() {
+		Some('\\') => true,
+		Some(_) => chars.next() == Some('\''),
+		None => false,
+	}
+}
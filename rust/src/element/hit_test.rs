@@ -0,0 +1,90 @@
+//! Hit-test debugging ("why didn't my click register?")
+//!
+//! With debugging enabled for a window (`gpui_set_hit_test_debug`), every
+//! mouse release queues a window-level `hittest` event describing the element
+//! stack under the cursor, innermost first: id, type and bounds of each
+//! element, whether it has mouse handlers, whether its hitbox was reachable
+//! (an element painted on top can block it) and which events it received.
+
+use std::{collections::HashSet, sync::Mutex};
+
+use gpui::{Hitbox, Pixels, Point, Window};
+use lazy_static::lazy_static;
+use serde_json::json;
+
+use super::{events::EventHandlerFlags, layout};
+use crate::{global_state::GLOBAL_STATE, renderer::dispatch_window_event, sync::MutexExt};
+
+lazy_static! {
+	static ref ENABLED: Mutex<HashSet<u64>> = Mutex::new(HashSet::new());
+}
+
+/// Drop all state of a closed window
+pub fn forget_window(window_id: u64) { ENABLED.lock_recover().remove(&window_id); }
+
+pub fn set_enabled(window_id: u64, enabled: bool) {
+	let mut windows = ENABLED.lock_recover();
+	if enabled {
+		windows.insert(window_id);
+	} else {
+		windows.remove(&window_id);
+	}
+}
+
+pub fn is_enabled(window_id: u64) -> bool { ENABLED.lock_recover().contains(&window_id) }
+
+/// Queue a `hittest` event for a mouse release at `position`
+/// `handlers` are the window's interactive elements this frame and `received`
+/// the events dispatched for this release, in dispatch order
+pub fn report(
+	window_id: u64,
+	position: Point<Pixels>,
+	button: u8,
+	handlers: &[(u64, Hitbox, EventHandlerFlags)],
+	received: &[(u64, &str)],
+	window: &Window,
+) {
+	let Some(root) =
+		GLOBAL_STATE.get_window(window_id).and_then(|w| w.state().element_tree.lock_recover().clone())
+	else {
+		return;
+	};
+
+	let stack: Vec<serde_json::Value> = layout::stack_at(window_id, &root, position)
+		.into_iter()
+		.map(|(element, bounds)| {
+			let handler = handlers.iter().find(|(id, ..)| *id == element.global_id);
+			let events: Vec<&str> = received
+				.iter()
+				.filter(|(id, _)| *id == element.global_id)
+				.map(|(_, event_type)| *event_type)
+				.collect();
+			json!({
+				"elementId": element.global_id,
+				"type": element.element_type,
+				"bounds": {
+					"x": f32::from(bounds.origin.x),
+					"y": f32::from(bounds.origin.y),
+					"width": f32::from(bounds.size.width),
+					"height": f32::from(bounds.size.height),
+				},
+				"interactive": handler.is_some_and(|(_, _, flags)| flags.has_any_mouse_handler()),
+				// An interactive element under the cursor that isn't hovered is covered by another
+				"reachable": handler.map(|(_, hitbox, _)| hitbox.is_hovered(window)),
+				"received": events,
+			})
+		})
+		.collect();
+
+	dispatch_window_event(
+		window_id,
+		"hittest",
+		json!({
+			"x": f32::from(position.x),
+			"y": f32::from(position.y),
+			"button": button,
+			"stack": stack,
+			"consumedBy": received.first().map(|(id, _)| *id),
+		}),
+	);
+}
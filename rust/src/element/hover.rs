@@ -7,6 +7,8 @@ use std::{collections::HashSet, sync::{Arc, Mutex}};
 
 use lazy_static::lazy_static;
 
+use crate::sync::MutexExt;
+
 /// Tracks the current hover state of elements
 pub struct HoverState {
 	/// Set of element IDs that are currently hovered
@@ -47,9 +49,9 @@ lazy_static! {
 /// Get a reference to the global hover state
 pub fn get_hover_state() -> &'static Arc<Mutex<HoverState>> { &HOVER_STATE }
 
+/// Drop the hover state of a released element, so a recycled id starts out
+/// unhovered and gets its mouseenter
+pub fn forget_element(element_id: u64) { HOVER_STATE.lock_recover().set_not_hovered(element_id); }
+
 /// Clear all hover states (call when window closes or during cleanup)
-pub fn clear_hover_state() {
-	if let Ok(mut state) = HOVER_STATE.lock() {
-		state.clear();
-	}
-}
+pub fn clear_hover_state() { HOVER_STATE.lock_recover().clear(); }
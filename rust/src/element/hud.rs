@@ -0,0 +1,133 @@
+//! Developer heads-up display
+//!
+//! Enabled per window with `gpui_set_hud`. A small panel in the window's
+//! top-left corner, painted after everything else, shows the frame rate over
+//! the last second, the time from the start of the last render to its paint,
+//! the number of elements, the depth of the event queue waiting for JS and
+//! commits applied over the last second. While the HUD is on, the window
+//! refreshes at least once a second so the numbers don't go stale when idle;
+//! those refreshes count as frames.
+
+use std::{collections::{HashMap, HashSet, VecDeque}, sync::Mutex, time::Duration};
+
+use gpui::{App, Bounds, Corners, Hsla, Window, fill, point, px, size};
+use lazy_static::lazy_static;
+
+use super::text_cache;
+use crate::{clock, global_state::GLOBAL_STATE, sync::MutexExt};
+
+/// Span the frame and commit rates are measured over
+const RATE_WINDOW_US: u64 = 1_000_000;
+/// Longest wait between HUD refreshes of an idle window
+const IDLE_REFRESH: Duration = Duration::from_secs(1);
+
+#[derive(Default)]
+struct Frames {
+	/// Start of the frame being rendered
+	render_started: u64,
+	/// Paint time and tree generation of the frames of the last second
+	painted:        VecDeque<(u64, u64)>,
+	/// An idle refresh is on its way
+	refresh_queued: bool,
+}
+
+lazy_static! {
+	static ref ENABLED: Mutex<HashSet<u64>> = Mutex::new(HashSet::new());
+	static ref FRAMES: Mutex<HashMap<u64, Frames>> = Mutex::new(HashMap::new());
+}
+
+/// Drop all state of a closed window
+pub fn forget_window(window_id: u64) {
+	ENABLED.lock_recover().remove(&window_id);
+	FRAMES.lock_recover().remove(&window_id);
+}
+
+pub fn set_enabled(window_id: u64, enabled: bool) {
+	if enabled {
+		ENABLED.lock_recover().insert(window_id);
+	} else {
+		ENABLED.lock_recover().remove(&window_id);
+		FRAMES.lock_recover().remove(&window_id);
+	}
+}
+
+pub fn is_enabled(window_id: u64) -> bool { ENABLED.lock_recover().contains(&window_id) }
+
+/// Note the start of a render
+pub fn begin_frame(window_id: u64) {
+	FRAMES.lock_recover().entry(window_id).or_default().render_started = clock::now_us();
+}
+
+/// Paint the panel, after the rest of the window
+pub fn paint(window_id: u64, window: &mut Window, cx: &mut App) {
+	let Some(state) = GLOBAL_STATE.get_window(window_id) else {
+		return;
+	};
+	let state = state.state();
+	let generation = state.get_tree_generation();
+	let elements = state.element_map.lock_recover().len();
+	let queued = state.event_queue.lock_recover().len();
+
+	let now = clock::now_us();
+	let (fps, frame_ms, commits, refresh) = {
+		let mut windows = FRAMES.lock_recover();
+		let frames = windows.entry(window_id).or_default();
+		frames.painted.push_back((now, generation));
+		while frames.painted.front().is_some_and(|(at, _)| now - at > RATE_WINDOW_US) {
+			frames.painted.pop_front();
+		}
+		let oldest = frames.painted.front().map_or(generation, |(_, generation)| *generation);
+		let frame_ms = now.saturating_sub(frames.render_started) as f32 / 1000.0;
+		let refresh = !std::mem::replace(&mut frames.refresh_queued, true);
+		(frames.painted.len(), frame_ms, generation - oldest, refresh)
+	};
+
+	let lines = [
+		format!("{} fps", fps),
+		format!("frame {:.1} ms", frame_ms),
+		format!("{} elements", elements),
+		format!("{} events queued", queued),
+		format!("{} commits/s", commits),
+	];
+	paint_panel(&lines, window, cx);
+
+	if refresh {
+		window
+			.spawn(cx, async move |cx| {
+				cx.background_executor().timer(IDLE_REFRESH).await;
+				if let Some(frames) = FRAMES.lock_recover().get_mut(&window_id) {
+					frames.refresh_queued = false;
+				}
+				let _ = cx.update(|window, _| window.refresh());
+			})
+			.detach();
+	}
+}
+
+fn paint_panel(lines: &[String], window: &mut Window, cx: &mut App) {
+	let font_size = px(11.0);
+	let line_height = font_size * 1.4;
+	let padding = px(6.0);
+	let font = window.text_style().font();
+	let color = Hsla { h: 0.33, s: 0.8, l: 0.7, a: 1.0 };
+	let shaped: Vec<_> = lines
+		.iter()
+		.map(|line| text_cache::shape_line(window, line.clone(), &font, font_size, color))
+		.collect();
+
+	let width = shaped.iter().map(|line| line.width).fold(px(0.0), |a, b| a.max(b));
+	let origin = point(px(8.0), px(8.0));
+	let panel = Bounds {
+		origin,
+		size: size(width + padding * 2.0, line_height * shaped.len() as f32 + padding * 2.0),
+	};
+	window.paint_quad(
+		fill(panel, Hsla { h: 0.0, s: 0.0, l: 0.05, a: 0.8 }).corner_radii(Corners::all(px(4.0))),
+	);
+	for (i, line) in shaped.iter().enumerate() {
+		let at = point(origin.x + padding, origin.y + padding + line_height * i as f32);
+		if let Err(e) = line.paint(at, line_height, window, cx) {
+			log::warn!("hud: failed to paint stats: {}", e);
+		}
+	}
+}
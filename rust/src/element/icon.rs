@@ -0,0 +1,211 @@
+//! Icon element
+//!
+//! An `icon` paints one glyph, `codepoint`, from its own `fontFamily` (an icon
+//! font registered with `gpui_register_font`, or any installed font), `size`
+//! pixels tall (the text size by default) and in its text color. Without a
+//! width or height it lays out as a `size` square with the glyph centered.
+//!
+//! The glyph is shaped once per (font, codepoint, size) and kept in an
+//! app-wide cache; later paints go straight to `paint_glyph`, so hundreds of
+//! icons cost no shaping at all. Registering a font clears the cache, since
+//! codepoints may then resolve to the new font.
+
+use std::{collections::HashMap, sync::{Arc, Mutex}};
+
+use gpui::{App, Bounds, Element, ElementId, Font, FontId, GlobalElementId, GlyphId, Hitbox, InspectorElementId, IntoElement, LayoutId, Pixels, TextRun, Window, point, px, rgb};
+use lazy_static::lazy_static;
+
+use super::{ElementStyle, ReactElement, border, events::{EventHandlerFlags, insert_hitbox_if_needed, register_event_handlers}, fonts, layout};
+use crate::sync::MutexExt;
+
+const DEFAULT_SIZE: f32 = 16.0;
+
+/// A shaped glyph, ready to paint at a baseline
+#[derive(Clone, Copy)]
+struct Glyph {
+	font_id:  FontId,
+	glyph_id: GlyphId,
+	is_emoji: bool,
+	width:    Pixels,
+	ascent:   Pixels,
+	descent:  Pixels,
+}
+
+lazy_static! {
+	/// Glyphs by font, codepoint and size bits; None when the codepoint has
+	/// no glyph in the font or its fallbacks
+	static ref GLYPHS: Mutex<HashMap<(Font, u32, u32), Option<Glyph>>> = Mutex::new(HashMap::new());
+}
+
+/// Drop every cached glyph, after the set of fonts changed
+pub fn forget_glyphs() { GLYPHS.lock_recover().clear(); }
+
+/// The glyph of `codepoint` in `font`, shaped on the first request only
+fn glyph(font: &Font, codepoint: u32, size: f32, window: &Window) -> Option<Glyph> {
+	let key = (font.clone(), codepoint, size.to_bits());
+	if let Some(glyph) = GLYPHS.lock_recover().get(&key) {
+		return *glyph;
+	}
+
+	let glyph = char::from_u32(codepoint).and_then(|c| {
+		let text = c.to_string();
+		let run = TextRun {
+			len:              text.len(),
+			font:             font.clone(),
+			color:            rgb(0xffffff).into(),
+			background_color: None,
+			underline:        None,
+			strikethrough:    None,
+		};
+		let line = window.text_system().shape_line(text.into(), px(size), &[run], None);
+		let run = line.runs.first()?;
+		let shaped = run.glyphs.first()?;
+		Some(Glyph {
+			font_id:  run.font_id,
+			glyph_id: shaped.id,
+			is_emoji: shaped.is_emoji,
+			width:    line.width,
+			ascent:   line.ascent,
+			descent:  line.descent,
+		})
+	});
+	GLYPHS.lock_recover().insert(key, glyph);
+	glyph
+}
+
+pub struct ReactIconElement {
+	element:      Arc<ReactElement>,
+	window_id:    u64,
+	parent_style: Option<ElementStyle>,
+}
+
+pub struct IconLayoutState {}
+
+pub struct IconPrepaintState {
+	hitbox:      Option<Hitbox>,
+	event_flags: EventHandlerFlags,
+}
+
+impl ReactIconElement {
+	pub fn new(
+		element: Arc<ReactElement>,
+		window_id: u64,
+		parent_style: Option<ElementStyle>,
+	) -> Self {
+		Self { element, window_id, parent_style }
+	}
+
+	fn size(&self) -> f32 {
+		let style = &self.element.style;
+		let effective = self.element.effective_style(self.parent_style.as_ref());
+		style.icon_size.or(effective.text_size).unwrap_or(DEFAULT_SIZE)
+	}
+}
+
+impl Element for ReactIconElement {
+	type PrepaintState = IconPrepaintState;
+	type RequestLayoutState = IconLayoutState;
+
+	fn id(&self) -> Option<ElementId> { Some(ElementId::Integer(self.element.global_id)) }
+
+	fn source_location(&self) -> Option<&'static std::panic::Location<'static>> { None }
+
+	fn request_layout(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		window: &mut Window,
+		cx: &mut App,
+	) -> (LayoutId, Self::RequestLayoutState) {
+		let es = &self.element.style;
+		let mut style = self.element.build_gpui_style(None);
+		let size = self.size();
+		if es.width.is_none() {
+			style.size.width = px(size).into();
+		}
+		if es.height.is_none() {
+			style.size.height = px(size).into();
+		}
+		let layout_id = window.request_layout(style, std::iter::empty(), cx);
+		(layout_id, IconLayoutState {})
+	}
+
+	fn prepaint(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		bounds: Bounds<Pixels>,
+		_request_layout: &mut Self::RequestLayoutState,
+		window: &mut Window,
+		_cx: &mut App,
+	) -> Self::PrepaintState {
+		layout::record_bounds(self.window_id, self.element.global_id, bounds);
+
+		let event_flags = EventHandlerFlags::from_handlers(
+			self.element.event_handlers.as_ref(),
+			self.element.style.tab_index,
+		);
+		let hitbox = insert_hitbox_if_needed(&event_flags, bounds, window);
+		IconPrepaintState { hitbox, event_flags }
+	}
+
+	fn paint(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		bounds: Bounds<Pixels>,
+		_request_layout: &mut Self::RequestLayoutState,
+		prepaint: &mut Self::PrepaintState,
+		window: &mut Window,
+		cx: &mut App,
+	) {
+		let element_style = &self.element.style;
+		let style = self.element.build_gpui_style(None);
+		style.paint(bounds, window, cx, |window, _| {
+			let Some(codepoint) = element_style.icon_codepoint else {
+				return;
+			};
+			let effective = self.element.effective_style(self.parent_style.as_ref());
+			let mut font = fonts::resolve(&effective, window);
+			// An inherited family is meant for text, only the element's own counts
+			if element_style.font_family.is_none() {
+				font.family = window.text_style().font().family;
+			}
+			let size = self.size();
+			let Some(glyph) = glyph(&font, codepoint, size, window) else {
+				return;
+			};
+
+			// Centered in the bounds, on the glyph's advance and line box
+			let origin = point(
+				bounds.origin.x + (bounds.size.width - glyph.width) / 2.0,
+				bounds.origin.y + (bounds.size.height - glyph.ascent - glyph.descent) / 2.0 + glyph.ascent,
+			);
+			let result = if glyph.is_emoji {
+				window.paint_emoji(origin, glyph.font_id, glyph.glyph_id, px(size))
+			} else {
+				let color = effective.text_color.unwrap_or_else(|| rgb(0xffffff).into());
+				window.paint_glyph(origin, glyph.font_id, glyph.glyph_id, px(size), color)
+			};
+			if let Err(e) = result {
+				log::warn!("[Rust] icon: failed to paint glyph {:#x}: {}", codepoint, e);
+			}
+		});
+		border::paint(element_style, bounds, window);
+		border::paint_outline(element_style, bounds, window);
+
+		register_event_handlers(
+			&prepaint.event_flags,
+			prepaint.hitbox.as_ref(),
+			self.window_id,
+			self.element.global_id,
+			window,
+		);
+	}
+}
+
+impl IntoElement for ReactIconElement {
+	type Element = Self;
+
+	fn into_element(self) -> Self::Element { self }
+}
@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use gpui::{AnyElement, App, Bounds, Element, ElementId, GlobalElementId, Hitbox, InspectorElementId, IntoElement, LayoutId, Pixels, Style, Window, div, prelude::*, px, rgb};
 
-use super::{ElementStyle, ReactElement, events::{EventHandlerFlags, insert_hitbox_if_needed, register_event_handlers}};
+use super::{ElementStyle, ReactElement, events::{EventHandlerFlags, insert_hitbox_if_needed, register_event_handlers}, layout};
 
 /// An image element
 /// - Displays images from src URL/path
@@ -74,7 +74,7 @@ impl ReactImgElement {
 
 		// Background color (placeholder background)
 		if let Some(bg) = es.bg_color {
-			style.background = Some(gpui::Fill::Color(rgb(bg).into()));
+			style.background = Some(gpui::Fill::Color(bg.into()));
 		} else {
 			// Default placeholder background
 			style.background = Some(gpui::Fill::Color(rgb(0x444444).into()));
@@ -123,11 +123,10 @@ impl Element for ReactImgElement {
 		};
 
 		// Create placeholder child element
-		let text_color = effective.text_color.unwrap_or(0x888888);
+		let text_color = effective.text_color.unwrap_or_else(|| rgb(0x888888).into());
 		let text_size = effective.text_size.unwrap_or(12.0);
 
-		let placeholder =
-			div().text_color(rgb(text_color)).text_size(px(text_size)).child(placeholder_text);
+		let placeholder = div().text_color(text_color).text_size(px(text_size)).child(placeholder_text);
 
 		let mut child = placeholder.into_any_element();
 		let child_layout_id = child.request_layout(window, cx);
@@ -146,6 +145,8 @@ impl Element for ReactImgElement {
 		window: &mut Window,
 		cx: &mut App,
 	) -> Self::PrepaintState {
+		layout::record_bounds(self.window_id, self.element.global_id, bounds);
+
 		if let Some(ref mut child) = self.placeholder_child {
 			child.prepaint(window, cx);
 		}
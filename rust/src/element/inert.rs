@@ -0,0 +1,91 @@
+//! Inert subtrees
+//!
+//! An element with `inert` paints as usual, but neither it nor anything
+//! inside it can be interacted with. Its elements:
+//!
+//! - record no mouse handlers, so clicks, hovers, wheel events and hover styles
+//!   pass them by
+//! - leave the tab order and can't take focus; a focused element that becomes
+//!   inert is blurred
+//! - are left out of find-in-page and live region announcements
+//!
+//! The set of inert elements is rebuilt at the start of the first render
+//! after each commit, and only once some style with `inert` has been seen.
+
+use std::{collections::{HashMap, HashSet}, sync::{Mutex, atomic::{AtomicBool, Ordering}}};
+
+use lazy_static::lazy_static;
+
+use super::{ElementStyle, ReactElement, focus};
+use crate::{event_types::{EventData, FocusEventData, types}, global_state::GLOBAL_STATE, renderer::dispatch_event_to_js, sync::MutexExt};
+
+static ANY_INERT: AtomicBool = AtomicBool::new(false);
+
+/// Inert elements of one window
+struct InertSet {
+	/// Tree generation the set was built from
+	generation: u64,
+	elements:   HashSet<u64>,
+}
+
+lazy_static! {
+	static ref WINDOWS: Mutex<HashMap<u64, InertSet>> = Mutex::new(HashMap::new());
+}
+
+/// Drop all state of a closed window
+pub fn forget_window(window_id: u64) { WINDOWS.lock_recover().remove(&window_id); }
+
+/// Note a newly parsed style, enabling the per-commit walk once one is inert
+pub fn note_style(style: &ElementStyle) {
+	if style.inert == Some(true) {
+		ANY_INERT.store(true, Ordering::Relaxed);
+	}
+}
+
+pub fn is_inert(window_id: u64, element_id: u64) -> bool {
+	ANY_INERT.load(Ordering::Relaxed)
+		&& WINDOWS.lock_recover().get(&window_id).is_some_and(|set| set.elements.contains(&element_id))
+}
+
+/// Rebuild the window's inert set if a commit landed since the last render
+/// Called at the start of every render of the window
+pub fn begin_frame(window_id: u64) {
+	if !ANY_INERT.load(Ordering::Relaxed) {
+		return;
+	}
+	let Some(window) = GLOBAL_STATE.get_window(window_id) else {
+		return;
+	};
+	let generation = window.state().get_tree_generation();
+	let built = WINDOWS.lock_recover().get(&window_id).map(|set| set.generation);
+	if built == Some(generation) {
+		return;
+	}
+	let mut elements = HashSet::new();
+	if let Some(root) = window.state().element_tree.lock_recover().clone() {
+		collect(&root, false, &mut elements);
+	}
+	let focused = focus::get_focused(window_id).filter(|id| elements.contains(id));
+	WINDOWS.lock_recover().insert(window_id, InertSet { generation, elements });
+
+	if let Some(element_id) = focused {
+		focus::clear_focus(window_id);
+		log::debug!("[Rust] inert: blurring element_id={}", element_id);
+		dispatch_event_to_js(
+			window_id,
+			element_id,
+			types::BLUR,
+			EventData::Focus(FocusEventData { related_target: None }),
+		);
+	}
+}
+
+fn collect(element: &ReactElement, parent_inert: bool, elements: &mut HashSet<u64>) {
+	let inert = parent_inert || element.style.inert == Some(true);
+	if inert {
+		elements.insert(element.global_id);
+	}
+	for child in &element.children {
+		collect(child, inert, elements);
+	}
+}
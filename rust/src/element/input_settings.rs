@@ -0,0 +1,165 @@
+//! Pointer input settings
+//!
+//! Thresholds used by the gesture recognizers in the dispatcher, and
+//! adjustments applied to wheel deltas before they reach JS or scroll a
+//! document:
+//!
+//! - `doubleClickMs`: longest gap between the clicks of a `dblclick`
+//! - `dragDistance`: pointer travel, in pixels, that turns a press into a drag,
+//!   cancelling a long press; also how far apart the two clicks of a `dblclick`
+//!   may be
+//! - `scrollMultiplier`: factor applied to wheel deltas
+//! - `invertScroll`: flip wheel deltas. Deltas already follow the OS
+//!   natural-scrolling preference; this overrides it for the app.
+//!
+//! The double-click time and drag distance default to the OS values where
+//! they can be read (user32 on Windows, `defaults` on macOS, `gsettings` on
+//! GNOME), looked up on a background thread at startup.
+//! `gpui_set_input_settings` overrides any of them; fields left out keep their
+//! current setting.
+
+use std::{sync::RwLock, time::Duration};
+
+use lazy_static::lazy_static;
+
+use crate::sync::RwLockExt;
+
+/// Used until (or unless) the OS reports its own
+const DEFAULT_DOUBLE_CLICK: Duration = Duration::from_millis(500);
+const DEFAULT_DRAG_DISTANCE: f32 = 10.0;
+
+#[derive(Debug, Default, serde::Deserialize)]
+pub struct InputSettingsOptions {
+	#[serde(rename = "doubleClickMs")]
+	pub double_click_ms:   Option<u64>,
+	#[serde(rename = "dragDistance")]
+	pub drag_distance:     Option<f32>,
+	#[serde(rename = "scrollMultiplier")]
+	pub scroll_multiplier: Option<f32>,
+	#[serde(rename = "invertScroll")]
+	pub invert_scroll:     Option<bool>,
+}
+
+/// Values read from the OS, where available
+#[derive(Default)]
+struct OsDefaults {
+	double_click:  Option<Duration>,
+	drag_distance: Option<f32>,
+}
+
+lazy_static! {
+	static ref OS_DEFAULTS: RwLock<OsDefaults> = RwLock::new(OsDefaults::default());
+	static ref OVERRIDES: RwLock<InputSettingsOptions> = RwLock::new(InputSettingsOptions::default());
+}
+
+pub fn configure(options: &InputSettingsOptions) {
+	let mut overrides = OVERRIDES.write_recover();
+	if options.double_click_ms.is_some() {
+		overrides.double_click_ms = options.double_click_ms;
+	}
+	if let Some(distance) = options.drag_distance.filter(|distance| *distance >= 0.0) {
+		overrides.drag_distance = Some(distance);
+	}
+	if let Some(multiplier) = options.scroll_multiplier.filter(|multiplier| multiplier.is_finite()) {
+		overrides.scroll_multiplier = Some(multiplier);
+	}
+	if options.invert_scroll.is_some() {
+		overrides.invert_scroll = options.invert_scroll;
+	}
+}
+
+/// Longest gap between the two clicks of a double click
+pub fn double_click_time() -> Duration {
+	if let Some(ms) = OVERRIDES.read_recover().double_click_ms {
+		return Duration::from_millis(ms);
+	}
+	OS_DEFAULTS.read_recover().double_click.unwrap_or(DEFAULT_DOUBLE_CLICK)
+}
+
+/// Pointer travel that starts a drag
+pub fn drag_distance() -> f32 {
+	if let Some(distance) = OVERRIDES.read_recover().drag_distance {
+		return distance;
+	}
+	OS_DEFAULTS.read_recover().drag_distance.unwrap_or(DEFAULT_DRAG_DISTANCE)
+}
+
+/// Wheel delta with the multiplier and direction applied
+pub fn scroll_delta(x: f32, y: f32) -> (f32, f32) {
+	let overrides = OVERRIDES.read_recover();
+	let mut factor = overrides.scroll_multiplier.unwrap_or(1.0);
+	if overrides.invert_scroll == Some(true) {
+		factor = -factor;
+	}
+	(x * factor, y * factor)
+}
+
+/// Read the OS defaults; slow (it may run a command), so off the UI thread
+pub fn detect_os_defaults() {
+	let defaults = os_defaults();
+	log::debug!(
+		"[Rust] input settings: OS double click {:?}, drag distance {:?}",
+		defaults.double_click,
+		defaults.drag_distance
+	);
+	*OS_DEFAULTS.write_recover() = defaults;
+}
+
+#[cfg(target_os = "windows")]
+fn os_defaults() -> OsDefaults {
+	#[link(name = "user32")]
+	unsafe extern "system" {
+		fn GetDoubleClickTime() -> u32;
+		fn GetSystemMetrics(index: i32) -> i32;
+	}
+	const SM_CXDRAG: i32 = 68;
+
+	let (double_click, drag) = unsafe { (GetDoubleClickTime(), GetSystemMetrics(SM_CXDRAG)) };
+	OsDefaults {
+		double_click:  (double_click > 0).then(|| Duration::from_millis(double_click as u64)),
+		drag_distance: (drag > 0).then_some(drag as f32),
+	}
+}
+
+#[cfg(target_os = "macos")]
+fn os_defaults() -> OsDefaults {
+	// Seconds, e.g. "0.5"; missing until the user changes the setting
+	let double_click =
+		command_output("defaults", &["read", "-g", "com.apple.mouse.doubleClickThreshold"])
+			.and_then(|seconds| seconds.parse::<f64>().ok())
+			.filter(|seconds| *seconds > 0.0)
+			.map(Duration::from_secs_f64);
+	OsDefaults { double_click, drag_distance: None }
+}
+
+#[cfg(target_os = "linux")]
+fn os_defaults() -> OsDefaults {
+	// GNOME prints typed values, e.g. "int32 400" or "8"
+	let read = |schema: &str, key: &str| {
+		command_output("gsettings", &["get", schema, key])?
+			.split_whitespace()
+			.last()?
+			.parse::<u32>()
+			.ok()
+			.filter(|value| *value > 0)
+	};
+	OsDefaults {
+		double_click:  read("org.gnome.desktop.peripherals.mouse", "double-click")
+			.map(|ms| Duration::from_millis(ms as u64)),
+		drag_distance: read("org.gnome.settings-daemon.peripherals.mouse", "drag-threshold")
+			.map(|pixels| pixels as f32),
+	}
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+fn os_defaults() -> OsDefaults { OsDefaults::default() }
+
+/// Trimmed stdout of a command that succeeded
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+	let output = std::process::Command::new(program).args(args).output().ok()?;
+	if !output.status.success() {
+		return None;
+	}
+	Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
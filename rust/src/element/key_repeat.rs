@@ -0,0 +1,183 @@
+//! Key repeat counting and synthesized repeats
+//!
+//! Every keydown carries a `repeatCount`: 0 for the press, then 1, 2, ... for
+//! repeats while the key stays down. Platforms deliver repeats for held keys
+//! at their own pace (and some backends not at all), so
+//! `gpui_set_key_repeat` can take over repeats of the editing keys
+//! (backspace, delete, arrows, home/end, page up/down): OS repeats of those
+//! are dropped and keydowns are synthesized after `delayMs`, `rate` times per
+//! second, until the key is released or the window loses focus.
+
+use std::{collections::HashMap, sync::{Mutex, RwLock, atomic::{AtomicU64, Ordering}}, time::Duration};
+
+use gpui::{App, Window};
+use lazy_static::lazy_static;
+
+use super::focus;
+use crate::{event_types::{EventData, KeyboardEventData, types}, renderer::dispatch_event_to_js, sync::{MutexExt, RwLockExt}};
+
+/// Codes of the keys whose repeats are synthesized when enabled
+const EDITING_KEYS: &[&str] = &[
+	"Backspace",
+	"Delete",
+	"ArrowLeft",
+	"ArrowRight",
+	"ArrowUp",
+	"ArrowDown",
+	"Home",
+	"End",
+	"PageUp",
+	"PageDown",
+];
+
+#[derive(Debug, Default, serde::Deserialize)]
+pub struct KeyRepeatOptions {
+	/// Synthesize repeats of editing keys instead of using the platform's
+	pub synthesize: Option<bool>,
+	/// Delay before the first synthesized repeat
+	#[serde(rename = "delayMs")]
+	pub delay_ms:   Option<u64>,
+	/// Synthesized repeats per second
+	pub rate:       Option<f32>,
+}
+
+struct Settings {
+	synthesize: bool,
+	delay:      Duration,
+	interval:   Duration,
+}
+
+/// The key held down in a window
+struct Held {
+	/// DOM code, which unlike the key doesn't change with shift
+	code:       String,
+	count:      u32,
+	/// Distinguishes presses, so a stale repeat loop stops
+	generation: u64,
+}
+
+/// Generation of the next press, unique across presses and windows; 0 is
+/// left to keys whose repeats come from the platform
+static NEXT_GENERATION: AtomicU64 = AtomicU64::new(1);
+
+lazy_static! {
+	static ref SETTINGS: RwLock<Settings> = RwLock::new(Settings {
+		synthesize: false,
+		delay:      Duration::from_millis(500),
+		interval:   Duration::from_millis(33),
+	});
+	static ref HELD: Mutex<HashMap<u64, Held>> = Mutex::new(HashMap::new());
+}
+
+/// Drop all state of a closed window
+pub fn forget_window(window_id: u64) { HELD.lock_recover().remove(&window_id); }
+
+pub fn configure(options: &KeyRepeatOptions) {
+	let mut settings = SETTINGS.write_recover();
+	if let Some(synthesize) = options.synthesize {
+		settings.synthesize = synthesize;
+	}
+	if let Some(delay_ms) = options.delay_ms {
+		settings.delay = Duration::from_millis(delay_ms);
+	}
+	// Rates too low for a Duration are ignored, as are non-positive ones
+	if let Some(interval) = options
+		.rate
+		.filter(|rate| *rate > 0.0)
+		.and_then(|rate| Duration::try_from_secs_f32(1.0 / rate).ok())
+	{
+		settings.interval = interval;
+	}
+}
+
+/// Fill in the repeat count of a keydown about to be dispatched
+/// Returns false if the event should be dropped: an OS repeat of a key whose
+/// repeats are synthesized. Starts the synthesized repeats on a fresh press
+pub fn key_down(
+	window_id: u64,
+	data: &mut KeyboardEventData,
+	window: &mut Window,
+	cx: &mut App,
+) -> bool {
+	let (synthesize, delay, interval) = {
+		let settings = SETTINGS.read_recover();
+		(
+			settings.synthesize && EDITING_KEYS.contains(&data.code.as_str()),
+			settings.delay,
+			settings.interval,
+		)
+	};
+
+	let mut held = HELD.lock_recover();
+	if data.repeat {
+		if synthesize {
+			return false;
+		}
+		match held.get_mut(&window_id) {
+			Some(current) if current.code == data.code => {
+				current.count += 1;
+				data.repeat_count = current.count;
+			}
+			_ => {
+				held.insert(window_id, Held {
+					code:       data.code.clone(),
+					count:      1,
+					generation: 0,
+				});
+				data.repeat_count = 1;
+			}
+		}
+		return true;
+	}
+
+	let generation = NEXT_GENERATION.fetch_add(1, Ordering::Relaxed);
+	held.insert(window_id, Held { code: data.code.clone(), count: 0, generation });
+	data.repeat_count = 0;
+	drop(held);
+
+	if synthesize {
+		let template = data.clone();
+		window
+			.spawn(cx, async move |cx| {
+				let mut wait = delay;
+				loop {
+					cx.background_executor().timer(wait).await;
+					wait = interval;
+					let active = cx.update(|window, _| window.is_window_active()).unwrap_or(false);
+					let Some(count) = next_repeat(window_id, generation, active) else {
+						break;
+					};
+					let Some(element_id) = focus::get_focused(window_id) else {
+						continue;
+					};
+					let data = KeyboardEventData { repeat: true, repeat_count: count, ..template.clone() };
+					dispatch_event_to_js(window_id, element_id, types::KEYDOWN, EventData::Keyboard(data));
+				}
+			})
+			.detach();
+	}
+	true
+}
+
+/// A key was released, ending its repeats
+pub fn key_up(window_id: u64, code: &str) {
+	let mut held = HELD.lock_recover();
+	if held.get(&window_id).is_some_and(|current| current.code == code) {
+		held.remove(&window_id);
+	}
+}
+
+/// Count of the next synthesized repeat, None once the press is over
+fn next_repeat(window_id: u64, generation: u64, active: bool) -> Option<u32> {
+	let mut held = HELD.lock_recover();
+	if held.get(&window_id).is_none_or(|current| current.generation != generation) {
+		return None;
+	}
+	if !active {
+		held.remove(&window_id);
+		return None;
+	}
+	let current = held.get_mut(&window_id)?;
+	current.count += 1;
+	Some(current.count)
+}
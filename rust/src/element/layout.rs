@@ -0,0 +1,369 @@
+//! Resolved layout bounds of painted elements
+//!
+//! Every element records its bounds during prepaint. `gpui_get_layout_snapshot`
+//! walks the element tree against this table and serializes it (id, kind,
+//! bounds, computed style subset) to stable JSON, so layout can be compared
+//! across runs.
+
+use std::{collections::HashMap, sync::Mutex};
+
+use gpui::{Bounds, Pixels, Point};
+use lazy_static::lazy_static;
+use serde_json::{Map, Value, json};
+
+use super::{ElementStyle, ReactElement};
+use crate::sync::MutexExt;
+
+lazy_static! {
+	/// Bounds recorded in the last frame, keyed by window then element id
+	static ref BOUNDS: Mutex<HashMap<u64, HashMap<u64, Bounds<Pixels>>>> = Mutex::new(HashMap::new());
+}
+
+/// Drop all state of a closed window
+pub fn forget_window(window_id: u64) { BOUNDS.lock_recover().remove(&window_id); }
+
+/// Drop the bounds of a released element, whose id may be recycled
+pub fn forget_element(window_id: u64, element_id: u64) {
+	if let Some(bounds) = BOUNDS.lock_recover().get_mut(&window_id) {
+		bounds.remove(&element_id);
+	}
+}
+
+/// Forget the previous frame's bounds for a window
+pub fn begin_frame(window_id: u64) { BOUNDS.lock_recover().entry(window_id).or_default().clear(); }
+
+/// Record an element's bounds, called from prepaint
+pub fn record_bounds(window_id: u64, element_id: u64, bounds: Bounds<Pixels>) {
+	BOUNDS.lock_recover().entry(window_id).or_default().insert(element_id, bounds);
+}
+
+/// Bounds an element painted at in the last frame
+pub fn bounds_of(window_id: u64, element_id: u64) -> Option<Bounds<Pixels>> {
+	BOUNDS.lock_recover().get(&window_id)?.get(&element_id).copied()
+}
+
+/// Elements of the tree whose last painted bounds contain `position`,
+/// innermost first, with their bounds
+pub fn stack_at(
+	window_id: u64,
+	root: &ReactElement,
+	position: Point<Pixels>,
+) -> Vec<(&ReactElement, Bounds<Pixels>)> {
+	let windows = BOUNDS.lock_recover();
+	let Some(bounds) = windows.get(&window_id) else {
+		return Vec::new();
+	};
+	let mut stack = Vec::new();
+	collect_stack(root, position, bounds, &mut stack);
+	stack.reverse();
+	stack
+}
+
+fn collect_stack<'a>(
+	element: &'a ReactElement,
+	position: Point<Pixels>,
+	bounds: &HashMap<u64, Bounds<Pixels>>,
+	stack: &mut Vec<(&'a ReactElement, Bounds<Pixels>)>,
+) {
+	if let Some(b) = bounds.get(&element.global_id)
+		&& b.contains(&position)
+	{
+		stack.push((element, *b));
+	}
+	// Children can overflow their parent, so they are searched regardless
+	for child in &element.children {
+		collect_stack(child, position, bounds, stack);
+	}
+}
+
+/// Serialize the layout of a tree rooted at `root`
+/// Keys come in a fixed order and numbers are rounded to 1/100 px so output
+/// is stable
+pub fn snapshot(window_id: u64, root: &ReactElement) -> Value {
+	let windows = BOUNDS.lock_recover();
+	let empty = HashMap::new();
+	let bounds = windows.get(&window_id).unwrap_or(&empty);
+	snapshot_element(root, None, bounds)
+}
+
+fn snapshot_element(
+	element: &ReactElement,
+	parent_style: Option<&ElementStyle>,
+	bounds: &HashMap<u64, Bounds<Pixels>>,
+) -> Value {
+	let effective = element.effective_style(parent_style);
+	let children: Vec<Value> = element
+		.children
+		.iter()
+		.map(|child| snapshot_element(child, Some(&effective), bounds))
+		.collect();
+
+	json!({
+		"id": element.global_id,
+		"type": element.element_type,
+		"bounds": bounds.get(&element.global_id).map(|b| json!({
+			"x": round(b.origin.x.into()),
+			"y": round(b.origin.y.into()),
+			"width": round(b.size.width.into()),
+			"height": round(b.size.height.into()),
+		})),
+		"style": computed_style(&effective),
+		"children": children,
+	})
+}
+
+/// The subset of computed style that affects layout and inheritance
+fn computed_style(style: &ElementStyle) -> Value {
+	let mut map = Map::new();
+	let mut put_str = |key: &str, value: &Option<String>| {
+		if let Some(v) = value {
+			map.insert(key.to_string(), Value::String(v.clone()));
+		}
+	};
+	put_str("display", &style.display);
+	put_str("flexDirection", &style.flex_direction);
+	put_str("flexWrap", &style.flex_wrap);
+	put_str("justifyContent", &style.justify_content);
+	put_str("alignItems", &style.align_items);
+	put_str("alignSelf", &style.align_self);
+	put_str("position", &style.position);
+	put_str("overflowX", &style.overflow_x);
+	put_str("overflowY", &style.overflow_y);
+	put_str("fontFamily", &style.font_family);
+
+	let numbers = [
+		("width", style.width),
+		("height", style.height),
+		("minWidth", style.min_width),
+		("maxWidth", style.max_width),
+		("minHeight", style.min_height),
+		("maxHeight", style.max_height),
+		("flexGrow", style.flex_grow),
+		("flexShrink", style.flex_shrink),
+		("flexBasis", style.flex_basis),
+		("gap", style.gap),
+		("top", style.top),
+		("right", style.right),
+		("bottom", style.bottom),
+		("left", style.left),
+		("textSize", style.text_size),
+		("lineHeight", style.line_height),
+	];
+	for (key, value) in numbers {
+		if let Some(v) = value {
+			map.insert(key.to_string(), json!(round(v)));
+		}
+	}
+	if let Some(weight) = style.font_weight {
+		map.insert("fontWeight".to_string(), json!(weight));
+	}
+	if let Some(color) = style.text_color {
+		// #rrggbb, with an alpha byte only when the color isn't opaque
+		let color = color.to_rgb();
+		let channel = |c: f32| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+		let mut hex =
+			format!("#{:02x}{:02x}{:02x}", channel(color.r), channel(color.g), channel(color.b));
+		if channel(color.a) < 255 {
+			hex.push_str(&format!("{:02x}", channel(color.a)));
+		}
+		map.insert("textColor".to_string(), json!(hex));
+	}
+	Value::Object(map)
+}
+
+fn round(v: f32) -> f64 { (v as f64 * 100.0).round() / 100.0 }
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+
+	use gpui::{TestAppContext, point, px, size};
+
+	use super::*;
+	use crate::element::{ElementKind, create_element};
+
+	fn element(
+		id: u64,
+		element_type: &str,
+		style: Value,
+		children: Vec<ReactElement>,
+	) -> ReactElement {
+		ReactElement {
+			global_id:         id,
+			element_type:      element_type.to_string(),
+			element_kind:      ElementKind::from_str(element_type),
+			text:              None,
+			children:          children.into_iter().map(Arc::new).collect(),
+			style:             Arc::new(ElementStyle::from_json(&style)),
+			event_handlers:    None,
+			cached_gpui_style: None,
+		}
+	}
+
+	/// Lay out and prepaint `root` in a headless window of the given size,
+	/// then snapshot the bounds its elements recorded
+	fn layout(cx: &mut TestAppContext, window_id: u64, root: ReactElement, space: [f32; 2]) -> Value {
+		let root = Arc::new(root);
+		let cx = cx.add_empty_window();
+		begin_frame(window_id);
+		cx.draw(point(px(0.0), px(0.0)), size(px(space[0]), px(space[1])), |_, _| {
+			create_element(root.clone(), window_id, None)
+		});
+		snapshot(window_id, &root)
+	}
+
+	fn bounds(x: f64, y: f64, width: f64, height: f64) -> Value {
+		json!({ "x": x, "y": y, "width": width, "height": height })
+	}
+
+	#[gpui::test]
+	fn flexbox_row(cx: &mut TestAppContext) {
+		let root = element(
+			1,
+			"div",
+			json!({ "display": "flex", "flexDirection": "row", "gap": 8, "alignItems": "center", "width": 200 }),
+			vec![
+				element(2, "div", json!({ "width": 40, "height": 20 }), vec![]),
+				element(3, "div", json!({ "flexGrow": 1, "height": 30 }), vec![]),
+			],
+		);
+		let snapshot = layout(cx, 101, root, [400.0, 300.0]);
+		assert_eq!(
+			serde_json::to_string(&snapshot).unwrap(),
+			concat!(
+				r#"{"id":1,"type":"div","bounds":{"x":0.0,"y":0.0,"width":200.0,"height":30.0},"#,
+				r#""style":{"display":"flex","flexDirection":"row","alignItems":"center","width":200.0,"gap":8.0},"#,
+				r#""children":[{"id":2,"type":"div","bounds":{"x":0.0,"y":5.0,"width":40.0,"height":20.0},"#,
+				r#""style":{"width":40.0,"height":20.0},"children":[]},"#,
+				r#"{"id":3,"type":"div","bounds":{"x":48.0,"y":0.0,"width":152.0,"height":30.0},"#,
+				r#""style":{"height":30.0,"flexGrow":1.0},"children":[]}]}"#,
+			)
+		);
+	}
+
+	#[gpui::test]
+	fn flexbox_column_justify(cx: &mut TestAppContext) {
+		let root = element(
+			1,
+			"div",
+			json!({ "display": "flex", "flexDirection": "column", "justifyContent": "space-between", "width": 50, "height": 100 }),
+			vec![
+				element(2, "div", json!({ "height": 10 }), vec![]),
+				element(3, "div", json!({ "height": 20 }), vec![]),
+			],
+		);
+		let snapshot = layout(cx, 102, root, [400.0, 300.0]);
+		// Stretched across the column, pushed to both ends
+		assert_eq!(snapshot["children"][0]["bounds"], bounds(0.0, 0.0, 50.0, 10.0));
+		assert_eq!(snapshot["children"][1]["bounds"], bounds(0.0, 80.0, 50.0, 20.0));
+	}
+
+	#[gpui::test]
+	fn inherited_text_style(cx: &mut TestAppContext) {
+		let text = element(3, "div", json!({ "textSize": 12 }), vec![]);
+		let root = element(
+			1,
+			"div",
+			json!({ "textColor": 0xff0000ffu32, "textSize": 14, "fontFamily": "Inter", "width": 100 }),
+			vec![element(2, "div", json!({ "height": 10 }), vec![text])],
+		);
+		let snapshot = layout(cx, 103, root, [400.0, 300.0]);
+		let child = &snapshot["children"][0];
+		assert_eq!(
+			child["style"],
+			json!({ "fontFamily": "Inter", "height": 10.0, "textSize": 14.0, "textColor": "#ff0000" })
+		);
+		assert_eq!(
+			child["children"][0]["style"],
+			json!({ "fontFamily": "Inter", "textSize": 12.0, "textColor": "#ff0000" })
+		);
+		// Block children fill the parent's width
+		assert_eq!(child["bounds"], bounds(0.0, 0.0, 100.0, 10.0));
+	}
+
+	#[gpui::test]
+	fn constraints(cx: &mut TestAppContext) {
+		let root = element(
+			1,
+			"div",
+			json!({ "display": "flex", "flexDirection": "row", "width": 300, "height": 50 }),
+			vec![
+				element(2, "div", json!({ "width": 250, "maxWidth": 120.456, "minHeight": 10, "maxHeight": 30 }), vec![]),
+				element(3, "div", json!({ "width": 5, "minWidth": 20, "height": 5 }), vec![]),
+			],
+		);
+		let snapshot = layout(cx, 104, root, [400.0, 300.0]);
+		assert_eq!(
+			snapshot["children"][0]["style"],
+			json!({ "width": 250.0, "maxWidth": 120.46, "minHeight": 10.0, "maxHeight": 30.0 })
+		);
+		// Stretched to the row height, then held to maxHeight; taffy rounds the
+		// width to the test window's device pixels (scale factor 2)
+		assert_eq!(snapshot["children"][0]["bounds"], bounds(0.0, 0.0, 120.5, 30.0));
+		assert_eq!(snapshot["children"][1]["bounds"], bounds(120.5, 0.0, 20.0, 5.0));
+	}
+
+	#[gpui::test]
+	fn positioning(cx: &mut TestAppContext) {
+		let root =
+			element(1, "div", json!({ "position": "relative", "width": 100, "height": 100 }), vec![
+				element(
+					2,
+					"div",
+					json!({ "position": "absolute", "top": 10, "left": 20, "width": 30, "height": 40 }),
+					vec![],
+				),
+				element(3, "div", json!({ "height": 15 }), vec![]),
+			]);
+		let snapshot = layout(cx, 105, root, [400.0, 300.0]);
+		assert_eq!(
+			snapshot["children"][0]["style"],
+			json!({ "position": "absolute", "width": 30.0, "height": 40.0, "top": 10.0, "left": 20.0 })
+		);
+		assert_eq!(snapshot["children"][0]["bounds"], bounds(20.0, 10.0, 30.0, 40.0));
+		// Out of flow: the sibling starts at the top
+		assert_eq!(snapshot["children"][1]["bounds"], bounds(0.0, 0.0, 100.0, 15.0));
+	}
+
+	#[gpui::test]
+	fn released_elements_have_no_bounds(cx: &mut TestAppContext) {
+		let root = element(1, "div", json!({ "width": 10, "height": 10 }), vec![]);
+		let snapshot = layout(cx, 106, root.clone(), [400.0, 300.0]);
+		assert_eq!(snapshot["bounds"], bounds(0.0, 0.0, 10.0, 10.0));
+		forget_element(106, 1);
+		assert_eq!(super::snapshot(106, &root)["bounds"], Value::Null);
+	}
+
+	#[gpui::test]
+	fn textarea_rows_and_cols(cx: &mut TestAppContext) {
+		// Advances of the test text system are 0.6 of the text size
+		let text = |lines: usize| (1..=lines).map(|n| n.to_string()).collect::<Vec<_>>().join("\n");
+		let root = element(
+			1,
+			"div",
+			json!({ "display": "flex", "flexDirection": "column", "alignItems": "flex-start" }),
+			vec![
+				element(2, "textarea", json!({ "textSize": 10, "rows": 3, "cols": 10, "value": text(3) }), vec![]),
+				// A gutter for three digits and the padding are added
+				element(
+					3,
+					"textarea",
+					json!({
+						"textSize": 10, "lineNumbers": true, "value": text(120),
+						"paddingTop": 4, "paddingRight": 4, "paddingBottom": 4, "paddingLeft": 4,
+					}),
+					vec![],
+				),
+				element(4, "textarea", json!({ "textSize": 10, "width": 50, "height": 30, "rows": 8 }), vec![]),
+				// Multi-line inputs are sized the same way
+				element(5, "input", json!({ "textSize": 10, "multiLine": true, "rows": 2, "cols": 5 }), vec![]),
+			],
+		);
+		let snapshot = layout(cx, 107, root, [400.0, 300.0]);
+		let children = &snapshot["children"];
+		assert_eq!(children[0]["bounds"], bounds(0.0, 0.0, 60.0, 42.0));
+		assert_eq!(children[1]["bounds"], bounds(0.0, 42.0, 158.0, 36.0));
+		assert_eq!(children[2]["bounds"], bounds(0.0, 78.0, 50.0, 30.0));
+		assert_eq!(children[3]["bounds"], bounds(0.0, 108.0, 30.0, 28.0));
+	}
+}
@@ -0,0 +1,157 @@
+//! ARIA-style live region announcements
+//!
+//! An element with `liveRegion: "polite"` or `"assertive"` announces changes
+//! to its text. The first frame painted after each commit collects the text
+//! of every region's subtree (nested regions, hidden and inert content left
+//! out) and queues a window-level `announce` event for each region whose text
+//! changed, assertive ones first. A region's initial text isn't announced, like
+//! ARIA. gpui has no platform accessibility tree yet, so speaking the
+//! announcement (screen reader or TTS) is left to the listener on the JS side.
+//!
+//! Windows are only walked once some style with `liveRegion` has been seen.
+
+use std::{collections::HashMap, sync::{Mutex, atomic::{AtomicBool, Ordering}}};
+
+use lazy_static::lazy_static;
+use serde_json::json;
+
+use super::{ElementStyle, ReactElement};
+use crate::{global_state::GLOBAL_STATE, renderer::dispatch_window_event, sync::MutexExt};
+
+static ANY_REGION: AtomicBool = AtomicBool::new(false);
+
+/// Announced text of the live regions of one window
+struct Regions {
+	/// Tree generation last checked
+	generation: u64,
+	texts:      HashMap<u64, String>,
+}
+
+lazy_static! {
+	static ref WINDOWS: Mutex<HashMap<u64, Regions>> = Mutex::new(HashMap::new());
+}
+
+/// Drop all state of a closed window
+pub fn forget_window(window_id: u64) { WINDOWS.lock_recover().remove(&window_id); }
+
+/// Drop the text of a released region, so a recycled id starts fresh
+pub fn forget_element(window_id: u64, element_id: u64) {
+	if let Some(regions) = WINDOWS.lock_recover().get_mut(&window_id) {
+		regions.texts.remove(&element_id);
+	}
+}
+
+/// Note a newly parsed style, enabling the per-commit walk once a region exists
+pub fn note_style(style: &ElementStyle) {
+	if politeness(style).is_some() {
+		ANY_REGION.store(true, Ordering::Relaxed);
+	}
+}
+
+fn politeness(style: &ElementStyle) -> Option<&'static str> {
+	match style.live_region.as_deref() {
+		Some("polite") => Some("polite"),
+		Some("assertive") => Some("assertive"),
+		_ => None,
+	}
+}
+
+/// Announce regions whose text changed if a commit landed since the last check
+/// Called after the window's element tree painted
+pub fn after_paint(window_id: u64) {
+	if !ANY_REGION.load(Ordering::Relaxed) {
+		return;
+	}
+	let Some(window) = GLOBAL_STATE.get_window(window_id) else {
+		return;
+	};
+	let generation = window.state().get_tree_generation();
+	let checked = WINDOWS.lock_recover().get(&window_id).map(|regions| regions.generation);
+	if checked == Some(generation) {
+		return;
+	}
+	let Some(root) = window.state().element_tree.lock_recover().clone() else {
+		return;
+	};
+
+	let mut current = Vec::new();
+	collect_regions(&root, false, &mut current);
+
+	let mut announcements = Vec::new();
+	{
+		let mut windows = WINDOWS.lock_recover();
+		let regions =
+			windows.entry(window_id).or_insert_with(|| Regions { generation, texts: HashMap::new() });
+		regions.generation = generation;
+		let mut texts = HashMap::with_capacity(current.len());
+		for (element_id, politeness, text) in current {
+			let previous = regions.texts.get(&element_id);
+			if previous.is_some_and(|previous| *previous != text) && !text.is_empty() {
+				announcements.push((element_id, politeness, text.clone()));
+			}
+			texts.insert(element_id, text);
+		}
+		regions.texts = texts;
+	}
+
+	// Assertive announcements interrupt, so they go first
+	announcements.sort_by_key(|(_, politeness, _)| *politeness != "assertive");
+	for (element_id, politeness, text) in announcements {
+		dispatch_window_event(
+			window_id,
+			"announce",
+			json!({ "targetId": element_id, "text": text, "politeness": politeness }),
+		);
+	}
+}
+
+/// Find visible live regions and their text
+fn collect_regions(
+	element: &ReactElement,
+	parent_hidden: bool,
+	regions: &mut Vec<(u64, &'static str, String)>,
+) {
+	// Inert content is hidden from assistive technology
+	if element.style.inert == Some(true) {
+		return;
+	}
+	let hidden = is_hidden(element, parent_hidden);
+	if !hidden && let Some(politeness) = politeness(&element.style) {
+		let mut parts = Vec::new();
+		collect_text(element, parent_hidden, true, &mut parts);
+		regions.push((element.global_id, politeness, parts.join(" ")));
+	}
+	for child in &element.children {
+		collect_regions(child, hidden, regions);
+	}
+}
+
+/// Visible text of a region's subtree, without nested regions
+fn collect_text(
+	element: &ReactElement,
+	parent_hidden: bool,
+	is_region: bool,
+	parts: &mut Vec<String>,
+) {
+	if (!is_region && politeness(&element.style).is_some()) || element.style.inert == Some(true) {
+		return;
+	}
+	let hidden = is_hidden(element, parent_hidden);
+	if !hidden
+		&& let Some(text) = element.text.as_deref().map(str::trim).filter(|text| !text.is_empty())
+	{
+		parts.push(text.to_string());
+	}
+	for child in &element.children {
+		collect_text(child, hidden, false, parts);
+	}
+}
+
+/// `visibility` is inherited, and a child can make itself visible again
+fn is_hidden(element: &ReactElement, parent_hidden: bool) -> bool {
+	match element.style.visibility.as_deref() {
+		Some("hidden") => true,
+		Some(_) => false,
+		None => parent_hidden,
+	}
+}
@@ -0,0 +1,111 @@
+//! Coalesced layout during interactive window resizes
+//!
+//! Enabled per window with `gpui_set_live_resize` and an interval in
+//! milliseconds. While the window is being resized, the tree keeps the size
+//! of its last layout and is only laid out again at the new size once per
+//! interval; in between, frames reuse the held size, so text keeps its line
+//! breaks and its cached shaping instead of rewrapping on every step of the
+//! drag. Where the window grew, the held layout leaves the window background
+//! showing; where it shrank, the layout is clipped.
+//!
+//! The platform doesn't tell when the user lets go of the window edge, so a
+//! resize ends once the size has stayed put for [`SETTLE`]; the tree is then
+//! laid out at the final size. Window events "resizestart" and "resizeend"
+//! mark the two, with the window's width and height.
+
+use std::{collections::HashMap, sync::Mutex, time::Duration};
+
+use gpui::{Pixels, Size};
+use lazy_static::lazy_static;
+
+use crate::{clock, renderer::dispatch_window_event, sync::MutexExt};
+
+/// Time without size changes after which a resize is over
+const SETTLE: Duration = Duration::from_millis(150);
+
+struct LiveResize {
+	interval:   Duration,
+	/// Viewport of the previous frame
+	viewport:   Option<Size<Pixels>>,
+	/// While resizing: the size the tree was last laid out at, and when
+	laid_out:   Option<(Size<Pixels>, u64)>,
+	/// Time of the last size change
+	changed_at: u64,
+}
+
+/// How the root view lays out a frame
+#[derive(Default)]
+pub struct Frame {
+	/// Size to lay the tree out at instead of the viewport's
+	pub held: Option<Size<Pixels>>,
+	/// Render again after this long, for the next layout or the end of the
+	/// resize
+	pub wake: Option<Duration>,
+}
+
+lazy_static! {
+	static ref WINDOWS: Mutex<HashMap<u64, LiveResize>> = Mutex::new(HashMap::new());
+}
+
+/// Drop all state of a closed window
+pub fn forget_window(window_id: u64) { WINDOWS.lock_recover().remove(&window_id); }
+
+/// Lay out at most once per `interval_ms` while the window is being resized,
+/// 0 lays out every frame again
+pub fn set_interval(window_id: u64, interval_ms: u32) {
+	if interval_ms == 0 {
+		WINDOWS.lock_recover().remove(&window_id);
+		return;
+	}
+	let mut windows = WINDOWS.lock_recover();
+	let state = windows.entry(window_id).or_insert(LiveResize {
+		interval:   Duration::ZERO,
+		viewport:   None,
+		laid_out:   None,
+		changed_at: 0,
+	});
+	state.interval = Duration::from_millis(u64::from(interval_ms));
+}
+
+/// Note the viewport of a frame about to render and decide its layout size
+pub fn begin_frame(window_id: u64, viewport: Size<Pixels>) -> Frame {
+	let mut windows = WINDOWS.lock_recover();
+	let Some(state) = windows.get_mut(&window_id) else {
+		return Frame::default();
+	};
+	let now = clock::now_us();
+	let previous = state.viewport.replace(viewport);
+	if let Some(previous) = previous
+		&& previous != viewport
+	{
+		state.changed_at = now;
+		if state.laid_out.is_none() {
+			// Hold the layout the window had before the drag started
+			state.laid_out = Some((previous, now));
+			dispatch_window_event(window_id, "resizestart", size_json(previous));
+		}
+	}
+	let Some((size, at)) = state.laid_out else {
+		return Frame::default();
+	};
+
+	let settled_at = state.changed_at + SETTLE.as_micros() as u64;
+	if now >= settled_at {
+		state.laid_out = None;
+		dispatch_window_event(window_id, "resizeend", size_json(viewport));
+		return Frame::default();
+	}
+	let settle_wait = Duration::from_micros(settled_at - now);
+	let elapsed = Duration::from_micros(now - at);
+	if size != viewport && elapsed < state.interval {
+		return Frame { held: Some(size), wake: Some(settle_wait.min(state.interval - elapsed)) };
+	}
+	if size != viewport {
+		state.laid_out = Some((viewport, now));
+	}
+	Frame { held: None, wake: Some(settle_wait) }
+}
+
+fn size_json(size: Size<Pixels>) -> serde_json::Value {
+	serde_json::json!({ "width": f32::from(size.width), "height": f32::from(size.height) })
+}
@@ -1,28 +1,92 @@
 use std::sync::Arc;
 
-use gpui::{AlignContent, AlignItems, AlignSelf, AnyElement, BoxShadow, Context, Fill, FlexDirection, FlexWrap, Hsla, InteractiveElement, IntoElement, JustifyContent, Overflow, ParentElement, Position, Rgba, Style, Window, point, px, rgb};
+use gpui::{AlignContent, AlignItems, AlignSelf, AnyElement, BoxShadow, Context, Fill, FlexDirection, FlexWrap, Hsla, InteractiveElement, IntoElement, JustifyContent, Overflow, ParentElement, Position, Style, Window, point, px, rgb, rgba};
 use serde_json::Value;
 
+pub mod avatar;
+pub mod barcode;
+pub mod border;
+pub mod button;
 pub mod canvas;
+pub mod cascade;
+pub mod checkbox;
+pub mod code;
+pub mod content_visibility;
+pub mod contrast;
+pub mod dispatcher;
 pub mod div;
+pub mod document;
+pub mod elide;
 pub mod events;
+pub mod find_overlay;
 pub mod focus;
+pub mod fonts;
+pub mod gauge;
+pub mod highlight;
+pub mod hit_test;
 mod hover;
+pub mod hud;
+pub mod icon;
 pub mod img;
+pub mod inert;
 mod input;
+pub mod input_settings;
+pub mod key_repeat;
+pub mod layout;
+pub mod live_region;
+pub mod live_resize;
+pub mod overlay;
+pub mod pacing;
+pub mod paint_track;
+pub mod pixel_snap;
+pub mod progress;
+pub mod qr;
+pub mod qrcode;
+pub mod rate_limit;
+pub mod resizer;
+pub mod richtext;
+pub mod safe_area;
+pub mod shader_view;
+pub mod skeleton;
+pub mod slider;
 pub mod span;
+pub mod sparkline;
+pub mod splash;
+pub mod style_store;
+pub mod style_variants;
+pub mod tab_capture;
 pub mod text;
+pub mod text_cache;
+pub mod tooltip;
+pub mod vertical_text;
 
+pub use avatar::ReactAvatarElement;
+pub use button::ReactButtonElement;
 pub use canvas::ReactCanvasElement;
+pub use checkbox::ReactCheckboxElement;
+pub use code::ReactCodeElement;
 pub use div::ReactDivElement;
+pub use document::ReactDocumentElement;
+pub use gauge::ReactGaugeElement;
+pub use icon::ReactIconElement;
 pub use img::ReactImgElement;
+pub use overlay::ReactOverlayElement;
+pub use progress::ReactProgressElement;
+pub use qrcode::ReactQrCodeElement;
+pub use resizer::ReactResizerElement;
+pub use richtext::ReactRichTextElement;
+pub use shader_view::ReactShaderViewElement;
+pub use skeleton::ReactSkeletonElement;
+pub use slider::ReactSliderElement;
 pub use span::ReactSpanElement;
+pub use sparkline::ReactSparklineElement;
 pub use text::ReactTextElement;
+pub use tooltip::ReactTooltipElement;
 
-use crate::{element::input::input::ReactInputElement, renderer::RootView};
+use crate::{element::input::input::ReactInputElement, renderer::RootView, sync::MutexExt};
 
 /// Pre-computed element kind to avoid string matching every frame
-#[derive(Clone, Copy, PartialEq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum ElementKind {
 	Canvas,
 	Div,
@@ -30,6 +94,25 @@ pub enum ElementKind {
 	Span,
 	Text,
 	Img,
+	ShaderView,
+	Document,
+	TextArea,
+	Checkbox,
+	Radio,
+	Slider,
+	Progress,
+	Overlay,
+	Tooltip,
+	Resizer,
+	Gauge,
+	Sparkline,
+	QrCode,
+	Avatar,
+	Code,
+	RichText,
+	Skeleton,
+	Button,
+	Icon,
 	Unknown,
 }
 
@@ -42,6 +125,25 @@ impl ElementKind {
 			"span" => ElementKind::Span,
 			"text" => ElementKind::Text,
 			"img" => ElementKind::Img,
+			"shaderview" => ElementKind::ShaderView,
+			"document" => ElementKind::Document,
+			"textarea" => ElementKind::TextArea,
+			"checkbox" => ElementKind::Checkbox,
+			"radio" => ElementKind::Radio,
+			"slider" => ElementKind::Slider,
+			"progress" => ElementKind::Progress,
+			"overlay" => ElementKind::Overlay,
+			"tooltip" => ElementKind::Tooltip,
+			"resizer" => ElementKind::Resizer,
+			"gauge" => ElementKind::Gauge,
+			"sparkline" => ElementKind::Sparkline,
+			"qrcode" => ElementKind::QrCode,
+			"avatar" => ElementKind::Avatar,
+			"code" => ElementKind::Code,
+			"richtext" => ElementKind::RichText,
+			"skeleton" => ElementKind::Skeleton,
+			"button" => ElementKind::Button,
+			"icon" => ElementKind::Icon,
 			_ => ElementKind::Unknown,
 		}
 	}
@@ -54,16 +156,17 @@ pub struct ReactElement {
 	pub element_kind:      ElementKind, // Pre-computed for fast dispatch
 	pub text:              Option<String>,
 	pub children:          Vec<Arc<ReactElement>>,
-	pub style:             ElementStyle,
+	/// Shared with every element that has the same style props (style_store)
+	pub style:             Arc<ElementStyle>,
 	pub event_handlers:    Option<Value>,
 	/// Cached GPUI Style to avoid recomputing every frame
-	pub cached_gpui_style: Option<Style>,
+	pub cached_gpui_style: Option<Arc<Style>>,
 }
 
 impl ReactElement {
 	/// Get effective style with inheritance applied from parent
 	pub fn effective_style(&self, parent_style: Option<&ElementStyle>) -> ElementStyle {
-		let mut style = self.style.clone();
+		let mut style = ElementStyle::clone(&self.style);
 		if let Some(parent) = parent_style {
 			style.inherit_from(parent);
 		}
@@ -76,7 +179,7 @@ impl ReactElement {
 	pub fn build_gpui_style(&self, default_bg: Option<u32>) -> Style {
 		// Use cached style if available (pre-computed in batch_update_elements)
 		if let Some(ref cached) = self.cached_gpui_style {
-			return cached.clone();
+			return Style::clone(cached);
 		}
 		// Fallback: compute style (shouldn't normally happen)
 		self.style.build_gpui_style(default_bg)
@@ -86,7 +189,7 @@ impl ReactElement {
 #[derive(Clone, PartialEq, Default, Debug)]
 pub struct ElementStyle {
 	// Text properties (inheritable)
-	pub text_color:     Option<u32>,
+	pub text_color:     Option<Hsla>,
 	pub text_size:      Option<f32>,
 	pub font_weight:    Option<u32>, // 100-900
 	pub font_family:    Option<String>,
@@ -94,12 +197,21 @@ pub struct ElementStyle {
 	pub text_align:     Option<String>, // "left", "center", "right"
 	pub letter_spacing: Option<f32>,
 
+	/// CSS font-feature-settings, e.g. `"liga" 0, "tnum"`
+	pub font_feature_settings: Option<String>,
+	/// "horizontal-tb", "vertical-rl" or "vertical-lr"
+	pub writing_mode:          Option<String>,
+	/// "start", "middle" or "end": one line, elided to fit (elide.rs)
+	pub elide_mode:            Option<String>,
+
 	// Other inheritable properties
-	pub cursor:     Option<String>,
-	pub visibility: Option<String>, // "visible", "hidden"
+	pub cursor:          Option<String>,
+	pub visibility:      Option<String>, // "visible", "hidden"
+	/// "crispEdges" snaps bounds and border widths to device pixels (pixel_snap)
+	pub shape_rendering: Option<String>,
 
 	// Non-inheritable properties
-	pub bg_color: Option<u32>,
+	pub bg_color: Option<Hsla>,
 	pub width:    Option<f32>,
 	pub height:   Option<f32>,
 
@@ -133,25 +245,42 @@ pub struct ElementStyle {
 	pub overflow_x: Option<String>, // "visible", "hidden", "scroll", "clip"
 	pub overflow_y: Option<String>,
 
+	// Content visibility
+	pub content_visibility:       Option<String>, // "visible", "auto"
+	pub contain_intrinsic_width:  Option<f32>,
+	pub contain_intrinsic_height: Option<f32>,
+
 	// Border widths (4 sides)
 	pub border_top_width:    Option<f32>,
 	pub border_right_width:  Option<f32>,
 	pub border_bottom_width: Option<f32>,
 	pub border_left_width:   Option<f32>,
-	pub border_style:        Option<String>, // "solid", "dashed"
-	pub border_color:        Option<u32>,
-	pub border_top_color:    Option<u32>,
-	pub border_right_color:  Option<u32>,
-	pub border_bottom_color: Option<u32>,
-	pub border_left_color:   Option<u32>,
+	pub border_style:        Option<String>, // "solid", "dashed", "dotted", "none"
+	pub border_top_style:    Option<String>,
+	pub border_right_style:  Option<String>,
+	pub border_bottom_style: Option<String>,
+	pub border_left_style:   Option<String>,
+	pub border_color:        Option<Hsla>,
+	pub border_top_color:    Option<Hsla>,
+	pub border_right_color:  Option<Hsla>,
+	pub border_bottom_color: Option<Hsla>,
+	pub border_left_color:   Option<Hsla>,
 	pub border_radius:       Option<f32>,
 
+	// Outline, painted outside the border box without affecting layout
+	pub outline_width:  Option<f32>,
+	pub outline_color:  Option<Hsla>,
+	pub outline_offset: Option<f32>,
+	pub outline_style:  Option<String>, // "solid", "dashed", "dotted", "none"
+
 	// Box shadow
 	pub box_shadow_offset_x: Option<f32>,
 	pub box_shadow_offset_y: Option<f32>,
 	pub box_shadow_blur:     Option<f32>,
 	pub box_shadow_spread:   Option<f32>,
-	pub box_shadow_color:    Option<u32>,
+	pub box_shadow_color:    Option<Hsla>,
+	pub elevation:           Option<f32>, // 0-24, expands to a key + ambient shadow pair
+	pub elevation_tint:      Option<bool>, // Lighten the background with elevation (dark themes)
 
 	// Flexbox
 	pub display:         Option<String>,
@@ -169,55 +298,146 @@ pub struct ElementStyle {
 	pub column_gap:      Option<f32>,
 
 	// Other
-	pub opacity:       Option<f32>,
-	pub src:           Option<String>,
-	pub alt:           Option<String>,
-	pub draw_commands: Option<serde_json::Value>,
-	pub x:             Option<f32>,
-	pub y:             Option<f32>,
+	pub opacity:         Option<f32>,
+	pub src:             Option<String>,
+	pub alt:             Option<String>,
+	pub draw_commands:   Option<serde_json::Value>,
+	/// Canvas commands add to what is drawn, once per `canvas_batch`
+	pub canvas_retained: Option<bool>,
+	pub canvas_batch:    Option<u64>,
+	pub x:               Option<f32>,
+	pub y:               Option<f32>,
 
 	// Focus properties
 	pub tab_index: Option<i32>,
 
 	// Input element properties
-	pub value:           Option<String>,
-	pub placeholder:     Option<String>,
-	pub input_type:      Option<String>, // "text", "password", "number", "email"
-	pub disabled:        Option<bool>,
-	pub read_only:       Option<bool>,
-	pub max_length:      Option<usize>,
-	pub multi_line:      Option<bool>,  // Enable multi-line mode
-	pub rows:            Option<usize>, // Number of visible rows
-	pub selection_color: Option<u32>,   // Selection background color
-
-	// Hover style
-	pub hover_style: Option<Box<ElementStyle>>,
+	pub value:            Option<String>,
+	pub placeholder:      Option<String>,
+	pub input_type:       Option<String>, // "text", "password", "number", "email"
+	pub disabled:         Option<bool>,
+	pub read_only:        Option<bool>,
+	pub max_length:       Option<usize>,
+	pub multi_line:       Option<bool>,  // Enable multi-line mode
+	pub rows:             Option<usize>, // Number of visible rows
+	pub cols:             Option<usize>, // Textarea width in "0" advances
+	pub line_numbers:     Option<bool>,  // Line-number gutter of documents and textareas
+	pub selection_color:  Option<Hsla>,  // Selection background color
+	pub capture_tab:      Option<bool>,  // Tab indents instead of moving focus (tab_capture)
+	pub tab_size:         Option<usize>, // Spaces per indent
+	pub indent_with_tabs: Option<bool>,  // Indent with a tab character instead of spaces
+
+	// Checkbox and radio properties
+	pub checked:      Option<bool>,
+	pub name:         Option<String>, // Radios with the same name uncheck each other
+	pub accent_color: Option<Hsla>,   // Fill of a checked control, slider, progress bar or gauge arc
+
+	// Slider, progress, gauge and sparkline properties
+	pub min:  Option<f64>,
+	pub max:  Option<f64>,
+	pub step: Option<f64>,
+
+	// Gauge properties
+	pub gauge_sweep:  Option<f32>, // Degrees of arc, 270 by default; 360 for a ring
+	pub gauge_ticks:  Option<u32>,
+	pub gauge_needle: Option<bool>,
+
+	// Sparkline properties
+	pub sparkline_capacity: Option<usize>, // Values kept, newest last
+	pub sparkline_interval: Option<u64>,   // Ms between checks for pushed values
+
+	// QR code properties
+	pub symbology:   Option<String>, // "qr" (default), "code128" or "ean13"
+	pub qr_ec_level: Option<String>, // "L", "M", "Q" or "H"
+	pub quiet_zone:  Option<u32>,    // Light modules around the code
+	pub logo_size:   Option<f32>,    // Cleared middle square, as a fraction of the width
+
+	// Avatar properties
+	pub avatar_status: Option<String>, // "online", "away", "busy" or "offline"
+
+	// Code properties
+	pub code_text:     Option<String>, // The source shown, from the `text` prop
+	pub code_language: Option<String>, // Highlighting, none for an unknown language
+
+	// Rich text properties
+	pub rich_text: Option<Arc<richtext::RichText>>, // Joined runs and their styles
+
+	// Skeleton properties
+	pub skeleton_variant: Option<String>, // "rect" (default), "text" or "circle"
+
+	// Icon properties
+	pub icon_codepoint: Option<u32>, // Glyph to paint
+	pub icon_size:      Option<f32>, // Glyph size and default box size in px
+
+	// Resizer properties
+	pub orientation: Option<String>, // "vertical" (default, resizes across) or "horizontal"
+
+	// Overlay properties
+	pub backdrop: Option<Hsla>, // Fills the window behind an overlay and blocks the pointer
+
+	// Tooltip properties
+	pub tooltip_delay:     Option<u64>, // Hover time in ms before it shows
+	pub tooltip_placement: Option<String>, // "top", "bottom", "left" or "right"
+
+	// Shader view properties
+	pub shader:        Option<String>, // Registered painter name
+	pub shader_params: Option<Value>,
+	pub animate:       Option<bool>, // Request a new frame after every paint
+
+	// Per-handler throttle/debounce, keyed by handler prop name
+	pub event_options: Option<Value>,
+
+	// Keep text and values out of logs (and event payloads, if configured)
+	pub privacy_mode: Option<bool>,
+
+	// Report paint changes of this subtree (see paint_track)
+	pub track_paint: Option<bool>,
+
+	// Announce text changes of this subtree, "polite" or "assertive" (see live_region)
+	pub live_region: Option<String>,
+
+	// Paint this subtree but take it out of interaction and focus (see inert)
+	pub inert: Option<bool>,
+
+	// Styles while hovered, focused or pressed, complete rather than just the
+	// overrides (see style_variants)
+	pub hover_style:  Option<Box<ElementStyle>>,
+	pub focus_style:  Option<Box<ElementStyle>>,
+	pub active_style: Option<Box<ElementStyle>>,
 }
 
 impl ElementStyle {
 	#[rustfmt::skip]
 	pub fn from_json(style_obj: &Value) -> Self {
-        // Parse hover style recursively
-        let hover_style = style_obj.get("hoverStyle")
-            .and_then(|v| v.as_object())
-            .map(|obj| Box::new(Self::from_json(&Value::Object(obj.clone()))));
+        // State styles: the whole style with the state's overrides applied (see cascade)
+        let state_style = |key: &str| cascade::resolve(style_obj, Some(key)).map(|state| Box::new(Self::from_json(&state)));
+        let hover_style = style_obj.get("hoverStyle").and_then(|_| state_style("hoverStyle"));
+        let focus_style = style_obj.get("focusStyle").and_then(|_| state_style("focusStyle"));
+        let active_style = style_obj.get("activeStyle").and_then(|_| state_style("activeStyle"));
+        // `!important` props win over the rest of the style
+        let resolved = style_obj.get("importantStyle").and_then(|_| cascade::resolve(style_obj, None));
+        let style_obj = resolved.as_ref().unwrap_or(style_obj);
 
         ElementStyle {
             // Text properties (inheritable)
-            text_color: style_obj.get("textColor").and_then(|v| v.as_u64()).map(|v| v as u32),
+            text_color: style_obj.get("textColor").and_then(color_from_json),
             text_size: style_obj.get("textSize").and_then(|v| v.as_f64()).map(|v| v as f32),
             font_weight: style_obj.get("fontWeight").and_then(|v| v.as_u64()).map(|v| v as u32),
             font_family: style_obj.get("fontFamily").and_then(|v| v.as_str()).map(|s| s.to_string()),
             line_height: style_obj.get("lineHeight").and_then(|v| v.as_f64()).map(|v| v as f32),
             text_align: style_obj.get("textAlign").and_then(|v| v.as_str()).map(|s| s.to_string()),
             letter_spacing: style_obj.get("letterSpacing").and_then(|v| v.as_f64()).map(|v| v as f32),
+            font_feature_settings: style_obj.get("fontFeatureSettings").and_then(|v| v.as_str()).map(|s| s.to_string()),
+            writing_mode: style_obj.get("writingMode").and_then(|v| v.as_str()).map(|s| s.to_string()),
+            elide_mode: style_obj.get("elideMode").and_then(|v| v.as_str()).map(|s| s.to_string()),
 
             // Other inheritable
             cursor: style_obj.get("cursor").and_then(|v| v.as_str()).map(|s| s.to_string()),
             visibility: style_obj.get("visibility").and_then(|v| v.as_str()).map(|s| s.to_string()),
+            shape_rendering: style_obj.get("shapeRendering").and_then(|v| v.as_str()).map(|s| s.to_string()),
 
             // Non-inheritable
-            bg_color: style_obj.get("bgColor").and_then(|v| v.as_u64()).map(|v| v as u32),
+            bg_color: style_obj.get("bgColor").and_then(color_from_json),
             width: style_obj.get("width").and_then(|v| v.as_f64()).map(|v| v as f32),
             height: style_obj.get("height").and_then(|v| v.as_f64()).map(|v| v as f32),
 
@@ -251,25 +471,42 @@ impl ElementStyle {
             overflow_x: style_obj.get("overflowX").and_then(|v| v.as_str()).map(|s| s.to_string()),
             overflow_y: style_obj.get("overflowY").and_then(|v| v.as_str()).map(|s| s.to_string()),
 
+            // Content visibility
+            content_visibility: style_obj.get("contentVisibility").and_then(|v| v.as_str()).map(|s| s.to_string()),
+            contain_intrinsic_width: style_obj.get("containIntrinsicWidth").and_then(|v| v.as_f64()).map(|v| v as f32),
+            contain_intrinsic_height: style_obj.get("containIntrinsicHeight").and_then(|v| v.as_f64()).map(|v| v as f32),
+
             // Border widths
             border_top_width: style_obj.get("borderTopWidth").and_then(|v| v.as_f64()).map(|v| v as f32),
             border_right_width: style_obj.get("borderRightWidth").and_then(|v| v.as_f64()).map(|v| v as f32),
             border_bottom_width: style_obj.get("borderBottomWidth").and_then(|v| v.as_f64()).map(|v| v as f32),
             border_left_width: style_obj.get("borderLeftWidth").and_then(|v| v.as_f64()).map(|v| v as f32),
             border_style: style_obj.get("borderStyle").and_then(|v| v.as_str()).map(|s| s.to_string()),
-            border_color: style_obj.get("borderColor").and_then(|v| v.as_u64()).map(|v| v as u32),
-            border_top_color: style_obj.get("borderTopColor").and_then(|v| v.as_u64()).map(|v| v as u32),
-            border_right_color: style_obj.get("borderRightColor").and_then(|v| v.as_u64()).map(|v| v as u32),
-            border_bottom_color: style_obj.get("borderBottomColor").and_then(|v| v.as_u64()).map(|v| v as u32),
-            border_left_color: style_obj.get("borderLeftColor").and_then(|v| v.as_u64()).map(|v| v as u32),
+            border_top_style: style_obj.get("borderTopStyle").and_then(|v| v.as_str()).map(|s| s.to_string()),
+            border_right_style: style_obj.get("borderRightStyle").and_then(|v| v.as_str()).map(|s| s.to_string()),
+            border_bottom_style: style_obj.get("borderBottomStyle").and_then(|v| v.as_str()).map(|s| s.to_string()),
+            border_left_style: style_obj.get("borderLeftStyle").and_then(|v| v.as_str()).map(|s| s.to_string()),
+            border_color: style_obj.get("borderColor").and_then(color_from_json),
+            border_top_color: style_obj.get("borderTopColor").and_then(color_from_json),
+            border_right_color: style_obj.get("borderRightColor").and_then(color_from_json),
+            border_bottom_color: style_obj.get("borderBottomColor").and_then(color_from_json),
+            border_left_color: style_obj.get("borderLeftColor").and_then(color_from_json),
             border_radius: style_obj.get("borderRadius").and_then(|v| v.as_f64()).map(|v| v as f32),
 
+            // Outline
+            outline_width: style_obj.get("outlineWidth").and_then(|v| v.as_f64()).map(|v| v as f32),
+            outline_color: style_obj.get("outlineColor").and_then(color_from_json),
+            outline_offset: style_obj.get("outlineOffset").and_then(|v| v.as_f64()).map(|v| v as f32),
+            outline_style: style_obj.get("outlineStyle").and_then(|v| v.as_str()).map(|s| s.to_string()),
+
             // Box shadow
             box_shadow_offset_x: style_obj.get("boxShadowOffsetX").and_then(|v| v.as_f64()).map(|v| v as f32),
             box_shadow_offset_y: style_obj.get("boxShadowOffsetY").and_then(|v| v.as_f64()).map(|v| v as f32),
             box_shadow_blur: style_obj.get("boxShadowBlur").and_then(|v| v.as_f64()).map(|v| v as f32),
             box_shadow_spread: style_obj.get("boxShadowSpread").and_then(|v| v.as_f64()).map(|v| v as f32),
-            box_shadow_color: style_obj.get("boxShadowColor").and_then(|v| v.as_u64()).map(|v| v as u32),
+            box_shadow_color: style_obj.get("boxShadowColor").and_then(color_from_json),
+            elevation: style_obj.get("elevation").and_then(|v| v.as_f64()).map(|v| v as f32),
+            elevation_tint: style_obj.get("elevationTint").and_then(|v| v.as_bool()),
 
             // Flexbox
             display: style_obj.get("display").and_then(|v| v.as_str()).map(|s| s.to_string()),
@@ -291,6 +528,8 @@ impl ElementStyle {
             src: style_obj.get("src").and_then(|v| v.as_str()).map(|s| s.to_string()),
             alt: style_obj.get("alt").and_then(|v| v.as_str()).map(|s| s.to_string()),
             draw_commands: style_obj.get("drawCommands").cloned(),
+            canvas_retained: style_obj.get("canvasRetained").and_then(|v| v.as_bool()),
+            canvas_batch: style_obj.get("canvasBatch").and_then(|v| v.as_u64()),
             x: style_obj.get("x").and_then(|v| v.as_f64()).map(|v| v as f32),
             y: style_obj.get("y").and_then(|v| v.as_f64()).map(|v| v as f32),
 
@@ -298,7 +537,7 @@ impl ElementStyle {
             tab_index: style_obj.get("tabIndex").and_then(|v| v.as_i64()).map(|v| v as i32),
 
             // Input element properties
-            value: style_obj.get("value").and_then(|v| v.as_str()).map(|s| s.to_string()),
+            value: style_obj.get("value").and_then(|v| v.as_str().map(|s| s.to_string()).or_else(|| v.as_f64().map(|n| n.to_string()))),
             placeholder: style_obj.get("placeholder").and_then(|v| v.as_str()).map(|s| s.to_string()),
             input_type: style_obj.get("inputType").and_then(|v| v.as_str()).map(|s| s.to_string()),
             disabled: style_obj.get("disabled").and_then(|v| v.as_bool()),
@@ -306,10 +545,89 @@ impl ElementStyle {
             max_length: style_obj.get("maxLength").and_then(|v| v.as_u64()).map(|v| v as usize),
             multi_line: style_obj.get("multiLine").and_then(|v| v.as_bool()),
             rows: style_obj.get("rows").and_then(|v| v.as_u64()).map(|v| v as usize),
-            selection_color: style_obj.get("selectionColor").and_then(|v| v.as_u64()).map(|v| v as u32),
+            cols: style_obj.get("cols").and_then(|v| v.as_u64()).map(|v| v as usize),
+            line_numbers: style_obj.get("lineNumbers").and_then(|v| v.as_bool()),
+            selection_color: style_obj.get("selectionColor").and_then(color_from_json),
+            capture_tab: style_obj.get("captureTab").and_then(|v| v.as_bool()),
+            tab_size: style_obj.get("tabSize").and_then(|v| v.as_u64()).map(|v| v as usize),
+            indent_with_tabs: style_obj.get("indentWithTabs").and_then(|v| v.as_bool()),
+
+            // Checkbox and radio properties
+            checked: style_obj.get("checked").and_then(|v| v.as_bool()),
+            name: style_obj.get("name").and_then(|v| v.as_str()).map(|s| s.to_string()),
+            accent_color: style_obj.get("accentColor").and_then(color_from_json),
+
+            // Slider, progress, gauge and sparkline properties
+            min: style_obj.get("min").and_then(|v| v.as_f64()),
+            max: style_obj.get("max").and_then(|v| v.as_f64()),
+            step: style_obj.get("step").and_then(|v| v.as_f64()),
+
+            // Gauge properties
+            gauge_sweep: style_obj.get("gaugeSweep").and_then(|v| v.as_f64()).map(|v| v as f32),
+            gauge_ticks: style_obj.get("gaugeTicks").and_then(|v| v.as_u64()).map(|v| v as u32),
+            gauge_needle: style_obj.get("gaugeNeedle").and_then(|v| v.as_bool()),
+
+            // Sparkline properties
+            sparkline_capacity: style_obj.get("sparklineCapacity").and_then(|v| v.as_u64()).map(|v| v as usize),
+            sparkline_interval: style_obj.get("sparklineInterval").and_then(|v| v.as_u64()),
+
+            // QR code properties
+            symbology: style_obj.get("symbology").and_then(|v| v.as_str()).map(|s| s.to_string()),
+            qr_ec_level: style_obj.get("qrEcLevel").and_then(|v| v.as_str()).map(|s| s.to_string()),
+            quiet_zone: style_obj.get("quietZone").and_then(|v| v.as_u64()).map(|v| v as u32),
+            logo_size: style_obj.get("logoSize").and_then(|v| v.as_f64()).map(|v| v as f32),
+
+            // Avatar properties
+            avatar_status: style_obj.get("avatarStatus").and_then(|v| v.as_str()).map(|s| s.to_string()),
+
+            // Code properties
+            code_text: style_obj.get("text").and_then(|v| v.as_str()).map(|s| s.to_string()),
+            code_language: style_obj.get("codeLanguage").and_then(|v| v.as_str()).map(|s| s.to_string()),
+
+            // Rich text properties
+            rich_text: style_obj.get("richRuns").and_then(richtext::parse_runs),
+
+            // Skeleton properties
+            skeleton_variant: style_obj.get("skeletonVariant").and_then(|v| v.as_str()).map(|s| s.to_string()),
 
-            // Hover style
+            // Icon properties
+            icon_codepoint: style_obj.get("iconCodepoint").and_then(|v| v.as_u64()).and_then(|v| u32::try_from(v).ok()),
+            icon_size: style_obj.get("iconSize").and_then(|v| v.as_f64()).map(|v| v as f32),
+
+            // Resizer properties
+            orientation: style_obj.get("orientation").and_then(|v| v.as_str()).map(|s| s.to_string()),
+
+            // Overlay properties
+            backdrop: style_obj.get("backdrop").and_then(color_from_json),
+
+            // Tooltip properties
+            tooltip_delay: style_obj.get("tooltipDelay").and_then(|v| v.as_u64()),
+            tooltip_placement: style_obj.get("tooltipPlacement").and_then(|v| v.as_str()).map(|s| s.to_string()),
+
+            // Shader view properties
+            shader: style_obj.get("shader").and_then(|v| v.as_str()).map(|s| s.to_string()),
+            shader_params: style_obj.get("shaderParams").cloned(),
+            animate: style_obj.get("animate").and_then(|v| v.as_bool()),
+
+            // Event options
+            event_options: style_obj.get("eventOptions").cloned(),
+
+            // Privacy
+            privacy_mode: style_obj.get("privacyMode").and_then(|v| v.as_bool()),
+
+            // Paint tracking
+            track_paint: style_obj.get("trackPaint").and_then(|v| v.as_bool()),
+
+            // Live region
+            live_region: style_obj.get("liveRegion").and_then(|v| v.as_str()).map(|s| s.to_string()),
+
+            // Inert subtree
+            inert: style_obj.get("inert").and_then(|v| v.as_bool()),
+
+            // State styles
             hover_style,
+            focus_style,
+            active_style,
         }
     }
 
@@ -338,6 +656,15 @@ impl ElementStyle {
 		if self.letter_spacing.is_none() {
 			self.letter_spacing = parent.letter_spacing;
 		}
+		if self.font_feature_settings.is_none() {
+			self.font_feature_settings = parent.font_feature_settings.clone();
+		}
+		if self.writing_mode.is_none() {
+			self.writing_mode = parent.writing_mode.clone();
+		}
+		if self.elide_mode.is_none() {
+			self.elide_mode = parent.elide_mode.clone();
+		}
 		// Other inheritable
 		if self.cursor.is_none() {
 			self.cursor = parent.cursor.clone();
@@ -345,6 +672,9 @@ impl ElementStyle {
 		if self.visibility.is_none() {
 			self.visibility = parent.visibility.clone();
 		}
+		if self.shape_rendering.is_none() {
+			self.shape_rendering = parent.shape_rendering.clone();
+		}
 	}
 
 	/// Build GPUI Style from ElementStyle
@@ -379,6 +709,12 @@ impl ElementStyle {
 			Some("row-reverse") => style.flex_direction = FlexDirection::RowReverse,
 			Some("column") => style.flex_direction = FlexDirection::Column,
 			Some("column-reverse") => style.flex_direction = FlexDirection::ColumnReverse,
+			// Vertical writing modes stack blocks horizontally
+			None => match self.writing_mode.as_deref() {
+				Some("vertical-rl") => style.flex_direction = FlexDirection::RowReverse,
+				Some("vertical-lr") => style.flex_direction = FlexDirection::Row,
+				_ => {}
+			},
 			_ => {}
 		}
 
@@ -591,31 +927,41 @@ impl ElementStyle {
 		}
 	}
 
-	/// Apply border width, color, and radius properties
+	/// Apply border width, color, style, and radius properties
 	fn apply_borders(&self, style: &mut Style) {
-		// Border widths
-		if let Some(w) = self.border_top_width {
+		// Border widths, sides styled "none" take no space
+		let [top, right, bottom, left] = border::side_styles(self);
+		let width = |w: Option<f32>, side| w.filter(|_| side != border::SideStyle::None);
+		if let Some(w) = width(self.border_top_width, top) {
 			style.border_widths.top = gpui::AbsoluteLength::Pixels(px(w));
 		}
-		if let Some(w) = self.border_right_width {
+		if let Some(w) = width(self.border_right_width, right) {
 			style.border_widths.right = gpui::AbsoluteLength::Pixels(px(w));
 		}
-		if let Some(w) = self.border_bottom_width {
+		if let Some(w) = width(self.border_bottom_width, bottom) {
 			style.border_widths.bottom = gpui::AbsoluteLength::Pixels(px(w));
 		}
-		if let Some(w) = self.border_left_width {
+		if let Some(w) = width(self.border_left_width, left) {
 			style.border_widths.left = gpui::AbsoluteLength::Pixels(px(w));
 		}
 
-		// Border color
-		let border_color = self.border_color.map(|c| rgb(c).into());
-		if border_color.is_some()
-			|| self.border_top_width.is_some()
-			|| self.border_right_width.is_some()
-			|| self.border_bottom_width.is_some()
-			|| self.border_left_width.is_some()
-		{
-			style.border_color = border_color.or(Some(rgb(0x808080).into()));
+		// Borders gpui can't draw keep their widths for layout but are painted
+		// by border::paint instead
+		if !border::needs_custom_paint(self) {
+			let (side, color) = border::uniform(self);
+			if side == border::SideStyle::Dashed {
+				style.border_style = gpui::BorderStyle::Dashed;
+			}
+
+			// Border color, the same on every side here
+			if self.border_color.is_some()
+				|| self.border_top_width.is_some()
+				|| self.border_right_width.is_some()
+				|| self.border_bottom_width.is_some()
+				|| self.border_left_width.is_some()
+			{
+				style.border_color = Some(color);
+			}
 		}
 
 		// Border radius
@@ -635,32 +981,34 @@ impl ElementStyle {
 			|| self.box_shadow_offset_x.is_some()
 			|| self.box_shadow_offset_y.is_some()
 		{
-			let color = self.box_shadow_color.unwrap_or(0x000000);
-			let (r, g, b) = ((color >> 16) & 0xff, (color >> 8) & 0xff, color & 0xff);
+			// Without a color, a half transparent black
+			let color = self.box_shadow_color.unwrap_or(Hsla { h: 0.0, s: 0.0, l: 0.0, a: 0.5 });
 			style.box_shadow = vec![BoxShadow {
-				color:         Hsla::from(Rgba {
-					r: r as f32 / 255.0,
-					g: g as f32 / 255.0,
-					b: b as f32 / 255.0,
-					a: 0.5,
-				}),
-				offset:        point(
+				color,
+				offset: point(
 					px(self.box_shadow_offset_x.unwrap_or(0.0)),
 					px(self.box_shadow_offset_y.unwrap_or(0.0)),
 				),
-				blur_radius:   px(self.box_shadow_blur.unwrap_or(0.0)),
+				blur_radius: px(self.box_shadow_blur.unwrap_or(0.0)),
 				spread_radius: px(self.box_shadow_spread.unwrap_or(0.0)),
 			}];
+		} else if let Some(elevation) = self.elevation.filter(|e| *e > 0.0) {
+			style.box_shadow = elevation_shadows(elevation.min(24.0));
 		}
 	}
 
 	/// Apply background, opacity, and other visual effects
 	fn apply_visual_effects(&self, style: &mut Style, default_bg: Option<u32>) {
 		// Background
-		if let Some(bg) = self.bg_color {
-			style.background = Some(Fill::Color(rgb(bg).into()));
-		} else if let Some(default) = default_bg {
-			style.background = Some(Fill::Color(rgb(default).into()));
+		if let Some(mut color) = self.bg_color.or_else(|| default_bg.map(|bg| rgb(bg).into())) {
+			if self.elevation_tint == Some(true)
+				&& let Some(elevation) = self.elevation.filter(|e| *e > 0.0)
+			{
+				// Material dark theme overlay: white at ~5% to 16.5% with elevation
+				let overlay = (4.5 * (elevation.min(24.0) + 1.0).ln() + 2.0) / 100.0;
+				color = color.blend(Hsla { h: 0.0, s: 0.0, l: 1.0, a: overlay });
+			}
+			style.background = Some(Fill::Color(color.into()));
 		}
 
 		// Opacity
@@ -676,6 +1024,30 @@ impl ElementStyle {
 	}
 }
 
+/// Read a style color, sent packed as 0xRRGGBBAA by the style mapping
+fn color_from_json(value: &Value) -> Option<Hsla> { value.as_u64().map(|v| rgba(v as u32).into()) }
+
+/// Key and ambient shadow pair for an elevation level
+/// The key light casts a sharper shadow offset downwards, the ambient light a
+/// soft one all around, both growing with elevation
+fn elevation_shadows(elevation: f32) -> Vec<BoxShadow> {
+	let black = |a: f32| Hsla { h: 0.0, s: 0.0, l: 0.0, a };
+	vec![
+		BoxShadow {
+			color:         black(0.22),
+			offset:        point(px(0.0), px(elevation * 0.5)),
+			blur_radius:   px(elevation),
+			spread_radius: px(0.0),
+		},
+		BoxShadow {
+			color:         black(0.12),
+			offset:        point(px(0.0), px(elevation * 0.15)),
+			blur_radius:   px(elevation * 1.5),
+			spread_radius: px(elevation * 0.1),
+		},
+	]
+}
+
 /// Paint children with optional overflow clipping
 /// This helper function reduces code duplication across element types
 pub fn paint_children_with_clip<F>(
@@ -716,15 +1088,119 @@ pub fn create_element(
 			ReactCanvasElement::new(element, window_id, parent_style).into_any_element()
 		}
 		ElementKind::Div => ReactDivElement::new(element, window_id, parent_style).into_any_element(),
+		// Painted like a textarea until the input element can lay out lines
+		ElementKind::Input if element.style.multi_line == Some(true) => {
+			ReactDocumentElement::new(element, window_id, parent_style).into_any_element()
+		}
 		ElementKind::Input => {
 			ReactInputElement::new(element, window_id, parent_style).into_any_element()
 		}
 		ElementKind::Span => ReactSpanElement::new(element, window_id, parent_style).into_any_element(),
 		ElementKind::Text => ReactTextElement::new(element, window_id, parent_style).into_any_element(),
 		ElementKind::Img => ReactImgElement::new(element, window_id, parent_style).into_any_element(),
+		ElementKind::ShaderView => {
+			ReactShaderViewElement::new(element, window_id, parent_style).into_any_element()
+		}
+		ElementKind::Document | ElementKind::TextArea => {
+			ReactDocumentElement::new(element, window_id, parent_style).into_any_element()
+		}
+		ElementKind::Checkbox | ElementKind::Radio => {
+			ReactCheckboxElement::new(element, window_id, parent_style).into_any_element()
+		}
+		ElementKind::Slider => {
+			ReactSliderElement::new(element, window_id, parent_style).into_any_element()
+		}
+		ElementKind::Progress => {
+			ReactProgressElement::new(element, window_id, parent_style).into_any_element()
+		}
+		ElementKind::Overlay => overlay::create(element, window_id, parent_style),
+		ElementKind::Tooltip => {
+			ReactTooltipElement::new(element, window_id, parent_style).into_any_element()
+		}
+		ElementKind::Resizer => {
+			ReactResizerElement::new(element, window_id, parent_style).into_any_element()
+		}
+		ElementKind::Gauge => {
+			ReactGaugeElement::new(element, window_id, parent_style).into_any_element()
+		}
+		ElementKind::Sparkline => {
+			ReactSparklineElement::new(element, window_id, parent_style).into_any_element()
+		}
+		ElementKind::QrCode => {
+			ReactQrCodeElement::new(element, window_id, parent_style).into_any_element()
+		}
+		ElementKind::Avatar => {
+			ReactAvatarElement::new(element, window_id, parent_style).into_any_element()
+		}
+		ElementKind::Code => ReactCodeElement::new(element, window_id, parent_style).into_any_element(),
+		ElementKind::RichText => {
+			ReactRichTextElement::new(element, window_id, parent_style).into_any_element()
+		}
+		ElementKind::Skeleton => {
+			ReactSkeletonElement::new(element, window_id, parent_style).into_any_element()
+		}
+		ElementKind::Button => {
+			ReactButtonElement::new(element, window_id, parent_style).into_any_element()
+		}
+		ElementKind::Icon => ReactIconElement::new(element, window_id, parent_style).into_any_element(),
 		ElementKind::Unknown => gpui::div()
 			.id(element.global_id as usize)
 			.child(format!("[Unknown: {}]", element.element_type))
 			.into_any_element(),
 	}
 }
+
+/// Drop every piece of per-window element state once the window is closed
+pub fn forget_window(window_id: u64) {
+	button::forget_window(window_id);
+	canvas::forget_window(window_id);
+	checkbox::forget_window(window_id);
+	code::forget_window(window_id);
+	content_visibility::forget_window(window_id);
+	contrast::forget_window(window_id);
+	dispatcher::forget_window(window_id);
+	document::forget_window(window_id);
+	find_overlay::forget_window(window_id);
+	gauge::forget_window(window_id);
+	hit_test::forget_window(window_id);
+	hud::forget_window(window_id);
+	inert::forget_window(window_id);
+	key_repeat::forget_window(window_id);
+	layout::forget_window(window_id);
+	live_region::forget_window(window_id);
+	live_resize::forget_window(window_id);
+	pacing::forget_window(window_id);
+	paint_track::forget_window(window_id);
+	qrcode::forget_window(window_id);
+	rate_limit::forget_window(window_id);
+	resizer::forget_window(window_id);
+	safe_area::forget_window(window_id);
+	slider::forget_window(window_id);
+	sparkline::forget_window(window_id);
+	style_variants::forget_window(window_id);
+	tab_capture::forget_window(window_id);
+	tooltip::forget_window(window_id);
+	focus::get_focus_manager().lock_recover().remove_window(window_id);
+}
+
+/// Drop per-element state of a released element, whose id may be recycled
+pub fn forget_element(window_id: u64, element_id: u64) {
+	button::forget_element(window_id, element_id);
+	canvas::forget_element(window_id, element_id);
+	checkbox::forget_element(window_id, element_id);
+	code::forget_element(window_id, element_id);
+	content_visibility::forget_element(window_id, element_id);
+	document::forget_element(window_id, element_id);
+	gauge::forget_element(window_id, element_id);
+	hover::forget_element(element_id);
+	layout::forget_element(window_id, element_id);
+	live_region::forget_element(window_id, element_id);
+	qrcode::forget_element(window_id, element_id);
+	rate_limit::forget_element(window_id, element_id);
+	resizer::forget_element(window_id, element_id);
+	slider::forget_element(window_id, element_id);
+	sparkline::forget_element(window_id, element_id);
+	style_variants::forget_element(window_id, element_id);
+	tooltip::forget_element(window_id, element_id);
+	focus::forget_element(window_id, element_id);
+}
@@ -0,0 +1,220 @@
+//! Overlay element
+//!
+//! The children of an `overlay` are painted in a top layer above the rest of
+//! the window, wherever the overlay sits in the tree: the layer covers the
+//! window, isn't clipped by the overlay's ancestors and takes the pointer
+//! before anything painted below it. The overlay's own style lays out the
+//! layer, so a dialog is centered with `justifyContent`/`alignItems`.
+//!
+//! - With a `backdrop` color the layer paints it over the window and blocks the
+//!   pointer from everything below (a modal). Without one the pointer only
+//!   stops at the overlay's children (a popover).
+//! - A mouse down outside the children sends `dismiss` to the overlay.
+//!
+//! Overlays are drawn with gpui's deferred draw, in tree order. An overlay
+//! inside another is already in the top layer and is painted in tree order
+//! within it.
+
+use std::sync::{Arc, atomic::{AtomicBool, Ordering}};
+
+use gpui::{AnchoredPositionMode, AnyElement, App, Bounds, DispatchPhase, Element, ElementId, GlobalElementId, Hitbox, HitboxBehavior, InspectorElementId, IntoElement, LayoutId, MouseDownEvent, Pixels, Window, anchored, deferred, fill, point, prelude::*, px};
+
+use super::{ElementStyle, ReactElement, border, dispatcher, events::{EventHandlerFlags, register_event_handlers}, inert, layout, style_variants};
+use crate::{event_types::{props, types}, renderer::dispatch_event_to_js};
+
+/// Deferred draw priority of the overlay layer
+const OVERLAY_PRIORITY: usize = 1;
+
+/// Set while the children of an overlay are built, so nested overlays
+/// don't defer again (gpui can't defer a draw during deferred drawing)
+static IN_LAYER: AtomicBool = AtomicBool::new(false);
+
+/// Whether the element being built is inside an overlay
+pub fn in_layer() -> bool { IN_LAYER.load(Ordering::Relaxed) }
+
+/// Create an overlay, deferred to the top layer unless already inside one
+pub fn create(
+	element: Arc<ReactElement>,
+	window_id: u64,
+	parent_style: Option<ElementStyle>,
+) -> AnyElement {
+	let layer = anchored()
+		.position_mode(AnchoredPositionMode::Window)
+		.position(point(px(0.0), px(0.0)))
+		.child(ReactOverlayElement::new(element, window_id, parent_style));
+	if in_layer() {
+		layer.into_any_element()
+	} else {
+		deferred(layer).with_priority(OVERLAY_PRIORITY).into_any_element()
+	}
+}
+
+pub struct ReactOverlayElement {
+	element:      Arc<ReactElement>,
+	window_id:    u64,
+	parent_style: Option<ElementStyle>,
+	children:     Vec<AnyElement>,
+}
+
+pub struct OverlayLayoutState {
+	child_layout_ids: Vec<LayoutId>,
+}
+
+pub struct OverlayPrepaintState {
+	hitbox:      Option<Hitbox>,
+	event_flags: EventHandlerFlags,
+	/// Bounds of the children; a mouse down anywhere else dismisses
+	content:     Vec<Bounds<Pixels>>,
+}
+
+impl ReactOverlayElement {
+	pub fn new(
+		element: Arc<ReactElement>,
+		window_id: u64,
+		parent_style: Option<ElementStyle>,
+	) -> Self {
+		Self { element, window_id, parent_style, children: Vec::new() }
+	}
+}
+
+impl Element for ReactOverlayElement {
+	type PrepaintState = OverlayPrepaintState;
+	type RequestLayoutState = OverlayLayoutState;
+
+	fn id(&self) -> Option<ElementId> { Some(ElementId::Integer(self.element.global_id)) }
+
+	fn source_location(&self) -> Option<&'static std::panic::Location<'static>> { None }
+
+	fn request_layout(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		window: &mut Window,
+		cx: &mut App,
+	) -> (LayoutId, Self::RequestLayoutState) {
+		let mut style = self.element.build_gpui_style(None);
+		let viewport = window.viewport_size();
+		style.size.width = viewport.width.into();
+		style.size.height = viewport.height.into();
+
+		let inherited_style =
+			style_variants::effective_style(self.window_id, &self.element, self.parent_style.as_ref());
+
+		let outer = IN_LAYER.swap(true, Ordering::Relaxed);
+		self.children = self
+			.element
+			.children
+			.iter()
+			.map(|child| {
+				super::create_element(child.clone(), self.window_id, Some(inherited_style.clone()))
+			})
+			.collect();
+		let child_layout_ids: Vec<LayoutId> =
+			self.children.iter_mut().map(|child| child.request_layout(window, cx)).collect();
+		IN_LAYER.store(outer, Ordering::Relaxed);
+
+		let layout_id = window.request_layout(style, child_layout_ids.iter().copied(), cx);
+		(layout_id, OverlayLayoutState { child_layout_ids })
+	}
+
+	fn prepaint(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		bounds: Bounds<Pixels>,
+		request_layout: &mut Self::RequestLayoutState,
+		window: &mut Window,
+		cx: &mut App,
+	) -> Self::PrepaintState {
+		layout::record_bounds(self.window_id, self.element.global_id, bounds);
+
+		let event_flags = EventHandlerFlags::from_handlers(
+			self.element.event_handlers.as_ref(),
+			self.element.style.tab_index,
+		);
+		let content: Vec<Bounds<Pixels>> =
+			request_layout.child_layout_ids.iter().map(|id| window.layout_bounds(*id)).collect();
+
+		// Inserted before the children's, so theirs stay on top
+		let blocking = !inert::is_inert(self.window_id, self.element.global_id);
+		let backdrop = blocking && self.element.style.backdrop.is_some();
+		let hitbox = if backdrop || event_flags.needs_hitbox() {
+			let behavior = if backdrop { HitboxBehavior::BlockMouse } else { HitboxBehavior::Normal };
+			Some(window.insert_hitbox(bounds, behavior))
+		} else {
+			None
+		};
+		if blocking && !backdrop {
+			for bounds in &content {
+				window.insert_hitbox(*bounds, HitboxBehavior::BlockMouse);
+			}
+		}
+
+		for child in &mut self.children {
+			child.prepaint(window, cx);
+		}
+
+		OverlayPrepaintState { hitbox, event_flags, content }
+	}
+
+	fn paint(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		bounds: Bounds<Pixels>,
+		_request_layout: &mut Self::RequestLayoutState,
+		prepaint: &mut Self::PrepaintState,
+		window: &mut Window,
+		cx: &mut App,
+	) {
+		let element_style = &self.element.style;
+		if let Some(backdrop) = element_style.backdrop {
+			window.paint_quad(fill(bounds, backdrop));
+		}
+		let style = self.element.build_gpui_style(None);
+		style.paint(bounds, window, cx, |window, cx| {
+			super::paint_children_with_clip(
+				&mut self.children,
+				bounds,
+				element_style.should_clip(),
+				window,
+				cx,
+				|child, window, cx| child.paint(window, cx),
+			);
+		});
+		border::paint(element_style, bounds, window);
+		border::paint_outline(element_style, bounds, window);
+
+		let (window_id, element_id) = (self.window_id, self.element.global_id);
+		let has_dismiss =
+			self.element.event_handlers.as_ref().is_some_and(|h| h.get(props::ON_DISMISS).is_some());
+		if has_dismiss && !inert::is_inert(window_id, element_id) {
+			let content = prepaint.content.clone();
+			window.on_mouse_event(move |event: &MouseDownEvent, phase, _, _| {
+				if phase != DispatchPhase::Bubble
+					|| content.iter().any(|bounds| bounds.contains(&event.position))
+				{
+					return;
+				}
+				log::debug!("[Rust] onDismiss: window_id={}, element_id={}", window_id, element_id);
+				let button = dispatcher::mouse_button_to_u8(event.button);
+				let data = dispatcher::mouse_data(event.position, bounds, button);
+				dispatch_event_to_js(window_id, element_id, types::DISMISS, data);
+			});
+		}
+
+		register_event_handlers(
+			&prepaint.event_flags,
+			prepaint.hitbox.as_ref(),
+			window_id,
+			element_id,
+			window,
+		);
+	}
+}
+
+impl IntoElement for ReactOverlayElement {
+	type Element = Self;
+
+	fn into_element(self) -> Self::Element { self }
+}
@@ -0,0 +1,90 @@
+//! Frame rate of continuous animations
+//!
+//! Elements that animate every frame ask for the next one through
+//! [`request_frame`] instead of `window.request_animation_frame()`. While the
+//! window is active that is the same thing; once it loses focus (or is
+//! minimized, which deactivates it) frames are spaced out to 10fps so apps
+//! left in the background stop burning battery. Focusing the window again
+//! refreshes it and the full rate resumes. Fully occluded windows get no
+//! frames at all: the platform stops their display link.
+//!
+//! Apps can lower the rate further: `gpui_set_max_fps` caps a window's
+//! animations, and power saving (`gpui_set_power_saver`, app-wide, e.g. while
+//! on battery) caps every window at 15fps and turns off purely decorative
+//! effects such as the skeleton shimmer.
+
+use std::{collections::{HashMap, HashSet}, sync::{Mutex, atomic::{AtomicBool, Ordering}}, time::Duration};
+
+use gpui::{App, Window};
+use lazy_static::lazy_static;
+
+use crate::sync::MutexExt;
+
+/// Frame interval for animations in inactive windows
+const BACKGROUND_FRAME_INTERVAL: Duration = Duration::from_millis(100);
+/// Frame interval for animations while power saving, 15fps
+const POWER_SAVER_FRAME_INTERVAL: Duration = Duration::from_micros(66_667);
+
+static POWER_SAVER: AtomicBool = AtomicBool::new(false);
+
+lazy_static! {
+	/// Windows with a delayed frame on its way
+	static ref PENDING: Mutex<HashSet<u64>> = Mutex::new(HashSet::new());
+	/// Shortest animation frame interval of windows with a max fps
+	static ref MIN_INTERVALS: Mutex<HashMap<u64, Duration>> = Mutex::new(HashMap::new());
+}
+
+/// Drop all state of a closed window
+pub fn forget_window(window_id: u64) {
+	PENDING.lock_recover().remove(&window_id);
+	MIN_INTERVALS.lock_recover().remove(&window_id);
+}
+
+/// Cap a window's animations at `fps` frames per second, 0 removes the cap
+/// So does an fps too small (or not a number) to give a frame interval
+pub fn set_max_fps(window_id: u64, fps: f32) {
+	let interval =
+		Some(fps).filter(|fps| *fps > 0.0).and_then(|fps| Duration::try_from_secs_f32(1.0 / fps).ok());
+	match interval {
+		Some(interval) => MIN_INTERVALS.lock_recover().insert(window_id, interval),
+		None => MIN_INTERVALS.lock_recover().remove(&window_id),
+	};
+}
+
+pub fn set_power_saver(enabled: bool) { POWER_SAVER.store(enabled, Ordering::Relaxed); }
+
+/// Decorative animations check this and stay still while it is on
+pub fn power_saver() -> bool { POWER_SAVER.load(Ordering::Relaxed) }
+
+/// Request another frame for an animation, at full rate only while the window
+/// is active and nothing caps it
+pub fn request_frame(window_id: u64, window: &mut Window, cx: &mut App) {
+	let interval = frame_interval(window_id, window.is_window_active());
+	if interval.is_zero() {
+		window.request_animation_frame();
+		return;
+	}
+	if !PENDING.lock_recover().insert(window_id) {
+		return;
+	}
+
+	window
+		.spawn(cx, async move |cx| {
+			cx.background_executor().timer(interval).await;
+			PENDING.lock_recover().remove(&window_id);
+			let _ = cx.update(|window, _| window.refresh());
+		})
+		.detach();
+}
+
+/// Time to wait before the next animation frame, zero for the next vsync
+fn frame_interval(window_id: u64, active: bool) -> Duration {
+	let mut interval = MIN_INTERVALS.lock_recover().get(&window_id).copied().unwrap_or_default();
+	if power_saver() {
+		interval = interval.max(POWER_SAVER_FRAME_INTERVAL);
+	}
+	if !active {
+		interval = interval.max(BACKGROUND_FRAME_INTERVAL);
+	}
+	interval
+}
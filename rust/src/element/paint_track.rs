@@ -0,0 +1,127 @@
+//! Paint change tracking for visual tests
+//!
+//! With tracking enabled for a window (`gpui_set_paint_tracking`), the first
+//! frame painted after each commit hashes every element flagged with
+//! `trackPaint` and queues a window-level `paintchanged` event for those whose
+//! hash differs from the last one reported. gpui can't read rendered pixels
+//! back, so the hash covers what determines them instead: type, text and
+//! effective style of every element in the subtree, and their painted bounds.
+
+use std::{collections::{HashMap, HashSet}, hash::{DefaultHasher, Hash, Hasher}, sync::Mutex};
+
+use lazy_static::lazy_static;
+use serde_json::json;
+
+use super::{ElementStyle, ReactElement, layout};
+use crate::{global_state::GLOBAL_STATE, renderer::dispatch_window_event, sync::MutexExt};
+
+/// Tracking state of one window
+struct Tracking {
+	/// Tree generation last checked
+	generation: u64,
+	/// Last reported hash of each tracked element
+	hashes:     HashMap<u64, u64>,
+}
+
+lazy_static! {
+	static ref WINDOWS: Mutex<HashMap<u64, Tracking>> = Mutex::new(HashMap::new());
+}
+
+/// Drop all state of a closed window
+pub fn forget_window(window_id: u64) { WINDOWS.lock_recover().remove(&window_id); }
+
+pub fn set_enabled(window_id: u64, enabled: bool) {
+	let mut windows = WINDOWS.lock_recover();
+	if enabled {
+		windows
+			.entry(window_id)
+			.or_insert_with(|| Tracking { generation: 0, hashes: HashMap::new() });
+	} else {
+		windows.remove(&window_id);
+	}
+}
+
+/// Hash tracked elements if a commit landed since the last check
+/// Called after the window's element tree painted
+pub fn after_paint(window_id: u64) {
+	let Some(window) = GLOBAL_STATE.get_window(window_id) else {
+		return;
+	};
+	let generation = window.state().get_tree_generation();
+	let checked = WINDOWS.lock_recover().get(&window_id).map(|tracking| tracking.generation);
+	if checked.is_none_or(|checked| checked == generation) {
+		return;
+	}
+	let Some(root) = window.state().element_tree.lock_recover().clone() else {
+		return;
+	};
+
+	let mut current = HashMap::new();
+	collect(window_id, &root, None, &mut current);
+
+	let mut changed = Vec::new();
+	{
+		let mut windows = WINDOWS.lock_recover();
+		let Some(tracking) = windows.get_mut(&window_id) else {
+			return;
+		};
+		tracking.generation = generation;
+		for (&element_id, &hash) in &current {
+			let previous = tracking.hashes.insert(element_id, hash);
+			if previous != Some(hash) {
+				changed.push((element_id, hash, previous));
+			}
+		}
+		let live: HashSet<u64> = current.keys().copied().collect();
+		tracking.hashes.retain(|id, _| live.contains(id));
+	}
+
+	for (element_id, hash, previous) in changed {
+		dispatch_window_event(
+			window_id,
+			"paintchanged",
+			json!({
+				"targetId": element_id,
+				"hash": format!("{:016x}", hash),
+				"previousHash": previous.map(|hash| format!("{:016x}", hash)),
+				"generation": generation,
+			}),
+		);
+	}
+}
+
+/// Find tracked elements, carrying inherited style down the tree
+fn collect(
+	window_id: u64,
+	element: &ReactElement,
+	parent_style: Option<&ElementStyle>,
+	hashes: &mut HashMap<u64, u64>,
+) {
+	let style = element.effective_style(parent_style);
+	if element.style.track_paint == Some(true) {
+		let mut hasher = DefaultHasher::new();
+		hash_subtree(window_id, element, &style, &mut hasher);
+		hashes.insert(element.global_id, hasher.finish());
+	}
+	for child in &element.children {
+		collect(window_id, child, Some(&style), hashes);
+	}
+}
+
+fn hash_subtree(
+	window_id: u64,
+	element: &ReactElement,
+	style: &ElementStyle,
+	hasher: &mut DefaultHasher,
+) {
+	element.element_type.hash(hasher);
+	element.text.hash(hasher);
+	format!("{:?}", style).hash(hasher);
+	if let Some(bounds) = layout::bounds_of(window_id, element.global_id) {
+		format!("{:?}", bounds).hash(hasher);
+	}
+	element.children.len().hash(hasher);
+	for child in &element.children {
+		hash_subtree(window_id, child, &child.effective_style(Some(style)), hasher);
+	}
+}
@@ -0,0 +1,75 @@
+//! Device-pixel snapping for `shapeRendering: "crispEdges"`
+//!
+//! Layout works in logical pixels, so at fractional scale factors (1.25x,
+//! 1.5x) an element's edges and 1px borders land between device pixels and
+//! get blended into blurry two-pixel lines. Elements asking for crisp edges
+//! (the hint is inherited, so setting it on a table covers its rows and
+//! dividers) are painted with their bounds rounded to device pixels and their
+//! border and outline widths rounded to whole device pixels, at least one.
+//! Layout, hit testing and the layout snapshot keep the unsnapped bounds.
+
+use gpui::{AbsoluteLength, Bounds, Pixels, Style, Window, point, px, size};
+
+use super::ElementStyle;
+
+/// Whether an element asked for crisp edges, itself or through its parent
+pub fn is_crisp(style: &ElementStyle, parent: Option<&ElementStyle>) -> bool {
+	let value = style.shape_rendering.as_deref();
+	value.or_else(|| parent.and_then(|parent| parent.shape_rendering.as_deref()))
+		== Some("crispEdges")
+}
+
+/// Snap what an element paints when it asks for crisp edges
+/// Returns the bounds to paint at and, if snapped, the element style with
+/// whole device pixel border and outline widths
+pub fn snap(
+	crisp: bool,
+	bounds: Bounds<Pixels>,
+	style: &mut Style,
+	element_style: &ElementStyle,
+	window: &Window,
+) -> (Bounds<Pixels>, Option<ElementStyle>) {
+	if !crisp {
+		return (bounds, None);
+	}
+	let scale = window.scale_factor();
+	for edge in [
+		&mut style.border_widths.top,
+		&mut style.border_widths.right,
+		&mut style.border_widths.bottom,
+		&mut style.border_widths.left,
+	] {
+		if let AbsoluteLength::Pixels(width) = *edge {
+			*edge = AbsoluteLength::Pixels(px(snap_width(f32::from(width), scale)));
+		}
+	}
+
+	let mut snapped = element_style.clone();
+	for width in [
+		&mut snapped.border_top_width,
+		&mut snapped.border_right_width,
+		&mut snapped.border_bottom_width,
+		&mut snapped.border_left_width,
+		&mut snapped.outline_width,
+	] {
+		*width = width.map(|w| snap_width(w, scale));
+	}
+	snapped.outline_offset = snapped.outline_offset.map(|offset| snap_coord(offset, scale));
+	(snap_bounds(bounds, scale), Some(snapped))
+}
+
+/// Round each edge of `bounds` to the nearest device pixel
+pub fn snap_bounds(bounds: Bounds<Pixels>, scale: f32) -> Bounds<Pixels> {
+	let left = snap_coord(f32::from(bounds.origin.x), scale);
+	let top = snap_coord(f32::from(bounds.origin.y), scale);
+	let right = snap_coord(f32::from(bounds.origin.x + bounds.size.width), scale);
+	let bottom = snap_coord(f32::from(bounds.origin.y + bounds.size.height), scale);
+	Bounds { origin: point(px(left), px(top)), size: size(px(right - left), px(bottom - top)) }
+}
+
+fn snap_coord(value: f32, scale: f32) -> f32 { (value * scale).round() / scale }
+
+/// Whole device pixels, so a nonzero width never rounds away
+fn snap_width(width: f32, scale: f32) -> f32 {
+	if width <= 0.0 { width } else { (width * scale).round().max(1.0) / scale }
+}
@@ -0,0 +1,180 @@
+//! Progress bar element
+//!
+//! With a numeric `value`, fills the bar up to `value / max` (`max` defaults
+//! to 1, like HTML). Without one the bar is indeterminate: a segment sweeps
+//! across it, animated in Rust from the shared clock so React doesn't render
+//! every frame and all indeterminate bars move in step. The fill uses
+//! `accentColor` and the track the background color.
+
+use std::sync::Arc;
+
+use gpui::{App, Bounds, Corners, Element, ElementId, GlobalElementId, Hitbox, InspectorElementId, IntoElement, LayoutId, Pixels, Window, fill, point, px, rgb, size};
+
+use super::{ElementStyle, ReactElement, border, events::{EventHandlerFlags, insert_hitbox_if_needed, register_event_handlers}, layout, pacing, pixel_snap};
+use crate::clock;
+
+/// Size of the bar when the style has none
+const DEFAULT_WIDTH: f32 = 160.0;
+const DEFAULT_HEIGHT: f32 = 8.0;
+/// Fill when the style has no `accentColor`
+const DEFAULT_ACCENT: u32 = 0x3b82f6;
+/// Time for the indeterminate segment to cross the bar once
+const SWEEP_US: u64 = 1_500_000;
+/// Width of the indeterminate segment, as a fraction of the bar
+const SEGMENT: f32 = 0.3;
+
+pub struct ReactProgressElement {
+	element:      Arc<ReactElement>,
+	window_id:    u64,
+	parent_style: Option<ElementStyle>,
+}
+
+pub struct ProgressLayoutState {}
+
+pub struct ProgressPrepaintState {
+	hitbox:      Option<Hitbox>,
+	event_flags: EventHandlerFlags,
+}
+
+impl ReactProgressElement {
+	pub fn new(
+		element: Arc<ReactElement>,
+		window_id: u64,
+		parent_style: Option<ElementStyle>,
+	) -> Self {
+		Self { element, window_id, parent_style }
+	}
+
+	/// Filled fraction, None while indeterminate
+	fn fraction(&self) -> Option<f32> {
+		let style = &self.element.style;
+		let value = style.value.as_deref()?.trim().parse::<f64>().ok()?;
+		let max = style.max.filter(|max| *max > 0.0).unwrap_or(1.0);
+		Some((value / max).clamp(0.0, 1.0) as f32)
+	}
+}
+
+impl Element for ReactProgressElement {
+	type PrepaintState = ProgressPrepaintState;
+	type RequestLayoutState = ProgressLayoutState;
+
+	fn id(&self) -> Option<ElementId> { Some(ElementId::Integer(self.element.global_id)) }
+
+	fn source_location(&self) -> Option<&'static std::panic::Location<'static>> { None }
+
+	fn request_layout(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		window: &mut Window,
+		cx: &mut App,
+	) -> (LayoutId, Self::RequestLayoutState) {
+		let mut style = self.element.build_gpui_style(None);
+		if self.element.style.width.is_none() {
+			style.size.width = px(DEFAULT_WIDTH).into();
+		}
+		if self.element.style.height.is_none() {
+			style.size.height = px(DEFAULT_HEIGHT).into();
+		}
+		let layout_id = window.request_layout(style, std::iter::empty(), cx);
+		(layout_id, ProgressLayoutState {})
+	}
+
+	fn prepaint(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		bounds: Bounds<Pixels>,
+		_request_layout: &mut Self::RequestLayoutState,
+		window: &mut Window,
+		_cx: &mut App,
+	) -> Self::PrepaintState {
+		layout::record_bounds(self.window_id, self.element.global_id, bounds);
+
+		let event_flags = EventHandlerFlags::from_handlers(
+			self.element.event_handlers.as_ref(),
+			self.element.style.tab_index,
+		);
+		let hitbox = insert_hitbox_if_needed(&event_flags, bounds, window);
+		ProgressPrepaintState { hitbox, event_flags }
+	}
+
+	fn paint(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		bounds: Bounds<Pixels>,
+		_request_layout: &mut Self::RequestLayoutState,
+		prepaint: &mut Self::PrepaintState,
+		window: &mut Window,
+		cx: &mut App,
+	) {
+		let mut style = self.element.build_gpui_style(None);
+		let crisp = pixel_snap::is_crisp(&self.element.style, self.parent_style.as_ref());
+		let (bounds, snapped) =
+			pixel_snap::snap(crisp, bounds, &mut style, &self.element.style, window);
+		let element_style = snapped.as_ref().unwrap_or(&self.element.style);
+		// The background is the track, painted with the bar's rounding
+		style.background = None;
+
+		let fraction = self.fraction();
+		style.paint(bounds, window, cx, |window, _| {
+			paint_bar(element_style, bounds, fraction, window);
+		});
+		border::paint(element_style, bounds, window);
+		border::paint_outline(element_style, bounds, window);
+
+		if fraction.is_none() {
+			pacing::request_frame(self.window_id, window, cx);
+		}
+
+		register_event_handlers(
+			&prepaint.event_flags,
+			prepaint.hitbox.as_ref(),
+			self.window_id,
+			self.element.global_id,
+			window,
+		);
+	}
+}
+
+impl IntoElement for ReactProgressElement {
+	type Element = Self;
+
+	fn into_element(self) -> Self::Element { self }
+}
+
+/// Paint the track and the filled part, or the sweeping segment
+fn paint_bar(
+	style: &ElementStyle,
+	bounds: Bounds<Pixels>,
+	fraction: Option<f32>,
+	window: &mut Window,
+) {
+	let accent = style.accent_color.unwrap_or_else(|| rgb(DEFAULT_ACCENT).into());
+	let track = style.bg_color.unwrap_or_else(|| rgb(0x808080).into());
+	let radius = match style.border_radius {
+		Some(radius) => px(radius),
+		None => bounds.size.height / 2.0,
+	};
+	let rounded = Corners::all(radius);
+	window.paint_quad(fill(bounds, track).corner_radii(rounded));
+
+	let width = f32::from(bounds.size.width);
+	let (from, to) = match fraction {
+		Some(fraction) => (0.0, width * fraction),
+		None => {
+			// The segment enters on the left and leaves on the right
+			let phase = (clock::now_us() % SWEEP_US) as f32 / SWEEP_US as f32;
+			let start = (phase * (1.0 + SEGMENT) - SEGMENT) * width;
+			(start.max(0.0), (start + SEGMENT * width).min(width))
+		}
+	};
+	if to > from {
+		let filled = Bounds {
+			origin: point(bounds.origin.x + px(from), bounds.origin.y),
+			size:   size(px(to - from), bounds.size.height),
+		};
+		window.paint_quad(fill(filled, accent).corner_radii(rounded));
+	}
+}
@@ -0,0 +1,652 @@
+//! QR code encoder
+//!
+//! Encodes text in byte mode (UTF-8) at the smallest version, 1 to 40, that
+//! holds it at the requested error correction level, and picks the mask with
+//! the lowest penalty. Follows ISO/IEC 18004; structured append, ECI and the
+//! numeric/alphanumeric modes aren't used.
+
+/// Error correction level, the share of codewords that can be restored
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EcLevel {
+	Low,
+	Medium,
+	Quartile,
+	High,
+}
+
+impl EcLevel {
+	pub fn from_str(s: &str) -> Option<Self> {
+		match s {
+			"L" | "l" => Some(EcLevel::Low),
+			"M" | "m" => Some(EcLevel::Medium),
+			"Q" | "q" => Some(EcLevel::Quartile),
+			"H" | "h" => Some(EcLevel::High),
+			_ => None,
+		}
+	}
+
+	fn index(self) -> usize {
+		match self {
+			EcLevel::Low => 0,
+			EcLevel::Medium => 1,
+			EcLevel::Quartile => 2,
+			EcLevel::High => 3,
+		}
+	}
+
+	fn format_bits(self) -> u32 {
+		match self {
+			EcLevel::Low => 1,
+			EcLevel::Medium => 0,
+			EcLevel::Quartile => 3,
+			EcLevel::High => 2,
+		}
+	}
+}
+
+#[rustfmt::skip]
+const ECC_CODEWORDS_PER_BLOCK: [[u8; 41]; 4] = [
+	[0, 7, 10, 15, 20, 26, 18, 20, 24, 30, 18, 20, 24, 26, 30, 22, 24, 28, 30, 28, 28, 28, 28, 30, 30, 26, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30],
+	[0, 10, 16, 26, 18, 24, 16, 18, 22, 22, 26, 30, 22, 22, 24, 24, 28, 28, 26, 26, 26, 26, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28],
+	[0, 13, 22, 18, 26, 18, 24, 18, 22, 20, 24, 28, 26, 24, 20, 30, 24, 28, 28, 26, 30, 28, 30, 30, 30, 30, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30],
+	[0, 17, 28, 22, 16, 22, 28, 26, 26, 24, 28, 24, 28, 22, 24, 24, 30, 28, 28, 26, 28, 30, 24, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30],
+];
+
+#[rustfmt::skip]
+const ERROR_CORRECTION_BLOCKS: [[u8; 41]; 4] = [
+	[0, 1, 1, 1, 1, 1, 2, 2, 2, 2, 4, 4, 4, 4, 4, 6, 6, 6, 6, 7, 8, 8, 9, 9, 10, 12, 12, 12, 13, 14, 15, 16, 17, 18, 19, 19, 20, 21, 22, 24, 25],
+	[0, 1, 1, 1, 2, 2, 4, 4, 4, 5, 5, 5, 8, 9, 9, 10, 10, 11, 13, 14, 16, 17, 17, 18, 20, 21, 23, 25, 26, 28, 29, 31, 33, 35, 37, 38, 40, 43, 45, 47, 49],
+	[0, 1, 1, 2, 2, 4, 4, 6, 6, 8, 8, 8, 10, 12, 16, 12, 17, 16, 18, 21, 20, 23, 23, 25, 27, 29, 34, 34, 35, 38, 40, 43, 45, 48, 51, 53, 56, 59, 62, 65, 68],
+	[0, 1, 1, 2, 4, 4, 4, 5, 6, 8, 8, 11, 11, 16, 16, 18, 16, 19, 21, 25, 25, 25, 34, 30, 32, 35, 37, 40, 42, 45, 48, 51, 54, 57, 60, 63, 66, 70, 74, 77, 81],
+];
+
+/// An encoded QR code: a square of dark and light modules, without quiet zone
+pub struct QrCode {
+	size:     usize,
+	modules:  Vec<bool>,
+	/// Finder, timing, alignment, format and version modules
+	function: Vec<bool>,
+}
+
+impl QrCode {
+	/// Encode `text`, None if it's too long for version 40 at this level
+	pub fn encode(text: &str, ec: EcLevel) -> Option<Self> {
+		let bytes = text.as_bytes();
+		let version = (1..=40).find(|&version| {
+			let count_bits = if version < 10 { 8 } else { 16 };
+			4 + count_bits + bytes.len() * 8 <= data_codewords(version, ec) * 8
+		})?;
+
+		let mut bits = BitBuffer::default();
+		bits.push(0b0100, 4);
+		bits.push(bytes.len() as u32, if version < 10 { 8 } else { 16 });
+		for byte in bytes {
+			bits.push(*byte as u32, 8);
+		}
+		// Terminator, then pad to whole bytes and fill with alternating pad bytes
+		let capacity = data_codewords(version, ec) * 8;
+		let terminator = (capacity - bits.len()).min(4);
+		bits.push(0, terminator);
+		bits.push(0, (8 - bits.len() % 8) % 8);
+		let mut data = bits.into_bytes();
+		for pad in [0xec, 0x11].into_iter().cycle() {
+			if data.len() * 8 >= capacity {
+				break;
+			}
+			data.push(pad);
+		}
+
+		let size = version * 4 + 17;
+		let mut qr =
+			QrCode { size, modules: vec![false; size * size], function: vec![false; size * size] };
+		qr.draw_function_patterns(version, ec);
+		qr.draw_codewords(&add_ecc_and_interleave(&data, version, ec));
+
+		let best = (0..8)
+			.min_by_key(|&mask| {
+				qr.apply_mask(mask);
+				qr.draw_format_bits(ec, mask);
+				let penalty = qr.penalty();
+				qr.apply_mask(mask);
+				penalty
+			})
+			.unwrap_or(0);
+		qr.apply_mask(best);
+		qr.draw_format_bits(ec, best);
+		Some(qr)
+	}
+
+	/// Modules per side
+	pub fn size(&self) -> usize { self.size }
+
+	pub fn is_dark(&self, x: usize, y: usize) -> bool { self.modules[y * self.size + x] }
+
+	fn set_function(&mut self, x: usize, y: usize, dark: bool) {
+		self.modules[y * self.size + x] = dark;
+		self.function[y * self.size + x] = true;
+	}
+
+	fn draw_function_patterns(&mut self, version: usize, ec: EcLevel) {
+		let size = self.size;
+		for i in 0..size {
+			self.set_function(6, i, i % 2 == 0);
+			self.set_function(i, 6, i % 2 == 0);
+		}
+
+		self.draw_finder(3, 3);
+		self.draw_finder(size - 4, 3);
+		self.draw_finder(3, size - 4);
+
+		let positions = alignment_positions(version);
+		let last = positions.len().saturating_sub(1);
+		for (i, &x) in positions.iter().enumerate() {
+			for (j, &y) in positions.iter().enumerate() {
+				// Corners taken by the finders
+				if [(0, 0), (0, last), (last, 0)].contains(&(i, j)) {
+					continue;
+				}
+				self.draw_alignment(x, y);
+			}
+		}
+
+		// Reserved now, drawn for real once the mask is chosen
+		self.draw_format_bits(ec, 0);
+		self.draw_version(version);
+	}
+
+	fn draw_finder(&mut self, x: usize, y: usize) {
+		for dy in -4i32..=4 {
+			for dx in -4i32..=4 {
+				let (xx, yy) = (x as i32 + dx, y as i32 + dy);
+				if (0..self.size as i32).contains(&xx) && (0..self.size as i32).contains(&yy) {
+					let distance = dx.abs().max(dy.abs());
+					self.set_function(xx as usize, yy as usize, distance != 2 && distance != 4);
+				}
+			}
+		}
+	}
+
+	fn draw_alignment(&mut self, x: usize, y: usize) {
+		for dy in -2i32..=2 {
+			for dx in -2i32..=2 {
+				let (xx, yy) = ((x as i32 + dx) as usize, (y as i32 + dy) as usize);
+				self.set_function(xx, yy, dx.abs().max(dy.abs()) != 1);
+			}
+		}
+	}
+
+	fn draw_format_bits(&mut self, ec: EcLevel, mask: u8) {
+		let data = (ec.format_bits() << 3) | mask as u32;
+		let mut remainder = data;
+		for _ in 0..10 {
+			remainder = (remainder << 1) ^ ((remainder >> 9) * 0x537);
+		}
+		let bits = ((data << 10) | remainder) ^ 0x5412;
+		let bit = |i: usize| (bits >> i) & 1 != 0;
+
+		// Around the top left finder
+		for i in 0..=5 {
+			self.set_function(8, i, bit(i));
+		}
+		self.set_function(8, 7, bit(6));
+		self.set_function(8, 8, bit(7));
+		self.set_function(7, 8, bit(8));
+		for i in 9..15 {
+			self.set_function(14 - i, 8, bit(i));
+		}
+
+		// Split between the other two finders
+		let size = self.size;
+		for i in 0..8 {
+			self.set_function(size - 1 - i, 8, bit(i));
+		}
+		for i in 8..15 {
+			self.set_function(8, size - 15 + i, bit(i));
+		}
+		self.set_function(8, size - 8, true);
+	}
+
+	fn draw_version(&mut self, version: usize) {
+		if version < 7 {
+			return;
+		}
+		let mut remainder = version as u32;
+		for _ in 0..12 {
+			remainder = (remainder << 1) ^ ((remainder >> 11) * 0x1f25);
+		}
+		let bits = ((version as u32) << 12) | remainder;
+		for i in 0..18 {
+			let dark = (bits >> i) & 1 != 0;
+			let (a, b) = (self.size - 11 + i % 3, i / 3);
+			self.set_function(a, b, dark);
+			self.set_function(b, a, dark);
+		}
+	}
+
+	/// Place the data in the zigzag of two-module columns, right to left
+	fn draw_codewords(&mut self, data: &[u8]) {
+		let size = self.size as i32;
+		let mut i = 0;
+		let mut right = size - 1;
+		while right >= 1 {
+			// Skip the vertical timing pattern
+			if right == 6 {
+				right = 5;
+			}
+			for vertical in 0..size {
+				for j in 0..2 {
+					let x = (right - j) as usize;
+					let upward = (right + 1) & 2 == 0;
+					let y = (if upward { size - 1 - vertical } else { vertical }) as usize;
+					if !self.function[y * self.size + x] && i < data.len() * 8 {
+						self.modules[y * self.size + x] = (data[i >> 3] >> (7 - (i & 7))) & 1 != 0;
+						i += 1;
+					}
+				}
+			}
+			right -= 2;
+		}
+	}
+
+	/// XOR a mask over the data modules; applying it twice undoes it
+	fn apply_mask(&mut self, mask: u8) {
+		for y in 0..self.size {
+			for x in 0..self.size {
+				let invert = match mask {
+					0 => (x + y) % 2 == 0,
+					1 => y % 2 == 0,
+					2 => x % 3 == 0,
+					3 => (x + y) % 3 == 0,
+					4 => (x / 3 + y / 2) % 2 == 0,
+					5 => x * y % 2 + x * y % 3 == 0,
+					6 => (x * y % 2 + x * y % 3) % 2 == 0,
+					_ => ((x + y) % 2 + x * y % 3) % 2 == 0,
+				};
+				let index = y * self.size + x;
+				if invert && !self.function[index] {
+					self.modules[index] ^= true;
+				}
+			}
+		}
+	}
+
+	/// Penalty of the current modules, lower scans more reliably
+	fn penalty(&self) -> usize {
+		let size = self.size;
+		let mut penalty = 0;
+		let lines = (0..size).flat_map(|y| {
+			[
+				(0..size).map(|x| self.is_dark(x, y)).collect::<Vec<_>>(),
+				(0..size).map(|x| self.is_dark(y, x)).collect::<Vec<_>>(),
+			]
+		});
+		for line in lines {
+			// Runs of five or more of one color
+			let mut run = 1;
+			for i in 1..=size {
+				if i < size && line[i] == line[i - 1] {
+					run += 1;
+					continue;
+				}
+				if run >= 5 {
+					penalty += 3 + run - 5;
+				}
+				run = 1;
+			}
+			// Finder-like 1:1:3:1:1 with four light modules on one side
+			const FINDER: [bool; 7] = [true, false, true, true, true, false, true];
+			for start in 0..=size.saturating_sub(11) {
+				let window = &line[start..start + 11];
+				if (window[..7] == FINDER && window[7..].iter().all(|dark| !dark))
+					|| (window[4..] == FINDER && window[..4].iter().all(|dark| !dark))
+				{
+					penalty += 40;
+				}
+			}
+		}
+
+		// 2x2 blocks of one color
+		for y in 0..size - 1 {
+			for x in 0..size - 1 {
+				let color = self.is_dark(x, y);
+				if color == self.is_dark(x + 1, y)
+					&& color == self.is_dark(x, y + 1)
+					&& color == self.is_dark(x + 1, y + 1)
+				{
+					penalty += 3;
+				}
+			}
+		}
+
+		// Dark share away from half, in steps of 5%
+		let total = (size * size) as i64;
+		let dark = self.modules.iter().filter(|dark| **dark).count() as i64;
+		let k = ((dark * 20 - total * 10).abs() + total - 1) / total - 1;
+		penalty + k.max(0) as usize * 10
+	}
+}
+
+#[derive(Default)]
+struct BitBuffer {
+	bits: Vec<bool>,
+}
+
+impl BitBuffer {
+	fn push(&mut self, value: u32, count: usize) {
+		for i in (0..count).rev() {
+			self.bits.push((value >> i) & 1 != 0);
+		}
+	}
+
+	fn len(&self) -> usize { self.bits.len() }
+
+	fn into_bytes(self) -> Vec<u8> {
+		self
+			.bits
+			.chunks(8)
+			.map(|chunk| chunk.iter().fold(0u8, |byte, bit| (byte << 1) | *bit as u8))
+			.collect()
+	}
+}
+
+/// Modules available for data and error correction in a version
+fn raw_data_modules(version: usize) -> usize {
+	let mut result = (16 * version + 128) * version + 64;
+	if version >= 2 {
+		let alignments = version / 7 + 2;
+		result -= (25 * alignments - 10) * alignments - 55;
+		if version >= 7 {
+			result -= 36;
+		}
+	}
+	result
+}
+
+fn data_codewords(version: usize, ec: EcLevel) -> usize {
+	raw_data_modules(version) / 8
+		- ECC_CODEWORDS_PER_BLOCK[ec.index()][version] as usize
+			* ERROR_CORRECTION_BLOCKS[ec.index()][version] as usize
+}
+
+/// Centers of the alignment patterns along each axis
+fn alignment_positions(version: usize) -> Vec<usize> {
+	if version == 1 {
+		return Vec::new();
+	}
+	let count = version / 7 + 2;
+	let step = (version * 8 + count * 3 + 5) / (count * 4 - 4) * 2;
+	let size = version * 4 + 17;
+	let mut positions: Vec<usize> = (0..count - 1).map(|i| size - 7 - i * step).collect();
+	positions.push(6);
+	positions.reverse();
+	positions
+}
+
+/// Split the data into blocks, append each block's Reed-Solomon codewords,
+/// then interleave the blocks
+fn add_ecc_and_interleave(data: &[u8], version: usize, ec: EcLevel) -> Vec<u8> {
+	let blocks = ERROR_CORRECTION_BLOCKS[ec.index()][version] as usize;
+	let ecc_len = ECC_CODEWORDS_PER_BLOCK[ec.index()][version] as usize;
+	let raw_codewords = raw_data_modules(version) / 8;
+	let short_blocks = blocks - raw_codewords % blocks;
+	let short_len = raw_codewords / blocks;
+
+	let divisor = reed_solomon_divisor(ecc_len);
+	let mut all = Vec::with_capacity(blocks);
+	let mut offset = 0;
+	for i in 0..blocks {
+		let data_len = short_len - ecc_len + usize::from(i >= short_blocks);
+		let mut block = data[offset..offset + data_len].to_vec();
+		offset += data_len;
+		let ecc = reed_solomon_remainder(&block, &divisor);
+		// Short blocks get a placeholder so all blocks line up
+		if i < short_blocks {
+			block.push(0);
+		}
+		block.extend(ecc);
+		all.push(block);
+	}
+
+	let mut result = Vec::with_capacity(raw_codewords);
+	for i in 0..all[0].len() {
+		for (j, block) in all.iter().enumerate() {
+			if i != short_len - ecc_len || j >= short_blocks {
+				result.push(block[i]);
+			}
+		}
+	}
+	result
+}
+
+fn reed_solomon_divisor(degree: usize) -> Vec<u8> {
+	let mut result = vec![0u8; degree];
+	result[degree - 1] = 1;
+	let mut root = 1u8;
+	for _ in 0..degree {
+		for j in 0..degree {
+			result[j] = gf_multiply(result[j], root);
+			if j + 1 < degree {
+				result[j] ^= result[j + 1];
+			}
+		}
+		root = gf_multiply(root, 0x02);
+	}
+	result
+}
+
+fn reed_solomon_remainder(data: &[u8], divisor: &[u8]) -> Vec<u8> {
+	let mut result = vec![0u8; divisor.len()];
+	for byte in data {
+		let factor = byte ^ result[0];
+		result.remove(0);
+		result.push(0);
+		for (x, y) in result.iter_mut().zip(divisor) {
+			*x ^= gf_multiply(*y, factor);
+		}
+	}
+	result
+}
+
+/// Multiply in GF(2^8) modulo x^8 + x^4 + x^3 + x^2 + 1
+fn gf_multiply(x: u8, y: u8) -> u8 {
+	let mut z = 0u8;
+	for i in (0..8).rev() {
+		z = (z << 1) ^ ((z >> 7) * 0x1d);
+		z ^= ((y >> i) & 1) * x;
+	}
+	z
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const LEVELS: [EcLevel; 4] = [EcLevel::Low, EcLevel::Medium, EcLevel::Quartile, EcLevel::High];
+
+	/// Format information by level then mask, after masking (ISO/IEC 18004
+	/// table C.1)
+	const FORMAT_INFO: [[u16; 8]; 4] = [
+		[0x77c4, 0x72f3, 0x7daa, 0x789d, 0x662f, 0x6318, 0x6c41, 0x6976],
+		[0x5412, 0x5125, 0x5e7c, 0x5b4b, 0x45f9, 0x40ce, 0x4f97, 0x4aa0],
+		[0x355f, 0x3068, 0x3f31, 0x3a06, 0x24b4, 0x2183, 0x2eda, 0x2bed],
+		[0x1689, 0x13be, 0x1ce7, 0x19d0, 0x0762, 0x0255, 0x0d0c, 0x083b],
+	];
+
+	/// Bytes that fit a version 1 symbol, by level
+	const VERSION_1_CAPACITY: [usize; 4] = [17, 14, 11, 7];
+
+	fn version(qr: &QrCode) -> usize { (qr.size - 17) / 4 }
+
+	/// Format information around the top left finder
+	fn format_info(qr: &QrCode) -> u16 {
+		let mut positions: Vec<(usize, usize)> = (0..=5).map(|y| (8, y)).collect();
+		positions.extend([(8, 7), (8, 8), (7, 8)]);
+		positions.extend((9..15).map(|i| (14 - i, 8)));
+		positions.iter().enumerate().map(|(i, &(x, y))| (qr.is_dark(x, y) as u16) << i).sum()
+	}
+
+	fn is_masked(mask: usize, x: usize, y: usize) -> bool {
+		let (i, j) = (y, x);
+		match mask {
+			0 => (i + j) % 2 == 0,
+			1 => i % 2 == 0,
+			2 => j % 3 == 0,
+			3 => (i + j) % 3 == 0,
+			4 => (i / 2 + j / 3) % 2 == 0,
+			5 => (i * j) % 2 + (i * j) % 3 == 0,
+			6 => ((i * j) % 2 + (i * j) % 3) % 2 == 0,
+			_ => ((i + j) % 2 + (i * j) % 3) % 2 == 0,
+		}
+	}
+
+	/// Evaluate a codeword polynomial, highest degree first, at `x`
+	fn evaluate(codewords: &[u8], x: u8) -> u8 {
+		codewords.iter().fold(0, |sum, c| gf_multiply(sum, x) ^ c)
+	}
+
+	/// Decode a symbol back to its byte mode payload, checking every
+	/// block's error correction on the way
+	fn decode(qr: &QrCode, ec: EcLevel) -> Vec<u8> {
+		let mask = FORMAT_INFO[ec.index()].iter().position(|f| *f == format_info(qr)).unwrap();
+
+		// Codeword bits along the zigzag, upwards from the bottom right
+		let mut bits = Vec::new();
+		let size = qr.size;
+		let mut right = size - 1;
+		let mut upward = true;
+		loop {
+			if right == 6 {
+				right = 5;
+			}
+			for step in 0..size {
+				let y = if upward { size - 1 - step } else { step };
+				for x in [right, right - 1] {
+					if !qr.function[y * size + x] {
+						bits.push(qr.is_dark(x, y) ^ is_masked(mask, x, y));
+					}
+				}
+			}
+			upward = !upward;
+			if right < 2 {
+				break;
+			}
+			right -= 2;
+		}
+		let codewords: Vec<u8> = bits
+			.chunks_exact(8)
+			.map(|byte| byte.iter().fold(0, |value, bit| (value << 1) | *bit as u8))
+			.collect();
+
+		// Undo the interleaving: data codewords column by column, short
+		// blocks first, then the error correction codewords
+		let version = version(qr);
+		let blocks = ERROR_CORRECTION_BLOCKS[ec.index()][version] as usize;
+		let ecc_len = ECC_CODEWORDS_PER_BLOCK[ec.index()][version] as usize;
+		let total = raw_data_modules(version) / 8;
+		let long_blocks = total % blocks;
+		let short_data = total / blocks - ecc_len;
+		let data_len = |block: usize| short_data + usize::from(block >= blocks - long_blocks);
+		let mut split: Vec<Vec<u8>> = vec![Vec::new(); blocks];
+		let mut next = codewords.iter().copied();
+		for i in 0..=short_data {
+			for (block, codewords) in split.iter_mut().enumerate() {
+				if i < data_len(block) {
+					codewords.push(next.next().unwrap());
+				}
+			}
+		}
+		for _ in 0..ecc_len {
+			for codewords in split.iter_mut() {
+				codewords.push(next.next().unwrap());
+			}
+		}
+
+		// A block is a codeword when its polynomial vanishes at every
+		// root of the generator
+		let mut data: Vec<u8> = Vec::new();
+		for (block, codewords) in split.iter().enumerate() {
+			let mut root = 1;
+			for _ in 0..ecc_len {
+				assert_eq!(evaluate(codewords, root), 0, "block {} fails error correction", block);
+				root = gf_multiply(root, 0x02);
+			}
+			data.extend(&codewords[..data_len(block)]);
+		}
+
+		let bit = |i: usize| (data[i / 8] >> (7 - i % 8)) & 1;
+		let read =
+			|from: usize, count: usize| (from..from + count).fold(0, |v, i| (v << 1) | bit(i) as usize);
+		assert_eq!(read(0, 4), 0b0100, "byte mode");
+		let count_bits = if version < 10 { 8 } else { 16 };
+		let len = read(4, count_bits);
+		(0..len).map(|i| read(4 + count_bits + i * 8, 8) as u8).collect()
+	}
+
+	#[test]
+	fn format_information_matches_the_standard_table() {
+		for ec in LEVELS {
+			let qr = QrCode::encode("https://example.com", ec).unwrap();
+			assert!(FORMAT_INFO[ec.index()].contains(&format_info(&qr)), "{:?}", ec);
+		}
+	}
+
+	#[test]
+	fn version_1_capacity_per_level() {
+		for ec in LEVELS {
+			let capacity = VERSION_1_CAPACITY[ec.index()];
+			let fits = QrCode::encode(&"a".repeat(capacity), ec).unwrap();
+			assert_eq!(fits.size(), 21, "{:?}", ec);
+			let overflows = QrCode::encode(&"a".repeat(capacity + 1), ec).unwrap();
+			assert_eq!(overflows.size(), 25, "{:?}", ec);
+		}
+	}
+
+	#[test]
+	fn character_count_grows_at_version_10() {
+		// Version 9-L holds 230 bytes, version 10-L 271
+		assert_eq!(version(&QrCode::encode(&"a".repeat(230), EcLevel::Low).unwrap()), 9);
+		assert_eq!(version(&QrCode::encode(&"a".repeat(231), EcLevel::Low).unwrap()), 10);
+	}
+
+	#[test]
+	fn version_40_is_the_limit() {
+		assert_eq!(version(&QrCode::encode(&"a".repeat(2953), EcLevel::Low).unwrap()), 40);
+		assert!(QrCode::encode(&"a".repeat(2954), EcLevel::Low).is_none());
+	}
+
+	#[test]
+	fn version_information_matches_the_standard() {
+		// Version 7 is 000111 110010010100 (ISO/IEC 18004 table D.1)
+		// Version 6-M holds 106 bytes, version 7-M 122
+		let qr = QrCode::encode(&"a".repeat(110), EcLevel::Medium).unwrap();
+		assert_eq!(version(&qr), 7);
+		let read = |transpose: bool| {
+			(0..18).fold(0u32, |bits, i| {
+				let (a, b) = (qr.size - 11 + i % 3, i / 3);
+				let (x, y) = if transpose { (b, a) } else { (a, b) };
+				bits | (qr.is_dark(x, y) as u32) << i
+			})
+		};
+		assert_eq!(read(false), 0x07c94);
+		assert_eq!(read(true), 0x07c94);
+	}
+
+	#[test]
+	fn decodes_at_every_level() {
+		for ec in LEVELS {
+			let qr = QrCode::encode("HELLO, QR", ec).unwrap();
+			assert_eq!(decode(&qr, ec), b"HELLO, QR", "{:?}", ec);
+		}
+	}
+
+	#[test]
+	fn decodes_multi_block_payloads() {
+		// Unicode text over several versions, with short and long blocks
+		let text = "Größe · 大きさ · size ".repeat(20);
+		for ec in LEVELS {
+			let qr = QrCode::encode(&text, ec).unwrap();
+			assert!(version(&qr) > 10, "{:?}", ec);
+			assert_eq!(decode(&qr, ec), text.as_bytes(), "{:?}", ec);
+		}
+	}
+}
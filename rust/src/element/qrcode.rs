@@ -0,0 +1,382 @@
+//! QR code and barcode element
+//!
+//! A `qrcode` encodes its `value` as a QR code, or as a Code 128 or EAN-13
+//! barcode with `symbology`, and paints it as large as its bounds allow. Each
+//! module is a whole number of device pixels when there's room, so the code
+//! stays sharp at any size and scale factor. Modules are painted in the text
+//! color (black by default) on the background color (white by default), with
+//! a quiet zone of `quietZone` modules around the code (4 for QR codes, 10 for
+//! barcodes).
+//!
+//! `logoSize`, a fraction of a QR code's width up to 0.3, clears a square in
+//! its middle and lays the element's children out in it. The error correction
+//! level then defaults to H instead of M, so the code still scans.
+
+use std::{collections::HashMap, sync::{Arc, Mutex}};
+
+use gpui::{AnyElement, App, AvailableSpace, Bounds, Element, ElementId, GlobalElementId, Hitbox, Hsla, InspectorElementId, IntoElement, LayoutId, Pixels, Point, Window, fill, point, px, rgb, size};
+use lazy_static::lazy_static;
+
+use super::{ElementStyle, ReactElement, barcode, border, events::{EventHandlerFlags, insert_hitbox_if_needed, register_event_handlers}, layout, pixel_snap, qr::{EcLevel, QrCode}, style_variants};
+use crate::sync::MutexExt;
+
+/// Size of a QR code when the style has none
+const DEFAULT_QR_SIZE: f32 = 128.0;
+/// Size of a barcode when the style has none
+const DEFAULT_BAR_WIDTH: f32 = 200.0;
+const DEFAULT_BAR_HEIGHT: f32 = 64.0;
+const QR_QUIET_ZONE: u32 = 4;
+const BAR_QUIET_ZONE: u32 = 10;
+/// Largest logo, as a fraction of the QR code's width
+const MAX_LOGO: f32 = 0.3;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Symbology {
+	Qr,
+	Code128,
+	Ean13,
+}
+
+impl Symbology {
+	fn from_style(style: &ElementStyle) -> Self {
+		match style.symbology.as_deref() {
+			Some("code128") => Symbology::Code128,
+			Some("ean13") => Symbology::Ean13,
+			_ => Symbology::Qr,
+		}
+	}
+}
+
+enum Encoded {
+	Matrix(QrCode),
+	/// Modules of a barcode, left to right
+	Bars(Vec<bool>),
+}
+
+/// Last encoding of an element, reused while its value and options stay put
+struct Cached {
+	value:     String,
+	symbology: Symbology,
+	ec:        EcLevel,
+	encoded:   Option<Arc<Encoded>>,
+}
+
+lazy_static! {
+	static ref CODES: Mutex<HashMap<(u64, u64), Cached>> = Mutex::new(HashMap::new());
+}
+
+/// Drop all state of a closed window
+pub fn forget_window(window_id: u64) { CODES.lock_recover().retain(|(id, _), _| *id != window_id); }
+
+/// Drop the cached encoding of a released element
+pub fn forget_element(window_id: u64, element_id: u64) {
+	CODES.lock_recover().remove(&(window_id, element_id));
+}
+
+/// Error correction level: the style's, else H with a logo and M without
+fn ec_level(style: &ElementStyle) -> EcLevel {
+	let default = if logo_fraction(style) > 0.0 { EcLevel::High } else { EcLevel::Medium };
+	style.qr_ec_level.as_deref().and_then(EcLevel::from_str).unwrap_or(default)
+}
+
+fn logo_fraction(style: &ElementStyle) -> f32 {
+	style.logo_size.unwrap_or(0.0).clamp(0.0, MAX_LOGO)
+}
+
+/// Encode the element's value, or reuse the last encoding
+/// None if the value can't be encoded in its symbology
+fn encode(window_id: u64, element: &ReactElement) -> Option<Arc<Encoded>> {
+	let style = &element.style;
+	let value = style.value.as_deref().unwrap_or_default();
+	let symbology = Symbology::from_style(style);
+	let ec = ec_level(style);
+
+	let mut codes = CODES.lock_recover();
+	if let Some(cached) = codes.get(&(window_id, element.global_id))
+		&& cached.value == value
+		&& cached.symbology == symbology
+		&& cached.ec == ec
+	{
+		return cached.encoded.clone();
+	}
+
+	let encoded = match symbology {
+		Symbology::Qr => QrCode::encode(value, ec).map(Encoded::Matrix),
+		Symbology::Code128 => barcode::code_128(value).map(Encoded::Bars),
+		Symbology::Ean13 => barcode::ean_13(value).map(Encoded::Bars),
+	}
+	.map(Arc::new);
+	if encoded.is_none() {
+		log::warn!(
+			"qrcode: can't encode {:?} as {}",
+			value,
+			style.symbology.as_deref().unwrap_or("qr")
+		);
+	}
+	let cached = Cached { value: value.to_string(), symbology, ec, encoded: encoded.clone() };
+	codes.insert((window_id, element.global_id), cached);
+	encoded
+}
+
+/// Where the symbol goes, quiet zone included
+struct Grid {
+	origin: Point<Pixels>,
+	/// Module width, and height in a QR code
+	module: Pixels,
+	/// Height of the symbol
+	height: Pixels,
+}
+
+/// Fit `columns` by `rows` modules, quiet zone included, in the bounds
+/// Modules are whole device pixels when at least one fits
+fn fit(bounds: Bounds<Pixels>, columns: usize, rows: Option<usize>, scale: f32) -> Grid {
+	let width = f32::from(bounds.size.width) / columns as f32;
+	let fitted = match rows {
+		Some(rows) => width.min(f32::from(bounds.size.height) / rows as f32),
+		None => width,
+	};
+	let device = (fitted * scale).floor();
+	let module = if device >= 1.0 { device / scale } else { fitted };
+
+	let snap = |value: f32| (value * scale).round() / scale;
+	let code_width = module * columns as f32;
+	let code_height = rows.map_or(f32::from(bounds.size.height), |rows| module * rows as f32);
+	let x = f32::from(bounds.origin.x) + (f32::from(bounds.size.width) - code_width) / 2.0;
+	let y = f32::from(bounds.origin.y) + (f32::from(bounds.size.height) - code_height) / 2.0;
+	Grid { origin: point(px(snap(x)), px(snap(y))), module: px(module), height: px(code_height) }
+}
+
+/// Modules of the square cleared for the logo: its first row/column and width
+fn logo_modules(size: usize, fraction: f32) -> Option<(usize, usize)> {
+	if fraction <= 0.0 {
+		return None;
+	}
+	let mut width = (size as f32 * fraction).ceil() as usize;
+	// Same parity as the code, so the square is centered on a module
+	if width % 2 != size % 2 {
+		width += 1;
+	}
+	Some(((size - width) / 2, width))
+}
+
+pub struct ReactQrCodeElement {
+	element:      Arc<ReactElement>,
+	window_id:    u64,
+	parent_style: Option<ElementStyle>,
+	/// Children laid out in the logo square
+	children:     Vec<AnyElement>,
+}
+
+pub struct QrCodeLayoutState {}
+
+pub struct QrCodePrepaintState {
+	hitbox:      Option<Hitbox>,
+	event_flags: EventHandlerFlags,
+	encoded:     Option<Arc<Encoded>>,
+}
+
+impl ReactQrCodeElement {
+	pub fn new(
+		element: Arc<ReactElement>,
+		window_id: u64,
+		parent_style: Option<ElementStyle>,
+	) -> Self {
+		Self { element, window_id, parent_style, children: Vec::new() }
+	}
+
+	/// Grid of the whole symbol, quiet zone included, and the quiet zone width
+	fn grid(&self, encoded: &Encoded, bounds: Bounds<Pixels>, scale: f32) -> (Grid, usize) {
+		let style = &self.element.style;
+		match encoded {
+			Encoded::Matrix(qr) => {
+				let quiet = style.quiet_zone.unwrap_or(QR_QUIET_ZONE) as usize;
+				let total = qr.size() + quiet * 2;
+				(fit(bounds, total, Some(total), scale), quiet)
+			}
+			Encoded::Bars(bars) => {
+				let quiet = style.quiet_zone.unwrap_or(BAR_QUIET_ZONE) as usize;
+				(fit(bounds, bars.len() + quiet * 2, None, scale), quiet)
+			}
+		}
+	}
+}
+
+impl Element for ReactQrCodeElement {
+	type PrepaintState = QrCodePrepaintState;
+	type RequestLayoutState = QrCodeLayoutState;
+
+	fn id(&self) -> Option<ElementId> { Some(ElementId::Integer(self.element.global_id)) }
+
+	fn source_location(&self) -> Option<&'static std::panic::Location<'static>> { None }
+
+	fn request_layout(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		window: &mut Window,
+		cx: &mut App,
+	) -> (LayoutId, Self::RequestLayoutState) {
+		let mut style = self.element.build_gpui_style(None);
+		let (width, height) = match Symbology::from_style(&self.element.style) {
+			Symbology::Qr => (DEFAULT_QR_SIZE, DEFAULT_QR_SIZE),
+			_ => (DEFAULT_BAR_WIDTH, DEFAULT_BAR_HEIGHT),
+		};
+		if self.element.style.width.is_none() {
+			style.size.width = px(width).into();
+		}
+		if self.element.style.height.is_none() {
+			style.size.height = px(height).into();
+		}
+		let layout_id = window.request_layout(style, std::iter::empty(), cx);
+		(layout_id, QrCodeLayoutState {})
+	}
+
+	fn prepaint(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		bounds: Bounds<Pixels>,
+		_request_layout: &mut Self::RequestLayoutState,
+		window: &mut Window,
+		cx: &mut App,
+	) -> Self::PrepaintState {
+		layout::record_bounds(self.window_id, self.element.global_id, bounds);
+
+		let encoded = encode(self.window_id, &self.element);
+		self.children.clear();
+		if let Some(code) = encoded.as_deref()
+			&& let Encoded::Matrix(qr) = code
+			&& let Some((start, width)) = logo_modules(qr.size(), logo_fraction(&self.element.style))
+			&& !self.element.children.is_empty()
+		{
+			let (grid, quiet) = self.grid(code, bounds, window.scale_factor());
+			let offset = grid.module * (quiet + start) as f32;
+			let origin = point(grid.origin.x + offset, grid.origin.y + offset);
+			let side = AvailableSpace::Definite(grid.module * width as f32);
+
+			let inherited_style =
+				style_variants::effective_style(self.window_id, &self.element, self.parent_style.as_ref());
+			for child in &self.element.children {
+				let mut child =
+					super::create_element(child.clone(), self.window_id, Some(inherited_style.clone()));
+				child.layout_as_root(size(side, side), window, cx);
+				window.with_absolute_element_offset(origin, |window| child.prepaint(window, cx));
+				self.children.push(child);
+			}
+		}
+
+		let event_flags = EventHandlerFlags::from_handlers(
+			self.element.event_handlers.as_ref(),
+			self.element.style.tab_index,
+		);
+		let hitbox = insert_hitbox_if_needed(&event_flags, bounds, window);
+		QrCodePrepaintState { hitbox, event_flags, encoded }
+	}
+
+	fn paint(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		bounds: Bounds<Pixels>,
+		_request_layout: &mut Self::RequestLayoutState,
+		prepaint: &mut Self::PrepaintState,
+		window: &mut Window,
+		cx: &mut App,
+	) {
+		let mut style = self.element.build_gpui_style(None);
+		let crisp = pixel_snap::is_crisp(&self.element.style, self.parent_style.as_ref());
+		let (bounds, snapped) =
+			pixel_snap::snap(crisp, bounds, &mut style, &self.element.style, window);
+		let element = self.element.clone();
+		let element_style = snapped.as_ref().unwrap_or(&element.style);
+
+		style.paint(bounds, window, cx, |window, cx| {
+			if let Some(encoded) = prepaint.encoded.as_deref() {
+				let (grid, quiet) = self.grid(encoded, bounds, window.scale_factor());
+				paint_code(element_style, encoded, &grid, quiet, window);
+			}
+			for child in &mut self.children {
+				child.paint(window, cx);
+			}
+		});
+		border::paint(element_style, bounds, window);
+		border::paint_outline(element_style, bounds, window);
+
+		register_event_handlers(
+			&prepaint.event_flags,
+			prepaint.hitbox.as_ref(),
+			self.window_id,
+			self.element.global_id,
+			window,
+		);
+	}
+}
+
+impl IntoElement for ReactQrCodeElement {
+	type Element = Self;
+
+	fn into_element(self) -> Self::Element { self }
+}
+
+/// Paint the quiet zone and the dark modules, one quad per horizontal run
+fn paint_code(
+	style: &ElementStyle,
+	encoded: &Encoded,
+	grid: &Grid,
+	quiet: usize,
+	window: &mut Window,
+) {
+	let dark: Hsla = style.text_color.unwrap_or_else(|| rgb(0x000000).into());
+	let light: Hsla = style.bg_color.unwrap_or_else(|| rgb(0xffffff).into());
+	let module = grid.module;
+	let run = |row: f32, rows: Pixels, from: usize, to: usize| {
+		let origin =
+			point(grid.origin.x + module * (quiet + from) as f32, grid.origin.y + module * row);
+		Bounds::new(origin, size(module * (to - from) as f32, rows))
+	};
+
+	match encoded {
+		Encoded::Matrix(qr) => {
+			let total = (qr.size() + quiet * 2) as f32;
+			window.paint_quad(fill(Bounds::new(grid.origin, size(module * total, grid.height)), light));
+
+			let logo = logo_modules(qr.size(), logo_fraction(style));
+			let in_logo = |x: usize, y: usize| {
+				logo.is_some_and(|(start, width)| {
+					(start..start + width).contains(&x) && (start..start + width).contains(&y)
+				})
+			};
+			for y in 0..qr.size() {
+				let dark_at = |x: usize| qr.is_dark(x, y) && !in_logo(x, y);
+				for (from, to) in runs(qr.size(), dark_at) {
+					window.paint_quad(fill(run((quiet + y) as f32, module, from, to), dark));
+				}
+			}
+		}
+		Encoded::Bars(bars) => {
+			let total = (bars.len() + quiet * 2) as f32;
+			window.paint_quad(fill(Bounds::new(grid.origin, size(module * total, grid.height)), light));
+			for (from, to) in runs(bars.len(), |x| bars[x]) {
+				window.paint_quad(fill(run(0.0, grid.height, from, to), dark));
+			}
+		}
+	}
+}
+
+/// Ranges of consecutive dark modules among `count`
+fn runs(count: usize, dark: impl Fn(usize) -> bool) -> Vec<(usize, usize)> {
+	let mut runs = Vec::new();
+	let mut x = 0;
+	while x < count {
+		if !dark(x) {
+			x += 1;
+			continue;
+		}
+		let from = x;
+		while x < count && dark(x) {
+			x += 1;
+		}
+		runs.push((from, x));
+	}
+	runs
+}
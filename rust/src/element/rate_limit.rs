@@ -0,0 +1,208 @@
+//! Per-element throttling and debouncing of events before they are queued
+//!
+//! Elements opt in with an `eventOptions` prop keyed by handler name, e.g.
+//! `{ onMouseMove: { throttleMs: 16 } }`. A throttled event is forwarded at
+//! most once per interval and the latest event inside the interval is held as
+//! a trailing event. A debounced event is held until no newer one arrived for
+//! the delay. A held event enters the queue once it's due, ahead of the next
+//! event queued for its window or when JS polls, so JS sees the window's
+//! events in the order they were released.
+
+use std::{collections::HashMap, sync::Mutex, time::{Duration, Instant}};
+
+use lazy_static::lazy_static;
+use serde_json::Value;
+
+use crate::{event_types::prop_to_event_type, sync::MutexExt, window::EventMessage};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Limit {
+	Throttle(Duration),
+	Debounce(Duration),
+}
+
+impl Limit {
+	fn interval(self) -> Duration {
+		match self {
+			Limit::Throttle(d) | Limit::Debounce(d) => d,
+		}
+	}
+}
+
+/// Coalescing state of one (window, element, event type)
+struct Slot {
+	interval:  Duration,
+	/// When an event was last forwarded
+	last_sent: Option<Instant>,
+	/// Held event and the instant it becomes due
+	pending:   Option<(EventMessage, Instant)>,
+}
+
+lazy_static! {
+	static ref SLOTS: Mutex<HashMap<(u64, u64, String), Slot>> = Mutex::new(HashMap::new());
+}
+
+/// Drop all state of a closed window
+pub fn forget_window(window_id: u64) {
+	SLOTS.lock_recover().retain(|(window, ..), _| *window != window_id);
+}
+
+/// Drop the slots of a released element, held events included
+pub fn forget_element(window_id: u64, element_id: u64) {
+	SLOTS
+		.lock_recover()
+		.retain(|(window, element, _), _| (*window, *element) != (window_id, element_id));
+}
+
+/// The limit an element's `eventOptions` configures for `event_type`
+/// `debounceMs` wins if both are given; values that aren't a positive,
+/// representable duration configure no limit
+pub fn limit_for(options: &Value, event_type: &str) -> Option<Limit> {
+	let (_, entry) =
+		options.as_object()?.iter().find(|(prop, _)| prop_to_event_type(prop) == Some(event_type))?;
+	let millis = |key: &str| {
+		entry
+			.get(key)
+			.and_then(Value::as_f64)
+			.filter(|ms| *ms > 0.0)
+			.and_then(|ms| Duration::try_from_secs_f64(ms / 1000.0).ok())
+	};
+	millis("debounceMs").map(Limit::Debounce).or_else(|| millis("throttleMs").map(Limit::Throttle))
+}
+
+/// Returns the event if it should be queued now, otherwise holds it
+pub fn admit(limit: Limit, message: EventMessage) -> Option<EventMessage> {
+	admit_at(limit, message, Instant::now())
+}
+
+fn admit_at(limit: Limit, message: EventMessage, now: Instant) -> Option<EventMessage> {
+	let key = (message.window_id, message.element_id, message.event_type.clone());
+	let mut slots = SLOTS.lock_recover();
+	let slot = slots.entry(key).or_insert(Slot {
+		interval:  limit.interval(),
+		last_sent: None,
+		pending:   None,
+	});
+	slot.interval = limit.interval();
+
+	match limit {
+		Limit::Throttle(interval) => match slot.last_sent.and_then(|last| last.checked_add(interval)) {
+			Some(next) if now < next => {
+				slot.pending = Some((message, next));
+				None
+			}
+			_ => {
+				slot.last_sent = Some(now);
+				slot.pending = None;
+				Some(message)
+			}
+		},
+		Limit::Debounce(delay) => match now.checked_add(delay) {
+			Some(due) => {
+				slot.pending = Some((message, due));
+				None
+			}
+			// Too far out to hold
+			None => Some(message),
+		},
+	}
+}
+
+/// Take the held events of a window that are due, in deadline order
+pub fn take_due(window_id: u64) -> Vec<EventMessage> { take_due_at(window_id, Instant::now()) }
+
+fn take_due_at(window_id: u64, now: Instant) -> Vec<EventMessage> {
+	let mut slots = SLOTS.lock_recover();
+	let mut due = Vec::new();
+	for ((window, ..), slot) in slots.iter_mut() {
+		if *window != window_id {
+			continue;
+		}
+		if let Some((message, at)) = slot.pending.take_if(|(_, at)| *at <= now) {
+			slot.last_sent = Some(now);
+			due.push((at, message));
+		}
+	}
+
+	// Slots with nothing held and an expired interval carry no state
+	slots.retain(|_, slot| {
+		slot.pending.is_some()
+			|| slot
+				.last_sent
+				.and_then(|last| last.checked_add(slot.interval))
+				.is_some_and(|end| now < end)
+	});
+
+	due.sort_by_key(|(at, _)| *at);
+	due.into_iter().map(|(_, message)| message).collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::window::WindowState;
+
+	const MS: Duration = Duration::from_millis(1);
+
+	fn event(window_id: u64, element_id: u64, event_type: &str, payload: &str) -> EventMessage {
+		EventMessage {
+			window_id,
+			element_id,
+			event_type: event_type.to_string(),
+			payload:    payload.to_string(),
+		}
+	}
+
+	fn payloads(events: Vec<EventMessage>) -> Vec<String> {
+		events.into_iter().map(|event| event.payload).collect()
+	}
+
+	#[test]
+	fn throttle_holds_the_latest_event_until_the_interval_ends() {
+		let (window_id, limit, t0) = (301, Limit::Throttle(16 * MS), Instant::now());
+		let admit = |payload, at| admit_at(limit, event(window_id, 1, "mousemove", payload), at);
+		assert!(admit("a", t0).is_some());
+		assert!(admit("b", t0 + 5 * MS).is_none());
+		assert!(admit("c", t0 + 10 * MS).is_none());
+
+		assert!(take_due_at(window_id, t0 + 15 * MS).is_empty());
+		assert_eq!(payloads(take_due_at(window_id, t0 + 16 * MS)), ["c"]);
+		assert!(take_due_at(window_id, t0 + 40 * MS).is_empty());
+	}
+
+	#[test]
+	fn debounce_restarts_the_delay_on_every_event() {
+		let (window_id, limit, t0) = (302, Limit::Debounce(16 * MS), Instant::now());
+		let admit = |payload, at| admit_at(limit, event(window_id, 1, "input", payload), at);
+		assert!(admit("a", t0).is_none());
+		assert!(admit("b", t0 + 10 * MS).is_none());
+
+		assert!(take_due_at(window_id, t0 + 20 * MS).is_empty());
+		assert_eq!(payloads(take_due_at(window_id, t0 + 26 * MS)), ["b"]);
+	}
+
+	#[test]
+	fn held_events_are_released_in_deadline_order() {
+		let (window_id, t0) = (303, Instant::now());
+		admit_at(Limit::Debounce(20 * MS), event(window_id, 1, "input", "late"), t0);
+		admit_at(Limit::Debounce(10 * MS), event(window_id, 2, "input", "early"), t0);
+		admit_at(Limit::Debounce(10 * MS), event(window_id + 1, 1, "input", "other"), t0);
+		assert_eq!(payloads(take_due_at(window_id, t0 + 30 * MS)), ["early", "late"]);
+	}
+
+	#[test]
+	fn due_events_are_queued_ahead_of_later_events() {
+		let window_id = 304;
+		let state = WindowState::new();
+		let hour = Duration::from_secs(3600);
+		assert!(admit(Limit::Debounce(MS), event(window_id, 1, "mousemove", "held")).is_none());
+		assert!(admit(Limit::Debounce(hour), event(window_id, 2, "input", "later")).is_none());
+		std::thread::sleep(2 * MS);
+
+		state.push_event(event(window_id, 3, "click", "click"));
+		assert_eq!(payloads(state.drain_events()), ["held", "click"]);
+		state.push_due_events(window_id);
+		assert!(state.drain_events().is_empty());
+		forget_window(window_id);
+	}
+}
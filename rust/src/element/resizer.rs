@@ -0,0 +1,412 @@
+//! Resizer element
+//!
+//! The handle between two panes of a splitter or two table columns. Its
+//! `value` is the size being resized, in pixels, kept between `min` (default
+//! 0) and `max`. A `"vertical"` resizer (the default, like a column border)
+//! follows the pointer across, a `"horizontal"` one (between rows) up and down.
+//!
+//! Dragging queues an `input` event for every new size and a `change` event
+//! when the button is released. The handle is focusable, and the arrow keys
+//! along its axis resize by `step` (default 10) and Home/End go to `min`/`max`,
+//! queueing the same `input` and `change` events. Every `change` is announced
+//! like a live region, as a window-level `announce` event.
+//!
+//! Like sliders, the new size overrides the `value` prop until the prop
+//! changes.
+
+use std::{collections::HashMap, sync::{Arc, Mutex}};
+
+use gpui::{App, Bounds, CursorStyle, DispatchPhase, Element, ElementId, GlobalElementId, Hitbox, Hsla, InspectorElementId, IntoElement, LayoutId, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent, Pixels, Point, Window, fill, point, px, rgb, size};
+use lazy_static::lazy_static;
+use serde_json::json;
+
+use super::{ElementKind, ElementStyle, ReactElement, border, events::{EventHandlerFlags, insert_hitbox_if_needed, register_event_handlers}, focus, inert, layout, pixel_snap};
+use crate::{event_types::{EventData, InputEventData, types}, global_state::GLOBAL_STATE, renderer::{dispatch_event_to_js, dispatch_window_event}, sync::MutexExt};
+
+/// Thickness of the handle when the style has none
+const DEFAULT_THICKNESS: f32 = 6.0;
+/// Size change per arrow key when the style has no `step`
+const DEFAULT_STEP: f64 = 10.0;
+/// Line of an active (hovered, dragged or focused) resizer without
+/// `accentColor`
+const DEFAULT_ACCENT: u32 = 0x3b82f6;
+
+/// Size set in Rust that JS hasn't confirmed yet
+struct Moved {
+	/// `value` prop at the time of the move
+	prop:  Option<String>,
+	value: f64,
+}
+
+/// A resizer being dragged
+struct Drag {
+	element_id: u64,
+	/// Pointer position along the axis, and the size, at the press
+	from:       f32,
+	start:      f64,
+}
+
+lazy_static! {
+	static ref MOVED: Mutex<HashMap<(u64, u64), Moved>> = Mutex::new(HashMap::new());
+	static ref DRAGGING: Mutex<HashMap<u64, Drag>> = Mutex::new(HashMap::new());
+}
+
+/// Drop all state of a closed window
+pub fn forget_window(window_id: u64) {
+	MOVED.lock_recover().retain(|(id, _), _| *id != window_id);
+	DRAGGING.lock_recover().remove(&window_id);
+}
+
+/// Drop the moved size of a released element
+pub fn forget_element(window_id: u64, element_id: u64) {
+	MOVED.lock_recover().remove(&(window_id, element_id));
+	DRAGGING.lock_recover().retain(|id, drag| (*id, drag.element_id) != (window_id, element_id));
+}
+
+/// Whether the resizer follows the pointer up and down
+fn is_horizontal(style: &ElementStyle) -> bool {
+	style.orientation.as_deref() == Some("horizontal")
+}
+
+/// Position along the resizer's axis
+fn along(style: &ElementStyle, position: Point<Pixels>) -> f32 {
+	f32::from(if is_horizontal(style) { position.y } else { position.x })
+}
+
+fn clamp(style: &ElementStyle, value: f64) -> f64 {
+	let min = style.min.unwrap_or(0.0);
+	let max = style.max.unwrap_or(f64::INFINITY).max(min);
+	// Drop float noise like 0.30000000000000004
+	(value.clamp(min, max) * 1e9).round() / 1e9
+}
+
+/// Current size, moved size first
+fn current_value(window_id: u64, element_id: u64, style: &ElementStyle) -> f64 {
+	if let Some(moved) = MOVED.lock_recover().get(&(window_id, element_id))
+		&& moved.prop == style.value
+	{
+		return moved.value;
+	}
+	let prop = style.value.as_deref().and_then(|value| value.trim().parse::<f64>().ok());
+	clamp(style, prop.unwrap_or(0.0))
+}
+
+/// Resize to `value` (clamped), queueing `input` if it changed
+/// Returns whether the size changed
+fn move_to(window_id: u64, element_id: u64, value: f64) -> bool {
+	let Some(window) = GLOBAL_STATE.get_window(window_id) else {
+		return false;
+	};
+	let (value, prop) = {
+		let element_map = window.state().element_map.lock_recover();
+		let Some(element) = element_map.get(&element_id) else {
+			return false;
+		};
+		let style = &element.style;
+		let value = clamp(style, value);
+		if style.disabled == Some(true) || value == current_value(window_id, element_id, style) {
+			return false;
+		}
+		(value, style.value.clone())
+	};
+	MOVED.lock_recover().insert((window_id, element_id), Moved { prop, value });
+	dispatch(window_id, element_id, types::INPUT, value);
+	true
+}
+
+fn dispatch(window_id: u64, element_id: u64, event_type: &str, value: f64) {
+	log::debug!("[Rust] resizer {}: element_id={}, value={}", event_type, element_id, value);
+	dispatch_event_to_js(
+		window_id,
+		element_id,
+		event_type,
+		EventData::Input(InputEventData { value: value.to_string(), ..Default::default() }),
+	);
+}
+
+/// Queue `change` with the current size and announce it
+fn commit(window_id: u64, element_id: u64) {
+	let Some(window) = GLOBAL_STATE.get_window(window_id) else {
+		return;
+	};
+	let value = {
+		let element_map = window.state().element_map.lock_recover();
+		let Some(element) = element_map.get(&element_id) else {
+			return;
+		};
+		current_value(window_id, element_id, &element.style)
+	};
+	dispatch(window_id, element_id, types::CHANGE, value);
+	dispatch_window_event(
+		window_id,
+		"announce",
+		json!({ "targetId": element_id, "text": format!("{}", value.round()), "politeness": "polite" }),
+	);
+}
+
+/// Resize a focused resizer with the keyboard
+/// Returns whether the key changed the size
+pub fn key_down(window_id: u64, element_id: u64, key: &str) -> bool {
+	let Some(window) = GLOBAL_STATE.get_window(window_id) else {
+		return false;
+	};
+	let target = {
+		let element_map = window.state().element_map.lock_recover();
+		let Some(element) = element_map.get(&element_id) else {
+			return false;
+		};
+		if element.element_kind != ElementKind::Resizer {
+			return false;
+		}
+		let style = &element.style;
+		let step = style.step.filter(|step| *step > 0.0).unwrap_or(DEFAULT_STEP);
+		let value = current_value(window_id, element_id, style);
+		match (key, is_horizontal(style)) {
+			("left", false) | ("up", true) => value - step,
+			("right", false) | ("down", true) => value + step,
+			("home", _) => style.min.unwrap_or(0.0),
+			("end", _) => match style.max {
+				Some(max) => max,
+				None => return false,
+			},
+			_ => return false,
+		}
+	};
+	if !move_to(window_id, element_id, target) {
+		return false;
+	}
+	commit(window_id, element_id);
+	true
+}
+
+pub struct ReactResizerElement {
+	element:      Arc<ReactElement>,
+	window_id:    u64,
+	parent_style: Option<ElementStyle>,
+}
+
+pub struct ResizerLayoutState {}
+
+pub struct ResizerPrepaintState {
+	hitbox:      Option<Hitbox>,
+	event_flags: EventHandlerFlags,
+}
+
+impl ReactResizerElement {
+	pub fn new(
+		element: Arc<ReactElement>,
+		window_id: u64,
+		parent_style: Option<ElementStyle>,
+	) -> Self {
+		Self { element, window_id, parent_style }
+	}
+}
+
+impl Element for ReactResizerElement {
+	type PrepaintState = ResizerPrepaintState;
+	type RequestLayoutState = ResizerLayoutState;
+
+	fn id(&self) -> Option<ElementId> { Some(ElementId::Integer(self.element.global_id)) }
+
+	fn source_location(&self) -> Option<&'static std::panic::Location<'static>> { None }
+
+	fn request_layout(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		window: &mut Window,
+		cx: &mut App,
+	) -> (LayoutId, Self::RequestLayoutState) {
+		let element_style = &self.element.style;
+		let mut style = self.element.build_gpui_style(None);
+		if is_horizontal(element_style) {
+			if element_style.height.is_none() {
+				style.size.height = px(DEFAULT_THICKNESS).into();
+			}
+		} else if element_style.width.is_none() {
+			style.size.width = px(DEFAULT_THICKNESS).into();
+		}
+		style.flex_shrink = 0.0;
+		let layout_id = window.request_layout(style, std::iter::empty(), cx);
+		(layout_id, ResizerLayoutState {})
+	}
+
+	fn prepaint(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		bounds: Bounds<Pixels>,
+		_request_layout: &mut Self::RequestLayoutState,
+		window: &mut Window,
+		_cx: &mut App,
+	) -> Self::PrepaintState {
+		layout::record_bounds(self.window_id, self.element.global_id, bounds);
+
+		let style = &self.element.style;
+		let mut event_flags =
+			EventHandlerFlags::from_handlers(self.element.event_handlers.as_ref(), style.tab_index);
+		if style.disabled != Some(true) {
+			event_flags.tab_index = event_flags.tab_index.or(Some(0));
+		}
+		let hitbox = insert_hitbox_if_needed(&event_flags, bounds, window);
+		ResizerPrepaintState { hitbox, event_flags }
+	}
+
+	fn paint(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		bounds: Bounds<Pixels>,
+		_request_layout: &mut Self::RequestLayoutState,
+		prepaint: &mut Self::PrepaintState,
+		window: &mut Window,
+		cx: &mut App,
+	) {
+		let mut style = self.element.build_gpui_style(None);
+		let crisp = pixel_snap::is_crisp(&self.element.style, self.parent_style.as_ref());
+		let (bounds, snapped) =
+			pixel_snap::snap(crisp, bounds, &mut style, &self.element.style, window);
+		let element_style = snapped.as_ref().unwrap_or(&self.element.style);
+
+		let (window_id, element_id) = (self.window_id, self.element.global_id);
+		let enabled =
+			self.element.style.disabled != Some(true) && !inert::is_inert(window_id, element_id);
+		let dragging =
+			DRAGGING.lock_recover().get(&window_id).is_some_and(|drag| drag.element_id == element_id);
+		let hovered = prepaint.hitbox.as_ref().is_some_and(|hitbox| hitbox.is_hovered(window));
+		let active = enabled && (dragging || hovered || focus::is_focused(window_id, element_id));
+		style.paint(bounds, window, cx, |window, _| {
+			paint_line(element_style, bounds, active, window);
+		});
+		border::paint(element_style, bounds, window);
+		border::paint_outline(element_style, bounds, window);
+
+		if let Some(hitbox) = prepaint.hitbox.clone()
+			&& enabled
+		{
+			let cursor = if is_horizontal(&self.element.style) {
+				CursorStyle::ResizeUpDown
+			} else {
+				CursorStyle::ResizeLeftRight
+			};
+			// Keep the cursor while the pointer runs ahead of the handle
+			if dragging {
+				window.set_window_cursor_style(cursor);
+			} else {
+				window.set_cursor_style(cursor, &hitbox);
+			}
+			listen_for_drag(window_id, element_id, hitbox, hovered, window);
+		}
+
+		register_event_handlers(
+			&prepaint.event_flags,
+			prepaint.hitbox.as_ref(),
+			window_id,
+			element_id,
+			window,
+		);
+	}
+}
+
+impl IntoElement for ReactResizerElement {
+	type Element = Self;
+
+	fn into_element(self) -> Self::Element { self }
+}
+
+/// Run `f` with the resizer's style, if it still exists
+fn with_style<R>(window_id: u64, element_id: u64, f: impl FnOnce(&ElementStyle) -> R) -> Option<R> {
+	let window = GLOBAL_STATE.get_window(window_id)?;
+	let element_map = window.state().element_map.lock_recover();
+	Some(f(&element_map.get(&element_id)?.style))
+}
+
+/// A press starts a drag, which resizes by the pointer's travel along the axis
+/// until the button is released
+fn listen_for_drag(
+	window_id: u64,
+	element_id: u64,
+	hitbox: Hitbox,
+	hovered: bool,
+	window: &mut Window,
+) {
+	let move_hitbox = hitbox.clone();
+	window.on_mouse_event(move |event: &MouseDownEvent, phase, window, _| {
+		if phase != DispatchPhase::Bubble
+			|| event.button != MouseButton::Left
+			|| !hitbox.is_hovered(window)
+		{
+			return;
+		}
+		let drag = with_style(window_id, element_id, |style| Drag {
+			element_id,
+			from: along(style, event.position),
+			start: current_value(window_id, element_id, style),
+		});
+		if let Some(drag) = drag {
+			DRAGGING.lock_recover().insert(window_id, drag);
+			window.refresh();
+		}
+	});
+	window.on_mouse_event(move |event: &MouseMoveEvent, phase, window, _| {
+		if phase != DispatchPhase::Bubble {
+			return;
+		}
+		// Highlight the handle while the pointer is over it
+		if move_hitbox.is_hovered(window) != hovered {
+			window.refresh();
+		}
+		let Some((from, start)) = DRAGGING
+			.lock_recover()
+			.get(&window_id)
+			.filter(|drag| drag.element_id == element_id)
+			.map(|drag| (drag.from, drag.start))
+		else {
+			return;
+		};
+		let Some(position) = with_style(window_id, element_id, |style| along(style, event.position))
+		else {
+			return;
+		};
+		if move_to(window_id, element_id, start + (position - from) as f64) {
+			window.refresh();
+		}
+	});
+	window.on_mouse_event(move |_: &MouseUpEvent, phase, window, _| {
+		if phase != DispatchPhase::Bubble {
+			return;
+		}
+		let mut dragging = DRAGGING.lock_recover();
+		if dragging.get(&window_id).is_none_or(|drag| drag.element_id != element_id) {
+			return;
+		}
+		dragging.remove(&window_id);
+		drop(dragging);
+		commit(window_id, element_id);
+		window.refresh();
+	});
+}
+
+/// Paint the line along the middle of the handle, in the accent color while
+/// the resizer is active
+fn paint_line(style: &ElementStyle, bounds: Bounds<Pixels>, active: bool, window: &mut Window) {
+	let (color, width) = if active {
+		(style.accent_color.unwrap_or_else(|| rgb(DEFAULT_ACCENT).into()), 2.0)
+	} else {
+		let gray: Hsla = rgb(0x808080).into();
+		(Hsla { a: 0.5, ..gray }, 1.0)
+	};
+	let center = bounds.center();
+	let line = if is_horizontal(style) {
+		Bounds {
+			origin: point(bounds.origin.x, center.y - px(width / 2.0)),
+			size:   size(bounds.size.width, px(width)),
+		}
+	} else {
+		Bounds {
+			origin: point(center.x - px(width / 2.0), bounds.origin.y),
+			size:   size(px(width), bounds.size.height),
+		}
+	};
+	window.paint_quad(fill(line, color));
+}
@@ -0,0 +1,199 @@
+//! Rich text element
+//!
+//! A `richtext` takes its content as `runs`, each a piece of text with its own
+//! color, background, weight, italic, underline (straight or wavy) and
+//! strikethrough. The runs are joined and shaped as one paragraph, wrapped at
+//! the element's width, so kerning and line breaks work across style changes
+//! the way they can't with a span per word. Runs without a color use the
+//! element's text color, which is inherited like a text's.
+//!
+//! Runs are parsed once per distinct style, when the style is interned.
+
+use std::{ops::Range, sync::Arc};
+
+use gpui::{AnyElement, App, Bounds, Element, ElementId, FontStyle, FontWeight, GlobalElementId, HighlightStyle, Hitbox, InspectorElementId, IntoElement, LayoutId, Pixels, SharedString, StrikethroughStyle, StyledText, UnderlineStyle, Window, div, prelude::*, px, rgb};
+use serde_json::Value;
+
+use super::{ElementStyle, ReactElement, border, color_from_json, events::{EventHandlerFlags, insert_hitbox_if_needed, register_event_handlers}, find_overlay, fonts, layout};
+
+const DEFAULT_TEXT_SIZE: f32 = 14.0;
+
+/// The runs of a richtext joined, with each styled run's range
+#[derive(Clone, PartialEq, Debug)]
+pub struct RichText {
+	pub text:       SharedString,
+	pub highlights: Vec<(Range<usize>, HighlightStyle)>,
+}
+
+/// Parse the `runs` array; runs without text are skipped
+pub fn parse_runs(value: &Value) -> Option<Arc<RichText>> {
+	let mut text = String::new();
+	let mut highlights = Vec::new();
+	for run in value.as_array()? {
+		let Some(piece) = run.get("text").and_then(Value::as_str).filter(|s| !s.is_empty()) else {
+			continue;
+		};
+		let start = text.len();
+		text.push_str(piece);
+		let style = run_style(run);
+		if style != HighlightStyle::default() {
+			highlights.push((start..text.len(), style));
+		}
+	}
+	Some(Arc::new(RichText { text: text.into(), highlights }))
+}
+
+fn run_style(run: &Value) -> HighlightStyle {
+	let color = run.get("color").and_then(color_from_json);
+	let underline = match run.get("underline") {
+		Some(Value::Bool(true)) => Some(false),
+		Some(Value::String(kind)) if kind == "wavy" => Some(true),
+		_ => None,
+	};
+	let flag = |key: &str| run.get(key).and_then(Value::as_bool).unwrap_or(false);
+	HighlightStyle {
+		color,
+		background_color: run.get("background").and_then(color_from_json),
+		font_weight: run.get("weight").and_then(Value::as_f64).map(|w| FontWeight(w as f32)),
+		font_style: flag("italic").then_some(FontStyle::Italic),
+		// Lines take the run's color when it has one, else the element's
+		underline: underline.map(|wavy| UnderlineStyle { thickness: px(1.0), color, wavy }),
+		strikethrough: flag("strikethrough")
+			.then_some(StrikethroughStyle { thickness: px(1.0), color }),
+		..Default::default()
+	}
+}
+
+pub struct ReactRichTextElement {
+	element:      Arc<ReactElement>,
+	window_id:    u64,
+	parent_style: Option<ElementStyle>,
+	child:        Option<AnyElement>,
+}
+
+pub struct RichTextLayoutState {}
+
+pub struct RichTextPrepaintState {
+	hitbox:      Option<Hitbox>,
+	event_flags: EventHandlerFlags,
+}
+
+impl ReactRichTextElement {
+	pub fn new(
+		element: Arc<ReactElement>,
+		window_id: u64,
+		parent_style: Option<ElementStyle>,
+	) -> Self {
+		Self { element, window_id, parent_style, child: None }
+	}
+}
+
+impl Element for ReactRichTextElement {
+	type PrepaintState = RichTextPrepaintState;
+	type RequestLayoutState = RichTextLayoutState;
+
+	fn id(&self) -> Option<ElementId> { Some(ElementId::Integer(self.element.global_id)) }
+
+	fn source_location(&self) -> Option<&'static std::panic::Location<'static>> { None }
+
+	fn request_layout(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		window: &mut Window,
+		cx: &mut App,
+	) -> (LayoutId, Self::RequestLayoutState) {
+		let style = self.element.build_gpui_style(None);
+		let Some(rich) = self.element.style.rich_text.clone() else {
+			let layout_id = window.request_layout(style, std::iter::empty(), cx);
+			return (layout_id, RichTextLayoutState {});
+		};
+
+		let effective = self.element.effective_style(self.parent_style.as_ref());
+		let text = StyledText::new(rich.text.clone()).with_highlights(rich.highlights.iter().cloned());
+		let mut child = div()
+			.text_color(effective.text_color.unwrap_or_else(|| rgb(0xffffff).into()))
+			.text_size(px(effective.text_size.unwrap_or(DEFAULT_TEXT_SIZE)))
+			.font(fonts::resolve(&effective, window))
+			.child(text)
+			.into_any_element();
+		let child_layout_id = child.request_layout(window, cx);
+		self.child = Some(child);
+
+		let layout_id = window.request_layout(style, std::iter::once(child_layout_id), cx);
+		(layout_id, RichTextLayoutState {})
+	}
+
+	fn prepaint(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		bounds: Bounds<Pixels>,
+		_request_layout: &mut Self::RequestLayoutState,
+		window: &mut Window,
+		cx: &mut App,
+	) -> Self::PrepaintState {
+		layout::record_bounds(self.window_id, self.element.global_id, bounds);
+
+		if let Some(child) = self.child.as_mut() {
+			child.prepaint(window, cx);
+		}
+
+		let event_flags = EventHandlerFlags::from_handlers(
+			self.element.event_handlers.as_ref(),
+			self.element.style.tab_index,
+		);
+		let hitbox = insert_hitbox_if_needed(&event_flags, bounds, window);
+		RichTextPrepaintState { hitbox, event_flags }
+	}
+
+	fn paint(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		bounds: Bounds<Pixels>,
+		_request_layout: &mut Self::RequestLayoutState,
+		prepaint: &mut Self::PrepaintState,
+		window: &mut Window,
+		cx: &mut App,
+	) {
+		let element_style = &self.element.style;
+		let style = self.element.build_gpui_style(None);
+		style.paint(bounds, window, cx, |window, cx| {
+			if let Some(child) = self.child.as_mut() {
+				child.paint(window, cx);
+			}
+		});
+		border::paint(element_style, bounds, window);
+		border::paint_outline(element_style, bounds, window);
+
+		// Searchable like a text
+		if let Some(rich) = element_style.rich_text.as_ref()
+			&& find_overlay::is_open(self.window_id)
+		{
+			let effective = self.element.effective_style(self.parent_style.as_ref());
+			find_overlay::record_text(
+				self.window_id,
+				self.element.global_id,
+				&rich.text,
+				bounds,
+				effective.text_size.unwrap_or(DEFAULT_TEXT_SIZE),
+				fonts::resolve(&effective, window),
+			);
+		}
+
+		register_event_handlers(
+			&prepaint.event_flags,
+			prepaint.hitbox.as_ref(),
+			self.window_id,
+			self.element.global_id,
+			window,
+		);
+	}
+}
+
+impl IntoElement for ReactRichTextElement {
+	type Element = Self;
+
+	fn into_element(self) -> Self::Element { self }
+}
@@ -0,0 +1,103 @@
+//! Window safe area
+//!
+//! The insets of a window's content that window chrome covers: the titlebar
+//! of a `titlebarTransparent` window on macOS, whose traffic lights sit over
+//! the content (in fullscreen the titlebar slides away and the inset goes
+//! with it), and whatever the app declares with `gpui_set_safe_area_insets`
+//! for chrome of its own, such as a custom titlebar or a docked panel. Each
+//! edge takes the larger of the two.
+//!
+//! A "safeareachange" window event with `top`, `right`, `bottom` and `left`
+//! goes out on the window's first frame and whenever the insets change; the
+//! reconciler resolves `env(safe-area-inset-*)` in style lengths against
+//! it. gpui exposes neither the screen's safe area nor the work area docks
+//! and taskbars leave, so a notch or taskbar only counts once the app
+//! declares it.
+
+use std::{collections::HashMap, sync::Mutex};
+
+use lazy_static::lazy_static;
+use serde::Deserialize;
+
+use crate::{renderer::dispatch_window_event, sync::MutexExt};
+
+/// Height of the standard macOS titlebar
+#[cfg(target_os = "macos")]
+const TITLEBAR_HEIGHT: f32 = 28.0;
+/// Elsewhere a transparent titlebar leaves the app to draw one, and to
+/// declare its height
+#[cfg(not(target_os = "macos"))]
+const TITLEBAR_HEIGHT: f32 = 0.0;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Insets {
+	pub top:    f32,
+	pub right:  f32,
+	pub bottom: f32,
+	pub left:   f32,
+}
+
+impl Insets {
+	fn max(self, other: Insets) -> Insets {
+		Insets {
+			top:    self.top.max(other.top),
+			right:  self.right.max(other.right),
+			bottom: self.bottom.max(other.bottom),
+			left:   self.left.max(other.left),
+		}
+	}
+}
+
+#[derive(Default)]
+struct SafeArea {
+	/// Content runs under the native titlebar
+	titlebar: bool,
+	declared: Insets,
+	/// Insets of the last "safeareachange", None before the first frame
+	reported: Option<Insets>,
+}
+
+lazy_static! {
+	static ref WINDOWS: Mutex<HashMap<u64, SafeArea>> = Mutex::new(HashMap::new());
+}
+
+/// Drop all state of a closed window
+pub fn forget_window(window_id: u64) { WINDOWS.lock_recover().remove(&window_id); }
+
+/// Note that a new window draws its content under a transparent titlebar
+pub fn set_titlebar_transparent(window_id: u64, transparent: bool) {
+	WINDOWS.lock_recover().entry(window_id).or_default().titlebar = transparent;
+}
+
+/// Insets of chrome the app draws itself, applied on the next frame
+pub fn set_declared(window_id: u64, insets: Insets) {
+	WINDOWS.lock_recover().entry(window_id).or_default().declared = insets;
+}
+
+/// Work out the insets of a frame about to render, sending "safeareachange"
+/// when they differ from the last ones sent
+pub fn begin_frame(window_id: u64, fullscreen: bool) {
+	let mut windows = WINDOWS.lock_recover();
+	let state = windows.entry(window_id).or_default();
+	let mut chrome = Insets::default();
+	if state.titlebar && !fullscreen {
+		chrome.top = TITLEBAR_HEIGHT;
+	}
+	let insets = chrome.max(state.declared);
+	if state.reported.replace(insets) == Some(insets) {
+		return;
+	}
+	drop(windows);
+
+	dispatch_window_event(
+		window_id,
+		"safeareachange",
+		serde_json::json!({
+			"top": insets.top,
+			"right": insets.right,
+			"bottom": insets.bottom,
+			"left": insets.left,
+		}),
+	);
+}
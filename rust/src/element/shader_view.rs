@@ -0,0 +1,190 @@
+//! Custom paint hook element ("shaderview")
+//!
+//! Rust code compiled into the renderer registers a named painter with
+//! [`register_shader_view`]. A `<shaderview shader="name" />` element reserves
+//! space in the normal layout and calls that painter every frame with its
+//! bounds and the GPUI window, so custom quads and paths can be drawn without
+//! round-tripping draw commands through JS.
+
+use std::{collections::HashMap, sync::{Arc, RwLock}, time::{Duration, Instant}};
+
+use gpui::{App, Bounds, ContentMask, Element, ElementId, GlobalElementId, Hitbox, InspectorElementId, IntoElement, LayoutId, Pixels, Window};
+use lazy_static::lazy_static;
+use serde_json::Value;
+
+use super::{ElementStyle, ReactElement, border, events::{EventHandlerFlags, insert_hitbox_if_needed, register_event_handlers}, layout, pacing, pixel_snap};
+use crate::sync::RwLockExt;
+
+/// Per-frame information handed to a registered painter
+pub struct ShaderPaintContext<'a> {
+	pub window_id:  u64,
+	pub element_id: u64,
+	pub bounds:     Bounds<Pixels>,
+	/// Time since the painter was registered, for time-based effects
+	pub elapsed:    Duration,
+	/// Value of the element's `shaderParams` prop
+	pub params:     Option<&'a Value>,
+}
+
+/// Paint callback signature for shader views
+pub type ShaderPainter = dyn Fn(&ShaderPaintContext, &mut Window, &mut App) + Send + Sync;
+
+struct RegisteredPainter {
+	painter:       Arc<ShaderPainter>,
+	registered_at: Instant,
+}
+
+lazy_static! {
+	static ref PAINTERS: RwLock<HashMap<String, RegisteredPainter>> = RwLock::new(HashMap::new());
+}
+
+/// Register (or replace) a named painter for `<shaderview shader="...">`
+pub fn register_shader_view<F>(name: impl Into<String>, painter: F)
+where
+	F: Fn(&ShaderPaintContext, &mut Window, &mut App) + Send + Sync + 'static,
+{
+	let name = name.into();
+	log::info!("[Rust] Registering shader view painter '{}'", name);
+	PAINTERS.write_recover().insert(name, RegisteredPainter {
+		painter:       Arc::new(painter),
+		registered_at: Instant::now(),
+	});
+}
+
+/// Remove a named painter. Returns true if it was registered.
+pub fn unregister_shader_view(name: &str) -> bool {
+	PAINTERS.write_recover().remove(name).is_some()
+}
+
+/// Look up a painter, cloning it out so the registry lock isn't held while
+/// painting
+fn get_painter(name: &str) -> Option<(Arc<ShaderPainter>, Duration)> {
+	let painters = PAINTERS.read_recover();
+	painters.get(name).map(|p| (p.painter.clone(), p.registered_at.elapsed()))
+}
+
+pub struct ReactShaderViewElement {
+	element:      Arc<ReactElement>,
+	window_id:    u64,
+	#[allow(dead_code)]
+	parent_style: Option<ElementStyle>,
+}
+
+pub struct ShaderViewLayoutState {}
+
+pub struct ShaderViewPrepaintState {
+	hitbox:      Option<Hitbox>,
+	event_flags: EventHandlerFlags,
+}
+
+impl ReactShaderViewElement {
+	pub fn new(
+		element: Arc<ReactElement>,
+		window_id: u64,
+		parent_style: Option<ElementStyle>,
+	) -> Self {
+		Self { element, window_id, parent_style }
+	}
+}
+
+impl Element for ReactShaderViewElement {
+	type PrepaintState = ShaderViewPrepaintState;
+	type RequestLayoutState = ShaderViewLayoutState;
+
+	fn id(&self) -> Option<ElementId> { Some(ElementId::Integer(self.element.global_id)) }
+
+	fn source_location(&self) -> Option<&'static std::panic::Location<'static>> { None }
+
+	fn request_layout(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		window: &mut Window,
+		cx: &mut App,
+	) -> (LayoutId, Self::RequestLayoutState) {
+		let style = self.element.build_gpui_style(None);
+		let layout_id = window.request_layout(style, std::iter::empty(), cx);
+		(layout_id, ShaderViewLayoutState {})
+	}
+
+	fn prepaint(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		bounds: Bounds<Pixels>,
+		_request_layout: &mut Self::RequestLayoutState,
+		window: &mut Window,
+		_cx: &mut App,
+	) -> Self::PrepaintState {
+		layout::record_bounds(self.window_id, self.element.global_id, bounds);
+
+		let event_flags = EventHandlerFlags::from_handlers(
+			self.element.event_handlers.as_ref(),
+			self.element.style.tab_index,
+		);
+		let hitbox = insert_hitbox_if_needed(&event_flags, bounds, window);
+		ShaderViewPrepaintState { hitbox, event_flags }
+	}
+
+	fn paint(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		bounds: Bounds<Pixels>,
+		_request_layout: &mut Self::RequestLayoutState,
+		prepaint: &mut Self::PrepaintState,
+		window: &mut Window,
+		cx: &mut App,
+	) {
+		let mut style = self.element.build_gpui_style(None);
+		let crisp = pixel_snap::is_crisp(&self.element.style, self.parent_style.as_ref());
+		let (bounds, snapped) =
+			pixel_snap::snap(crisp, bounds, &mut style, &self.element.style, window);
+		let element_style = snapped.as_ref().unwrap_or(&self.element.style);
+		let shader = self.element.style.shader.as_deref();
+
+		style.paint(bounds, window, cx, |window, cx| {
+			let Some(name) = shader else {
+				return;
+			};
+			let Some((painter, elapsed)) = get_painter(name) else {
+				log::trace!("[Rust] shaderview: no painter registered for '{}'", name);
+				return;
+			};
+
+			let context = ShaderPaintContext {
+				window_id: self.window_id,
+				element_id: self.element.global_id,
+				bounds,
+				elapsed,
+				params: self.element.style.shader_params.as_ref(),
+			};
+			// Painters never draw outside their own box
+			window.with_content_mask(Some(ContentMask { bounds }), |window| {
+				painter(&context, window, cx);
+			});
+		});
+		border::paint(element_style, bounds, window);
+		border::paint_outline(element_style, bounds, window);
+
+		// Continuous effects keep requesting frames while mounted, slowed down
+		// while the window is in the background
+		if self.element.style.animate == Some(true) {
+			pacing::request_frame(self.window_id, window, cx);
+		}
+
+		register_event_handlers(
+			&prepaint.event_flags,
+			prepaint.hitbox.as_ref(),
+			self.window_id,
+			self.element.global_id,
+			window,
+		);
+	}
+}
+
+impl IntoElement for ReactShaderViewElement {
+	type Element = Self;
+
+	fn into_element(self) -> Self::Element { self }
+}
@@ -0,0 +1,199 @@
+//! Skeleton element
+//!
+//! A `skeleton` is a loading placeholder: a shape in the background color
+//! (light gray by default) with a lighter band sweeping across it. `variant`
+//! picks the shape: "rect" (the default, 16px tall), "text" (a bar as tall as
+//! the text size) or "circle" (40px across). `borderRadius` overrides the
+//! shape's corners and `accentColor` the band.
+//!
+//! The band's position comes from the clock, so every skeleton on screen
+//! sweeps in step, and each paint asks the frame pacing for the next frame;
+//! there is no per-element state and nothing runs in JS. While power saving
+//! the band is left out and the shape stays still.
+
+use std::sync::Arc;
+
+use gpui::{App, Bounds, Corners, Element, ElementId, GlobalElementId, Hitbox, Hsla, InspectorElementId, IntoElement, LayoutId, Pixels, Window, fill, linear_color_stop, linear_gradient, px, rgb};
+
+use super::{ElementStyle, ReactElement, border, events::{EventHandlerFlags, insert_hitbox_if_needed, register_event_handlers}, layout, pacing};
+use crate::clock;
+
+const DEFAULT_COLOR: u32 = 0xe5e7eb;
+const DEFAULT_RECT_HEIGHT: f32 = 16.0;
+const DEFAULT_CIRCLE_SIZE: f32 = 40.0;
+const DEFAULT_TEXT_SIZE: f32 = 14.0;
+const DEFAULT_RADIUS: f32 = 4.0;
+/// Time for the band to cross the shape and start over
+const PERIOD_US: u64 = 1_500_000;
+/// Width of each half of the band, as a fraction of the shape's width
+const BAND: f32 = 0.25;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Variant {
+	Rect,
+	Text,
+	Circle,
+}
+
+impl Variant {
+	fn from_style(style: &ElementStyle) -> Self {
+		match style.skeleton_variant.as_deref() {
+			Some("text") => Variant::Text,
+			Some("circle") => Variant::Circle,
+			_ => Variant::Rect,
+		}
+	}
+}
+
+pub struct ReactSkeletonElement {
+	element:      Arc<ReactElement>,
+	window_id:    u64,
+	parent_style: Option<ElementStyle>,
+}
+
+pub struct SkeletonLayoutState {}
+
+pub struct SkeletonPrepaintState {
+	hitbox:      Option<Hitbox>,
+	event_flags: EventHandlerFlags,
+}
+
+impl ReactSkeletonElement {
+	pub fn new(
+		element: Arc<ReactElement>,
+		window_id: u64,
+		parent_style: Option<ElementStyle>,
+	) -> Self {
+		Self { element, window_id, parent_style }
+	}
+}
+
+impl Element for ReactSkeletonElement {
+	type PrepaintState = SkeletonPrepaintState;
+	type RequestLayoutState = SkeletonLayoutState;
+
+	fn id(&self) -> Option<ElementId> { Some(ElementId::Integer(self.element.global_id)) }
+
+	fn source_location(&self) -> Option<&'static std::panic::Location<'static>> { None }
+
+	fn request_layout(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		window: &mut Window,
+		cx: &mut App,
+	) -> (LayoutId, Self::RequestLayoutState) {
+		let es = &self.element.style;
+		let mut style = self.element.build_gpui_style(None);
+		match Variant::from_style(es) {
+			Variant::Rect if es.height.is_none() => {
+				style.size.height = px(DEFAULT_RECT_HEIGHT).into();
+			}
+			Variant::Text if es.height.is_none() => {
+				let effective = self.element.effective_style(self.parent_style.as_ref());
+				style.size.height = px(effective.text_size.unwrap_or(DEFAULT_TEXT_SIZE)).into();
+			}
+			Variant::Circle => {
+				if es.width.is_none() {
+					style.size.width = px(DEFAULT_CIRCLE_SIZE).into();
+				}
+				if es.height.is_none() {
+					style.size.height = px(DEFAULT_CIRCLE_SIZE).into();
+				}
+			}
+			_ => {}
+		}
+		let layout_id = window.request_layout(style, std::iter::empty(), cx);
+		(layout_id, SkeletonLayoutState {})
+	}
+
+	fn prepaint(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		bounds: Bounds<Pixels>,
+		_request_layout: &mut Self::RequestLayoutState,
+		window: &mut Window,
+		_cx: &mut App,
+	) -> Self::PrepaintState {
+		layout::record_bounds(self.window_id, self.element.global_id, bounds);
+
+		let event_flags = EventHandlerFlags::from_handlers(
+			self.element.event_handlers.as_ref(),
+			self.element.style.tab_index,
+		);
+		let hitbox = insert_hitbox_if_needed(&event_flags, bounds, window);
+		SkeletonPrepaintState { hitbox, event_flags }
+	}
+
+	fn paint(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		bounds: Bounds<Pixels>,
+		_request_layout: &mut Self::RequestLayoutState,
+		prepaint: &mut Self::PrepaintState,
+		window: &mut Window,
+		cx: &mut App,
+	) {
+		let element_style = &self.element.style;
+		let mut style = self.element.build_gpui_style(None);
+		// The shimmer paints the background
+		style.background = None;
+		style.paint(bounds, window, cx, |window, _| {
+			paint_shimmer(element_style, bounds, window);
+		});
+		border::paint(element_style, bounds, window);
+		border::paint_outline(element_style, bounds, window);
+
+		if !pacing::power_saver() {
+			pacing::request_frame(self.window_id, window, cx);
+		}
+
+		register_event_handlers(
+			&prepaint.event_flags,
+			prepaint.hitbox.as_ref(),
+			self.window_id,
+			self.element.global_id,
+			window,
+		);
+	}
+}
+
+impl IntoElement for ReactSkeletonElement {
+	type Element = Self;
+
+	fn into_element(self) -> Self::Element { self }
+}
+
+/// Paint the shape with the band at the clock's position
+fn paint_shimmer(style: &ElementStyle, bounds: Bounds<Pixels>, window: &mut Window) {
+	let base: Hsla = style.bg_color.unwrap_or_else(|| rgb(DEFAULT_COLOR).into());
+	let band = style.accent_color.unwrap_or_else(|| Hsla { l: (base.l + 0.1).min(1.0), ..base });
+	let side = f32::from(bounds.size.width.min(bounds.size.height));
+	let radius = match (style.border_radius, Variant::from_style(style)) {
+		(Some(radius), _) => radius,
+		(None, Variant::Circle) => side / 2.0,
+		(None, _) => DEFAULT_RADIUS,
+	};
+	let corners = Corners::all(px(radius));
+	if pacing::power_saver() {
+		window.paint_quad(fill(bounds, base).corner_radii(corners));
+		return;
+	}
+
+	// The band's middle runs from just off the left edge to just off the right
+	let phase = (clock::now_us() % PERIOD_US) as f32 / PERIOD_US as f32;
+	let middle = -BAND + phase * (1.0 + 2.0 * BAND);
+	// Base rising to the band up to the middle, then a second coat fading the
+	// band back into the base after it
+	let rise =
+		linear_gradient(90.0, linear_color_stop(base, middle - BAND), linear_color_stop(band, middle));
+	let fall = linear_gradient(
+		90.0,
+		linear_color_stop(base.opacity(0.0), middle),
+		linear_color_stop(base, middle + BAND),
+	);
+	window.paint_quad(fill(bounds, rise).corner_radii(corners));
+	window.paint_quad(fill(bounds, fall).corner_radii(corners));
+}
@@ -0,0 +1,408 @@
+//! Slider (range input) element
+//!
+//! Paints a track, the part of it up to the value in the accent color, and a
+//! round thumb. `min`, `max` and `step` default to 0, 100 and 1; `value` is
+//! read as a number. Dragging the thumb (or pressing anywhere on the track)
+//! and the arrow, Page Up/Down, Home and End keys while focused move the
+//! value in steps, queueing an `input` event for every new value and a
+//! `change` event once the drag or key press ends.
+//!
+//! Like checkboxes, the new value shows right away and overrides the `value`
+//! prop until the prop changes.
+
+use std::{collections::HashMap, sync::{Arc, Mutex}};
+
+use gpui::{App, BorderStyle, Bounds, Corners, DispatchPhase, Edges, Element, ElementId, GlobalElementId, Hitbox, Hsla, InspectorElementId, IntoElement, LayoutId, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent, PaintQuad, Pixels, Window, fill, point, px, rgb, size};
+use lazy_static::lazy_static;
+
+use super::{ElementKind, ElementStyle, ReactElement, border, events::{EventHandlerFlags, insert_hitbox_if_needed, register_event_handlers}, focus, inert, layout, pixel_snap};
+use crate::{event_types::{EventData, InputEventData, types}, global_state::GLOBAL_STATE, renderer::dispatch_event_to_js, sync::MutexExt};
+
+/// Size of the slider when the style has none
+const DEFAULT_WIDTH: f32 = 160.0;
+const DEFAULT_HEIGHT: f32 = 16.0;
+const TRACK_HEIGHT: f32 = 4.0;
+/// Fill of the track up to the value when the style has no `accentColor`
+const DEFAULT_ACCENT: u32 = 0x3b82f6;
+/// Steps moved by Page Up and Page Down
+const PAGE_STEPS: f64 = 10.0;
+
+/// Value set in Rust that JS hasn't confirmed yet
+struct Moved {
+	/// `value` prop at the time of the move
+	prop:  Option<String>,
+	value: f64,
+}
+
+lazy_static! {
+	static ref MOVED: Mutex<HashMap<(u64, u64), Moved>> = Mutex::new(HashMap::new());
+	/// Slider being dragged in each window
+	static ref DRAGGING: Mutex<HashMap<u64, u64>> = Mutex::new(HashMap::new());
+}
+
+/// Drop all state of a closed window
+pub fn forget_window(window_id: u64) {
+	MOVED.lock_recover().retain(|(id, _), _| *id != window_id);
+	DRAGGING.lock_recover().remove(&window_id);
+}
+
+/// Drop the moved value of a released element
+pub fn forget_element(window_id: u64, element_id: u64) {
+	MOVED.lock_recover().remove(&(window_id, element_id));
+	DRAGGING.lock_recover().retain(|id, dragging| (*id, *dragging) != (window_id, element_id));
+}
+
+/// `min`, `max` and `step` of a slider, with max never below min
+struct Range {
+	min:  f64,
+	max:  f64,
+	step: f64,
+}
+
+impl Range {
+	fn of(style: &ElementStyle) -> Self {
+		let min = style.min.unwrap_or(0.0);
+		let max = style.max.unwrap_or(100.0).max(min);
+		let step = style.step.filter(|step| *step > 0.0).unwrap_or(1.0);
+		Self { min, max, step }
+	}
+
+	/// Nearest step to `value`, inside the range
+	fn snap(&self, value: f64) -> f64 {
+		let steps = ((value - self.min) / self.step).round();
+		let value = (self.min + steps * self.step).clamp(self.min, self.max);
+		// Drop float noise like 0.30000000000000004
+		(value * 1e9).round() / 1e9
+	}
+
+	/// Position of `value` between min (0) and max (1)
+	fn fraction(&self, value: f64) -> f64 {
+		if self.max > self.min { (value - self.min) / (self.max - self.min) } else { 0.0 }
+	}
+}
+
+/// Current value of a slider, moved value first
+fn current_value(window_id: u64, element_id: u64, style: &ElementStyle) -> f64 {
+	let range = Range::of(style);
+	if let Some(moved) = MOVED.lock_recover().get(&(window_id, element_id))
+		&& moved.prop == style.value
+	{
+		return moved.value;
+	}
+	let prop = style.value.as_deref().and_then(|value| value.trim().parse::<f64>().ok());
+	range.snap(prop.unwrap_or(range.min))
+}
+
+/// Move a slider to `value` (snapped), queueing `input` if it changed
+/// Returns whether the value changed
+fn move_to(window_id: u64, element_id: u64, value: f64) -> bool {
+	let Some(window) = GLOBAL_STATE.get_window(window_id) else {
+		return false;
+	};
+	let (value, prop) = {
+		let element_map = window.state().element_map.lock_recover();
+		let Some(element) = element_map.get(&element_id) else {
+			return false;
+		};
+		let style = &element.style;
+		let value = Range::of(style).snap(value);
+		if style.disabled == Some(true) || value == current_value(window_id, element_id, style) {
+			return false;
+		}
+		(value, style.value.clone())
+	};
+	MOVED.lock_recover().insert((window_id, element_id), Moved { prop, value });
+	dispatch(window_id, element_id, types::INPUT, value);
+	true
+}
+
+fn dispatch(window_id: u64, element_id: u64, event_type: &str, value: f64) {
+	log::debug!("[Rust] slider {}: element_id={}, value={}", event_type, element_id, value);
+	dispatch_event_to_js(
+		window_id,
+		element_id,
+		event_type,
+		EventData::Input(InputEventData { value: value.to_string(), ..Default::default() }),
+	);
+}
+
+/// Adjust a focused slider with the keyboard
+/// Returns whether the key moved the value
+pub fn key_down(window_id: u64, element_id: u64, key: &str) -> bool {
+	let Some(window) = GLOBAL_STATE.get_window(window_id) else {
+		return false;
+	};
+	let target = {
+		let element_map = window.state().element_map.lock_recover();
+		let Some(element) = element_map.get(&element_id) else {
+			return false;
+		};
+		if element.element_kind != ElementKind::Slider {
+			return false;
+		}
+		let range = Range::of(&element.style);
+		let value = current_value(window_id, element_id, &element.style);
+		match key {
+			"left" | "down" => value - range.step,
+			"right" | "up" => value + range.step,
+			"pagedown" => value - range.step * PAGE_STEPS,
+			"pageup" => value + range.step * PAGE_STEPS,
+			"home" => range.min,
+			"end" => range.max,
+			_ => return false,
+		}
+	};
+	if !move_to(window_id, element_id, target) {
+		return false;
+	}
+	let value = {
+		let element_map = window.state().element_map.lock_recover();
+		element_map.get(&element_id).map(|element| current_value(window_id, element_id, &element.style))
+	};
+	if let Some(value) = value {
+		dispatch(window_id, element_id, types::CHANGE, value);
+	}
+	true
+}
+
+pub struct ReactSliderElement {
+	element:      Arc<ReactElement>,
+	window_id:    u64,
+	parent_style: Option<ElementStyle>,
+}
+
+pub struct SliderLayoutState {}
+
+pub struct SliderPrepaintState {
+	hitbox:      Option<Hitbox>,
+	event_flags: EventHandlerFlags,
+}
+
+impl ReactSliderElement {
+	pub fn new(
+		element: Arc<ReactElement>,
+		window_id: u64,
+		parent_style: Option<ElementStyle>,
+	) -> Self {
+		Self { element, window_id, parent_style }
+	}
+}
+
+impl Element for ReactSliderElement {
+	type PrepaintState = SliderPrepaintState;
+	type RequestLayoutState = SliderLayoutState;
+
+	fn id(&self) -> Option<ElementId> { Some(ElementId::Integer(self.element.global_id)) }
+
+	fn source_location(&self) -> Option<&'static std::panic::Location<'static>> { None }
+
+	fn request_layout(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		window: &mut Window,
+		cx: &mut App,
+	) -> (LayoutId, Self::RequestLayoutState) {
+		let mut style = self.element.build_gpui_style(None);
+		if self.element.style.width.is_none() {
+			style.size.width = px(DEFAULT_WIDTH).into();
+		}
+		if self.element.style.height.is_none() {
+			style.size.height = px(DEFAULT_HEIGHT).into();
+		}
+		let layout_id = window.request_layout(style, std::iter::empty(), cx);
+		(layout_id, SliderLayoutState {})
+	}
+
+	fn prepaint(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		bounds: Bounds<Pixels>,
+		_request_layout: &mut Self::RequestLayoutState,
+		window: &mut Window,
+		_cx: &mut App,
+	) -> Self::PrepaintState {
+		layout::record_bounds(self.window_id, self.element.global_id, bounds);
+
+		let style = &self.element.style;
+		let mut event_flags =
+			EventHandlerFlags::from_handlers(self.element.event_handlers.as_ref(), style.tab_index);
+		if style.disabled != Some(true) {
+			event_flags.tab_index = event_flags.tab_index.or(Some(0));
+		}
+		let hitbox = insert_hitbox_if_needed(&event_flags, bounds, window);
+		SliderPrepaintState { hitbox, event_flags }
+	}
+
+	fn paint(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		bounds: Bounds<Pixels>,
+		_request_layout: &mut Self::RequestLayoutState,
+		prepaint: &mut Self::PrepaintState,
+		window: &mut Window,
+		cx: &mut App,
+	) {
+		let mut style = self.element.build_gpui_style(None);
+		let crisp = pixel_snap::is_crisp(&self.element.style, self.parent_style.as_ref());
+		let (bounds, snapped) =
+			pixel_snap::snap(crisp, bounds, &mut style, &self.element.style, window);
+		let element_style = snapped.as_ref().unwrap_or(&self.element.style);
+		// The background fills the track, not the whole box
+		style.background = None;
+
+		let (window_id, element_id) = (self.window_id, self.element.global_id);
+		let range = Range::of(&self.element.style);
+		let fraction = range.fraction(current_value(window_id, element_id, &self.element.style));
+		let focused = focus::is_focused(window_id, element_id);
+		style.paint(bounds, window, cx, |window, _| {
+			paint_slider(element_style, bounds, fraction as f32, focused, window);
+		});
+		border::paint(element_style, bounds, window);
+		border::paint_outline(element_style, bounds, window);
+
+		if let Some(hitbox) = prepaint.hitbox.clone()
+			&& self.element.style.disabled != Some(true)
+			&& !inert::is_inert(window_id, element_id)
+		{
+			listen_for_drag(window_id, element_id, hitbox, window);
+		}
+
+		register_event_handlers(
+			&prepaint.event_flags,
+			prepaint.hitbox.as_ref(),
+			window_id,
+			element_id,
+			window,
+		);
+	}
+}
+
+impl IntoElement for ReactSliderElement {
+	type Element = Self;
+
+	fn into_element(self) -> Self::Element { self }
+}
+
+/// Value under the pointer, from where the thumb's center can go
+fn value_at(window_id: u64, element_id: u64, bounds: Bounds<Pixels>, x: Pixels) -> Option<f64> {
+	let window = GLOBAL_STATE.get_window(window_id)?;
+	let element_map = window.state().element_map.lock_recover();
+	let range = Range::of(&element_map.get(&element_id)?.style);
+	let thumb = f32::from(bounds.size.height);
+	let travel = (f32::from(bounds.size.width) - thumb).max(1.0);
+	let fraction = ((f32::from(x - bounds.origin.x) - thumb / 2.0) / travel).clamp(0.0, 1.0);
+	Some(range.min + fraction as f64 * (range.max - range.min))
+}
+
+/// Press on the track moves the thumb there and starts a drag, which follows
+/// the pointer until the button is released
+fn listen_for_drag(window_id: u64, element_id: u64, hitbox: Hitbox, window: &mut Window) {
+	let bounds = hitbox.bounds;
+	window.on_mouse_event(move |event: &MouseDownEvent, phase, window, _| {
+		if phase != DispatchPhase::Bubble
+			|| event.button != MouseButton::Left
+			|| !hitbox.is_hovered(window)
+		{
+			return;
+		}
+		DRAGGING.lock_recover().insert(window_id, element_id);
+		if let Some(value) = value_at(window_id, element_id, bounds, event.position.x)
+			&& move_to(window_id, element_id, value)
+		{
+			window.refresh();
+		}
+	});
+	window.on_mouse_event(move |event: &MouseMoveEvent, phase, window, _| {
+		if phase != DispatchPhase::Bubble
+			|| DRAGGING.lock_recover().get(&window_id) != Some(&element_id)
+		{
+			return;
+		}
+		if let Some(value) = value_at(window_id, element_id, bounds, event.position.x)
+			&& move_to(window_id, element_id, value)
+		{
+			window.refresh();
+		}
+	});
+	window.on_mouse_event(move |_: &MouseUpEvent, phase, _, _| {
+		if phase != DispatchPhase::Bubble {
+			return;
+		}
+		let mut dragging = DRAGGING.lock_recover();
+		if dragging.get(&window_id) != Some(&element_id) {
+			return;
+		}
+		dragging.remove(&window_id);
+		drop(dragging);
+
+		let Some(window) = GLOBAL_STATE.get_window(window_id) else {
+			return;
+		};
+		let value = {
+			let element_map = window.state().element_map.lock_recover();
+			element_map
+				.get(&element_id)
+				.map(|element| current_value(window_id, element_id, &element.style))
+		};
+		if let Some(value) = value {
+			dispatch(window_id, element_id, types::CHANGE, value);
+		}
+	});
+}
+
+/// Paint the track, its filled part, the thumb and the focus ring
+fn paint_slider(
+	style: &ElementStyle,
+	bounds: Bounds<Pixels>,
+	fraction: f32,
+	focused: bool,
+	window: &mut Window,
+) {
+	let mut accent = style.accent_color.unwrap_or_else(|| rgb(DEFAULT_ACCENT).into());
+	let mut track = style.bg_color.unwrap_or_else(|| rgb(0x808080).into());
+	let mut thumb_fill: Hsla = rgb(0xffffff).into();
+	if style.disabled == Some(true) {
+		for color in [&mut accent, &mut track, &mut thumb_fill] {
+			color.a *= 0.5;
+		}
+	}
+
+	let thumb = f32::from(bounds.size.height);
+	let left = f32::from(bounds.origin.x) + thumb / 2.0;
+	let travel = (f32::from(bounds.size.width) - thumb).max(0.0);
+	let center_y = f32::from(bounds.origin.y) + thumb / 2.0;
+	let track_height = TRACK_HEIGHT.min(thumb);
+	let bar = |from: f32, to: f32| Bounds {
+		origin: point(px(from), px(center_y - track_height / 2.0)),
+		size:   size(px((to - from).max(0.0)), px(track_height)),
+	};
+	let thumb_x = left + travel * fraction.clamp(0.0, 1.0);
+	let rounded = Corners::all(px(track_height / 2.0));
+	window.paint_quad(fill(bar(left, left + travel), track).corner_radii(rounded));
+	window.paint_quad(fill(bar(left, thumb_x), accent).corner_radii(rounded));
+
+	let knob = Bounds {
+		origin: point(px(thumb_x - thumb / 2.0), px(center_y - thumb / 2.0)),
+		size:   size(px(thumb), px(thumb)),
+	};
+	if focused {
+		let ring = Hsla { a: accent.a * 0.5, ..accent };
+		window.paint_quad(circle(knob.dilate(px(2.0)), Hsla::transparent_black(), 2.0, ring));
+	}
+	window.paint_quad(circle(knob, thumb_fill, 1.5, accent));
+}
+
+/// Circle filling `bounds`, with a solid border
+fn circle(bounds: Bounds<Pixels>, background: Hsla, width: f32, color: Hsla) -> PaintQuad {
+	PaintQuad {
+		bounds,
+		corner_radii: Corners::all(bounds.size.height / 2.0),
+		background: background.into(),
+		border_widths: Edges::all(px(width)),
+		border_color: color,
+		border_style: BorderStyle::Solid,
+	}
+}
@@ -1,8 +1,8 @@
 use std::sync::Arc;
 
-use gpui::{AnyElement, App, Bounds, Element, ElementId, GlobalElementId, Hitbox, InspectorElementId, IntoElement, LayoutId, Pixels, Window, div, prelude::*, px, rgb};
+use gpui::{AnyElement, App, Bounds, Element, ElementId, GlobalElementId, Hitbox, InspectorElementId, IntoElement, LayoutId, Pixels, Window, px, rgb};
 
-use super::{ElementStyle, ReactElement, events::{EventHandlerFlags, insert_hitbox_if_needed, register_event_handlers}};
+use super::{ElementStyle, ReactElement, border, events::{EventHandlerFlags, insert_hitbox_if_needed, register_event_handlers}, fonts, layout, pixel_snap, text_cache};
 
 /// A span element - similar to div but:
 /// - No default background (transparent by default)
@@ -66,12 +66,17 @@ impl Element for ReactSpanElement {
 		// If element has text content, add it as a child
 		if let Some(ref text) = self.element.text {
 			if !text.is_empty() {
-				let text_color = inherited_style.text_color.unwrap_or(0xffffff);
+				let text_color = inherited_style.text_color.unwrap_or_else(|| rgb(0xffffff).into());
 				let text_size = inherited_style.text_size.unwrap_or(14.0);
 
-				let text_element =
-					div().text_color(rgb(text_color)).text_size(px(text_size)).child(text.clone());
-				self.children.push(text_element.into_any_element());
+				let font = fonts::resolve(&inherited_style, window);
+				self.children.push(text_cache::text_element(
+					text.clone().into(),
+					font,
+					px(text_size),
+					text_color,
+					window,
+				));
 			}
 		}
 
@@ -92,6 +97,8 @@ impl Element for ReactSpanElement {
 		window: &mut Window,
 		cx: &mut App,
 	) -> Self::PrepaintState {
+		layout::record_bounds(self.window_id, self.element.global_id, bounds);
+
 		for child in &mut self.children {
 			child.prepaint(window, cx);
 		}
@@ -116,7 +123,11 @@ impl Element for ReactSpanElement {
 		window: &mut Window,
 		cx: &mut App,
 	) {
-		let style = self.element.build_gpui_style(None);
+		let mut style = self.element.build_gpui_style(None);
+		let crisp = pixel_snap::is_crisp(&self.element.style, self.parent_style.as_ref());
+		let (bounds, snapped) =
+			pixel_snap::snap(crisp, bounds, &mut style, &self.element.style, window);
+		let element_style = snapped.as_ref().unwrap_or(&self.element.style);
 
 		// Paint background and children
 		style.paint(bounds, window, cx, |window, cx| {
@@ -130,6 +141,8 @@ impl Element for ReactSpanElement {
 				|child, window, cx| child.paint(window, cx),
 			);
 		});
+		border::paint(element_style, bounds, window);
+		border::paint_outline(element_style, bounds, window);
 
 		// Register event handlers using shared module
 		register_event_handlers(
@@ -0,0 +1,267 @@
+//! Sparkline element
+//!
+//! A `sparkline` draws the last `capacity` values (100 by default) pushed to
+//! it with gpui_push_sparkline as a line in `accentColor`, newest at the right.
+//! Values are scaled between `min` and `max`, or between the lowest and
+//! highest value held when those aren't set.
+//!
+//! Pushes only append to the element's ring buffer. While the sparkline is
+//! painted a timer checks the buffer every `interval` ms (100 by default) and
+//! repaints once new values arrived, so live metrics cost neither a JSON
+//! update nor a commit per value.
+
+use std::{collections::{HashMap, VecDeque}, sync::{Arc, Mutex}, time::Duration};
+
+use gpui::{App, Bounds, Element, ElementId, GlobalElementId, Hitbox, Hsla, InspectorElementId, IntoElement, LayoutId, PathBuilder, Pixels, Window, point, px, rgb};
+use lazy_static::lazy_static;
+
+use super::{ElementStyle, ReactElement, border, events::{EventHandlerFlags, insert_hitbox_if_needed, register_event_handlers}, layout, pixel_snap};
+use crate::sync::MutexExt;
+
+/// Size of the sparkline when the style has none
+const DEFAULT_WIDTH: f32 = 120.0;
+const DEFAULT_HEIGHT: f32 = 32.0;
+/// Line when the style has no `accentColor`
+const DEFAULT_ACCENT: u32 = 0x3b82f6;
+const DEFAULT_CAPACITY: usize = 100;
+/// Time between checks for new values when the style has no interval
+const DEFAULT_INTERVAL_MS: u64 = 100;
+const LINE_WIDTH: f32 = 1.5;
+
+/// Ring buffer of a sparkline's values
+struct Series {
+	values:   VecDeque<f64>,
+	capacity: usize,
+	/// Bumped by every push
+	version:  u64,
+	/// Version of the last paint
+	painted:  u64,
+	/// A timer is waiting for new values
+	polling:  bool,
+}
+
+impl Series {
+	fn new() -> Self {
+		Self {
+			values:   VecDeque::new(),
+			capacity: DEFAULT_CAPACITY,
+			version:  0,
+			painted:  0,
+			polling:  false,
+		}
+	}
+
+	fn trim(&mut self) {
+		let excess = self.values.len().saturating_sub(self.capacity);
+		self.values.drain(..excess);
+	}
+}
+
+lazy_static! {
+	static ref SERIES: Mutex<HashMap<(u64, u64), Series>> = Mutex::new(HashMap::new());
+}
+
+/// Drop all state of a closed window
+pub fn forget_window(window_id: u64) {
+	SERIES.lock_recover().retain(|(id, _), _| *id != window_id);
+}
+
+/// Drop the values of a released element
+pub fn forget_element(window_id: u64, element_id: u64) {
+	SERIES.lock_recover().remove(&(window_id, element_id));
+}
+
+/// Append values to a sparkline's buffer, dropping the oldest past its
+/// capacity; values that aren't finite are skipped
+pub fn push(window_id: u64, element_id: u64, values: &[f64]) {
+	let mut all = SERIES.lock_recover();
+	let series = all.entry((window_id, element_id)).or_insert_with(Series::new);
+	series.values.extend(values.iter().copied().filter(|value| value.is_finite()));
+	series.trim();
+	series.version += 1;
+}
+
+pub struct ReactSparklineElement {
+	element:      Arc<ReactElement>,
+	window_id:    u64,
+	parent_style: Option<ElementStyle>,
+}
+
+pub struct SparklineLayoutState {}
+
+pub struct SparklinePrepaintState {
+	hitbox:      Option<Hitbox>,
+	event_flags: EventHandlerFlags,
+}
+
+impl ReactSparklineElement {
+	pub fn new(
+		element: Arc<ReactElement>,
+		window_id: u64,
+		parent_style: Option<ElementStyle>,
+	) -> Self {
+		Self { element, window_id, parent_style }
+	}
+}
+
+impl Element for ReactSparklineElement {
+	type PrepaintState = SparklinePrepaintState;
+	type RequestLayoutState = SparklineLayoutState;
+
+	fn id(&self) -> Option<ElementId> { Some(ElementId::Integer(self.element.global_id)) }
+
+	fn source_location(&self) -> Option<&'static std::panic::Location<'static>> { None }
+
+	fn request_layout(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		window: &mut Window,
+		cx: &mut App,
+	) -> (LayoutId, Self::RequestLayoutState) {
+		let mut style = self.element.build_gpui_style(None);
+		if self.element.style.width.is_none() {
+			style.size.width = px(DEFAULT_WIDTH).into();
+		}
+		if self.element.style.height.is_none() {
+			style.size.height = px(DEFAULT_HEIGHT).into();
+		}
+		let layout_id = window.request_layout(style, std::iter::empty(), cx);
+		(layout_id, SparklineLayoutState {})
+	}
+
+	fn prepaint(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		bounds: Bounds<Pixels>,
+		_request_layout: &mut Self::RequestLayoutState,
+		window: &mut Window,
+		_cx: &mut App,
+	) -> Self::PrepaintState {
+		layout::record_bounds(self.window_id, self.element.global_id, bounds);
+
+		let event_flags = EventHandlerFlags::from_handlers(
+			self.element.event_handlers.as_ref(),
+			self.element.style.tab_index,
+		);
+		let hitbox = insert_hitbox_if_needed(&event_flags, bounds, window);
+		SparklinePrepaintState { hitbox, event_flags }
+	}
+
+	fn paint(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		bounds: Bounds<Pixels>,
+		_request_layout: &mut Self::RequestLayoutState,
+		prepaint: &mut Self::PrepaintState,
+		window: &mut Window,
+		cx: &mut App,
+	) {
+		let mut style = self.element.build_gpui_style(None);
+		let crisp = pixel_snap::is_crisp(&self.element.style, self.parent_style.as_ref());
+		let (bounds, snapped) =
+			pixel_snap::snap(crisp, bounds, &mut style, &self.element.style, window);
+		let element_style = snapped.as_ref().unwrap_or(&self.element.style);
+
+		let key = (self.window_id, self.element.global_id);
+		let capacity = element_style.sparkline_capacity.unwrap_or(DEFAULT_CAPACITY).max(2);
+		let (values, start_polling) = {
+			let mut all = SERIES.lock_recover();
+			let series = all.entry(key).or_insert_with(Series::new);
+			series.capacity = capacity;
+			series.trim();
+			series.painted = series.version;
+			let start_polling = !std::mem::replace(&mut series.polling, true);
+			(series.values.iter().copied().collect::<Vec<_>>(), start_polling)
+		};
+
+		style.paint(bounds, window, cx, |window, _| {
+			paint_line(element_style, bounds, &values, capacity, window);
+		});
+		border::paint(element_style, bounds, window);
+		border::paint_outline(element_style, bounds, window);
+
+		// One timer per sparkline, ended by the repaint it asks for
+		if start_polling {
+			let interval = element_style.sparkline_interval.unwrap_or(DEFAULT_INTERVAL_MS).max(1);
+			let interval = Duration::from_millis(interval);
+			window
+				.spawn(cx, async move |cx| {
+					loop {
+						cx.background_executor().timer(interval).await;
+						let changed = {
+							let mut all = SERIES.lock_recover();
+							let Some(series) = all.get_mut(&key) else {
+								return;
+							};
+							let changed = series.version != series.painted;
+							series.polling = !changed;
+							changed
+						};
+						if changed {
+							let _ = cx.update(|window, _| window.refresh());
+							return;
+						}
+					}
+				})
+				.detach();
+		}
+
+		register_event_handlers(
+			&prepaint.event_flags,
+			prepaint.hitbox.as_ref(),
+			self.window_id,
+			self.element.global_id,
+			window,
+		);
+	}
+}
+
+impl IntoElement for ReactSparklineElement {
+	type Element = Self;
+
+	fn into_element(self) -> Self::Element { self }
+}
+
+/// Stroke the values, `capacity` of them spanning the width
+fn paint_line(
+	style: &ElementStyle,
+	bounds: Bounds<Pixels>,
+	values: &[f64],
+	capacity: usize,
+	window: &mut Window,
+) {
+	if values.len() < 2 {
+		return;
+	}
+	let low = style.min.unwrap_or_else(|| values.iter().copied().fold(f64::INFINITY, f64::min));
+	let high = style.max.unwrap_or_else(|| values.iter().copied().fold(f64::NEG_INFINITY, f64::max));
+	let color: Hsla = style.accent_color.unwrap_or_else(|| rgb(DEFAULT_ACCENT).into());
+
+	// Keep the stroke inside the bounds
+	let inset = LINE_WIDTH / 2.0;
+	let width = f32::from(bounds.size.width);
+	let height = f32::from(bounds.size.height) - LINE_WIDTH;
+	let step = width / (capacity - 1) as f32;
+	let newest = values.len() - 1;
+	let at = |i: usize, value: f64| {
+		// A flat series sits in the middle
+		let level =
+			if high > low { ((value - low) / (high - low)).clamp(0.0, 1.0) as f32 } else { 0.5 };
+		let x = width - (newest - i) as f32 * step;
+		let y = inset + height * (1.0 - level);
+		point(bounds.origin.x + px(x), bounds.origin.y + px(y))
+	};
+
+	let mut path = PathBuilder::stroke(px(LINE_WIDTH));
+	path.move_to(at(0, values[0]));
+	for (i, value) in values.iter().enumerate().skip(1) {
+		path.line_to(at(i, *value));
+	}
+	match path.build() {
+		Ok(path) => window.paint_path(path, color),
+		Err(e) => log::warn!("sparkline: failed to build path: {}", e),
+	}
+}
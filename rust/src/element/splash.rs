@@ -0,0 +1,49 @@
+//! Window splash content
+//!
+//! A window created with a `splash` tree shows it, over the window's
+//! `backgroundColor`, from its first frame until React's first commit lands;
+//! the splash then fades out over [`FADE_US`] on top of the new tree. The
+//! tree is plain element JSON nested through `children` (`{type, text?,
+//! style?, children?}`, styles already mapped like a commit's), so a logo
+//! `img` and an indeterminate `progress` need nothing from JS once the window
+//! exists.
+
+use std::sync::{Arc, atomic::{AtomicU64, Ordering}};
+
+use serde_json::Value;
+
+use super::{ElementKind, ReactElement, style_store};
+
+/// Length of the cross-fade from the splash to the first tree
+pub const FADE_US: u64 = 200_000;
+
+/// Splash elements count down from the top of the id space, far from the ids
+/// JS hands out
+static NEXT_ID: AtomicU64 = AtomicU64::new(u64::MAX);
+
+/// Build the splash tree, None if the root isn't an object
+pub fn parse(value: &Value) -> Option<Arc<ReactElement>> {
+	let object = value.as_object()?;
+	let element_type = object.get("type").and_then(Value::as_str).unwrap_or("div").to_string();
+	let (style, gpui_style) = style_store::intern(object.get("style"));
+	let children = object
+		.get("children")
+		.and_then(Value::as_array)
+		.map(|children| children.iter().filter_map(parse).collect())
+		.unwrap_or_default();
+	Some(Arc::new(ReactElement {
+		global_id: NEXT_ID.fetch_sub(1, Ordering::Relaxed),
+		element_kind: ElementKind::from_str(&element_type),
+		element_type,
+		text: object.get("text").and_then(Value::as_str).map(str::to_string),
+		children,
+		style,
+		event_handlers: None,
+		cached_gpui_style: Some(gpui_style),
+	}))
+}
+
+/// Opacity of the splash `elapsed_us` into the fade, None once it is over
+pub fn fade_opacity(elapsed_us: u64) -> Option<f32> {
+	(elapsed_us < FADE_US).then(|| 1.0 - elapsed_us as f32 / FADE_US as f32)
+}
@@ -0,0 +1,106 @@
+//! Shared element styles (flyweight)
+//!
+//! Large lists repeat the same style object on thousands of elements. Styles
+//! are interned on ingest by their JSON text: elements with identical style
+//! props share one `Arc<ElementStyle>` and one prebuilt GPUI Style, so memory
+//! grows with the number of distinct styles rather than elements, and two
+//! elements have the same style exactly when the Arcs are the same pointer.
+//! Entries are weak; styles no element uses any more are pruned as the store
+//! grows.
+//!
+//! A style patch (`gpui_patch_styles`) merges into the JSON an element's style
+//! was interned from, so the patched style is shared too; toggling between
+//! styles already in use is a lookup, not a parse.
+
+use std::{collections::HashMap, sync::{Arc, Mutex, Weak}};
+
+use gpui::Style;
+use lazy_static::lazy_static;
+use serde_json::{Map, Value};
+
+use super::{ElementStyle, inert, live_region};
+use crate::sync::MutexExt;
+
+/// Entries below which the store is never pruned
+const MIN_PRUNE_SIZE: usize = 1024;
+
+struct Entry {
+	style: Weak<ElementStyle>,
+	gpui:  Weak<Style>,
+}
+
+#[derive(Default)]
+struct Store {
+	entries:  HashMap<String, Entry>,
+	/// Key of each live style, by address
+	keys:     HashMap<usize, String>,
+	/// Store size that triggers the next prune
+	prune_at: usize,
+}
+
+lazy_static! {
+	static ref STORE: Mutex<Store> = Mutex::new(Store::default());
+}
+
+/// Shared style and GPUI Style for a style object (None for no style)
+pub fn intern(style_json: Option<&Value>) -> (Arc<ElementStyle>, Arc<Style>) {
+	let key = style_json.map(Value::to_string).unwrap_or_default();
+	let mut store = STORE.lock_recover();
+	if let Some(entry) = store.entries.get(&key)
+		&& let (Some(style), Some(gpui)) = (entry.style.upgrade(), entry.gpui.upgrade())
+	{
+		return (style, gpui);
+	}
+
+	let style = Arc::new(style_json.map(ElementStyle::from_json).unwrap_or_default());
+	live_region::note_style(&style);
+	inert::note_style(&style);
+	// Div and span have no default background
+	let gpui = Arc::new(style.build_gpui_style(None));
+	store.keys.insert(Arc::as_ptr(&style) as usize, key.clone());
+	store.entries.insert(key, Entry { style: Arc::downgrade(&style), gpui: Arc::downgrade(&gpui) });
+
+	if store.entries.len() >= store.prune_at.max(MIN_PRUNE_SIZE) {
+		store.entries.retain(|_, entry| entry.style.strong_count() > 0);
+		let Store { entries, keys, .. } = &mut *store;
+		keys.retain(|&ptr, key| {
+			entries.get(key).is_some_and(|entry| entry.style.as_ptr() as usize == ptr)
+		});
+		store.prune_at = store.entries.len() * 2;
+	}
+	(style, gpui)
+}
+
+/// Shared style for `style` with `props` set (a null value removes the prop)
+pub fn patch(
+	style: &Arc<ElementStyle>,
+	props: &Map<String, Value>,
+) -> (Arc<ElementStyle>, Arc<Style>) {
+	let mut merged = source(style).unwrap_or_default();
+	for (prop, value) in props {
+		if value.is_null() {
+			merged.remove(prop);
+		} else {
+			merged.insert(prop.clone(), value.clone());
+		}
+	}
+	intern(Some(&Value::Object(merged)))
+}
+
+/// Style object a shared style was interned from
+/// None for styles not from the store (placeholders), which are all default
+fn source(style: &Arc<ElementStyle>) -> Option<Map<String, Value>> {
+	let store = STORE.lock_recover();
+	let key = store.keys.get(&(Arc::as_ptr(style) as usize))?;
+	// The address may have been reused since the key was recorded
+	let entry = store.entries.get(key)?;
+	if !std::ptr::eq(entry.style.as_ptr(), Arc::as_ptr(style)) {
+		return None;
+	}
+	serde_json::from_str(key).ok()
+}
+
+/// Number of distinct styles in use
+pub fn unique_styles() -> usize {
+	STORE.lock_recover().entries.values().filter(|entry| entry.style.strong_count() > 0).count()
+}
@@ -0,0 +1,281 @@
+//! Hover, focus and active styles, precomputed while the window is idle
+//!
+//! An element with `_hover`, `_focus` or `_active` styles renders that
+//! variant while the pointer is over it, while it has focus or while a left
+//! button press on it is held; with several at once the active variant wins
+//! over the focus variant, which wins over the hover variant. The variants'
+//! GPUI Styles are built ahead of time: a short while after each commit the
+//! tree is walked in small chunks and every missing variant is cached, so the
+//! first hover doesn't pay for building it and state flips just swap cached
+//! styles. Variants are keyed by element id and state and checked against
+//! the element's interned style (see style_store): a commit that leaves an
+//! element's style props alone keeps its variants, one that changes them
+//! gets them rebuilt.
+//!
+//! Hover and press state is tracked by the window's dispatcher, from the
+//! hitboxes divs record for their state styles; focus comes from focus.rs.
+
+use std::{collections::{HashMap, HashSet}, sync::{Arc, Mutex}};
+
+use gpui::Style;
+use lazy_static::lazy_static;
+
+use super::{ElementStyle, ReactElement, focus};
+use crate::{global_state::GLOBAL_STATE, sync::MutexExt};
+
+/// Variants built per precompute step, so a step never holds up a frame
+pub const PRECOMPUTE_CHUNK: usize = 64;
+
+/// Element state with its own style, lowest precedence first
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum State {
+	Hover,
+	Focus,
+	Active,
+}
+
+impl State {
+	const ALL: [State; 3] = [State::Hover, State::Focus, State::Active];
+
+	/// The element's complete style in this state, if it has one
+	pub fn style(self, style: &ElementStyle) -> Option<&ElementStyle> {
+		match self {
+			State::Hover => style.hover_style.as_deref(),
+			State::Focus => style.focus_style.as_deref(),
+			State::Active => style.active_style.as_deref(),
+		}
+	}
+}
+
+/// Cached variant of one element in one state
+struct Variant {
+	/// Interned style the variant was built from, held so its address can't
+	/// be reused by another style
+	source: Arc<ElementStyle>,
+	style:  Style,
+}
+
+#[derive(Default)]
+struct WindowVariants {
+	variants:  HashMap<(u64, State), Variant>,
+	/// Elements the pointer was over when their window last handled a move
+	hovered:   HashSet<u64>,
+	/// Element a held left button press started on
+	active:    Option<u64>,
+	/// Tree generation whose variants are all built
+	completed: Option<u64>,
+}
+
+lazy_static! {
+	static ref WINDOWS: Mutex<HashMap<u64, WindowVariants>> = Mutex::new(HashMap::new());
+}
+
+/// Drop all state of a closed window
+pub fn forget_window(window_id: u64) { WINDOWS.lock_recover().remove(&window_id); }
+
+/// Drop the variants and hover state of a released element
+pub fn forget_element(window_id: u64, element_id: u64) {
+	if let Some(window) = WINDOWS.lock_recover().get_mut(&window_id) {
+		window.variants.retain(|(id, _), _| *id != element_id);
+		window.hovered.remove(&element_id);
+		if window.active == Some(element_id) {
+			window.active = None;
+		}
+	}
+}
+
+/// Whether an element has any state style to track
+pub fn has_state_styles(style: &ElementStyle) -> bool {
+	State::ALL.iter().any(|state| state.style(style).is_some())
+}
+
+/// Record the hover state of an element, returning true if it changed
+pub fn set_hovered(window_id: u64, element_id: u64, hovered: bool) -> bool {
+	let mut windows = WINDOWS.lock_recover();
+	let window = windows.entry(window_id).or_default();
+	if hovered { window.hovered.insert(element_id) } else { window.hovered.remove(&element_id) }
+}
+
+/// Record the element a press started on, or None once the button is
+/// released, returning true if it changed
+pub fn set_active(window_id: u64, element_id: Option<u64>) -> bool {
+	let mut windows = WINDOWS.lock_recover();
+	let window = windows.entry(window_id).or_default();
+	std::mem::replace(&mut window.active, element_id) != element_id
+}
+
+/// The state whose style an element renders, if any
+pub fn current_state(window_id: u64, element: &ReactElement) -> Option<State> {
+	let style = &element.style;
+	let (hovered, active) = {
+		let windows = WINDOWS.lock_recover();
+		let window = windows.get(&window_id);
+		(
+			window.is_some_and(|window| window.hovered.contains(&element.global_id)),
+			window.is_some_and(|window| window.active == Some(element.global_id)),
+		)
+	};
+	if active && style.active_style.is_some() {
+		Some(State::Active)
+	} else if style.focus_style.is_some() && focus::is_focused(window_id, element.global_id) {
+		Some(State::Focus)
+	} else if hovered && style.hover_style.is_some() {
+		Some(State::Hover)
+	} else {
+		None
+	}
+}
+
+/// GPUI Style of an element's variant for `state`, from the cache when
+/// possible
+pub fn variant_style(window_id: u64, element: &Arc<ReactElement>, state: State) -> Option<Style> {
+	let variant = state.style(&element.style)?;
+	let key = (element.global_id, state);
+	let mut windows = WINDOWS.lock_recover();
+	let window = windows.entry(window_id).or_default();
+	if let Some(cached) = window.variants.get(&key)
+		&& Arc::ptr_eq(&cached.source, &element.style)
+	{
+		return Some(cached.style.clone());
+	}
+	let style = variant.build_gpui_style(None);
+	window.variants.insert(key, Variant { source: element.style.clone(), style: style.clone() });
+	Some(style)
+}
+
+/// Element style to lay out and inherit from: the current state's variant,
+/// if any
+pub fn effective_style(
+	window_id: u64,
+	element: &ReactElement,
+	parent_style: Option<&ElementStyle>,
+) -> ElementStyle {
+	match current_state(window_id, element).and_then(|state| state.style(&element.style)) {
+		Some(variant) => {
+			let mut style = variant.clone();
+			if let Some(parent) = parent_style {
+				style.inherit_from(parent);
+			}
+			style
+		}
+		None => element.effective_style(parent_style),
+	}
+}
+
+/// Build up to `budget` missing variants of a window's current tree
+/// Returns true if variants are still missing afterwards
+pub fn precompute(window_id: u64, budget: usize) -> bool {
+	let Some(window) = GLOBAL_STATE.get_window(window_id) else {
+		return false;
+	};
+	let generation = window.state().get_tree_generation();
+	if WINDOWS
+		.lock_recover()
+		.get(&window_id)
+		.is_some_and(|window| window.completed == Some(generation))
+	{
+		return false;
+	}
+	let Some(root) = window.state().element_tree.lock_recover().clone() else {
+		return false;
+	};
+
+	let mut with_states = Vec::new();
+	collect(&root, &mut with_states);
+
+	let mut windows = WINDOWS.lock_recover();
+	let window = windows.entry(window_id).or_default();
+	let live: HashSet<u64> = with_states.iter().map(|element| element.global_id).collect();
+	window.variants.retain(|(id, _), _| live.contains(id));
+
+	let mut built = 0;
+	for element in with_states {
+		for state in State::ALL {
+			let Some(variant) = state.style(&element.style) else {
+				continue;
+			};
+			let key = (element.global_id, state);
+			if window.variants.get(&key).is_some_and(|v| Arc::ptr_eq(&v.source, &element.style)) {
+				continue;
+			}
+			if built == budget {
+				return true;
+			}
+			let style = variant.build_gpui_style(None);
+			window.variants.insert(key, Variant { source: element.style.clone(), style });
+			built += 1;
+		}
+	}
+	window.completed = Some(generation);
+	false
+}
+
+fn collect(element: &Arc<ReactElement>, out: &mut Vec<Arc<ReactElement>>) {
+	if has_state_styles(&element.style) {
+		out.push(element.clone());
+	}
+	for child in &element.children {
+		collect(child, out);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json::json;
+
+	use super::*;
+	use crate::element::ElementKind;
+
+	fn element(id: u64, style: serde_json::Value) -> Arc<ReactElement> {
+		Arc::new(ReactElement {
+			global_id:         id,
+			element_type:      "div".to_string(),
+			element_kind:      ElementKind::Div,
+			text:              None,
+			children:          Vec::new(),
+			style:             Arc::new(ElementStyle::from_json(&style)),
+			event_handlers:    None,
+			cached_gpui_style: None,
+		})
+	}
+
+	#[test]
+	fn active_wins_over_focus_over_hover() {
+		let window_id = 201;
+		let element = element(2, json!({
+			"opacity": 0.1,
+			"hoverStyle": { "opacity": 0.2 },
+			"focusStyle": { "opacity": 0.3 },
+			"activeStyle": { "opacity": 0.4 },
+		}));
+		let opacity = || effective_style(window_id, &element, None).opacity;
+		assert_eq!(current_state(window_id, &element), None);
+		assert_eq!(opacity(), Some(0.1));
+
+		assert!(set_hovered(window_id, 2, true));
+		assert_eq!(current_state(window_id, &element), Some(State::Hover));
+		focus::set_focus(window_id, 2);
+		assert_eq!(current_state(window_id, &element), Some(State::Focus));
+		assert!(set_active(window_id, Some(2)));
+		assert_eq!(current_state(window_id, &element), Some(State::Active));
+		assert_eq!(opacity(), Some(0.4));
+
+		assert!(set_active(window_id, None));
+		assert!(!set_active(window_id, None));
+		focus::clear_focus(window_id);
+		assert_eq!(opacity(), Some(0.2));
+	}
+
+	#[test]
+	fn states_without_a_style_are_ignored() {
+		let window_id = 202;
+		let element = element(2, json!({ "focusStyle": { "opacity": 0.5 } }));
+		set_hovered(window_id, 2, true);
+		set_active(window_id, Some(2));
+		assert_eq!(current_state(window_id, &element), None);
+		assert!(variant_style(window_id, &element, State::Hover).is_none());
+		assert!(variant_style(window_id, &element, State::Focus).is_some());
+		forget_element(window_id, 2);
+		assert!(!set_active(window_id, None));
+	}
+}
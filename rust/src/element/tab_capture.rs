@@ -0,0 +1,88 @@
+//! Tab key inside elements that capture it
+//!
+//! Tab moves focus, except in a focused element with `captureTab`, such as a
+//! multi-line input used as a code editor. There Tab is sent to the element
+//! as a keydown and then a beforeinput inserting one indent (`tabSize`
+//! spaces, or a tab with `indentWithTabs`), and Shift+Tab as a beforeinput
+//! with `inputType: "formatOutdent"` carrying the indent to remove. The
+//! element applies the edit like any other beforeinput.
+//!
+//! Escape followed by Tab moves focus as usual, so keyboard users can always
+//! leave the field.
+
+use std::{collections::HashSet, sync::Mutex};
+
+use gpui::Keystroke;
+use lazy_static::lazy_static;
+
+use crate::{event_types::{EventData, InputEventData, types}, global_state::GLOBAL_STATE, renderer::dispatch_event_to_js, sync::MutexExt};
+
+const DEFAULT_TAB_SIZE: usize = 4;
+
+lazy_static! {
+	/// Windows where Escape was pressed in a Tab-capturing element
+	static ref ESCAPED: Mutex<HashSet<u64>> = Mutex::new(HashSet::new());
+}
+
+/// Drop all state of a closed window
+pub fn forget_window(window_id: u64) { ESCAPED.lock_recover().remove(&window_id); }
+
+/// Whether the focused element takes this keystroke as an indent instead of
+/// focus navigation
+/// Also tracks Escape, which lets the next Tab through
+pub fn captures(window_id: u64, focused: Option<u64>, keystroke: &Keystroke) -> bool {
+	let escaped = ESCAPED.lock_recover().remove(&window_id);
+	let Some(element_id) = focused else {
+		return false;
+	};
+	match keystroke.key.as_str() {
+		"escape" => {
+			if indent(window_id, element_id).is_some() {
+				ESCAPED.lock_recover().insert(window_id);
+			}
+			false
+		}
+		"tab" => {
+			let modifiers = &keystroke.modifiers;
+			!(escaped || modifiers.control || modifiers.alt || modifiers.platform)
+				&& indent(window_id, element_id).is_some()
+		}
+		_ => false,
+	}
+}
+
+/// Send the indent (or outdent, with shift) of a captured Tab to the element
+pub fn dispatch_indent(window_id: u64, element_id: u64, outdent: bool) {
+	let Some((indent, value)) = indent(window_id, element_id) else {
+		return;
+	};
+	let input_type = if outdent { "formatOutdent" } else { "insertText" };
+	dispatch_event_to_js(
+		window_id,
+		element_id,
+		types::BEFOREINPUT,
+		EventData::Input(InputEventData {
+			value,
+			data: Some(indent),
+			input_type: input_type.to_string(),
+			is_composing: false,
+			checked: None,
+		}),
+	);
+}
+
+/// Indent an element inserts and its current value, None unless it captures Tab
+fn indent(window_id: u64, element_id: u64) -> Option<(String, String)> {
+	let window = GLOBAL_STATE.get_window(window_id)?;
+	let element_map = window.state().element_map.lock_recover();
+	let style = &element_map.get(&element_id)?.style;
+	if style.capture_tab != Some(true) {
+		return None;
+	}
+	let indent = if style.indent_with_tabs == Some(true) {
+		"\t".to_string()
+	} else {
+		" ".repeat(style.tab_size.unwrap_or(DEFAULT_TAB_SIZE))
+	};
+	Some((indent, style.value.clone().unwrap_or_default()))
+}
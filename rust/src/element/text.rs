@@ -1,8 +1,8 @@
 use std::sync::Arc;
 
-use gpui::{AnyElement, App, Bounds, Element, ElementId, GlobalElementId, Hitbox, InspectorElementId, IntoElement, LayoutId, Pixels, Style, Window, div, prelude::*, px, rgb};
+use gpui::{AnyElement, App, Bounds, Element, ElementId, GlobalElementId, Hitbox, InspectorElementId, IntoElement, LayoutId, Pixels, ShapedLine, Style, Window, point, px, relative, rgb};
 
-use super::{ElementStyle, ReactElement, events::{EventHandlerFlags, insert_hitbox_if_needed, register_event_handlers}};
+use super::{ElementStyle, ReactElement, elide::{self, ElideMode}, events::{EventHandlerFlags, insert_hitbox_if_needed, register_event_handlers}, find_overlay, fonts, layout, text_cache, vertical_text::{self, VerticalLayout}};
 
 /// A specialized text element that renders text content
 /// Uses GPUI's built-in text rendering for proper layout integration
@@ -11,6 +11,12 @@ pub struct ReactTextElement {
 	window_id:    u64,
 	parent_style: Option<ElementStyle>,
 	text_child:   Option<AnyElement>,
+	/// Set instead of `text_child` in a vertical writing mode
+	vertical:     Option<VerticalLayout>,
+	/// Set instead of `text_child` for single-line elided text
+	elide:        Option<ElideMode>,
+	/// The elided line, fitted to the bounds in prepaint
+	elided:       Option<ShapedLine>,
 }
 
 pub struct TextLayoutState {
@@ -28,7 +34,15 @@ impl ReactTextElement {
 		window_id: u64,
 		parent_style: Option<ElementStyle>,
 	) -> Self {
-		Self { element, window_id, parent_style, text_child: None }
+		Self {
+			element,
+			window_id,
+			parent_style,
+			text_child: None,
+			vertical: None,
+			elide: None,
+			elided: None,
+		}
 	}
 }
 
@@ -65,19 +79,56 @@ impl Element for ReactTextElement {
 			));
 		}
 
+		// Vertical text is measured and painted here rather than by a child
+		if !text.is_empty() && vertical_text::is_vertical(&effective) {
+			let vertical = vertical_text::layout(&text, &effective);
+			let size = vertical.size();
+			if effective.width.is_none() {
+				style.size.width = gpui::Length::Definite(gpui::DefiniteLength::Absolute(
+					gpui::AbsoluteLength::Pixels(size.width),
+				));
+			}
+			if effective.height.is_none() {
+				style.size.height = gpui::Length::Definite(gpui::DefiniteLength::Absolute(
+					gpui::AbsoluteLength::Pixels(size.height),
+				));
+			}
+			self.vertical = Some(vertical);
+			let layout_id = window.request_layout(style, std::iter::empty(), cx);
+			return (layout_id, TextLayoutState { child_layout_id: None });
+		}
+
+		// Elided text is shaped and painted here, to fit the width it gets
+		if let Some(mode) = elide::mode(&effective)
+			&& !text.is_empty()
+			&& !text.contains('\n')
+		{
+			let font_size = px(effective.text_size.unwrap_or(14.0));
+			if effective.width.is_none() {
+				let font = fonts::resolve(&effective, window);
+				let color = effective.text_color.unwrap_or_else(|| rgb(0xffffff).into());
+				let line = text_cache::shape_line(window, text, &font, font_size, color);
+				style.size.width = line.width.into();
+			}
+			if effective.height.is_none() {
+				style.size.height = elide::line_height(font_size, window).into();
+			}
+			// Shrinks like any flex item, down to the ellipsis alone
+			style.min_size.width = px(0.0).into();
+			style.max_size.width = relative(1.0).into();
+			self.elide = Some(mode);
+			let layout_id = window.request_layout(style, std::iter::empty(), cx);
+			return (layout_id, TextLayoutState { child_layout_id: None });
+		}
+
 		// Create child text element if we have text content
 		let child_layout_id = if !text.is_empty() {
-			let text_color = effective.text_color.unwrap_or(0xffffff);
+			let text_color = effective.text_color.unwrap_or_else(|| rgb(0xffffff).into());
 			let text_size = effective.text_size.unwrap_or(14.0);
 
-			let mut text_element = div().text_color(rgb(text_color)).text_size(px(text_size)).child(text);
-
-			// Apply font weight if specified
-			if let Some(weight) = effective.font_weight {
-				text_element = text_element.font_weight(gpui::FontWeight(weight as f32));
-			}
-
-			let mut child = text_element.into_any_element();
+			let font = fonts::resolve(&effective, window);
+			let mut child =
+				text_cache::text_element(text.into(), font, px(text_size), text_color, window);
 			let layout_id = child.request_layout(window, cx);
 			self.text_child = Some(child);
 			Some(layout_id)
@@ -104,10 +155,22 @@ impl Element for ReactTextElement {
 		window: &mut Window,
 		cx: &mut App,
 	) -> Self::PrepaintState {
+		layout::record_bounds(self.window_id, self.element.global_id, bounds);
+
 		// Prepaint child
 		if let Some(ref mut child) = self.text_child {
 			child.prepaint(window, cx);
 		}
+		if let Some(mode) = self.elide
+			&& let Some(ref text) = self.element.text
+		{
+			let effective = self.element.effective_style(self.parent_style.as_ref());
+			let font = fonts::resolve(&effective, window);
+			let font_size = px(effective.text_size.unwrap_or(14.0));
+			let color = effective.text_color.unwrap_or_else(|| rgb(0xffffff).into());
+			self.elided =
+				Some(elide::fit(text, mode, bounds.size.width, &font, font_size, color, window));
+		}
 
 		// Check event handlers and insert hitbox if needed
 		let event_flags = EventHandlerFlags::from_handlers(
@@ -123,7 +186,7 @@ impl Element for ReactTextElement {
 		&mut self,
 		_id: Option<&GlobalElementId>,
 		_inspector_id: Option<&InspectorElementId>,
-		_bounds: Bounds<Pixels>,
+		bounds: Bounds<Pixels>,
 		_request_layout: &mut Self::RequestLayoutState,
 		prepaint: &mut Self::PrepaintState,
 		window: &mut Window,
@@ -133,6 +196,36 @@ impl Element for ReactTextElement {
 		if let Some(ref mut child) = self.text_child {
 			child.paint(window, cx);
 		}
+		if let Some(ref vertical) = self.vertical {
+			let effective = self.element.effective_style(self.parent_style.as_ref());
+			let font = fonts::resolve(&effective, window);
+			let color = effective.text_color.unwrap_or_else(|| rgb(0xffffff).into());
+			vertical.paint(bounds, &font, color, window, cx);
+		}
+		if let Some(ref line) = self.elided {
+			let effective = self.element.effective_style(self.parent_style.as_ref());
+			let line_height = elide::line_height(px(effective.text_size.unwrap_or(14.0)), window);
+			let top = bounds.origin.y + (bounds.size.height - line_height) / 2.0;
+			if let Err(e) = line.paint(point(bounds.origin.x, top), line_height, window, cx) {
+				log::warn!("elided text: failed to paint: {}", e);
+			}
+		}
+
+		// Make the text searchable by the find overlay
+		if let Some(ref text) = self.element.text
+			&& find_overlay::is_open(self.window_id)
+		{
+			let effective = self.element.effective_style(self.parent_style.as_ref());
+			let font = fonts::resolve(&effective, window);
+			find_overlay::record_text(
+				self.window_id,
+				self.element.global_id,
+				text,
+				bounds,
+				effective.text_size.unwrap_or(14.0),
+				font,
+			);
+		}
 
 		// Register event handlers using shared module
 		register_event_handlers(
@@ -0,0 +1,403 @@
+//! Shaped-line cache shared by all elements that shape text themselves
+//!
+//! Shaping is keyed by (text, font, size, color) and kept in a global LRU so
+//! repeated shaping of identical content (every frame, on every mouse move)
+//! only pays for a hash lookup. Hit/miss counters are kept for diagnostics.
+//!
+//! The text of text, span and div elements goes through `text_element`: a
+//! line that fits its width is painted straight from the cache, a line that
+//! doesn't is wrapped through a second cache keyed by the shape key and the
+//! wrap width. Only text with line breaks is laid out by gpui's text system.
+
+use std::{collections::HashMap, hash::Hash, sync::{Arc, Mutex}};
+
+use gpui::{AnyElement, App, AvailableSpace, Bounds, Element, ElementId, Font, GlobalElementId, Hsla, InspectorElementId, IntoElement, LayoutId, Pixels, ShapedLine, SharedString, Size, Style, TextAlign, TextRun, Window, WrappedLine, div, prelude::*, size};
+use lazy_static::lazy_static;
+
+use super::elide;
+use crate::sync::MutexExt;
+
+/// Maximum number of shaped lines kept alive
+const CAPACITY: usize = 1024;
+
+/// Maximum number of wrapped texts kept alive
+const WRAP_CAPACITY: usize = 256;
+
+/// How often (in lookups) hit rate is written to the debug log
+const REPORT_INTERVAL: u64 = 4096;
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct ShapeKey {
+	text:      SharedString,
+	font:      Font,
+	font_size: u32,
+	color:     [u32; 4],
+}
+
+impl ShapeKey {
+	fn new(text: &SharedString, font: &Font, font_size: Pixels, color: Hsla) -> Self {
+		Self {
+			text:      text.clone(),
+			font:      font.clone(),
+			font_size: f32::from(font_size).to_bits(),
+			color:     [color.h.to_bits(), color.s.to_bits(), color.l.to_bits(), color.a.to_bits()],
+		}
+	}
+}
+
+/// Counters describing cache effectiveness
+#[derive(Clone, Copy, Default, Debug)]
+pub struct TextCacheStats {
+	pub entries:   usize,
+	pub hits:      u64,
+	pub misses:    u64,
+	pub evictions: u64,
+}
+
+impl TextCacheStats {
+	/// Fraction of lookups served from the cache (0.0 when unused)
+	pub fn hit_rate(&self) -> f64 {
+		let total = self.hits + self.misses;
+		if total == 0 { 0.0 } else { self.hits as f64 / total as f64 }
+	}
+}
+
+/// Wrapped lines by shape key and wrap width
+type WrapCache = TextCache<(ShapeKey, u32), Arc<[WrappedLine]>>;
+
+struct TextCache<K, V> {
+	/// Name in the debug log
+	name:      &'static str,
+	entries:   HashMap<K, (V, u64)>,
+	capacity:  usize,
+	/// Monotonic access counter used as the LRU timestamp
+	tick:      u64,
+	hits:      u64,
+	misses:    u64,
+	evictions: u64,
+}
+
+impl<K: Eq + Hash, V: Clone> TextCache<K, V> {
+	fn new(name: &'static str, capacity: usize) -> Self {
+		Self { name, entries: HashMap::new(), capacity, tick: 0, hits: 0, misses: 0, evictions: 0 }
+	}
+
+	/// Cached value of `key`, counting the lookup as a hit or miss
+	fn get(&mut self, key: &K) -> Option<V> {
+		self.tick += 1;
+		let tick = self.tick;
+		let line = self.entries.get_mut(key).map(|(line, last_used)| {
+			*last_used = tick;
+			line.clone()
+		});
+		if line.is_some() {
+			self.hits += 1;
+		} else {
+			self.misses += 1;
+		}
+		self.report();
+		line
+	}
+
+	/// Cache a freshly computed value, evicting first when full
+	fn insert(&mut self, key: K, value: V) {
+		if self.entries.len() >= self.capacity {
+			self.evict();
+		}
+		self.entries.insert(key, (value, self.tick));
+	}
+
+	/// Drop the least recently used quarter of the cache, at least one entry
+	fn evict(&mut self) {
+		let mut ticks: Vec<u64> = self.entries.values().map(|(_, tick)| *tick).collect();
+		if ticks.is_empty() {
+			return;
+		}
+		ticks.sort_unstable();
+		let cutoff = ticks[(ticks.len() / 4).max(1) - 1];
+		let before = self.entries.len();
+		self.entries.retain(|_, (_, tick)| *tick > cutoff);
+		self.evictions += (before - self.entries.len()) as u64;
+	}
+
+	fn report(&self) {
+		let total = self.hits + self.misses;
+		if total.is_multiple_of(REPORT_INTERVAL) {
+			log::debug!(
+				"[Rust] {} cache: {} entries, hit rate {:.1}% ({} hits, {} misses, {} evicted)",
+				self.name,
+				self.entries.len(),
+				self.stats().hit_rate() * 100.0,
+				self.hits,
+				self.misses,
+				self.evictions
+			);
+		}
+	}
+
+	fn stats(&self) -> TextCacheStats {
+		TextCacheStats {
+			entries:   self.entries.len(),
+			hits:      self.hits,
+			misses:    self.misses,
+			evictions: self.evictions,
+		}
+	}
+}
+
+lazy_static! {
+	static ref TEXT_CACHE: Mutex<TextCache<ShapeKey, ShapedLine>> =
+		Mutex::new(TextCache::new("text", CAPACITY));
+	static ref WRAP_CACHE: Mutex<WrapCache> = Mutex::new(TextCache::new("wrap", WRAP_CAPACITY));
+}
+
+/// Current counters of the shaped-line cache
+pub fn stats() -> TextCacheStats { TEXT_CACHE.lock_recover().stats() }
+
+/// Shape a single line of text, reusing a previous result when possible
+pub fn shape_line(
+	window: &Window,
+	text: impl Into<SharedString>,
+	font: &Font,
+	font_size: Pixels,
+	color: Hsla,
+) -> ShapedLine {
+	let text = text.into();
+	let key = ShapeKey::new(&text, font, font_size, color);
+
+	if let Some(line) = TEXT_CACHE.lock_recover().get(&key) {
+		return line;
+	}
+
+	let run = text_run(&text, font, color);
+	let line = window.text_system().shape_line(text, font_size, &[run], None);
+	TEXT_CACHE.lock_recover().insert(key, line.clone());
+	line
+}
+
+/// Element painting `text` in one font, size and color
+pub fn text_element(
+	text: SharedString,
+	font: Font,
+	font_size: Pixels,
+	color: Hsla,
+	window: &Window,
+) -> AnyElement {
+	if text.contains('\n') {
+		return div().text_color(color).text_size(font_size).font(font).child(text).into_any_element();
+	}
+	let line = shape_line(window, text.clone(), &font, font_size, color);
+	CachedText { text, font, font_size, color, line, wrapped: None }.into_any_element()
+}
+
+fn text_run(text: &str, font: &Font, color: Hsla) -> TextRun {
+	TextRun {
+		len: text.len(),
+		font: font.clone(),
+		color,
+		background_color: None,
+		underline: None,
+		strikethrough: None,
+	}
+}
+
+/// Wrap `text` at `width`, reusing a previous result when possible
+fn wrap(
+	text: &SharedString,
+	font: &Font,
+	font_size: Pixels,
+	color: Hsla,
+	width: Pixels,
+	window: &Window,
+) -> Arc<[WrappedLine]> {
+	let key = (ShapeKey::new(text, font, font_size, color), f32::from(width).to_bits());
+	if let Some(lines) = WRAP_CACHE.lock_recover().get(&key) {
+		return lines;
+	}
+
+	let run = text_run(text, font, color);
+	match window.text_system().shape_text(text.clone(), font_size, &[run], Some(width), None) {
+		Ok(lines) => {
+			let lines: Arc<[WrappedLine]> = lines.into_vec().into();
+			WRAP_CACHE.lock_recover().insert(key, lines.clone());
+			lines
+		}
+		Err(e) => {
+			log::warn!("text cache: failed to wrap text: {}", e);
+			Arc::from([])
+		}
+	}
+}
+
+/// A line of text shaped through the cache, wrapped only if it doesn't fit
+struct CachedText {
+	text:      SharedString,
+	font:      Font,
+	font_size: Pixels,
+	color:     Hsla,
+	line:      ShapedLine,
+	/// Lines of the text wrapped at its bounds, None while it fits on one
+	wrapped:   Option<Arc<[WrappedLine]>>,
+}
+
+impl Element for CachedText {
+	type PrepaintState = ();
+	type RequestLayoutState = ();
+
+	fn id(&self) -> Option<ElementId> { None }
+
+	fn source_location(&self) -> Option<&'static std::panic::Location<'static>> { None }
+
+	fn request_layout(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		window: &mut Window,
+		_cx: &mut App,
+	) -> (LayoutId, Self::RequestLayoutState) {
+		let line_height = elide::line_height(self.font_size, window);
+		let (text, font, font_size, color) =
+			(self.text.clone(), self.font.clone(), self.font_size, self.color);
+		let width = self.line.width;
+		let layout_id =
+			window.request_measured_layout(Style::default(), move |known, available, window, _cx| {
+				let wrap_width = known.width.or(match available.width {
+					AvailableSpace::Definite(width) => Some(width),
+					_ => None,
+				});
+				match wrap_width {
+					Some(wrap_width) if width > wrap_width => {
+						let mut measured: Size<Pixels> = Size::default();
+						for line in wrap(&text, &font, font_size, color, wrap_width, window).iter() {
+							let line_size = line.size(line_height);
+							measured.height += line_size.height;
+							measured.width = measured.width.max(line_size.width).ceil();
+						}
+						measured
+					}
+					_ => size(width.ceil(), line_height),
+				}
+			});
+		(layout_id, ())
+	}
+
+	fn prepaint(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		bounds: Bounds<Pixels>,
+		_request_layout: &mut Self::RequestLayoutState,
+		window: &mut Window,
+		_cx: &mut App,
+	) -> Self::PrepaintState {
+		self.wrapped = (self.line.width > bounds.size.width.ceil())
+			.then(|| wrap(&self.text, &self.font, self.font_size, self.color, bounds.size.width, window));
+	}
+
+	fn paint(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		bounds: Bounds<Pixels>,
+		_request_layout: &mut Self::RequestLayoutState,
+		_prepaint: &mut Self::PrepaintState,
+		window: &mut Window,
+		cx: &mut App,
+	) {
+		let line_height = elide::line_height(self.font_size, window);
+		let result = match &self.wrapped {
+			None => self.line.paint(bounds.origin, line_height, window, cx),
+			Some(lines) => {
+				let mut origin = bounds.origin;
+				lines.iter().try_for_each(|line| {
+					line.paint(origin, line_height, TextAlign::default(), Some(bounds), window, cx)?;
+					origin.y += line.size(line_height).height;
+					Ok(())
+				})
+			}
+		};
+		if let Err(e) = result {
+			log::warn!("text cache: failed to paint text: {}", e);
+		}
+	}
+}
+
+impl IntoElement for CachedText {
+	type Element = Self;
+
+	fn into_element(self) -> Self::Element { self }
+}
+
+#[cfg(test)]
+mod tests {
+	use gpui::{TestAppContext, black, font, hsla, px};
+
+	use super::*;
+
+	fn key(text: &'static str, font_size: f32) -> ShapeKey {
+		ShapeKey::new(&text.into(), &font("Helvetica"), px(font_size), hsla(0., 0., 0., 1.))
+	}
+
+	/// Look `key` up like `shape_line`, caching a placeholder line on a miss
+	fn shape(cache: &mut TextCache<ShapeKey, ShapedLine>, key: ShapeKey) -> bool {
+		if cache.get(&key).is_some() {
+			return true;
+		}
+		cache.insert(key, ShapedLine::default());
+		false
+	}
+
+	#[test]
+	fn identical_text_font_and_size_hit() {
+		let mut cache = TextCache::new("text", CAPACITY);
+		assert!(!shape(&mut cache, key("hello", 14.)));
+		assert!(shape(&mut cache, key("hello", 14.)));
+		assert!(!shape(&mut cache, key("hello", 16.)));
+		assert!(!shape(&mut cache, key("hello!", 14.)));
+		assert_eq!(cache.stats().entries, 3);
+	}
+
+	#[test]
+	fn counters_track_lookups() {
+		let mut cache = TextCache::new("text", CAPACITY);
+		assert_eq!(cache.stats().hit_rate(), 0.0);
+		shape(&mut cache, key("a", 14.));
+		shape(&mut cache, key("a", 14.));
+		shape(&mut cache, key("a", 14.));
+		shape(&mut cache, key("b", 14.));
+		let stats = cache.stats();
+		assert_eq!((stats.hits, stats.misses, stats.evictions), (2, 2, 0));
+		assert_eq!(stats.hit_rate(), 0.5);
+	}
+
+	#[test]
+	fn least_recently_used_lines_are_evicted_at_capacity() {
+		let mut cache = TextCache::new("text", 4);
+		for text in ["a", "b", "c", "d"] {
+			shape(&mut cache, key(text, 14.));
+		}
+		// "a" becomes the most recently used
+		assert!(shape(&mut cache, key("a", 14.)));
+		// A quarter of the cache goes: only "b"
+		assert!(!shape(&mut cache, key("e", 14.)));
+		assert_eq!(cache.stats().evictions, 1);
+		assert_eq!(cache.stats().entries, 4);
+		for text in ["a", "c", "d", "e"] {
+			assert!(shape(&mut cache, key(text, 14.)));
+		}
+		assert!(!shape(&mut cache, key("b", 14.)));
+	}
+
+	#[gpui::test]
+	fn wrapped_lines_are_reused_per_width(cx: &mut TestAppContext) {
+		let cx = cx.add_empty_window();
+		cx.update(|window, _| {
+			let text = SharedString::from("wrapped once per width");
+			let wrap = |width: f32| wrap(&text, &font("Helvetica"), px(10.0), black(), px(width), window);
+			let narrow = wrap(60.0);
+			assert!(narrow.iter().any(|line| !line.wrap_boundaries().is_empty()));
+			assert!(Arc::ptr_eq(&narrow, &wrap(60.0)));
+			assert!(!Arc::ptr_eq(&narrow, &wrap(90.0)));
+		});
+	}
+}
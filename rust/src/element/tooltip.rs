@@ -0,0 +1,288 @@
+//! Tooltip element
+//!
+//! A `tooltip` is placed inside the element it describes and takes no space
+//! there. Once the pointer has rested on that parent for the tooltip's delay
+//! (500ms by default), the tooltip is painted as a div next to the parent's
+//! bounds, on the side its placement names (top by default). It flips to the
+//! opposite side when it wouldn't fit, then is kept inside the window. A mouse
+//! down hides it until the pointer leaves the parent.
+//!
+//! Shown tooltips are deferred draws above overlays. A tooltip inside an
+//! overlay can't defer again; it's painted with its parent instead.
+
+use std::{collections::HashMap, sync::{Arc, Mutex}, time::{Duration, Instant}};
+
+use gpui::{AnyElement, App, AvailableSpace, Bounds, DispatchPhase, Edges, Element, ElementId, GlobalElementId, Hitbox, HitboxBehavior, InspectorElementId, IntoElement, LayoutId, MouseDownEvent, MouseMoveEvent, Pixels, Point, Position, Size, Style, Window, point, px, size};
+use lazy_static::lazy_static;
+
+use super::{ElementStyle, ReactDivElement, ReactElement, inert, overlay};
+use crate::sync::MutexExt;
+
+/// Hover time before a tooltip shows when the style has no delay
+const DEFAULT_DELAY: Duration = Duration::from_millis(500);
+/// Deferred draw priority, above overlays
+const TOOLTIP_PRIORITY: usize = 2;
+/// Space between the parent and the tooltip
+const GAP: f32 = 6.0;
+/// Closest the tooltip gets to the window edges
+const MARGIN: f32 = 4.0;
+
+/// Pointer resting on a tooltip's parent
+struct Hover {
+	since:      Instant,
+	/// Hidden by a mouse down until the pointer leaves
+	suppressed: bool,
+}
+
+lazy_static! {
+	static ref HOVERS: Mutex<HashMap<(u64, u64), Hover>> = Mutex::new(HashMap::new());
+}
+
+/// Drop all state of a closed window
+pub fn forget_window(window_id: u64) {
+	HOVERS.lock_recover().retain(|(id, _), _| *id != window_id);
+}
+
+/// Drop the hover state of a released element
+pub fn forget_element(window_id: u64, element_id: u64) {
+	HOVERS.lock_recover().remove(&(window_id, element_id));
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Placement {
+	Top,
+	Bottom,
+	Left,
+	Right,
+}
+
+impl Placement {
+	fn from_style(style: &ElementStyle) -> Self {
+		match style.tooltip_placement.as_deref() {
+			Some("bottom") => Placement::Bottom,
+			Some("left") => Placement::Left,
+			Some("right") => Placement::Right,
+			_ => Placement::Top,
+		}
+	}
+
+	fn opposite(self) -> Self {
+		match self {
+			Placement::Top => Placement::Bottom,
+			Placement::Bottom => Placement::Top,
+			Placement::Left => Placement::Right,
+			Placement::Right => Placement::Left,
+		}
+	}
+}
+
+fn hover_delay(style: &ElementStyle) -> Duration {
+	style.tooltip_delay.map(Duration::from_millis).unwrap_or(DEFAULT_DELAY)
+}
+
+fn is_shown(window_id: u64, element_id: u64, delay: Duration) -> bool {
+	HOVERS
+		.lock_recover()
+		.get(&(window_id, element_id))
+		.is_some_and(|hover| !hover.suppressed && hover.since.elapsed() >= delay)
+}
+
+/// Track the pointer entering or leaving the parent
+/// Returns None if nothing changed, else whether a shown tooltip was hidden
+fn set_hovered(window_id: u64, element_id: u64, hovered: bool, delay: Duration) -> Option<bool> {
+	let mut hovers = HOVERS.lock_recover();
+	let key = (window_id, element_id);
+	match (hovered, hovers.contains_key(&key)) {
+		(true, false) => {
+			hovers.insert(key, Hover { since: Instant::now(), suppressed: false });
+			Some(false)
+		}
+		(false, true) => {
+			let hover = hovers.remove(&key)?;
+			Some(!hover.suppressed && hover.since.elapsed() >= delay)
+		}
+		_ => None,
+	}
+}
+
+/// Origin of a tooltip of `size` next to `anchor`, inside the window
+fn place(
+	anchor: Bounds<Pixels>,
+	size: Size<Pixels>,
+	placement: Placement,
+	viewport: Size<Pixels>,
+) -> Point<Pixels> {
+	let gap = px(GAP);
+	let at = |placement| match placement {
+		Placement::Top => {
+			point(anchor.center().x - size.width / 2.0, anchor.origin.y - gap - size.height)
+		}
+		Placement::Bottom => point(anchor.center().x - size.width / 2.0, anchor.bottom() + gap),
+		Placement::Left => {
+			point(anchor.origin.x - gap - size.width, anchor.center().y - size.height / 2.0)
+		}
+		Placement::Right => point(anchor.right() + gap, anchor.center().y - size.height / 2.0),
+	};
+	let fits = |origin: Point<Pixels>| {
+		origin.x >= px(0.0)
+			&& origin.y >= px(0.0)
+			&& origin.x + size.width <= viewport.width
+			&& origin.y + size.height <= viewport.height
+	};
+
+	let mut origin = at(placement);
+	if !fits(origin) && fits(at(placement.opposite())) {
+		origin = at(placement.opposite());
+	}
+	let margin = px(MARGIN);
+	let clamp =
+		|value: Pixels, extent: Pixels, room: Pixels| value.min(room - extent - margin).max(margin);
+	point(clamp(origin.x, size.width, viewport.width), clamp(origin.y, size.height, viewport.height))
+}
+
+pub struct ReactTooltipElement {
+	element:      Arc<ReactElement>,
+	window_id:    u64,
+	parent_style: Option<ElementStyle>,
+	/// Built inside an overlay, so painted in place rather than deferred
+	in_layer:     bool,
+	/// The shown tooltip, when painted in place
+	bubble:       Option<AnyElement>,
+}
+
+pub struct TooltipLayoutState {}
+
+pub struct TooltipPrepaintState {
+	hitbox: Option<Hitbox>,
+}
+
+impl ReactTooltipElement {
+	pub fn new(
+		element: Arc<ReactElement>,
+		window_id: u64,
+		parent_style: Option<ElementStyle>,
+	) -> Self {
+		Self { element, window_id, parent_style, in_layer: overlay::in_layer(), bubble: None }
+	}
+}
+
+impl Element for ReactTooltipElement {
+	type PrepaintState = TooltipPrepaintState;
+	type RequestLayoutState = TooltipLayoutState;
+
+	fn id(&self) -> Option<ElementId> { None }
+
+	fn source_location(&self) -> Option<&'static std::panic::Location<'static>> { None }
+
+	fn request_layout(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		window: &mut Window,
+		cx: &mut App,
+	) -> (LayoutId, Self::RequestLayoutState) {
+		// Cover the parent, to know when the pointer is over it
+		let style = Style {
+			position: Position::Absolute,
+			inset: Edges::all(px(0.0).into()),
+			..Default::default()
+		};
+		let layout_id = window.request_layout(style, std::iter::empty(), cx);
+		(layout_id, TooltipLayoutState {})
+	}
+
+	fn prepaint(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		bounds: Bounds<Pixels>,
+		_request_layout: &mut Self::RequestLayoutState,
+		window: &mut Window,
+		cx: &mut App,
+	) -> Self::PrepaintState {
+		let (window_id, element_id) = (self.window_id, self.element.global_id);
+		if inert::is_inert(window_id, element_id) {
+			forget_element(window_id, element_id);
+			return TooltipPrepaintState { hitbox: None };
+		}
+		let hitbox = window.insert_hitbox(bounds, HitboxBehavior::Normal);
+
+		if is_shown(window_id, element_id, hover_delay(&self.element.style)) {
+			let mut bubble =
+				ReactDivElement::new(self.element.clone(), window_id, self.parent_style.clone())
+					.into_any_element();
+			let max_content = size(AvailableSpace::MaxContent, AvailableSpace::MaxContent);
+			let bubble_size = bubble.layout_as_root(max_content, window, cx);
+			let placement = Placement::from_style(&self.element.style);
+			let origin = place(bounds, bubble_size, placement, window.viewport_size());
+			if self.in_layer {
+				window.with_absolute_element_offset(origin, |window| bubble.prepaint(window, cx));
+				self.bubble = Some(bubble);
+			} else {
+				window.defer_draw(bubble, origin, TOOLTIP_PRIORITY);
+			}
+		}
+
+		TooltipPrepaintState { hitbox: Some(hitbox) }
+	}
+
+	fn paint(
+		&mut self,
+		_id: Option<&GlobalElementId>,
+		_inspector_id: Option<&InspectorElementId>,
+		_bounds: Bounds<Pixels>,
+		_request_layout: &mut Self::RequestLayoutState,
+		prepaint: &mut Self::PrepaintState,
+		window: &mut Window,
+		cx: &mut App,
+	) {
+		if let Some(bubble) = self.bubble.as_mut() {
+			bubble.paint(window, cx);
+		}
+		let Some(hitbox) = prepaint.hitbox.clone() else {
+			return;
+		};
+
+		let (window_id, element_id) = (self.window_id, self.element.global_id);
+		let delay = hover_delay(&self.element.style);
+		let update = move |hovered: bool, window: &mut Window, cx: &mut App| {
+			match set_hovered(window_id, element_id, hovered, delay) {
+				// Show it once the pointer has rested for the delay
+				Some(false) if hovered => {
+					window
+						.spawn(cx, async move |cx| {
+							cx.background_executor().timer(delay).await;
+							let _ = cx.update(|window, _| window.refresh());
+						})
+						.detach();
+				}
+				Some(true) => window.refresh(),
+				_ => {}
+			}
+		};
+		update(hitbox.is_hovered(window), window, cx);
+
+		let move_hitbox = hitbox.clone();
+		window.on_mouse_event(move |_: &MouseMoveEvent, phase, window, cx| {
+			if phase == DispatchPhase::Capture {
+				update(move_hitbox.is_hovered(window), window, cx);
+			}
+		});
+		window.on_mouse_event(move |_: &MouseDownEvent, phase, window, _| {
+			if phase != DispatchPhase::Capture || !hitbox.is_hovered(window) {
+				return;
+			}
+			if let Some(hover) = HOVERS.lock_recover().get_mut(&(window_id, element_id))
+				&& !std::mem::replace(&mut hover.suppressed, true)
+			{
+				window.refresh();
+			}
+		});
+	}
+}
+
+impl IntoElement for ReactTooltipElement {
+	type Element = Self;
+
+	fn into_element(self) -> Self::Element { self }
+}
@@ -0,0 +1,93 @@
+//! Vertical writing modes for text elements
+//!
+//! With `writingMode: "vertical-rl"` (or `"vertical-lr"`) text runs top to
+//! bottom in columns, right to left (left to right). Every character stays
+//! upright and takes one em of height, which is how CJK text is set; each
+//! column is one line-height wide. Columns break at newlines and, if the
+//! element has a height, when a column is full. Latin runs are not rotated
+//! sideways: gpui can't paint rotated text.
+
+use gpui::{App, Bounds, Font, Hsla, Pixels, Size, Window, point, px};
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::{ElementStyle, text_cache};
+
+/// Characters of each column, in reading order
+pub struct VerticalLayout {
+	columns:       Vec<Vec<String>>,
+	/// Height of one character
+	advance:       f32,
+	column_width:  f32,
+	right_to_left: bool,
+}
+
+pub fn is_vertical(style: &ElementStyle) -> bool {
+	matches!(style.writing_mode.as_deref(), Some("vertical-rl" | "vertical-lr"))
+}
+
+/// Break text into columns for an element with the given (inherited) style
+pub fn layout(text: &str, style: &ElementStyle) -> VerticalLayout {
+	let text_size = style.text_size.unwrap_or(14.0);
+	let advance = text_size;
+	let column_width = style.line_height.unwrap_or(text_size * 1.4);
+	let per_column =
+		style.height.map(|height| ((height / advance).floor() as usize).max(1)).unwrap_or(usize::MAX);
+
+	let mut columns = Vec::new();
+	for line in text.split('\n') {
+		let chars: Vec<String> = line.graphemes(true).map(str::to_string).collect();
+		if chars.is_empty() {
+			columns.push(Vec::new());
+			continue;
+		}
+		columns.extend(chars.chunks(per_column).map(<[String]>::to_vec));
+	}
+
+	VerticalLayout {
+		columns,
+		advance,
+		column_width,
+		right_to_left: style.writing_mode.as_deref() != Some("vertical-lr"),
+	}
+}
+
+impl VerticalLayout {
+	/// Size the text needs, to lay the element out with
+	pub fn size(&self) -> Size<Pixels> {
+		let longest = self.columns.iter().map(Vec::len).max().unwrap_or(0);
+		Size {
+			width:  px(self.columns.len() as f32 * self.column_width),
+			height: px(longest as f32 * self.advance),
+		}
+	}
+
+	/// Paint the columns inside `bounds`, each character centered in its cell
+	pub fn paint(
+		&self,
+		bounds: Bounds<Pixels>,
+		font: &Font,
+		color: Hsla,
+		window: &mut Window,
+		cx: &mut App,
+	) {
+		let left = f32::from(bounds.origin.x);
+		let right = left + f32::from(bounds.size.width);
+		let top = f32::from(bounds.origin.y);
+
+		for (index, column) in self.columns.iter().enumerate() {
+			let x = if self.right_to_left {
+				right - (index + 1) as f32 * self.column_width
+			} else {
+				left + index as f32 * self.column_width
+			};
+			for (row, ch) in column.iter().enumerate() {
+				let line = text_cache::shape_line(window, ch.clone(), font, px(self.advance), color);
+				let offset = (self.column_width - f32::from(line.width)) / 2.0;
+				let origin = point(px(x + offset), px(top + row as f32 * self.advance));
+				if let Err(e) = line.paint(origin, px(self.advance), window, cx) {
+					log::warn!("vertical text: failed to paint: {}", e);
+				}
+			}
+		}
+	}
+}
@@ -15,6 +15,8 @@ pub mod props {
 	pub const ON_MOUSE_ENTER: &str = "onMouseEnter";
 	pub const ON_MOUSE_LEAVE: &str = "onMouseLeave";
 	pub const ON_HOVER: &str = "onHover";
+	pub const ON_LONG_PRESS: &str = "onLongPress";
+	pub const ON_DISMISS: &str = "onDismiss";
 	pub const ON_KEY_DOWN: &str = "onKeyDown";
 	pub const ON_KEY_UP: &str = "onKeyUp";
 	pub const ON_KEY_PRESS: &str = "onKeyPress";
@@ -38,6 +40,8 @@ pub mod types {
 	pub const MOUSEENTER: &str = "mouseenter";
 	pub const MOUSELEAVE: &str = "mouseleave";
 	pub const HOVER: &str = "hover";
+	pub const LONGPRESS: &str = "longpress";
+	pub const DISMISS: &str = "dismiss";
 	pub const KEYDOWN: &str = "keydown";
 	pub const KEYUP: &str = "keyup";
 	pub const KEYPRESS: &str = "keypress";
@@ -67,13 +71,14 @@ pub struct MouseEventData {
 /// Keyboard event data
 #[derive(Default, Clone)]
 pub struct KeyboardEventData {
-	pub key:    String,
-	pub code:   String,
-	pub repeat: bool,
-	pub ctrl:   bool,
-	pub shift:  bool,
-	pub alt:    bool,
-	pub meta:   bool,
+	pub key:          String,
+	pub code:         String,
+	pub repeat:       bool,
+	pub repeat_count: u32,
+	pub ctrl:         bool,
+	pub shift:        bool,
+	pub alt:          bool,
+	pub meta:         bool,
 }
 
 /// Scroll/wheel event data
@@ -97,6 +102,7 @@ pub struct InputEventData {
 	pub data:         Option<String>,
 	pub input_type:   String,
 	pub is_composing: bool,
+	pub checked:      Option<bool>,
 }
 
 /// Unified event data enum
@@ -122,6 +128,7 @@ pub fn prop_to_event_type(prop: &str) -> Option<&'static str> {
 		props::ON_MOUSE_ENTER => Some(types::MOUSEENTER),
 		props::ON_MOUSE_LEAVE => Some(types::MOUSELEAVE),
 		props::ON_HOVER => Some(types::HOVER),
+		props::ON_LONG_PRESS => Some(types::LONGPRESS),
 		props::ON_KEY_DOWN => Some(types::KEYDOWN),
 		props::ON_KEY_UP => Some(types::KEYUP),
 		props::ON_KEY_PRESS => Some(types::KEYPRESS),
@@ -148,6 +155,7 @@ pub fn is_mouse_event(event_type: &str) -> bool {
 			| types::MOUSEENTER
 			| types::MOUSELEAVE
 			| types::HOVER
+			| types::LONGPRESS
 	)
 }
 
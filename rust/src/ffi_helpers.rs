@@ -37,6 +37,27 @@ pub unsafe fn ptr_to_u64(ptr: *const u8) -> u64 {
 	}
 }
 
+/// Upper bound for array lengths passed across the FFI boundary
+pub const MAX_FFI_ARRAY_LEN: usize = 1 << 20;
+
+/// Copy a u64 array from a pointer/length pair
+/// Null or empty input yields an empty Vec. Lengths above
+/// MAX_FFI_ARRAY_LEN are rejected and unaligned pointers are read safely.
+#[inline]
+pub unsafe fn read_u64_array(ptr: *const u64, len: usize) -> Result<Vec<u64>, String> {
+	if ptr.is_null() || len == 0 {
+		return Ok(Vec::new());
+	}
+	if len > MAX_FFI_ARRAY_LEN {
+		return Err(format!("array length {} exceeds limit {}", len, MAX_FFI_ARRAY_LEN));
+	}
+	if ptr.is_aligned() {
+		Ok(unsafe { std::slice::from_raw_parts(ptr, len) }.to_vec())
+	} else {
+		Ok((0..len).map(|i| unsafe { ptr.add(i).read_unaligned() }).collect())
+	}
+}
+
 /// Validate result pointer and return mutable reference
 #[inline]
 pub unsafe fn validate_result_ptr<T>(ptr: *mut T, context: &str) -> Option<&mut T> {
@@ -10,7 +10,7 @@ impl FfiResult {
 	pub fn success() -> Self { FfiResult { status: 0, error_msg: std::ptr::null_mut() } }
 
 	pub fn error(message: &str) -> Self {
-		FfiResult { status: 1, error_msg: CString::new(message).unwrap().into_raw() }
+		FfiResult { status: 1, error_msg: error_c_string(message) }
 	}
 }
 
@@ -27,14 +27,15 @@ impl WindowCreateResult {
 	}
 
 	pub fn error(message: &str) -> Self {
-		WindowCreateResult {
-			status:    1,
-			window_id: 0,
-			error_msg: CString::new(message).unwrap().into_raw(),
-		}
+		WindowCreateResult { status: 1, window_id: 0, error_msg: error_c_string(message) }
 	}
 }
 
+/// Error messages may echo untrusted input; interior NULs must not panic
+fn error_c_string(message: &str) -> *mut c_char {
+	CString::new(message.replace('\0', " ")).unwrap_or_default().into_raw()
+}
+
 #[repr(C)]
 pub struct ElementData {
 	pub global_id:    u64,
@@ -45,27 +46,80 @@ pub struct ElementData {
 	pub children_ptr: *const u64,
 }
 
+/// Scheduling priority of the GPUI thread
+#[derive(Clone, Copy, Debug, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThreadPriority {
+	Low,
+	Normal,
+	High,
+}
+
+/// Options of `gpui_init_with_options`, read once when the GPUI thread starts
+#[derive(Debug, Default, serde::Deserialize, Clone)]
+pub struct InitOptions {
+	#[serde(rename = "threadPriority")]
+	pub thread_priority: Option<ThreadPriority>,
+	/// CPU indices the GPUI thread may run on
+	#[serde(rename = "cpuAffinity")]
+	pub cpu_affinity:    Option<Vec<usize>>,
+}
+
 #[derive(Debug, serde::Deserialize, Clone)]
 pub struct WindowOptions {
-	pub width:      f32,
-	pub height:     f32,
-	pub title:      Option<String>,
-	pub x:          Option<f32>,
-	pub y:          Option<f32>,
-	pub resizable:  Option<bool>,
-	pub fullscreen: Option<bool>,
+	pub width:                  f32,
+	pub height:                 f32,
+	pub title:                  Option<String>,
+	pub x:                      Option<f32>,
+	pub y:                      Option<f32>,
+	pub resizable:              Option<bool>,
+	pub fullscreen:             Option<bool>,
+	/// Enable the built-in Ctrl+F find overlay
+	#[serde(rename = "findOverlay")]
+	pub find_overlay:           Option<bool>,
+	/// Apply commits from JS at most once per this many milliseconds
+	#[serde(rename = "frameBudgetMs")]
+	pub frame_budget_ms:        Option<f64>,
+	/// Application id on Linux: the Wayland app_id and X11 WM_CLASS, which
+	/// desktops match against the `.desktop` file name to group windows
+	#[serde(rename = "appId")]
+	pub app_id:                 Option<String>,
+	/// Draw content under a transparent titlebar, with the title hidden
+	#[serde(rename = "titlebarTransparent")]
+	pub titlebar_transparent:   Option<bool>,
+	/// Position of the macOS traffic lights, from the window's top left
+	#[serde(rename = "trafficLightPosition")]
+	pub traffic_light_position: Option<Position>,
+	/// Window background as 0xRRGGBBAA, painted from the first frame
+	#[serde(rename = "backgroundColor")]
+	pub background_color:       Option<u32>,
+	/// Element tree shown until the first commit (see element::splash)
+	pub splash:                 Option<serde_json::Value>,
+}
+
+#[derive(Debug, serde::Deserialize, Clone, Copy)]
+pub struct Position {
+	pub x: f32,
+	pub y: f32,
 }
 
 impl Default for WindowOptions {
 	fn default() -> Self {
 		WindowOptions {
-			width:      800.0,
-			height:     600.0,
-			title:      Some("React-GPUI".to_string()),
-			x:          None,
-			y:          None,
-			resizable:  None,
-			fullscreen: None,
+			width:                  800.0,
+			height:                 600.0,
+			title:                  Some("React-GPUI".to_string()),
+			x:                      None,
+			y:                      None,
+			resizable:              None,
+			fullscreen:             None,
+			find_overlay:           None,
+			frame_budget_ms:        None,
+			app_id:                 None,
+			titlebar_transparent:   None,
+			traffic_light_position: None,
+			background_color:       None,
+			splash:                 None,
 		}
 	}
 }
@@ -86,8 +140,15 @@ impl From<WindowOptions> for gpui::WindowOptions {
 
 		gpui::WindowOptions {
 			window_bounds: Some(window_bounds_type),
-			titlebar: Some(gpui::TitlebarOptions { title: Some(title.into()), ..Default::default() }),
+			titlebar: Some(gpui::TitlebarOptions {
+				title:                  Some(title.into()),
+				appears_transparent:    opts.titlebar_transparent.unwrap_or(false),
+				traffic_light_position: opts
+					.traffic_light_position
+					.map(|p| gpui::Point { x: gpui::px(p.x), y: gpui::px(p.y) }),
+			}),
 			is_resizable: opts.resizable.unwrap_or(true),
+			app_id: opts.app_id,
 			..Default::default()
 		}
 	}
@@ -0,0 +1,30 @@
+//! Entry points for the cargo-fuzz targets in `fuzz/`
+//!
+//! Only compiled with the `fuzzing` feature. The FFI functions themselves are
+//! fuzzed directly through the rlib; these helpers expose the pure parsing
+//! stages that sit behind them so they can be exercised without a window.
+
+use crate::{element::ElementStyle, window};
+
+/// Parse arbitrary bytes as an element batch
+pub fn parse_element_batch(data: &[u8]) {
+	let Ok(value) = serde_json::from_slice::<serde_json::Value>(data) else {
+		return;
+	};
+	if let Ok(elements) = window::parse_element_batch(&value) {
+		for (element, _) in elements {
+			let _ = element.style.build_gpui_style(None);
+		}
+	}
+}
+
+/// Parse arbitrary bytes as a style object and resolve it against itself
+pub fn parse_style(data: &[u8]) {
+	let Ok(value) = serde_json::from_slice::<serde_json::Value>(data) else {
+		return;
+	};
+	let style = ElementStyle::from_json(&value);
+	let mut inherited = ElementStyle::from_json(&value);
+	inherited.inherit_from(&style);
+	let _ = inherited.build_gpui_style(None);
+}
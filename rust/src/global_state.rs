@@ -2,7 +2,7 @@ use std::{collections::HashMap, sync::{Arc, RwLock, atomic::{AtomicBool, Orderin
 
 use gpui::{AnyWindowHandle, App, Global, WindowHandle};
 
-use crate::{renderer::RootView, window::Window};
+use crate::{renderer::RootView, sync::RwLockExt, window::Window};
 
 pub struct GlobalState {
 	gpui_initialized:    AtomicBool,
@@ -36,23 +36,26 @@ impl GlobalState {
 	/// Add a window with its GPUI handle
 	pub fn add_window(&self, handle: AnyWindowHandle) {
 		let window_id = handle.window_id().as_u64();
-		let mut windows = self.windows.write().expect("Failed to acquire windows write lock");
+		let mut windows = self.windows.write_recover();
 		windows.insert(window_id, Arc::new(Window::new(handle)));
 	}
 
 	/// Get a window by ID, returns None if not found
 	pub fn get_window(&self, window_id: u64) -> Option<Arc<Window>> {
-		let windows = self.windows.read().expect("Failed to acquire windows read lock");
+		let windows = self.windows.read_recover();
 		windows.get(&window_id).cloned()
 	}
 
 	pub fn get_window_ref(&self, window_id: u64) -> Option<Arc<Window>> {
-		let windows = self.windows.read().expect("Failed to acquire windows read lock");
+		let windows = self.windows.read_recover();
 		windows.get(&window_id).cloned()
 	}
 
+	/// IDs of every open window
+	pub fn window_ids(&self) -> Vec<u64> { self.windows.read_recover().keys().copied().collect() }
+
 	pub fn remove_window(&self, window_id: u64) {
-		let mut windows = self.windows.write().expect("Failed to acquire windows write lock");
+		let mut windows = self.windows.write_recover();
 		windows.remove(&window_id);
 	}
 }
@@ -1,10 +1,9 @@
-use std::sync::{Arc, OnceLock, atomic::{AtomicBool, Ordering}};
+use std::sync::{Arc, RwLock, atomic::{AtomicBool, Ordering}, mpsc};
 
 use gpui::{App, AppContext, AsyncApp};
-use serde_json::Value;
 use tokio::sync::oneshot;
 
-use crate::{global_state::GLOBAL_STATE, renderer::RootView};
+use crate::{global_state::GLOBAL_STATE, renderer::{self, RootView}, sync::RwLockExt, watchdog::{self, Stage}, window::EventMessage};
 
 #[derive(Debug)]
 pub enum HostCommand {
@@ -15,16 +14,21 @@ pub enum HostCommand {
 	TriggerRender {
 		window_id: u64,
 	},
-	UpdateElement {
-		window_id:    u64,
-		global_id:    u64,
-		element_type: String,
-		text:         Option<String>,
-		children:     Vec<u64>,
-	},
-	BatchUpdateElements {
+	/// Apply a window's staged commits on a background thread, once its frame
+	/// budget allows, then render the published tree
+	ApplyCommits {
 		window_id: u64,
-		elements:  Value,
+	},
+	/// Load a font file into the text system, answering with the error if it
+	/// couldn't be read or parsed
+	RegisterFont {
+		path:        String,
+		response_tx: oneshot::Sender<Result<(), String>>,
+	},
+	/// Close every window and stop the bus, answering with the events that
+	/// were still queued (including a final "shutdown" event per window)
+	Shutdown {
+		response_tx: mpsc::SyncSender<Vec<EventMessage>>,
 	},
 }
 
@@ -65,7 +69,17 @@ impl CommandSender {
 		if self.inner.is_shutting_down() {
 			return Err(CommandError::ShuttingDown);
 		}
-		self.inner.sender.send_blocking(command).map_err(|_| CommandError::ReceiverGone)
+		// Counted before sending, so the GPUI thread can't handle it first
+		let is_host = matches!(command, Command::Host(_));
+		if is_host {
+			watchdog::command_queued();
+		}
+		self.inner.sender.send_blocking(command).map_err(|_| {
+			if is_host {
+				watchdog::command_handled();
+			}
+			CommandError::ReceiverGone
+		})
 	}
 
 	pub fn send_host(&self, command: HostCommand) -> Result<(), CommandError> {
@@ -73,25 +87,37 @@ impl CommandSender {
 	}
 }
 
-static BUS: OnceLock<Arc<Inner>> = OnceLock::new();
+lazy_static::lazy_static! {
+	/// Reset to None on shutdown so a later gpui_init can start a fresh bus
+	static ref BUS: RwLock<Option<Arc<Inner>>> = RwLock::new(None);
+}
 
 pub fn init(cx: &mut App) {
-	if BUS.get().is_some() {
+	let mut bus = BUS.write_recover();
+	if bus.is_some() {
 		return;
 	}
 
 	let (sender, receiver) = async_channel::unbounded();
 	let inner =
 		Arc::new(Inner { sender, shutdown: AtomicBool::new(false), ready: AtomicBool::new(false) });
+	*bus = Some(inner.clone());
+	drop(bus);
 
-	if BUS.set(inner.clone()).is_ok() {
-		let inner_for_spawn = inner.clone();
-		cx.spawn(async move |cx: &mut AsyncApp| {
-			run_loop(inner_for_spawn, receiver, cx).await;
-		})
-		.detach();
+	let inner_for_spawn = inner.clone();
+	cx.spawn(async move |cx: &mut AsyncApp| {
+		run_loop(inner_for_spawn, receiver, cx).await;
+	})
+	.detach();
 
-		inner.ready.store(true, Ordering::SeqCst);
+	inner.ready.store(true, Ordering::SeqCst);
+}
+
+/// Refuse further commands and let the run loop exit
+fn close() {
+	if let Some(inner) = BUS.write_recover().take() {
+		inner.shutdown.store(true, Ordering::SeqCst);
+		let _ = inner.sender.try_send(Command::Shutdown);
 	}
 }
 
@@ -106,7 +132,13 @@ async fn run_loop(
 		}
 
 		let result = match command {
-			Command::Host(cmd) => cx.update(|app| handle_on_app_thread(cmd, app)),
+			Command::Host(cmd) => {
+				watchdog::command_handled();
+				watchdog::enter(Stage::Command);
+				let result = cx.update(|app| handle_on_app_thread(cmd, app));
+				watchdog::enter(Stage::Idle);
+				result
+			}
 			Command::Shutdown => {
 				inner.shutdown.store(true, Ordering::SeqCst);
 				break;
@@ -118,7 +150,11 @@ async fn run_loop(
 		}
 	}
 
-	while receiver.try_recv().is_ok() {}
+	while let Ok(command) = receiver.try_recv() {
+		if let Command::Host(_) = command {
+			watchdog::command_handled();
+		}
+	}
 }
 
 pub fn handle_on_app_thread(command: HostCommand, app: &mut App) {
@@ -129,6 +165,14 @@ pub fn handle_on_app_thread(command: HostCommand, app: &mut App) {
 			let title = options.title.as_deref().unwrap_or("React-GPUI");
 			let w = options.width;
 			let h = options.height;
+			let find_overlay = options.find_overlay == Some(true);
+			let titlebar_transparent = options.titlebar_transparent == Some(true);
+			let frame_budget = options
+				.frame_budget_ms
+				.filter(|ms| *ms > 0.0)
+				.and_then(|ms| std::time::Duration::try_from_secs_f64(ms / 1000.0).ok());
+			let background = options.background_color.map(|color| gpui::rgba(color).into());
+			let splash = options.splash.as_ref().and_then(crate::element::splash::parse);
 			log::debug!("Creating window: {} ({}x{})", title, w, h);
 			let window_options: gpui::WindowOptions = options.into();
 			app
@@ -139,7 +183,16 @@ pub fn handle_on_app_thread(command: HostCommand, app: &mut App) {
 					log::debug!("Created window with id: {}", window_id);
 					let _ = response_tx.send(window_id);
 					GLOBAL_STATE.add_window(window_handle);
-					cx.new(|_| RootView::new(state, window_id, w, h))
+					if find_overlay {
+						crate::element::find_overlay::enable(window_id);
+					}
+					crate::element::safe_area::set_titlebar_transparent(window_id, titlebar_transparent);
+					if let Some(budget) = frame_budget
+						&& let Some(window) = GLOBAL_STATE.get_window(window_id)
+					{
+						window.state().set_frame_budget(budget);
+					}
+					cx.new(|_| RootView::new(state, window_id, w, h, background, splash))
 				})
 				.unwrap();
 		}
@@ -150,30 +203,73 @@ pub fn handle_on_app_thread(command: HostCommand, app: &mut App) {
 			};
 			window.refresh(app);
 		}
-		HostCommand::UpdateElement { window_id, global_id, element_type, text, children } => {
+		HostCommand::ApplyCommits { window_id } => {
 			let Some(window) = GLOBAL_STATE.get_window(window_id) else {
-				log::warn!("UpdateElement: window {} not found", window_id);
+				log::warn!("ApplyCommits: window {} not found", window_id);
 				return;
 			};
-			window.render_element(global_id, element_type, text, &children);
-			window.refresh(app)
+			app
+				.spawn(async move |cx| {
+					// Commits staged while waiting are merged into this apply
+					while let Some(wait) = window.state().commit_wait() {
+						cx.background_executor().timer(wait).await;
+					}
+					let state = window.state().clone();
+					cx.background_executor().spawn(async move { state.apply_staged() }).await;
+					// Swapped in at the frame boundary, never mid-frame
+					let _ = cx.update(|cx| window.publish_at_next_frame(cx));
+				})
+				.detach();
 		}
-		HostCommand::BatchUpdateElements { window_id, elements } => {
-			let Some(window) = GLOBAL_STATE.get_window(window_id) else {
-				log::warn!("BatchUpdateElements: window {} not found", window_id);
-				return;
-			};
-			window.batch_update_elements(&elements);
-			window.refresh(app)
+		HostCommand::RegisterFont { path, response_tx } => {
+			let registered = std::fs::read(&path)
+				.map_err(|e| format!("Failed to read font {}: {}", path, e))
+				.and_then(|data| app.text_system().add_fonts(vec![data.into()]).map_err(|e| e.to_string()));
+			if registered.is_ok() {
+				// Icons cached before may come from this font now
+				crate::element::icon::forget_glyphs();
+				for window_id in GLOBAL_STATE.window_ids() {
+					if let Some(window) = GLOBAL_STATE.get_window(window_id) {
+						window.refresh(app);
+					}
+				}
+			}
+			let _ = response_tx.send(registered);
+		}
+		HostCommand::Shutdown { response_tx } => {
+			let mut events = Vec::new();
+			for window_id in GLOBAL_STATE.window_ids() {
+				let Some(window) = GLOBAL_STATE.get_window(window_id) else {
+					continue;
+				};
+				renderer::dispatch_window_event(window_id, "shutdown", serde_json::json!({}));
+				events.extend(window.state().drain_events());
+				if let Err(err) = app.update_window(window.handle(), |_, window, _| window.remove_window())
+				{
+					log::warn!("Shutdown: failed to close window {}: {}", window_id, err);
+				}
+				GLOBAL_STATE.remove_window(window_id);
+				crate::element::forget_window(window_id);
+				crate::latency::forget_window(window_id);
+			}
+			close();
+			let _ = response_tx.send(events);
+			app.quit();
 		}
 	}
 }
 
 pub fn sender() -> Result<CommandSender, CommandError> {
-	BUS.get().map(|inner| CommandSender { inner: inner.clone() }).ok_or(CommandError::NotInitialized)
+	BUS
+		.read_recover()
+		.as_ref()
+		.map(|inner| CommandSender { inner: inner.clone() })
+		.ok_or(CommandError::NotInitialized)
 }
 
-pub fn is_bus_ready() -> bool { BUS.get().map(|inner| inner.is_ready()).unwrap_or(false) }
+pub fn is_bus_ready() -> bool {
+	BUS.read_recover().as_ref().map(|inner| inner.is_ready()).unwrap_or(false)
+}
 
 pub fn send_host_command(command: HostCommand) {
 	for _ in 0..100 {
@@ -0,0 +1,93 @@
+//! DOM `key` and `code` values for keyboard events
+//!
+//! gpui names keys in lowercase ("enter", "left", "a"); handlers written
+//! against the DOM expect `key` to be what was typed or the key's DOM name
+//! ("Enter", "ArrowLeft", "A" with shift) and `code` to name the physical key
+//! ("KeyA", "Digit1", "Minus"). gpui reports keypad digits as plain digits
+//! and doesn't deliver media keys, so neither gets a distinct value.
+
+use gpui::Keystroke;
+
+/// DOM `key` of a keystroke
+pub fn key(keystroke: &Keystroke) -> String {
+	if let Some(name) = named_key(&keystroke.key) {
+		return name.to_string();
+	}
+	if let Some(key_char) = keystroke.key_char.as_deref().filter(|c| !c.is_empty()) {
+		return key_char.to_string();
+	}
+	if keystroke.modifiers.shift && keystroke.key.len() == 1 {
+		return keystroke.key.to_uppercase();
+	}
+	keystroke.key.clone()
+}
+
+/// DOM `code` of a keystroke, from gpui's unshifted key name
+pub fn code(keystroke: &Keystroke) -> String {
+	let key = keystroke.key.as_str();
+	if let Some(name) = named_key(key) {
+		return match name {
+			" " => "Space".to_string(),
+			name => name.to_string(),
+		};
+	}
+	let mut chars = key.chars();
+	if let (Some(c), None) = (chars.next(), chars.next()) {
+		if c.is_ascii_alphabetic() {
+			return format!("Key{}", c.to_ascii_uppercase());
+		}
+		if c.is_ascii_digit() {
+			return format!("Digit{}", c);
+		}
+		let punctuation = match c {
+			'-' => "Minus",
+			'=' => "Equal",
+			'[' => "BracketLeft",
+			']' => "BracketRight",
+			'\\' => "Backslash",
+			';' => "Semicolon",
+			'\'' => "Quote",
+			',' => "Comma",
+			'.' => "Period",
+			'/' => "Slash",
+			'`' => "Backquote",
+			_ => return key.to_string(),
+		};
+		return punctuation.to_string();
+	}
+	key.to_string()
+}
+
+/// DOM name of a key that doesn't type a character, `" "` for space
+fn named_key(key: &str) -> Option<&'static str> {
+	let name = match key {
+		"enter" => "Enter",
+		"escape" => "Escape",
+		"backspace" => "Backspace",
+		"delete" => "Delete",
+		"tab" => "Tab",
+		"space" => " ",
+		"insert" => "Insert",
+		"up" => "ArrowUp",
+		"down" => "ArrowDown",
+		"left" => "ArrowLeft",
+		"right" => "ArrowRight",
+		"home" => "Home",
+		"end" => "End",
+		"pageup" => "PageUp",
+		"pagedown" => "PageDown",
+		"menu" => "ContextMenu",
+		_ => return function_key(key),
+	};
+	Some(name)
+}
+
+/// "F1" through "F24"
+fn function_key(key: &str) -> Option<&'static str> {
+	const NAMES: [&str; 24] = [
+		"F1", "F2", "F3", "F4", "F5", "F6", "F7", "F8", "F9", "F10", "F11", "F12", "F13", "F14", "F15",
+		"F16", "F17", "F18", "F19", "F20", "F21", "F22", "F23", "F24",
+	];
+	let number: usize = key.strip_prefix('f')?.parse().ok()?;
+	NAMES.get(number.checked_sub(1)?).copied()
+}
@@ -0,0 +1,196 @@
+//! Input latency tracing across the bridge
+//!
+//! With tracing on (`gpui_set_latency_tracing`), every key press and mouse
+//! button event gets a `traceId` in its payload and is timed through four
+//! points on the monotonic clock: queued by Rust, polled by JS, the first
+//! commit applied after that poll, and the frame that commit painted.
+//! `gpui_get_latency_stats` reports percentiles of each stage over the most
+//! recent events of a window, along with the slowest of them by `traceId`
+//! so it can be matched to the event JS received. Events that cause no
+//! commit are attributed to the next one, so idle periods can inflate the
+//! commit stage.
+
+use std::{collections::{HashMap, VecDeque}, sync::{Mutex, atomic::{AtomicBool, AtomicU64, Ordering}}};
+
+use lazy_static::lazy_static;
+use serde_json::{Value, json};
+
+use crate::{clock, event_types::types, sync::MutexExt};
+
+/// Completed traces kept per window
+const MAX_SAMPLES: usize = 1024;
+/// Traces waiting on a stage, per window; older ones are dropped
+const MAX_PENDING: usize = 256;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static NEXT_TRACE_ID: AtomicU64 = AtomicU64::new(1);
+
+lazy_static! {
+	static ref WINDOWS: Mutex<HashMap<u64, WindowTraces>> = Mutex::new(HashMap::new());
+}
+
+/// Drop all state of a closed window
+pub fn forget_window(window_id: u64) { WINDOWS.lock_recover().remove(&window_id); }
+
+/// Timestamps of one event, in microseconds
+#[derive(Clone, Copy)]
+struct Trace {
+	/// The `traceId` in the event's payload
+	id:      u64,
+	queued:  u64,
+	polled:  u64,
+	applied: u64,
+}
+
+/// Stage durations of a completed trace, in microseconds
+#[derive(Clone, Copy)]
+struct Sample {
+	trace_id:        u64,
+	queue_to_poll:   u64,
+	poll_to_commit:  u64,
+	commit_to_paint: u64,
+}
+
+impl Sample {
+	fn total(&self) -> u64 { self.queue_to_poll + self.poll_to_commit + self.commit_to_paint }
+}
+
+#[derive(Default)]
+struct WindowTraces {
+	queued:  VecDeque<Trace>,
+	polled:  VecDeque<Trace>,
+	applied: VecDeque<Trace>,
+	samples: VecDeque<Sample>,
+}
+
+pub fn set_enabled(enabled: bool) {
+	ENABLED.store(enabled, Ordering::Relaxed);
+	WINDOWS.lock_recover().clear();
+}
+
+/// Start a trace for an event being queued, returning its id if traced
+pub fn trace(window_id: u64, event_type: &str) -> Option<u64> {
+	if !ENABLED.load(Ordering::Relaxed) || !is_traced(event_type) {
+		return None;
+	}
+	let id = NEXT_TRACE_ID.fetch_add(1, Ordering::Relaxed);
+	let trace = Trace { id, queued: clock::now_us(), polled: 0, applied: 0 };
+	push_bounded(
+		&mut WINDOWS.lock_recover().entry(window_id).or_default().queued,
+		trace,
+		MAX_PENDING,
+	);
+	Some(id)
+}
+
+/// JS drained the window's event queue
+pub fn polled(window_id: u64) {
+	advance(
+		window_id,
+		|traces| (&mut traces.queued, &mut traces.polled),
+		|trace, now| trace.polled = now,
+	);
+}
+
+/// A commit was applied to the window's element tree
+pub fn applied(window_id: u64) {
+	advance(
+		window_id,
+		|traces| (&mut traces.polled, &mut traces.applied),
+		|trace, now| trace.applied = now,
+	);
+}
+
+/// The window painted a frame
+pub fn painted(window_id: u64) {
+	if !ENABLED.load(Ordering::Relaxed) {
+		return;
+	}
+	let now = clock::now_us();
+	let mut windows = WINDOWS.lock_recover();
+	let Some(traces) = windows.get_mut(&window_id) else {
+		return;
+	};
+	while let Some(trace) = traces.applied.pop_front() {
+		let sample = Sample {
+			trace_id:        trace.id,
+			queue_to_poll:   trace.polled.saturating_sub(trace.queued),
+			poll_to_commit:  trace.applied.saturating_sub(trace.polled),
+			commit_to_paint: now.saturating_sub(trace.applied),
+		};
+		push_bounded(&mut traces.samples, sample, MAX_SAMPLES);
+	}
+}
+
+/// Latency percentiles of a window's recent events
+pub fn stats(window_id: u64) -> Value {
+	let windows = WINDOWS.lock_recover();
+	let traces = windows.get(&window_id);
+	let samples: Vec<Sample> =
+		traces.map(|traces| traces.samples.iter().copied().collect()).unwrap_or_default();
+	let pending =
+		traces.map_or(0, |traces| traces.queued.len() + traces.polled.len() + traces.applied.len());
+
+	json!({
+		"windowId": window_id,
+		"enabled": ENABLED.load(Ordering::Relaxed),
+		"samples": samples.len(),
+		"pending": pending,
+		"queueToPollUs": percentiles(samples.iter().map(|s| s.queue_to_poll).collect()),
+		"pollToCommitUs": percentiles(samples.iter().map(|s| s.poll_to_commit).collect()),
+		"commitToPaintUs": percentiles(samples.iter().map(|s| s.commit_to_paint).collect()),
+		"totalUs": percentiles(samples.iter().map(Sample::total).collect()),
+		"slowest": samples.iter().max_by_key(|s| s.total()).map(|s| json!({
+			"traceId": s.trace_id,
+			"queueToPollUs": s.queue_to_poll,
+			"pollToCommitUs": s.poll_to_commit,
+			"commitToPaintUs": s.commit_to_paint,
+			"totalUs": s.total(),
+		})),
+	})
+}
+
+/// Key presses and mouse buttons; moves and hovers would swamp the samples
+fn is_traced(event_type: &str) -> bool {
+	matches!(
+		event_type,
+		types::KEYDOWN | types::KEYUP | types::MOUSEDOWN | types::MOUSEUP | types::CLICK
+	)
+}
+
+/// Move every trace of one stage to the next, stamping it
+fn advance(
+	window_id: u64,
+	stages: impl FnOnce(&mut WindowTraces) -> (&mut VecDeque<Trace>, &mut VecDeque<Trace>),
+	stamp: impl Fn(&mut Trace, u64),
+) {
+	if !ENABLED.load(Ordering::Relaxed) {
+		return;
+	}
+	let now = clock::now_us();
+	let mut windows = WINDOWS.lock_recover();
+	let Some(traces) = windows.get_mut(&window_id) else {
+		return;
+	};
+	let (from, to) = stages(traces);
+	for mut trace in from.drain(..) {
+		stamp(&mut trace, now);
+		push_bounded(to, trace, MAX_PENDING);
+	}
+}
+
+fn push_bounded<T>(queue: &mut VecDeque<T>, item: T, limit: usize) {
+	if queue.len() >= limit {
+		queue.pop_front();
+	}
+	queue.push_back(item);
+}
+
+fn percentiles(mut values: Vec<u64>) -> Value {
+	if values.is_empty() {
+		return Value::Null;
+	}
+	values.sort_unstable();
+	let at = |p: f64| values[((values.len() - 1) as f64 * p).round() as usize];
+	json!({ "p50": at(0.5), "p90": at(0.9), "p99": at(0.99), "max": values[values.len() - 1] })
+}
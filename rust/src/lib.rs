@@ -1,23 +1,50 @@
 extern crate core;
 
+mod clock;
 mod element;
 mod event_types;
 mod ffi_helpers;
 mod ffi_types;
+#[cfg(feature = "fuzzing")]
+pub mod fuzzing;
 mod global_state;
 mod host_command;
+mod key_names;
+mod latency;
 mod logging;
+mod privacy;
 mod renderer;
+mod resource_stats;
+mod shortcut;
+mod sync;
+mod thread_tuning;
+mod watchdog;
 mod window;
 
-use std::ffi::{c_char, CStr, CString};
+use std::{ffi::{c_char, CStr, CString}, sync::mpsc::{self, RecvTimeoutError}, time::Instant};
 
 use tokio::sync::oneshot;
 
-use crate::{ffi_helpers::{ptr_to_u64, read_c_string, read_opt_c_string, validate_result_ptr}, ffi_types::{FfiResult, WindowCreateResult, WindowOptions}, global_state::GLOBAL_STATE, host_command::{is_bus_ready, send_host_command, HostCommand}, renderer::start_gpui_thread};
+pub use crate::{element::shader_view::{ShaderPaintContext, ShaderPainter, register_shader_view, unregister_shader_view}, ffi_types::{FfiResult, WindowCreateResult}};
 
+use crate::{ffi_helpers::{ptr_to_u64, read_c_string, read_opt_c_string, read_u64_array, validate_result_ptr}, ffi_types::{InitOptions, WindowOptions}, global_state::GLOBAL_STATE, host_command::{is_bus_ready, send_host_command, HostCommand}, renderer::start_gpui_thread, sync::MutexExt};
+
+#[unsafe(no_mangle)]
+pub extern "C" fn gpui_init(result: *mut FfiResult) { init(InitOptions::default(), result) }
+
+/// Like gpui_init, with a JSON object of InitOptions for the GPUI thread
+/// Options are ignored if GPUI is already initialized
 #[unsafe(no_mangle)]
-pub extern "C" fn gpui_init(result: *mut FfiResult) {
+pub extern "C" fn gpui_init_with_options(options_ptr: *const c_char, result: *mut FfiResult) {
+	let options_json = unsafe { read_c_string(options_ptr, "{}") };
+	let options: InitOptions = serde_json::from_str(&options_json).unwrap_or_else(|e| {
+		log::error!("Failed to parse init options JSON: {}", e);
+		InitOptions::default()
+	});
+	init(options, result)
+}
+
+fn init(options: InitOptions, result: *mut FfiResult) {
 	unsafe {
 		logging::init_logging();
 		log::info!("gpui_init: checking initialization...");
@@ -29,7 +56,7 @@ pub extern "C" fn gpui_init(result: *mut FfiResult) {
 		}
 
 		log::info!("gpui_init: starting GPUI thread...");
-		start_gpui_thread();
+		start_gpui_thread(options);
 		GLOBAL_STATE.set_initialized(true);
 
 		if GLOBAL_STATE.is_thread_started() {
@@ -42,6 +69,53 @@ pub extern "C" fn gpui_init(result: *mut FfiResult) {
 	}
 }
 
+/// How long gpui_shutdown waits for the GPUI thread to answer and exit
+const SHUTDOWN_JOIN_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Close every window, stop the GPUI thread and reset so gpui_init can start
+/// it again (e.g. between tests)
+/// Returns a JSON array of the events that were still queued, ending with a
+/// "shutdown" event per window, caller must free with gpui_free_event_string
+/// Returns null if GPUI isn't initialized
+#[unsafe(no_mangle)]
+pub extern "C" fn gpui_shutdown() -> *mut c_char {
+	if !GLOBAL_STATE.is_initialized() {
+		return std::ptr::null_mut();
+	}
+	log::info!("gpui_shutdown: closing windows...");
+
+	let deadline = Instant::now() + SHUTDOWN_JOIN_TIMEOUT;
+	let (response_tx, response_rx) = mpsc::sync_channel(1);
+	send_host_command(HostCommand::Shutdown { response_tx });
+	let events = match response_rx.recv_timeout(SHUTDOWN_JOIN_TIMEOUT) {
+		Ok(events) => events,
+		Err(RecvTimeoutError::Timeout) => {
+			log::warn!(
+				"gpui_shutdown: GPUI thread didn't answer in {:?}, no final events",
+				SHUTDOWN_JOIN_TIMEOUT
+			);
+			Vec::new()
+		}
+		Err(RecvTimeoutError::Disconnected) => {
+			log::warn!("gpui_shutdown: command bus gone, no final events");
+			Vec::new()
+		}
+	};
+
+	renderer::join_gpui_thread(deadline.saturating_duration_since(Instant::now()));
+	GLOBAL_STATE.set_initialized(false);
+	GLOBAL_STATE.set_thread_started(false);
+	log::info!("gpui_shutdown: done");
+
+	let payloads: Vec<serde_json::Value> =
+		events.iter().filter_map(|e| serde_json::from_str(&e.payload).ok()).collect();
+	let json_str = serde_json::to_string(&payloads).unwrap_or_else(|_| "[]".to_string());
+	match CString::new(json_str) {
+		Ok(c_string) => c_string.into_raw(),
+		Err(_) => std::ptr::null_mut(),
+	}
+}
+
 #[unsafe(no_mangle)]
 pub extern "C" fn gpui_create_window(options_ptr: *const c_char, result: *mut WindowCreateResult) {
 	let options_json = unsafe { read_c_string(options_ptr, "{}") };
@@ -101,11 +175,13 @@ pub extern "C" fn gpui_render_frame(
 		let element_type = read_c_string(type_ptr, "unknown");
 		let text = read_opt_c_string(text_ptr);
 
-		let children: Vec<u64> = if children_ptr.is_null() || child_count == 0 {
-			Vec::new()
-		} else {
-			let slice = std::slice::from_raw_parts(children_ptr, child_count);
-			slice.to_vec()
+		let children = match read_u64_array(children_ptr, child_count) {
+			Ok(children) => children,
+			Err(e) => {
+				log::error!("gpui_render_frame: invalid children: {}", e);
+				*result_ptr = FfiResult::error(&format!("Invalid children: {}", e));
+				return;
+			}
 		};
 
 		log::debug!(
@@ -113,18 +189,22 @@ pub extern "C" fn gpui_render_frame(
 			window_id,
 			global_id,
 			element_type,
-			text,
+			text.as_deref().map(|text| privacy::log_text(window_id, global_id, text)),
 			child_count,
 			children
 		);
 
-		send_host_command(HostCommand::UpdateElement {
-			window_id,
-			global_id,
-			element_type,
-			text,
-			children,
-		});
+		// The commit is staged and applied off the GPUI thread, together with
+		// any other commit that arrives before the apply runs
+		let Some(window) = GLOBAL_STATE.get_window(window_id) else {
+			log::warn!("gpui_render_frame: window {} not found", window_id);
+			*result_ptr = FfiResult::error(&format!("Window {} not found", window_id));
+			return;
+		};
+		window.render_element(global_id, element_type, text, &children);
+		if window.state().schedule_commit() {
+			send_host_command(HostCommand::ApplyCommits { window_id });
+		}
 
 		let result_buf = std::slice::from_raw_parts_mut(result_ptr as *mut u8, 8);
 		result_buf[0] = 0;
@@ -149,8 +229,16 @@ pub extern "C" fn gpui_batch_update_elements(
 ) {
 	log::debug!("gpui_batch_update_elements: called");
 	unsafe {
+		let Some(result) = validate_result_ptr(result, "gpui_batch_update_elements") else {
+			return;
+		};
+		if elements_json_ptr.is_null() {
+			*result = FfiResult::error("Elements JSON pointer is null");
+			return;
+		}
+
 		let window_id = ptr_to_u64(window_id_ptr);
-		let _count = std::ptr::read_volatile(count_ptr) as u64;
+		let _count = ptr_to_u64(count_ptr);
 
 		// Safe UTF-8 conversion with error handling
 		let elements_json_str = match CStr::from_ptr(elements_json_ptr).to_str() {
@@ -172,15 +260,152 @@ pub extern "C" fn gpui_batch_update_elements(
 			}
 		};
 
-		let _ = GLOBAL_STATE.get_window(window_id);
+		let Some(window) = GLOBAL_STATE.get_window(window_id) else {
+			log::warn!("gpui_batch_update_elements: window {} not found", window_id);
+			*result = FfiResult::error(&format!("Window {} not found", window_id));
+			return;
+		};
 
-		send_host_command(HostCommand::BatchUpdateElements { window_id, elements: elements_value });
+		// Parse styles and precompute GPUI styles here, off the GPUI thread; the
+		// parsed batch is staged for the next apply
+		let prepare_start = std::time::Instant::now();
+		if let Err(e) = window.batch_update_elements(&elements_value) {
+			log::error!("gpui_batch_update_elements: rejected batch: {}", e);
+			*result = FfiResult::error(&format!("Invalid element batch: {}", e));
+			return;
+		}
+		log::trace!("gpui_batch_update_elements: batch prepared in {:?}", prepare_start.elapsed());
+
+		if window.state().schedule_commit() {
+			send_host_command(HostCommand::ApplyCommits { window_id });
+		}
 
 		*result = FfiResult::success();
 		log::debug!("gpui_batch_update_elements: completed successfully");
 	}
 }
 
+/// Set individual style props of existing elements
+/// `patches_json` is `[{id, prop, value}]`; a null value removes the prop. Only
+/// the patched elements' styles change, without re-sending or re-parsing them
+#[unsafe(no_mangle)]
+pub extern "C" fn gpui_patch_styles(
+	window_id_ptr: *const u8,
+	patches_json_ptr: *const c_char,
+	result: *mut FfiResult,
+) {
+	unsafe {
+		let Some(result) = validate_result_ptr(result, "gpui_patch_styles") else {
+			return;
+		};
+		let patches_json = read_c_string(patches_json_ptr, "[]");
+		let patches: serde_json::Value = match serde_json::from_str(&patches_json) {
+			Ok(v) => v,
+			Err(e) => {
+				*result = FfiResult::error(&format!("Failed to parse patches JSON: {}", e));
+				return;
+			}
+		};
+
+		let window_id = ptr_to_u64(window_id_ptr);
+		let Some(window) = GLOBAL_STATE.get_window(window_id) else {
+			*result = FfiResult::error(&format!("Window {} not found", window_id));
+			return;
+		};
+		if let Err(e) = window.patch_styles(&patches) {
+			log::error!("gpui_patch_styles: rejected patches: {}", e);
+			*result = FfiResult::error(&format!("Invalid style patches: {}", e));
+			return;
+		}
+
+		if window.state().schedule_commit() {
+			send_host_command(HostCommand::ApplyCommits { window_id });
+		}
+		*result = FfiResult::success();
+	}
+}
+
+/// Edit the text of text elements (the value of inputs) in place
+/// `edits_json` is `[{id, text, start?, deleteCount?}]` with offsets in UTF-16
+/// code units; an edit without `start` appends, for streamed text
+#[unsafe(no_mangle)]
+pub extern "C" fn gpui_edit_text(
+	window_id_ptr: *const u8,
+	edits_json_ptr: *const c_char,
+	result: *mut FfiResult,
+) {
+	unsafe {
+		let Some(result) = validate_result_ptr(result, "gpui_edit_text") else {
+			return;
+		};
+		let edits_json = read_c_string(edits_json_ptr, "[]");
+		let edits: serde_json::Value = match serde_json::from_str(&edits_json) {
+			Ok(v) => v,
+			Err(e) => {
+				*result = FfiResult::error(&format!("Failed to parse edits JSON: {}", e));
+				return;
+			}
+		};
+
+		let window_id = ptr_to_u64(window_id_ptr);
+		let Some(window) = GLOBAL_STATE.get_window(window_id) else {
+			*result = FfiResult::error(&format!("Window {} not found", window_id));
+			return;
+		};
+		if let Err(e) = window.edit_text(&edits) {
+			log::error!("gpui_edit_text: rejected edits: {}", e);
+			*result = FfiResult::error(&format!("Invalid text edits: {}", e));
+			return;
+		}
+
+		if window.state().schedule_commit() {
+			send_host_command(HostCommand::ApplyCommits { window_id });
+		}
+		*result = FfiResult::success();
+	}
+}
+
+/// Release deleted elements so their allocations can be reused
+/// `ids_json` is a JSON array of element ids. JS recycles released ids for
+/// new elements of the same type, so releases must come before the batch
+/// that reuses them
+#[unsafe(no_mangle)]
+pub extern "C" fn gpui_release_elements(
+	window_id_ptr: *const u8,
+	ids_json_ptr: *const c_char,
+	result: *mut FfiResult,
+) {
+	unsafe {
+		let Some(result) = validate_result_ptr(result, "gpui_release_elements") else {
+			return;
+		};
+		let ids_json = read_c_string(ids_json_ptr, "[]");
+		let ids: serde_json::Value = match serde_json::from_str(&ids_json) {
+			Ok(v) => v,
+			Err(e) => {
+				*result = FfiResult::error(&format!("Failed to parse ids JSON: {}", e));
+				return;
+			}
+		};
+
+		let window_id = ptr_to_u64(window_id_ptr);
+		let Some(window) = GLOBAL_STATE.get_window(window_id) else {
+			*result = FfiResult::error(&format!("Window {} not found", window_id));
+			return;
+		};
+		if let Err(e) = window.release_elements(&ids) {
+			log::error!("gpui_release_elements: rejected ids: {}", e);
+			*result = FfiResult::error(&format!("Invalid element ids: {}", e));
+			return;
+		}
+
+		if window.state().schedule_commit() {
+			send_host_command(HostCommand::ApplyCommits { window_id });
+		}
+		*result = FfiResult::success();
+	}
+}
+
 /// Free the memory allocated for FfiResult's error message
 #[unsafe(no_mangle)]
 pub extern "C" fn gpui_free_result(result: FfiResult) {
@@ -226,11 +451,13 @@ pub extern "C" fn gpui_poll_events(window_id_ptr: *const u8) -> *mut c_char {
 			return std::ptr::null_mut();
 		};
 
+		window.state().push_due_events(window_id);
 		let events = window.state().drain_events();
 
 		if events.is_empty() {
 			return std::ptr::null_mut();
 		}
+		latency::polled(window_id);
 
 		// Convert events to JSON array
 		let payloads: Vec<serde_json::Value> =
@@ -262,8 +489,7 @@ pub extern "C" fn gpui_get_input_value(
 			return std::ptr::null_mut();
 		};
 
-		let element_map =
-			window.state().element_map.lock().expect("Failed to acquire element_map lock");
+		let element_map = window.state().element_map.lock_recover();
 		if let Some(element) = element_map.get(&element_id) {
 			// Get the value from style props
 			let value = element.style.value.clone();
@@ -277,3 +503,479 @@ pub extern "C" fn gpui_get_input_value(
 		std::ptr::null_mut()
 	}
 }
+
+/// Search a document element for `query` and highlight every match
+/// Returns a JSON string {"count": n, "current": i|null}, caller must free
+/// with gpui_free_event_string. Returns null if the element isn't found
+#[unsafe(no_mangle)]
+pub extern "C" fn gpui_find(
+	window_id_ptr: *const u8,
+	element_id_ptr: *const u8,
+	query_ptr: *const c_char,
+) -> *mut c_char {
+	unsafe {
+		let window_id = ptr_to_u64(window_id_ptr);
+		let element_id = ptr_to_u64(element_id_ptr);
+		if query_ptr.is_null() {
+			return std::ptr::null_mut();
+		}
+		let query = match CStr::from_ptr(query_ptr).to_str() {
+			Ok(s) => s,
+			Err(e) => {
+				log::error!("gpui_find: invalid UTF-8 in query: {}", e);
+				return std::ptr::null_mut();
+			}
+		};
+
+		let Some(found) = element::document::find(window_id, element_id, query) else {
+			return std::ptr::null_mut();
+		};
+		send_host_command(HostCommand::TriggerRender { window_id });
+		find_result_to_c_string(found)
+	}
+}
+
+/// Move to the next (or previous, if `backwards`) match of the last query
+/// Returns the same JSON as gpui_find
+#[unsafe(no_mangle)]
+pub extern "C" fn gpui_find_next(
+	window_id_ptr: *const u8,
+	element_id_ptr: *const u8,
+	backwards: bool,
+) -> *mut c_char {
+	unsafe {
+		let window_id = ptr_to_u64(window_id_ptr);
+		let element_id = ptr_to_u64(element_id_ptr);
+
+		let Some(found) = element::document::find_step(window_id, element_id, !backwards) else {
+			return std::ptr::null_mut();
+		};
+		send_host_command(HostCommand::TriggerRender { window_id });
+		find_result_to_c_string(found)
+	}
+}
+
+fn find_result_to_c_string(found: element::document::FindResult) -> *mut c_char {
+	let json_str = serde_json::json!({ "count": found.count, "current": found.current }).to_string();
+	match CString::new(json_str) {
+		Ok(c_string) => c_string.into_raw(),
+		Err(_) => std::ptr::null_mut(),
+	}
+}
+
+/// Append `count` values to a sparkline element's ring buffer
+/// `values_ptr` points to `count` little-endian f64s. The sparkline repaints on
+/// its own timer, so pushing doesn't commit or render
+#[unsafe(no_mangle)]
+pub extern "C" fn gpui_push_sparkline(
+	window_id_ptr: *const u8,
+	element_id_ptr: *const u8,
+	values_ptr: *const u8,
+	count: u32,
+) {
+	unsafe {
+		if values_ptr.is_null() {
+			return;
+		}
+		let window_id = ptr_to_u64(window_id_ptr);
+		let element_id = ptr_to_u64(element_id_ptr);
+		let bytes = std::slice::from_raw_parts(values_ptr, count as usize * 8);
+		let values: Vec<f64> = bytes
+			.chunks_exact(8)
+			.map(|chunk| f64::from_le_bytes(chunk.try_into().unwrap_or([0; 8])))
+			.collect();
+		element::sparkline::push(window_id, element_id, &values);
+	}
+}
+
+/// Serialize the resolved layout of a window's element tree
+/// Returns a JSON string with id, type, bounds and computed style of every
+/// element as of the last painted frame, caller must free with
+/// gpui_free_event_string. Returns null if the window has no tree yet
+#[unsafe(no_mangle)]
+pub extern "C" fn gpui_get_layout_snapshot(window_id_ptr: *const u8) -> *mut c_char {
+	unsafe {
+		let window_id = ptr_to_u64(window_id_ptr);
+
+		let Some(window) = GLOBAL_STATE.get_window(window_id) else {
+			return std::ptr::null_mut();
+		};
+		let root = window.state().element_tree.lock_recover().clone();
+		let Some(root) = root else {
+			return std::ptr::null_mut();
+		};
+
+		let json_str = element::layout::snapshot(window_id, &root).to_string();
+		match CString::new(json_str) {
+			Ok(c_string) => c_string.into_raw(),
+			Err(_) => std::ptr::null_mut(),
+		}
+	}
+}
+
+/// Report the focus state of a window
+/// Returns a JSON string with the focused element, the Tab order and the tab
+/// index and focus group (enclosing overlay) of every element with a tab
+/// index, caller must free with gpui_free_event_string. Returns null if the
+/// window has no tree yet
+#[unsafe(no_mangle)]
+pub extern "C" fn gpui_get_focus_state(window_id_ptr: *const u8) -> *mut c_char {
+	unsafe {
+		let window_id = ptr_to_u64(window_id_ptr);
+
+		let Some(window) = GLOBAL_STATE.get_window(window_id) else {
+			return std::ptr::null_mut();
+		};
+		let root = window.state().element_tree.lock_recover().clone();
+		let Some(root) = root else {
+			return std::ptr::null_mut();
+		};
+
+		let json_str = element::focus::snapshot(window_id, &root).to_string();
+		match CString::new(json_str) {
+			Ok(c_string) => c_string.into_raw(),
+			Err(_) => std::ptr::null_mut(),
+		}
+	}
+}
+
+/// Format a shortcut such as "cmd-shift-p" for display on this platform
+/// Returns "⇧⌘P" on macOS and "Ctrl+Shift+P" style strings elsewhere, caller
+/// must free with gpui_free_event_string. Returns null if it doesn't parse
+#[unsafe(no_mangle)]
+pub extern "C" fn gpui_format_shortcut(shortcut_ptr: *const c_char) -> *mut c_char {
+	unsafe {
+		let Some(shortcut) = read_opt_c_string(shortcut_ptr) else {
+			return std::ptr::null_mut();
+		};
+		let Some(display) = shortcut::format(&shortcut) else {
+			log::warn!("gpui_format_shortcut: invalid shortcut {:?}", shortcut);
+			return std::ptr::null_mut();
+		};
+		match CString::new(display) {
+			Ok(c_string) => c_string.into_raw(),
+			Err(_) => std::ptr::null_mut(),
+		}
+	}
+}
+
+/// Report resource usage of a window and the process
+/// Returns a JSON string with element counts, queued events and commits,
+/// text cache counters and CPU times, caller must free with
+/// gpui_free_event_string. Returns null if the window isn't found
+#[unsafe(no_mangle)]
+pub extern "C" fn gpui_get_resource_stats(window_id_ptr: *const u8) -> *mut c_char {
+	unsafe {
+		let window_id = ptr_to_u64(window_id_ptr);
+
+		let Some(stats) = resource_stats::collect(window_id) else {
+			return std::ptr::null_mut();
+		};
+		match CString::new(stats.to_string()) {
+			Ok(c_string) => c_string.into_raw(),
+			Err(_) => std::ptr::null_mut(),
+		}
+	}
+}
+
+/// Configure redaction of user content, from a JSON object of PrivacyOptions
+/// Fields that are left out keep their current setting
+#[unsafe(no_mangle)]
+pub extern "C" fn gpui_set_privacy_options(options_ptr: *const c_char, result: *mut FfiResult) {
+	unsafe {
+		let Some(result) = validate_result_ptr(result, "gpui_set_privacy_options") else {
+			return;
+		};
+		let options_json = read_c_string(options_ptr, "{}");
+		match serde_json::from_str::<privacy::PrivacyOptions>(&options_json) {
+			Ok(options) => {
+				privacy::configure(&options);
+				*result = FfiResult::success();
+			}
+			Err(e) => {
+				log::error!("gpui_set_privacy_options: invalid options: {}", e);
+				*result = FfiResult::error(&format!("Invalid privacy options: {}", e));
+			}
+		}
+	}
+}
+
+/// Set the font fallback chain appended to every element's fontFamily, from
+/// a JSON array of family names (e.g. a CJK font, then an emoji font)
+/// Takes effect from the next frame
+#[unsafe(no_mangle)]
+pub extern "C" fn gpui_set_font_fallbacks(families_ptr: *const c_char, result: *mut FfiResult) {
+	unsafe {
+		let Some(result) = validate_result_ptr(result, "gpui_set_font_fallbacks") else {
+			return;
+		};
+		let families_json = read_c_string(families_ptr, "[]");
+		match serde_json::from_str::<Vec<String>>(&families_json) {
+			Ok(families) => {
+				element::fonts::set_default_fallbacks(families);
+				*result = FfiResult::success();
+			}
+			Err(e) => {
+				log::error!("gpui_set_font_fallbacks: invalid families: {}", e);
+				*result = FfiResult::error(&format!("Invalid font fallbacks: {}", e));
+			}
+		}
+	}
+}
+
+/// Load a font file (TTF/OTF) so elements can name its family in fontFamily,
+/// e.g. an icon font for `icon` elements
+/// Blocks until the GPUI thread has loaded it
+#[unsafe(no_mangle)]
+pub extern "C" fn gpui_register_font(path_ptr: *const c_char, result: *mut FfiResult) {
+	unsafe {
+		let Some(result) = validate_result_ptr(result, "gpui_register_font") else {
+			return;
+		};
+		let path = read_c_string(path_ptr, "");
+		let (response_tx, response_rx) = oneshot::channel();
+		send_host_command(HostCommand::RegisterFont { path, response_tx });
+		*result = match response_rx.blocking_recv() {
+			Ok(Ok(())) => FfiResult::success(),
+			Ok(Err(e)) => {
+				log::error!("gpui_register_font: {}", e);
+				FfiResult::error(&e)
+			}
+			Err(_) => FfiResult::error("GPUI thread is gone"),
+		};
+	}
+}
+
+/// Enable or disable hit-test debugging for a window
+/// While enabled, every mouse release queues a window-level "hittest" event
+/// describing the element stack under the cursor
+#[unsafe(no_mangle)]
+pub extern "C" fn gpui_set_hit_test_debug(window_id_ptr: *const u8, enabled: bool) {
+	unsafe {
+		let window_id = ptr_to_u64(window_id_ptr);
+		element::hit_test::set_enabled(window_id, enabled);
+	}
+}
+
+/// Show or hide the developer HUD of a window: frame rate, frame time,
+/// element count, event queue depth and commit rate in the top-left corner
+#[unsafe(no_mangle)]
+pub extern "C" fn gpui_set_hud(window_id_ptr: *const u8, enabled: bool) {
+	unsafe {
+		let window_id = ptr_to_u64(window_id_ptr);
+		element::hud::set_enabled(window_id, enabled);
+		send_host_command(HostCommand::TriggerRender { window_id });
+	}
+}
+
+/// Cap the frame rate of a window's continuous animations (indeterminate
+/// progress, gauges easing, skeletons, shader views) at `fps`, 0 removes it
+#[unsafe(no_mangle)]
+pub extern "C" fn gpui_set_max_fps(window_id_ptr: *const u8, fps: f32) {
+	unsafe {
+		let window_id = ptr_to_u64(window_id_ptr);
+		element::pacing::set_max_fps(window_id, fps);
+	}
+}
+
+/// Turn power saving on or off for every window: animations run at 15fps at
+/// most and decorative ones, like the skeleton shimmer, stand still
+#[unsafe(no_mangle)]
+pub extern "C" fn gpui_set_power_saver(enabled: bool) {
+	element::pacing::set_power_saver(enabled);
+	// Stilled animations only ask for frames again once painted
+	for window_id in GLOBAL_STATE.window_ids() {
+		send_host_command(HostCommand::TriggerRender { window_id });
+	}
+}
+
+/// Coalesce layout while a window is being resized: the tree keeps its last
+/// layout size and is laid out again at most once per `interval_ms`, then at
+/// the final size once the size stops changing. 0 turns it off
+/// Resizes are marked by "resizestart" and "resizeend" window events
+#[unsafe(no_mangle)]
+pub extern "C" fn gpui_set_live_resize(window_id_ptr: *const u8, interval_ms: u32) {
+	unsafe {
+		let window_id = ptr_to_u64(window_id_ptr);
+		element::live_resize::set_interval(window_id, interval_ms);
+	}
+}
+
+/// Declare the insets of chrome the app draws over a window's content, such
+/// as a custom titlebar or a docked panel, from a JSON object with any of
+/// `top`, `right`, `bottom` and `left`
+/// Each edge of the window's safe area is the larger of these and the native
+/// chrome's; changes are sent as a "safeareachange" window event
+#[unsafe(no_mangle)]
+pub extern "C" fn gpui_set_safe_area_insets(
+	window_id_ptr: *const u8,
+	insets_json_ptr: *const c_char,
+	result: *mut FfiResult,
+) {
+	unsafe {
+		let Some(result) = validate_result_ptr(result, "gpui_set_safe_area_insets") else {
+			return;
+		};
+		let window_id = ptr_to_u64(window_id_ptr);
+		let insets_json = read_c_string(insets_json_ptr, "{}");
+		let insets = match serde_json::from_str::<element::safe_area::Insets>(&insets_json) {
+			Ok(insets) => insets,
+			Err(e) => {
+				log::error!("gpui_set_safe_area_insets: invalid insets: {}", e);
+				*result = FfiResult::error(&format!("Invalid insets: {}", e));
+				return;
+			}
+		};
+		element::safe_area::set_declared(window_id, insets);
+		send_host_command(HostCommand::TriggerRender { window_id });
+		*result = FfiResult::success();
+	}
+}
+
+/// Set which element event types are queued for a window, from a JSON array
+/// of types (e.g. `["click", "keydown"]`) or null to queue all of them
+/// Masked events are never queued; window-level events are not affected
+#[unsafe(no_mangle)]
+pub extern "C" fn gpui_set_event_mask(
+	window_id_ptr: *const u8,
+	types_json_ptr: *const c_char,
+	result: *mut FfiResult,
+) {
+	unsafe {
+		let Some(result) = validate_result_ptr(result, "gpui_set_event_mask") else {
+			return;
+		};
+		let window_id = ptr_to_u64(window_id_ptr);
+		let types_json = read_c_string(types_json_ptr, "null");
+		let event_types =
+			match serde_json::from_str::<Option<std::collections::HashSet<String>>>(&types_json) {
+				Ok(event_types) => event_types,
+				Err(e) => {
+					log::error!("gpui_set_event_mask: invalid event types: {}", e);
+					*result = FfiResult::error(&format!("Invalid event types: {}", e));
+					return;
+				}
+			};
+		let Some(window) = GLOBAL_STATE.get_window(window_id) else {
+			*result = FfiResult::error(&format!("Window {} not found", window_id));
+			return;
+		};
+		window.state().set_event_mask(event_types);
+		*result = FfiResult::success();
+	}
+}
+
+/// Read the monotonic clock event timestamps use
+/// Returns a JSON string {"nowUs", "epochUnixUs"}: microseconds since the
+/// clock's epoch and the wall-clock time of that epoch, caller must free with
+/// gpui_free_event_string
+#[unsafe(no_mangle)]
+pub extern "C" fn gpui_get_clock() -> *mut c_char {
+	let clock = serde_json::json!({
+		"nowUs": clock::now_us(),
+		"epochUnixUs": clock::epoch_unix_us(),
+	});
+	match CString::new(clock.to_string()) {
+		Ok(c_string) => c_string.into_raw(),
+		Err(_) => std::ptr::null_mut(),
+	}
+}
+
+/// Enable or disable input latency tracing, for every window
+/// Toggling it discards the samples collected so far
+#[unsafe(no_mangle)]
+pub extern "C" fn gpui_set_latency_tracing(enabled: bool) { latency::set_enabled(enabled) }
+
+/// Report input latency percentiles of a window
+/// Returns a JSON string with queue-to-poll, poll-to-commit, commit-to-paint
+/// and total latency in microseconds, caller must free with
+/// gpui_free_event_string
+#[unsafe(no_mangle)]
+pub extern "C" fn gpui_get_latency_stats(window_id_ptr: *const u8) -> *mut c_char {
+	unsafe {
+		let window_id = ptr_to_u64(window_id_ptr);
+		match CString::new(latency::stats(window_id).to_string()) {
+			Ok(c_string) => c_string.into_raw(),
+			Err(_) => std::ptr::null_mut(),
+		}
+	}
+}
+
+/// Configure key repeats, from a JSON object of KeyRepeatOptions
+/// Fields that are left out keep their current setting
+#[unsafe(no_mangle)]
+pub extern "C" fn gpui_set_key_repeat(options_ptr: *const c_char, result: *mut FfiResult) {
+	unsafe {
+		let Some(result) = validate_result_ptr(result, "gpui_set_key_repeat") else {
+			return;
+		};
+		let options_json = read_c_string(options_ptr, "{}");
+		match serde_json::from_str::<element::key_repeat::KeyRepeatOptions>(&options_json) {
+			Ok(options) => {
+				element::key_repeat::configure(&options);
+				*result = FfiResult::success();
+			}
+			Err(e) => {
+				log::error!("gpui_set_key_repeat: invalid options: {}", e);
+				*result = FfiResult::error(&format!("Invalid key repeat options: {}", e));
+			}
+		}
+	}
+}
+
+/// Configure pointer input, from a JSON object of InputSettingsOptions
+/// (double-click time, drag distance, scroll multiplier and direction)
+/// Fields that are left out keep their current setting
+#[unsafe(no_mangle)]
+pub extern "C" fn gpui_set_input_settings(options_ptr: *const c_char, result: *mut FfiResult) {
+	unsafe {
+		let Some(result) = validate_result_ptr(result, "gpui_set_input_settings") else {
+			return;
+		};
+		let options_json = read_c_string(options_ptr, "{}");
+		match serde_json::from_str::<element::input_settings::InputSettingsOptions>(&options_json) {
+			Ok(options) => {
+				element::input_settings::configure(&options);
+				*result = FfiResult::success();
+			}
+			Err(e) => {
+				log::error!("gpui_set_input_settings: invalid options: {}", e);
+				*result = FfiResult::error(&format!("Invalid input settings: {}", e));
+			}
+		}
+	}
+}
+
+/// Configure the GPUI thread watchdog, from a JSON object of WatchdogOptions
+/// Fields that are left out keep their current setting. Stalls longer than
+/// `deadlineMs` queue a window-level "hang" event on every window
+#[unsafe(no_mangle)]
+pub extern "C" fn gpui_set_watchdog(options_ptr: *const c_char, result: *mut FfiResult) {
+	unsafe {
+		let Some(result) = validate_result_ptr(result, "gpui_set_watchdog") else {
+			return;
+		};
+		let options_json = read_c_string(options_ptr, "{}");
+		match serde_json::from_str::<watchdog::WatchdogOptions>(&options_json) {
+			Ok(options) => {
+				watchdog::configure(&options);
+				*result = FfiResult::success();
+			}
+			Err(e) => {
+				log::error!("gpui_set_watchdog: invalid options: {}", e);
+				*result = FfiResult::error(&format!("Invalid watchdog options: {}", e));
+			}
+		}
+	}
+}
+
+/// Enable or disable paint tracking for a window
+/// While enabled, the first frame after each commit queues a window-level
+/// "paintchanged" event for every trackPaint element whose hash changed
+#[unsafe(no_mangle)]
+pub extern "C" fn gpui_set_paint_tracking(window_id_ptr: *const u8, enabled: bool) {
+	unsafe {
+		let window_id = ptr_to_u64(window_id_ptr);
+		element::paint_track::set_enabled(window_id, enabled);
+	}
+}
@@ -0,0 +1,83 @@
+//! Redaction of user content in logs and event payloads
+//!
+//! Text typed into or shown by a private element (one with `privacyMode`, or a
+//! password input) is logged as its length and a short hash, never verbatim.
+//! `gpui_set_privacy_options` can extend this to all logged content
+//! (`redactLogs`) and can also redact the key, value and data of events from
+//! `privacyMode` elements before they are queued (`redactEvents`), for apps
+//! that forward raw events to analytics.
+
+use std::{borrow::Cow, hash::{DefaultHasher, Hash, Hasher}, sync::atomic::{AtomicBool, Ordering}};
+
+use crate::{event_types::EventData, global_state::GLOBAL_STATE, sync::MutexExt};
+
+static REDACT_LOGS: AtomicBool = AtomicBool::new(false);
+static REDACT_EVENTS: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Default, serde::Deserialize)]
+pub struct PrivacyOptions {
+	/// Redact content of every element in logs, not just private ones
+	#[serde(rename = "redactLogs")]
+	pub redact_logs:   Option<bool>,
+	/// Redact event payloads of `privacyMode` elements before they are queued
+	#[serde(rename = "redactEvents")]
+	pub redact_events: Option<bool>,
+}
+
+pub fn configure(options: &PrivacyOptions) {
+	if let Some(redact_logs) = options.redact_logs {
+		REDACT_LOGS.store(redact_logs, Ordering::Relaxed);
+	}
+	if let Some(redact_events) = options.redact_events {
+		REDACT_EVENTS.store(redact_events, Ordering::Relaxed);
+	}
+}
+
+/// Length and hash of a string, enough to tell values apart in a log
+pub fn redact(text: &str) -> String {
+	let mut hasher = DefaultHasher::new();
+	text.hash(&mut hasher);
+	format!("<{} chars #{:08x}>", text.chars().count(), hasher.finish() as u32)
+}
+
+/// Text of an element as it may appear in a log line
+pub fn log_text(window_id: u64, element_id: u64, text: &str) -> Cow<'_, str> {
+	if REDACT_LOGS.load(Ordering::Relaxed) || element_flags(window_id, element_id).0 {
+		Cow::Owned(redact(text))
+	} else {
+		Cow::Borrowed(text)
+	}
+}
+
+/// Redact the content of an event about to be queued, if configured
+pub fn redact_event(window_id: u64, element_id: u64, data: &mut EventData) {
+	if !REDACT_EVENTS.load(Ordering::Relaxed) || !element_flags(window_id, element_id).1 {
+		return;
+	}
+	match data {
+		EventData::Keyboard(keyboard) => {
+			keyboard.key = redact(&keyboard.key);
+			keyboard.code = redact(&keyboard.code);
+		}
+		EventData::Input(input) => {
+			input.value = redact(&input.value);
+			input.data = input.data.as_deref().map(redact);
+		}
+		_ => {}
+	}
+}
+
+/// (private, privacy mode) of an element
+/// Password inputs are private without `privacyMode`
+fn element_flags(window_id: u64, element_id: u64) -> (bool, bool) {
+	let Some(window) = GLOBAL_STATE.get_window(window_id) else {
+		return (false, false);
+	};
+	let element_map = window.state().element_map.lock_recover();
+	let Some(element) = element_map.get(&element_id) else {
+		return (false, false);
+	};
+	let privacy_mode = element.style.privacy_mode == Some(true);
+	let password = element.style.input_type.as_deref() == Some("password");
+	(privacy_mode || password, privacy_mode)
+}
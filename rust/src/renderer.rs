@@ -1,7 +1,20 @@
-use gpui::{div, prelude::*, rgb, Application as GpuiApp, Entity, FocusHandle, InteractiveElement, KeyDownEvent, KeyUpEvent, Render, Window};
+use std::{sync::{Arc, Mutex}, thread::JoinHandle, time::{Duration, Instant}};
 
-use crate::{element::create_element, event_types::{types, EventData, FocusEventData, KeyboardEventData}, global_state::GLOBAL_STATE, host_command, window::EventMessage};
-use crate::element::focus;
+use gpui::{canvas, div, prelude::*, rgb, Application as GpuiApp, Entity, FocusHandle, Hsla, InteractiveElement, KeyDownEvent, KeyUpEvent, Render, Window};
+
+use crate::{clock, element::{create_element, ReactElement}, event_types::EventData, ffi_types::InitOptions, global_state::GLOBAL_STATE, host_command, latency, privacy, resource_stats, sync::MutexExt, thread_tuning, watchdog::{self, Stage}, window::EventMessage};
+use crate::element::{contrast, dispatcher, events, find_overlay, hud, inert, input_settings, layout, live_region, live_resize, paint_track, rate_limit, safe_area, splash, style_variants};
+
+/// Quiet period after a commit before hover variants are built
+const STYLE_PRECOMPUTE_DELAY: Duration = Duration::from_millis(100);
+
+/// Pause between precompute chunks, so frames can run in between
+const STYLE_PRECOMPUTE_STEP: Duration = Duration::from_millis(1);
+
+lazy_static::lazy_static! {
+	/// The running GPUI thread, joined by gpui_shutdown
+	static ref GPUI_THREAD: Mutex<Option<JoinHandle<()>>> = Mutex::new(None);
+}
 
 /// Dispatch an event to the event queue for JS polling
 /// This is thread-safe and doesn't require calling JS directly from Rust
@@ -9,15 +22,20 @@ pub(crate) fn dispatch_event_to_js(
 	window_id: u64,
 	element_id: u64,
 	event_type: &str,
-	event_data: EventData,
+	mut event_data: EventData,
 ) {
-	let timestamp = std::time::SystemTime::now()
-		.duration_since(std::time::UNIX_EPOCH)
-		.map(|d| d.as_millis() as u64)
-		.unwrap_or(0);
+	// Event types masked out by JS are dropped before any work is done
+	if GLOBAL_STATE
+		.get_window(window_id)
+		.is_some_and(|window| !window.state().wants_event(event_type))
+	{
+		return;
+	}
+	privacy::redact_event(window_id, element_id, &mut event_data);
+	let timestamp = clock::now_us();
 
 	// Build JSON payload based on event data type
-	let json_payload = match event_data {
+	let mut json_payload = match event_data {
 		EventData::Mouse(data) => {
 			serde_json::json!({
 				"windowId": window_id,
@@ -39,6 +57,7 @@ pub(crate) fn dispatch_event_to_js(
 				"key": data.key,
 				"code": data.code,
 				"repeat": data.repeat,
+				"repeatCount": data.repeat_count,
 				"ctrlKey": data.ctrl,
 				"shiftKey": data.shift,
 				"altKey": data.alt,
@@ -75,6 +94,7 @@ pub(crate) fn dispatch_event_to_js(
 				"data": data.data,
 				"inputType": data.input_type,
 				"isComposing": data.is_composing,
+				"checked": data.checked,
 				"timestamp": timestamp
 			})
 		}
@@ -87,17 +107,35 @@ pub(crate) fn dispatch_event_to_js(
 			})
 		}
 	};
+	if let Some(trace_id) = latency::trace(window_id, event_type) {
+		json_payload["traceId"] = trace_id.into();
+	}
 
 	let json_str = json_payload.to_string();
 
 	// Push event to window's event queue instead of calling JS directly
 	if let Some(window) = GLOBAL_STATE.get_window(window_id) {
-		window.state().push_event(EventMessage {
-			window_id,
-			element_id,
-			event_type: event_type.to_string(),
-			payload: json_str,
-		});
+		let message =
+			EventMessage { window_id, element_id, event_type: event_type.to_string(), payload: json_str };
+		let limit = {
+			let element_map = window.state().element_map.lock_recover();
+			// Released: its id may already belong to a new element in JS
+			let Some(element) = element_map.get(&element_id) else {
+				log::trace!("[Rust] Event for released element {} dropped", element_id);
+				return;
+			};
+			let options = element.style.event_options.as_ref();
+			options.and_then(|options| rate_limit::limit_for(options, event_type))
+		};
+		let message = match limit {
+			Some(limit) => match rate_limit::admit(limit, message) {
+				Some(message) => message,
+				// Held back, flushed by a later poll
+				None => return,
+			},
+			None => message,
+		};
+		window.state().push_event(message);
 		log::trace!(
 			"[Rust] Event queued: window_id={}, element_id={}, event_type={}",
 			window_id,
@@ -109,6 +147,34 @@ pub(crate) fn dispatch_event_to_js(
 	}
 }
 
+/// Dispatch a window-level event (not tied to an element) to JS
+/// Window events use element id 0 and carry an arbitrary JSON payload
+pub(crate) fn dispatch_window_event(window_id: u64, event_type: &str, data: serde_json::Value) {
+	let timestamp = clock::now_us();
+
+	let mut payload = serde_json::json!({
+		"windowId": window_id,
+		"elementId": 0,
+		"eventType": event_type,
+		"timestamp": timestamp
+	});
+	if let (Some(payload), serde_json::Value::Object(data)) = (payload.as_object_mut(), data) {
+		payload.extend(data);
+	}
+
+	if let Some(window) = GLOBAL_STATE.get_window(window_id) {
+		window.state().push_event(EventMessage {
+			window_id,
+			element_id: 0,
+			event_type: event_type.to_string(),
+			payload: payload.to_string(),
+		});
+		log::trace!("[Rust] Window event queued: window_id={}, event_type={}", window_id, event_type);
+	} else {
+		log::warn!("[Rust] dispatch_window_event: window {} not found", window_id);
+	}
+}
+
 pub struct RootState {
 	pub render_count: u64,
 }
@@ -116,14 +182,42 @@ pub struct RootState {
 pub struct RootView {
 	state:             Entity<RootState>,
 	last_render:       u64,
+	/// Tree generation of the previous frame; the first frame of a new one
+	/// records its commit as applied and precomputes its hover variants
+	last_generation:   u64,
 	window_id:         u64,
 	focus_handle:      Option<FocusHandle>,
 	focus_initialized: bool,
+	/// Fullscreen state seen by the previous frame, None before the first
+	fullscreen:        Option<bool>,
+	background:        Option<Hsla>,
+	/// Shown until the first tree, dropped once it has faded out
+	splash:            Option<Arc<ReactElement>>,
+	/// Start of the splash fade
+	splash_fade_start: Option<u64>,
 }
 
 impl RootView {
-	pub fn new(state: Entity<RootState>, window_id: u64, _w: f32, _h: f32) -> RootView {
-		return Self { state, last_render: 0, window_id, focus_handle: None, focus_initialized: false };
+	pub fn new(
+		state: Entity<RootState>,
+		window_id: u64,
+		_w: f32,
+		_h: f32,
+		background: Option<Hsla>,
+		splash: Option<Arc<ReactElement>>,
+	) -> RootView {
+		return Self {
+			state,
+			last_render: 0,
+			last_generation: 0,
+			window_id,
+			focus_handle: None,
+			focus_initialized: false,
+			fullscreen: None,
+			background,
+			splash,
+			splash_fade_start: None,
+		};
 	}
 
 	fn get_or_create_focus_handle(&mut self, cx: &mut Context<Self>) -> FocusHandle {
@@ -134,6 +228,35 @@ impl RootView {
 		self.focus_handle.clone().unwrap()
 	}
 
+	/// Send `fullscreenchange` when the window entered or left fullscreen
+	/// Native transitions resize the window, so they always reach a frame
+	fn track_fullscreen(&mut self, window: &Window) {
+		let fullscreen = window.is_fullscreen();
+		if self.fullscreen.replace(fullscreen).is_some_and(|was| was != fullscreen) {
+			dispatch_window_event(
+				self.window_id,
+				"fullscreenchange",
+				serde_json::json!({ "fullscreen": fullscreen }),
+			);
+		}
+	}
+
+	/// Opacity of the splash this frame, None once it is gone
+	fn splash_opacity(&mut self, has_tree: bool, window: &mut Window) -> Option<f32> {
+		self.splash.as_ref()?;
+		if !has_tree {
+			return Some(1.0);
+		}
+		let started = *self.splash_fade_start.get_or_insert_with(clock::now_us);
+		let opacity = splash::fade_opacity(clock::now_us().saturating_sub(started));
+		if opacity.is_some() {
+			window.request_animation_frame();
+		} else {
+			self.splash = None;
+		}
+		opacity
+	}
+
 	fn ensure_focus(&mut self, window: &mut Window) {
 		if !self.focus_initialized {
 			if let Some(ref handle) = self.focus_handle {
@@ -174,34 +297,88 @@ impl Render for RootView {
 		cx: &mut gpui::Context<Self>,
 	) -> impl gpui::IntoElement {
 		let render_start = std::time::Instant::now();
+		watchdog::enter(Stage::Render);
+		resource_stats::sample_gpui_thread();
 		self.update_state(cx);
+		dispatcher::begin_frame(self.window_id);
+		layout::begin_frame(self.window_id);
+		inert::begin_frame(self.window_id);
+		let show_find_overlay = find_overlay::is_enabled(self.window_id);
+		if show_find_overlay {
+			find_overlay::begin_frame(self.window_id);
+		}
+		let show_hud = hud::is_enabled(self.window_id);
+		if show_hud {
+			hud::begin_frame(self.window_id);
+		}
 
 		let focus_handle = self.get_or_create_focus_handle(cx);
 		self.ensure_focus(gpui_window);
+		self.track_fullscreen(gpui_window);
 		let window_id = self.window_id;
+		safe_area::begin_frame(window_id, gpui_window.is_fullscreen());
+		let resize = live_resize::begin_frame(window_id, gpui_window.viewport_size());
+		if let Some(wait) = resize.wake {
+			cx.spawn(async move |this, cx| {
+				cx.background_executor().timer(wait).await;
+				let _ = this.update(cx, |_, cx| cx.notify());
+			})
+			.detach();
+		}
 
 		let Some(window_state) = GLOBAL_STATE.get_window(self.window_id) else {
 			log::warn!("RootView.render: window {} not found", self.window_id);
+			watchdog::enter(Stage::Idle);
 			return div().child("Window not found").into_any_element();
 		};
-		
-		let tree = window_state
-			.state()
-			.element_tree
-			.lock()
-			.expect("Failed to acquire element_tree lock in RootView.render");
+
+		let tree = window_state.state().element_tree.lock_recover();
 
 		log::debug!("RootView.render: window_id={}, has_tree={}", self.window_id, tree.is_some());
+		let generation = window_state.state().get_tree_generation();
+		if generation != self.last_generation {
+			self.last_generation = generation;
+			latency::applied(window_id);
+			// Build hover variants of the new tree once the window goes quiet
+			cx.spawn(async move |_, cx| {
+				cx.background_executor().timer(STYLE_PRECOMPUTE_DELAY).await;
+				while style_variants::precompute(window_id, style_variants::PRECOMPUTE_CHUNK) {
+					cx.background_executor().timer(STYLE_PRECOMPUTE_STEP).await;
+				}
+			})
+			.detach();
+		}
+		let splash_opacity = self.splash_opacity(tree.is_some(), gpui_window);
 		let child_element = match &*tree {
 			Some(element) => {
 				// Use the new Element trait implementation
 				create_element(element.clone(), self.window_id, None)
 			}
+			None if splash_opacity.is_some() => div().into_any_element(),
 			None => {
 				div().id("base").child("Waiting for React...").text_color(rgb(0x888888)).into_any_element()
 			}
 		};
 
+		// Mid-resize, the tree keeps the size of its last layout
+		let child_element = match resize.held {
+			Some(size) => {
+				div().w(size.width).h(size.height).overflow_hidden().child(child_element).into_any_element()
+			}
+			None => child_element,
+		};
+
+		// The splash covers the window until the first tree, then fades out over it
+		let background = self.background;
+		let splash_layer = splash_opacity.zip(self.splash.clone()).map(|(opacity, splash)| {
+			div()
+				.absolute()
+				.size_full()
+				.opacity(opacity)
+				.when_some(background, |layer, background| layer.bg(background))
+				.child(create_element(splash, window_id, None))
+		});
+
 		let render_duration = render_start.elapsed();
 		log::debug!("RootView.render completed in {:?}", render_duration);
 
@@ -209,117 +386,59 @@ impl Render for RootView {
 		div()
 			.id("gpui-root")
 			.size_full()
+			.when_some(background, |root, background| root.bg(background))
 			.track_focus(&focus_handle)
-			.on_key_down(move |event: &KeyDownEvent, _window, _cx| {
-				let keystroke = &event.keystroke;
-				log::debug!(
-					"[Rust] Window {} KeyDown: key={}, shift={}",
-					window_id,
-					keystroke.key,
-					keystroke.modifiers.shift
-				);
-
-				// Get the currently focused element for this window
-				let focused_element = focus::get_focused(window_id);
-
-				// Handle Tab key for focus navigation
-				if keystroke.key == "tab" {
-					log::debug!(
-						"[Rust] Tab key pressed, current focused={:?}, shift={}",
-						focused_element,
-						keystroke.modifiers.shift
-					);
-
-					let (blur_id, focus_id) = if keystroke.modifiers.shift {
-						focus::focus_prev(window_id)
-					} else {
-						focus::focus_next(window_id)
-					};
-
-					log::debug!(
-						"[Rust] Focus navigation result: blur_id={:?}, focus_id={:?}",
-						blur_id,
-						focus_id
-					);
-
-					// Dispatch blur event
-					if let Some(blur_element_id) = blur_id {
-						dispatch_event_to_js(
-							window_id,
-							blur_element_id,
-							types::BLUR,
-							EventData::Focus(FocusEventData { related_target: focus_id }),
-						);
-					}
-
-					// Dispatch focus event
-					if let Some(focus_element_id) = focus_id {
-						dispatch_event_to_js(
-							window_id,
-							focus_element_id,
-							types::FOCUS,
-							EventData::Focus(FocusEventData { related_target: blur_id }),
-						);
-					}
-
-					return; // Don't dispatch Tab as keydown to the element
-				}
-
-				// Dispatch keydown event to the focused element
-				if let Some(element_id) = focused_element {
-					let event_data = EventData::Keyboard(KeyboardEventData {
-						key:    keystroke.key.clone(),
-						code:   keystroke.key.clone(),
-						repeat: event.is_held,
-						ctrl:   keystroke.modifiers.control,
-						shift:  keystroke.modifiers.shift,
-						alt:    keystroke.modifiers.alt,
-						meta:   keystroke.modifiers.platform,
-					});
-
-					log::debug!(
-						"[Rust] Dispatching onKeyDown to element_id={}, key={}",
-						element_id,
-						keystroke.key
-					);
-					dispatch_event_to_js(window_id, element_id, types::KEYDOWN, event_data);
-				}
-			})
-			.on_key_up(move |event: &KeyUpEvent, _window, _cx| {
-				// Get the currently focused element for this window
-				let focused_element = focus::get_focused(window_id);
-
-				// Dispatch keyup event to the focused element
-				if let Some(element_id) = focused_element {
-					let keystroke = &event.keystroke;
-					let event_data = EventData::Keyboard(KeyboardEventData {
-						key:    keystroke.key.clone(),
-						code:   keystroke.key.clone(),
-						repeat: false,
-						ctrl:   keystroke.modifiers.control,
-						shift:  keystroke.modifiers.shift,
-						alt:    keystroke.modifiers.alt,
-						meta:   keystroke.modifiers.platform,
-					});
-
-					log::debug!(
-						"[Rust] Dispatching onKeyUp to element_id={}, key={}",
-						element_id,
-						keystroke.key
-					);
-					dispatch_event_to_js(window_id, element_id, types::KEYUP, event_data);
-				}
+			.on_key_down(move |event: &KeyDownEvent, window, cx| {
+				events::key_down(window_id, event, window, cx)
 			})
+			.on_key_up(move |event: &KeyUpEvent, window, _cx| events::key_up(window_id, event, window))
 			.child(child_element)
+			.when_some(splash_layer, |root, layer| root.child(layer))
+			// Painted last: installs the central mouse dispatcher for this frame
+			.child(
+				canvas(
+					// Prepainted after the whole tree, painted after it too
+					|_, _, _| watchdog::enter(Stage::Paint),
+					move |_, _, window, _| {
+						dispatcher::install_window_listeners(window_id, window);
+						latency::painted(window_id);
+						paint_track::after_paint(window_id);
+						live_region::after_paint(window_id);
+						contrast::after_paint(window_id);
+						watchdog::enter(Stage::Idle);
+					},
+				)
+				.absolute()
+				.size_full(),
+			)
+			// Built-in find overlay, on top of everything else
+			.when(show_find_overlay, |root| {
+				root.child(
+					canvas(|_, _, _| {}, move |_, _, window, cx| find_overlay::paint(window_id, window, cx))
+						.absolute()
+						.size_full(),
+				)
+			})
+			// Developer HUD, above the find overlay
+			.when(show_hud, |root| {
+				root.child(
+					canvas(|_, _, _| {}, move |_, _, window, cx| hud::paint(window_id, window, cx))
+						.absolute()
+						.size_full(),
+				)
+			})
 			.into_any_element()
 	}
 }
 
-pub fn start_gpui_thread() {
+pub fn start_gpui_thread(options: InitOptions) {
 	log::info!("start_gpui_thread: spawning thread...");
+	// May run a command, so neither this thread nor the GPUI thread waits on it
+	std::thread::spawn(input_settings::detect_os_defaults);
 
-	std::thread::spawn(move || {
+	let handle = std::thread::spawn(move || {
 		log::info!("GPUI thread: starting...");
+		thread_tuning::apply(&options);
 		GLOBAL_STATE.set_thread_started(true);
 
 		let app = GpuiApp::new();
@@ -334,6 +453,27 @@ pub fn start_gpui_thread() {
 
 		log::debug!("GPUI thread: app.run() returned");
 	});
+	*GPUI_THREAD.lock_recover() = Some(handle);
 
 	log::info!("start_gpui_thread: thread spawned");
 }
+
+/// Wait up to `timeout` for the GPUI thread to return from app.run()
+/// Returns false if it is still running, it is then left detached
+pub fn join_gpui_thread(timeout: Duration) -> bool {
+	let Some(handle) = GPUI_THREAD.lock_recover().take() else {
+		return true;
+	};
+	let deadline = Instant::now() + timeout;
+	while !handle.is_finished() {
+		if Instant::now() >= deadline {
+			log::warn!("join_gpui_thread: GPUI thread still running after {:?}", timeout);
+			return false;
+		}
+		std::thread::sleep(Duration::from_millis(5));
+	}
+	if handle.join().is_err() {
+		log::error!("join_gpui_thread: GPUI thread panicked");
+	}
+	true
+}
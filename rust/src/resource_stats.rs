@@ -0,0 +1,84 @@
+//! Resource usage report for performance panels and leak hunting
+//!
+//! Gathers counters kept elsewhere into one JSON object: the element counts of
+//! a window, events and commits waiting to be consumed, the shaped-text cache
+//! and CPU time of the process, the GPUI thread (as of its last frame) and the
+//! calling thread. Numbers that keep growing over a long session point at a
+//! leak. Texture memory isn't reported: gpui keeps its sprite atlas private.
+
+use std::{sync::atomic::{AtomicU64, Ordering}, time::Duration};
+
+use serde_json::{Value, json};
+
+use crate::{element::{ReactElement, style_store, text_cache}, global_state::GLOBAL_STATE, sync::MutexExt};
+
+/// CPU time of the GPUI thread in microseconds, sampled every frame
+static GPUI_THREAD_CPU_US: AtomicU64 = AtomicU64::new(0);
+
+/// Record the GPUI thread's CPU time, called from the frame callback
+pub fn sample_gpui_thread() {
+	if let Some(time) = thread_cpu_time() {
+		GPUI_THREAD_CPU_US.store(time.as_micros() as u64, Ordering::Relaxed);
+	}
+}
+
+/// Stats of one window plus process-wide numbers, None if the window is gone
+pub fn collect(window_id: u64) -> Option<Value> {
+	let window = GLOBAL_STATE.get_window(window_id)?;
+	let state = window.state();
+	let elements = state.element_map.lock_recover().len();
+	let tree_elements = state.element_tree.lock_recover().as_deref().map_or(0, count_tree);
+	let text = text_cache::stats();
+	let millis = |time: Option<Duration>| time.map(|t| t.as_secs_f64() * 1000.0);
+
+	Some(json!({
+		"windowId": window_id,
+		"elements": elements,
+		"treeElements": tree_elements,
+		"queuedEvents": state.event_queue.lock_recover().len(),
+		"stagedCommits": state.staged_commits(),
+		"renderCount": state.get_render_count(),
+		"uniqueStyles": style_store::unique_styles(),
+		"textCache": {
+			"entries": text.entries,
+			"hits": text.hits,
+			"misses": text.misses,
+			"evictions": text.evictions,
+			"hitRate": text.hit_rate(),
+		},
+		"cpu": {
+			"processMs": millis(process_cpu_time()),
+			"gpuiThreadMs": millis(gpui_thread_cpu_time()),
+			"callerThreadMs": millis(thread_cpu_time()),
+		},
+	}))
+}
+
+/// Elements reachable from the root, which excludes unmounted leftovers
+fn count_tree(element: &ReactElement) -> usize {
+	1 + element.children.iter().map(|child| count_tree(child)).sum::<usize>()
+}
+
+fn gpui_thread_cpu_time() -> Option<Duration> {
+	let micros = GPUI_THREAD_CPU_US.load(Ordering::Relaxed);
+	(micros > 0).then(|| Duration::from_micros(micros))
+}
+
+#[cfg(unix)]
+fn clock_time(clock: libc::clockid_t) -> Option<Duration> {
+	let mut ts = libc::timespec { tv_sec: 0, tv_nsec: 0 };
+	let rc = unsafe { libc::clock_gettime(clock, &mut ts) };
+	(rc == 0).then(|| Duration::new(ts.tv_sec as u64, ts.tv_nsec as u32))
+}
+
+#[cfg(unix)]
+fn thread_cpu_time() -> Option<Duration> { clock_time(libc::CLOCK_THREAD_CPUTIME_ID) }
+
+#[cfg(unix)]
+fn process_cpu_time() -> Option<Duration> { clock_time(libc::CLOCK_PROCESS_CPUTIME_ID) }
+
+#[cfg(not(unix))]
+fn thread_cpu_time() -> Option<Duration> { None }
+
+#[cfg(not(unix))]
+fn process_cpu_time() -> Option<Duration> { None }
@@ -0,0 +1,143 @@
+//! Display strings for keyboard shortcuts
+//!
+//! Shortcuts use gpui's keystroke syntax ("cmd-shift-p", "ctrl-k ctrl-s"),
+//! the same strings key bindings are matched against, so a hint rendered from
+//! a binding always names the keys that trigger it. As in key bindings, "cmd"
+//! is the secondary modifier: Command on macOS and Ctrl elsewhere ("super" and
+//! "win" still name the platform key). macOS shows modifier symbols in the
+//! system order ("⇧⌘P"); other platforms spell modifiers out ("Ctrl+Shift+P").
+
+use gpui::Keystroke;
+
+/// Format a shortcut for the current platform
+/// Returns None if any keystroke in the sequence fails to parse
+pub fn format(shortcut: &str) -> Option<String> {
+	let strokes = shortcut
+		.split_whitespace()
+		.map(|source| {
+			Keystroke::parse(&cmd_as_secondary(source)).ok().map(|keystroke| format_keystroke(&keystroke))
+		})
+		.collect::<Option<Vec<_>>>()?;
+	if strokes.is_empty() {
+		return None;
+	}
+	Some(strokes.join(" "))
+}
+
+/// Rewrite a "cmd" modifier as gpui's "secondary", leaving the key itself alone
+fn cmd_as_secondary(source: &str) -> String {
+	let mut components: Vec<&str> = source.split('-').collect();
+	let modifiers = components.len() - 1;
+	for component in &mut components[..modifiers] {
+		if component.eq_ignore_ascii_case("cmd") {
+			*component = "secondary";
+		}
+	}
+	components.join("-")
+}
+
+#[cfg(target_os = "macos")]
+fn format_keystroke(keystroke: &Keystroke) -> String {
+	let modifiers = &keystroke.modifiers;
+	let mut out = String::new();
+	if modifiers.function {
+		out.push_str("fn");
+	}
+	if modifiers.control {
+		out.push('⌃');
+	}
+	if modifiers.alt {
+		out.push('⌥');
+	}
+	if modifiers.shift {
+		out.push('⇧');
+	}
+	if modifiers.platform {
+		out.push('⌘');
+	}
+	let key = match keystroke.key.as_str() {
+		"enter" => "↩",
+		"escape" => "⎋",
+		"backspace" => "⌫",
+		"delete" => "⌦",
+		"tab" => "⇥",
+		"space" => "Space",
+		"up" => "↑",
+		"down" => "↓",
+		"left" => "←",
+		"right" => "→",
+		"pageup" => "⇞",
+		"pagedown" => "⇟",
+		"home" => "↖",
+		"end" => "↘",
+		key => return out + &key_label(key),
+	};
+	out + key
+}
+
+#[cfg(not(target_os = "macos"))]
+fn format_keystroke(keystroke: &Keystroke) -> String {
+	let modifiers = &keystroke.modifiers;
+	let mut parts: Vec<String> = Vec::new();
+	if modifiers.control {
+		parts.push("Ctrl".into());
+	}
+	if modifiers.alt {
+		parts.push("Alt".into());
+	}
+	if modifiers.shift {
+		parts.push("Shift".into());
+	}
+	if modifiers.platform {
+		parts.push(if cfg!(target_os = "windows") { "Win" } else { "Super" }.into());
+	}
+	if modifiers.function {
+		parts.push("Fn".into());
+	}
+	let key = match keystroke.key.as_str() {
+		"escape" => "Esc".to_string(),
+		"pageup" => "PageUp".to_string(),
+		"pagedown" => "PageDown".to_string(),
+		key => key_label(key),
+	};
+	parts.push(key);
+	parts.join("+")
+}
+
+/// Uppercase single characters and capitalize named keys ("f5" -> "F5")
+fn key_label(key: &str) -> String {
+	let mut chars = key.chars();
+	match chars.next() {
+		Some(first) => first.to_uppercase().chain(chars).collect(),
+		None => String::new(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	#[cfg(not(target_os = "macos"))]
+	fn cmd_is_ctrl_off_macos() {
+		assert_eq!(format("cmd-shift-p").as_deref(), Some("Ctrl+Shift+P"));
+		assert_eq!(format("cmd-k cmd-s").as_deref(), Some("Ctrl+K Ctrl+S"));
+		assert_eq!(
+			format("super-l").as_deref(),
+			Some(if cfg!(target_os = "windows") { "Win+L" } else { "Super+L" })
+		);
+		assert_eq!(format("cmd--").as_deref(), Some("Ctrl+-"));
+	}
+
+	#[test]
+	#[cfg(target_os = "macos")]
+	fn cmd_is_command_on_macos() {
+		assert_eq!(format("cmd-shift-p").as_deref(), Some("⇧⌘P"));
+	}
+
+	#[test]
+	fn invalid_shortcuts() {
+		assert_eq!(format(""), None);
+		assert_eq!(format("   "), None);
+	}
+}
@@ -0,0 +1,183 @@
+//! Poison-tolerant locking for the renderer's shared state
+//!
+//! A panic while a lock is held poisons it, and with plain `lock().unwrap()`
+//! every later FFI call or frame would panic too. `lock_recover` and friends
+//! log the poisoning once, clear it and hand out the guard anyway: the state
+//! behind these locks (element maps, handler tables, caches) is rebuilt from
+//! the next batch or frame, so continuing is always better than wedging.
+//!
+//! With the `lock-audit` feature every guard also records how long it waited
+//! for and held its lock, keyed by call site, and the worst offenders are
+//! logged periodically.
+
+use std::{ops::{Deref, DerefMut}, panic::Location, sync::{Mutex, MutexGuard, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard}};
+
+/// Guard returned by the `*_recover` methods
+pub struct Guard<G> {
+	// Declared first so the lock is released before the audit record is taken
+	inner: G,
+	#[cfg(feature = "lock-audit")]
+	audit: audit::Hold,
+}
+
+impl<G> Guard<G> {
+	fn new(inner: G, site: &'static Location<'static>, waited: std::time::Duration) -> Self {
+		#[cfg(not(feature = "lock-audit"))]
+		let _ = (site, waited);
+		Self {
+			inner,
+			#[cfg(feature = "lock-audit")]
+			audit: audit::Hold::start(site, waited),
+		}
+	}
+}
+
+impl<G: Deref> Deref for Guard<G> {
+	type Target = G::Target;
+
+	fn deref(&self) -> &Self::Target { &self.inner }
+}
+
+impl<G: DerefMut> DerefMut for Guard<G> {
+	fn deref_mut(&mut self) -> &mut Self::Target { &mut self.inner }
+}
+
+fn recover<G>(err: PoisonError<G>, site: &'static Location<'static>) -> G {
+	log::error!("[Rust] recovering poisoned lock at {}", site);
+	err.into_inner()
+}
+
+pub trait MutexExt<T> {
+	/// Lock, recovering the data if a previous holder panicked
+	fn lock_recover(&self) -> Guard<MutexGuard<'_, T>>;
+}
+
+impl<T> MutexExt<T> for Mutex<T> {
+	#[track_caller]
+	fn lock_recover(&self) -> Guard<MutexGuard<'_, T>> {
+		let site = Location::caller();
+		let start = std::time::Instant::now();
+		let guard = self.lock().unwrap_or_else(|err| {
+			self.clear_poison();
+			recover(err, site)
+		});
+		Guard::new(guard, site, start.elapsed())
+	}
+}
+
+pub trait RwLockExt<T> {
+	/// Read-lock, recovering the data if a previous writer panicked
+	fn read_recover(&self) -> Guard<RwLockReadGuard<'_, T>>;
+
+	/// Write-lock, recovering the data if a previous writer panicked
+	fn write_recover(&self) -> Guard<RwLockWriteGuard<'_, T>>;
+}
+
+impl<T> RwLockExt<T> for RwLock<T> {
+	#[track_caller]
+	fn read_recover(&self) -> Guard<RwLockReadGuard<'_, T>> {
+		let site = Location::caller();
+		let start = std::time::Instant::now();
+		let guard = self.read().unwrap_or_else(|err| {
+			self.clear_poison();
+			recover(err, site)
+		});
+		Guard::new(guard, site, start.elapsed())
+	}
+
+	#[track_caller]
+	fn write_recover(&self) -> Guard<RwLockWriteGuard<'_, T>> {
+		let site = Location::caller();
+		let start = std::time::Instant::now();
+		let guard = self.write().unwrap_or_else(|err| {
+			self.clear_poison();
+			recover(err, site)
+		});
+		Guard::new(guard, site, start.elapsed())
+	}
+}
+
+#[cfg(feature = "lock-audit")]
+mod audit {
+	use std::{collections::HashMap, panic::Location, sync::Mutex, time::{Duration, Instant}};
+
+	use lazy_static::lazy_static;
+
+	/// Holds longer than this are logged individually
+	const SLOW_HOLD: Duration = Duration::from_millis(8);
+
+	/// How often (in acquisitions) the hotspot summary is logged
+	const REPORT_INTERVAL: u64 = 10_000;
+
+	#[derive(Default)]
+	struct SiteStats {
+		count:      u64,
+		total_wait: Duration,
+		max_wait:   Duration,
+		total_hold: Duration,
+		max_hold:   Duration,
+	}
+
+	#[derive(Default)]
+	struct Stats {
+		acquisitions: u64,
+		sites:        HashMap<&'static Location<'static>, SiteStats>,
+	}
+
+	lazy_static! {
+		// A plain Mutex on purpose: auditing the audit table would recurse
+		static ref STATS: Mutex<Stats> = Mutex::new(Stats::default());
+	}
+
+	pub struct Hold {
+		site:     &'static Location<'static>,
+		waited:   Duration,
+		acquired: Instant,
+	}
+
+	impl Hold {
+		pub fn start(site: &'static Location<'static>, waited: Duration) -> Self {
+			Self { site, waited, acquired: Instant::now() }
+		}
+	}
+
+	impl Drop for Hold {
+		fn drop(&mut self) {
+			let held = self.acquired.elapsed();
+			if held > SLOW_HOLD {
+				log::warn!("[Rust] lock audit: {} held a lock for {:?}", self.site, held);
+			}
+
+			let mut stats = STATS.lock().unwrap_or_else(|err| err.into_inner());
+			let site = stats.sites.entry(self.site).or_default();
+			site.count += 1;
+			site.total_wait += self.waited;
+			site.max_wait = site.max_wait.max(self.waited);
+			site.total_hold += held;
+			site.max_hold = site.max_hold.max(held);
+
+			stats.acquisitions += 1;
+			if stats.acquisitions % REPORT_INTERVAL == 0 {
+				report(&stats);
+			}
+		}
+	}
+
+	/// Log the call sites that spent the most time waiting for their locks
+	fn report(stats: &Stats) {
+		let mut sites: Vec<_> = stats.sites.iter().collect();
+		sites.sort_by(|a, b| b.1.total_wait.cmp(&a.1.total_wait));
+		log::info!("[Rust] lock audit: {} acquisitions, top contention:", stats.acquisitions);
+		for (location, site) in sites.into_iter().take(5) {
+			log::info!(
+				"[Rust]   {}: {} locks, wait total {:?} max {:?}, hold total {:?} max {:?}",
+				location,
+				site.count,
+				site.total_wait,
+				site.max_wait,
+				site.total_hold,
+				site.max_hold
+			);
+		}
+	}
+}
@@ -0,0 +1,89 @@
+//! Scheduling of the GPUI thread
+//!
+//! Embedders with realtime threads of their own (audio engines, game loops)
+//! can lower the UI thread's priority or pin it to a set of cores through
+//! `gpui_init_with_options`. Priority maps to the thread's nice value on Linux
+//! and to a QoS class on macOS; pinning is Linux only. Settings that can't be
+//! applied are logged and otherwise ignored.
+
+use crate::ffi_types::{InitOptions, ThreadPriority};
+
+/// Apply init options to the calling thread, the GPUI thread as it starts
+pub fn apply(options: &InitOptions) {
+	if let Some(priority) = options.thread_priority
+		&& priority != ThreadPriority::Normal
+	{
+		set_priority(priority);
+	}
+	if let Some(cpus) = options.cpu_affinity.as_deref().filter(|cpus| !cpus.is_empty()) {
+		set_affinity(cpus);
+	}
+}
+
+#[cfg(target_os = "linux")]
+fn set_priority(priority: ThreadPriority) {
+	let nice = match priority {
+		ThreadPriority::Low => 10,
+		ThreadPriority::Normal => 0,
+		// Raising priority needs CAP_SYS_NICE
+		ThreadPriority::High => -5,
+	};
+	let rc = unsafe { libc::setpriority(libc::PRIO_PROCESS, libc::gettid() as libc::id_t, nice) };
+	if rc != 0 {
+		log::warn!(
+			"[Rust] thread tuning: failed to set nice {}: {}",
+			nice,
+			std::io::Error::last_os_error()
+		);
+	}
+}
+
+#[cfg(target_os = "macos")]
+fn set_priority(priority: ThreadPriority) {
+	use libc::qos_class_t::{QOS_CLASS_USER_INITIATED, QOS_CLASS_USER_INTERACTIVE, QOS_CLASS_UTILITY};
+
+	let class = match priority {
+		ThreadPriority::Low => QOS_CLASS_UTILITY,
+		ThreadPriority::Normal => QOS_CLASS_USER_INITIATED,
+		ThreadPriority::High => QOS_CLASS_USER_INTERACTIVE,
+	};
+	let rc = unsafe { libc::pthread_set_qos_class_self_np(class, 0) };
+	if rc != 0 {
+		log::warn!("[Rust] thread tuning: failed to set QoS class: error {}", rc);
+	}
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn set_priority(priority: ThreadPriority) {
+	log::warn!("[Rust] thread tuning: thread priority {:?} is not supported here", priority);
+}
+
+#[cfg(target_os = "linux")]
+fn set_affinity(cpus: &[usize]) {
+	// CPU_SET panics past the end of the set
+	let (valid, invalid): (Vec<usize>, Vec<usize>) =
+		cpus.iter().copied().partition(|cpu| *cpu < libc::CPU_SETSIZE as usize);
+	if !invalid.is_empty() {
+		log::warn!("[Rust] thread tuning: ignoring out of range CPUs {:?}", invalid);
+	}
+	if valid.is_empty() {
+		return;
+	}
+	let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+	for &cpu in &valid {
+		unsafe { libc::CPU_SET(cpu, &mut set) };
+	}
+	let rc = unsafe { libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set) };
+	if rc != 0 {
+		log::warn!(
+			"[Rust] thread tuning: failed to pin to CPUs {:?}: {}",
+			cpus,
+			std::io::Error::last_os_error()
+		);
+	}
+}
+
+#[cfg(not(target_os = "linux"))]
+fn set_affinity(cpus: &[usize]) {
+	log::warn!("[Rust] thread tuning: pinning to CPUs {:?} is not supported here", cpus);
+}
@@ -0,0 +1,180 @@
+//! Hang detection for the GPUI thread
+//!
+//! The GPUI thread marks the stage it is in (handling a host command,
+//! rendering, painting) and goes back to idle when a frame
+//! is painted. With the watchdog on (`gpui_set_watchdog`), a separate thread
+//! checks that no stage runs longer than `deadlineMs` and that queued host
+//! commands are picked up within it. A stall is logged, then sent to every
+//! window as a window-level "hang" event with the stage it is stuck in, once
+//! per stall. Logging comes first: if the stall is a deadlock on the window
+//! map, the event can't be queued.
+//!
+//! With `captureStack`, every stage entry records a backtrace that is
+//! reported with the hang. Another thread's stack can't be sampled portably,
+//! so it shows where the stuck stage was entered, not the blocking frame.
+
+use std::{backtrace::Backtrace, sync::{Mutex, Once, RwLock, atomic::{AtomicU8, AtomicU64, Ordering}}, time::Duration};
+
+use lazy_static::lazy_static;
+use serde_json::json;
+
+use crate::{clock, global_state::GLOBAL_STATE, renderer::dispatch_window_event, sync::{MutexExt, RwLockExt}};
+
+/// Pause between checks while the watchdog is off
+const DISABLED_POLL: Duration = Duration::from_millis(250);
+
+/// Where the GPUI thread is, reported as `stage` of the "hang" event
+#[derive(Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum Stage {
+	Idle,
+	Command,
+	Render,
+	Paint,
+}
+
+impl Stage {
+	fn name(self) -> &'static str {
+		match self {
+			Stage::Idle => "idle",
+			Stage::Command => "command",
+			Stage::Render => "render",
+			Stage::Paint => "paint",
+		}
+	}
+
+	fn from_u8(value: u8) -> Self {
+		match value {
+			1 => Stage::Command,
+			2 => Stage::Render,
+			3 => Stage::Paint,
+			_ => Stage::Idle,
+		}
+	}
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+pub struct WatchdogOptions {
+	/// Longest a stage or a queued command may wait, 0 turns the watchdog off
+	#[serde(rename = "deadlineMs")]
+	pub deadline_ms:   Option<u64>,
+	/// Record a backtrace at every stage entry
+	#[serde(rename = "captureStack")]
+	pub capture_stack: Option<bool>,
+}
+
+struct Settings {
+	deadline:      Option<Duration>,
+	capture_stack: bool,
+}
+
+static STAGE: AtomicU8 = AtomicU8::new(Stage::Idle as u8);
+static STAGE_SINCE: AtomicU64 = AtomicU64::new(0);
+static PENDING_COMMANDS: AtomicU64 = AtomicU64::new(0);
+/// When the oldest pending command started waiting, or the last one was handled
+static COMMANDS_SINCE: AtomicU64 = AtomicU64::new(0);
+/// Start of the stall last reported, so each stall is reported once
+static REPORTED: AtomicU64 = AtomicU64::new(u64::MAX);
+static START: Once = Once::new();
+
+lazy_static! {
+	static ref SETTINGS: RwLock<Settings> =
+		RwLock::new(Settings { deadline: None, capture_stack: false });
+	static ref STACK: Mutex<Option<Backtrace>> = Mutex::new(None);
+}
+
+pub fn configure(options: &WatchdogOptions) {
+	{
+		let mut settings = SETTINGS.write_recover();
+		if let Some(deadline_ms) = options.deadline_ms {
+			settings.deadline = (deadline_ms > 0).then(|| Duration::from_millis(deadline_ms));
+		}
+		if let Some(capture_stack) = options.capture_stack {
+			settings.capture_stack = capture_stack;
+			if !capture_stack {
+				*STACK.lock_recover() = None;
+			}
+		}
+	}
+	START.call_once(|| {
+		let spawned = std::thread::Builder::new().name("gpui-watchdog".into()).spawn(run);
+		if let Err(err) = spawned {
+			log::error!("watchdog: failed to start: {}", err);
+		}
+	});
+}
+
+/// Mark the stage the GPUI thread enters
+pub fn enter(stage: Stage) {
+	STAGE.store(stage as u8, Ordering::Relaxed);
+	STAGE_SINCE.store(clock::now_us(), Ordering::Relaxed);
+	if stage != Stage::Idle && SETTINGS.read_recover().capture_stack {
+		*STACK.lock_recover() = Some(Backtrace::force_capture());
+	}
+}
+
+/// A host command was queued for the GPUI thread
+pub fn command_queued() {
+	if PENDING_COMMANDS.fetch_add(1, Ordering::Relaxed) == 0 {
+		COMMANDS_SINCE.store(clock::now_us(), Ordering::Relaxed);
+	}
+}
+
+/// The GPUI thread took a queued host command off the queue
+pub fn command_handled() {
+	let _ = PENDING_COMMANDS.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1));
+	COMMANDS_SINCE.store(clock::now_us(), Ordering::Relaxed);
+}
+
+fn run() {
+	loop {
+		let deadline = SETTINGS.read_recover().deadline;
+		let Some(deadline) = deadline else {
+			std::thread::sleep(DISABLED_POLL);
+			continue;
+		};
+		std::thread::sleep((deadline / 4).max(Duration::from_millis(10)));
+		check(deadline);
+	}
+}
+
+fn check(deadline: Duration) {
+	let now = clock::now_us();
+	let deadline_us = deadline.as_micros() as u64;
+	let stage = Stage::from_u8(STAGE.load(Ordering::Relaxed));
+	let pending = PENDING_COMMANDS.load(Ordering::Relaxed);
+
+	let since = if stage != Stage::Idle {
+		STAGE_SINCE.load(Ordering::Relaxed)
+	} else if pending > 0 {
+		COMMANDS_SINCE.load(Ordering::Relaxed)
+	} else {
+		return;
+	};
+	let stalled_us = now.saturating_sub(since);
+	if stalled_us < deadline_us || REPORTED.swap(since, Ordering::Relaxed) == since {
+		return;
+	}
+
+	let stack = match stage {
+		Stage::Idle => None,
+		_ => STACK.lock_recover().as_ref().map(|stack| stack.to_string()),
+	};
+	log::error!(
+		"watchdog: GPUI thread stalled for {}ms in stage {} ({} commands pending){}",
+		stalled_us / 1000,
+		stage.name(),
+		pending,
+		stack.as_deref().map(|stack| format!("\n{}", stack)).unwrap_or_default()
+	);
+
+	let data = json!({
+		"stage": stage.name(),
+		"stalledMs": stalled_us / 1000,
+		"pendingCommands": pending,
+		"stack": stack,
+	});
+	for window_id in GLOBAL_STATE.window_ids() {
+		dispatch_window_event(window_id, "hang", data.clone());
+	}
+}
@@ -1,8 +1,14 @@
-use std::{collections::{HashMap, VecDeque}, sync::{Arc, Mutex, atomic::{AtomicU64, Ordering}}};
+use std::{collections::{HashMap, HashSet, VecDeque}, sync::{Arc, Mutex, atomic::{AtomicBool, AtomicU64, Ordering}}, time::{Duration, Instant}};
 
 use gpui::{AnyWindowHandle, App, AppContext};
 
-use crate::element::{ElementKind, ElementStyle, ReactElement};
+use crate::{element::{self, ElementKind, ElementStyle, ReactElement, rate_limit, style_store}, sync::MutexExt};
+
+/// Deepest element tree that will be built (guards against stack overflow)
+const MAX_TREE_DEPTH: usize = 512;
+
+/// Staged commits beyond this are applied without waiting for a frame
+const MAX_STAGED_COMMITS: usize = 64;
 
 /// Event message to be sent to JS
 #[derive(Clone, Debug)]
@@ -41,6 +47,26 @@ impl Window {
 		}
 	}
 
+	/// Publish the prepared element tree when the next frame starts, and draw
+	/// that frame with it
+	/// Frame callbacks run before the frame is drawn, so a commit never lands
+	/// in the middle of one
+	pub fn publish_at_next_frame(&self, app: &mut App) {
+		let state = self.state.clone();
+		let window_id = self.window_id;
+		if let Err(e) = app.update_window(self.h, |_view, w, _app| {
+			w.on_next_frame(move |w, _| {
+				if state.publish_prepared_tree() {
+					state.increment_render_count();
+					w.refresh();
+					log::trace!("Published the prepared tree of window {}", window_id);
+				}
+			});
+		}) {
+			log::error!("window publish err {}", e)
+		}
+	}
+
 	/// Get the window state
 	pub fn state(&self) -> &Arc<WindowState> { &self.state }
 
@@ -48,9 +74,8 @@ impl Window {
 	pub fn state_mut(&mut self) -> &mut Arc<WindowState> { &mut self.state }
 
 	/// Render a single element with its children
-	/// This method sets the root element ID and rebuilds the element tree
-	/// It should be called after batch_update_elements has populated the
-	/// element_map
+	/// Sets the root element id and its children once the commit is applied,
+	/// after the batch_update_elements calls that populated the element map
 	pub fn render_element(
 		&self,
 		global_id: u64,
@@ -58,150 +83,493 @@ impl Window {
 		_text: Option<String>,
 		children: &[u64],
 	) {
-		// Don't create a new element if it already exists in the map
-		// batch_update_elements should have already populated the elements with proper
-		// styles
-		let mut element_map =
-			self.state.element_map.lock().expect("Failed to acquire element_map lock in render_element");
-
-		// Only create placeholder elements for children that don't exist
-		for &child_id in children {
-			if !element_map.contains_key(&child_id) {
-				let placeholder = Arc::new(ReactElement {
-					global_id:         child_id,
-					element_type:      "placeholder".to_string(),
-					element_kind:      ElementKind::Unknown,
-					text:              None,
-					children:          Vec::new(),
-					style:             ElementStyle::default(),
-					event_handlers:    None,
-					cached_gpui_style: None,
-				});
-				element_map.insert(child_id, placeholder);
-			}
-		}
-
-		drop(element_map);
-
-		self.state.set_root_element_id(global_id);
-		self.state.rebuild_tree(global_id, children);
-		self.state.update_element_tree();
+		self.state.stage(StagedCommit::Root { global_id, children: children.to_vec() });
 	}
 
 	/// Batch update multiple elements from JSON data
-	pub fn batch_update_elements(&self, elements: &serde_json::Value) {
-		let elements_array = elements.as_array().expect("Elements must be an array");
+	/// The batch is validated and parsed here, off the GPUI thread, as a whole
+	/// before anything is staged
+	pub fn batch_update_elements(&self, elements: &serde_json::Value) -> Result<(), String> {
+		let parsed = parse_element_batch(elements)?;
+		self.state.stage(StagedCommit::Batch(parsed));
+		Ok(())
+	}
 
-		{
-			let mut element_map = self
-				.state
-				.element_map
-				.lock()
-				.expect("Failed to acquire element_map lock in batch_update_elements");
-
-			// First pass: create all elements
-			for elem_value in elements_array {
-				if let Some(elem_obj) = elem_value.as_object() {
-					let global_id = elem_obj.get("globalId").and_then(|v| v.as_u64()).unwrap_or(0);
-
-					let element_type =
-						elem_obj.get("type").and_then(|v| v.as_str()).unwrap_or("").to_string();
-
-					let text = elem_obj.get("text").and_then(|v| v.as_str()).map(|s| s.to_string());
-
-					let style = elem_obj.get("style").map(ElementStyle::from_json).unwrap_or_default();
-					if element_type == "canvas" {
-						log::trace!(
-							"canvas element: drawCommands={}",
-							style
-								.draw_commands
-								.as_ref()
-								.map(|v| v.to_string())
-								.unwrap_or_else(|| "None".to_string())
-						);
-					}
+	/// Set individual style props of existing elements
+	/// Patches are merged into the elements' styles when the commit is applied,
+	/// in order with the batches around them
+	pub fn patch_styles(&self, patches: &serde_json::Value) -> Result<(), String> {
+		let parsed = parse_style_patches(patches)?;
+		self.state.stage(StagedCommit::Patch(parsed));
+		Ok(())
+	}
 
-					let event_handlers = elem_obj.get("eventHandlers").cloned();
+	/// Append to or splice the text of text elements (the value of inputs)
+	/// Only the edit crosses FFI; the resulting text is shaped again as a whole
+	pub fn edit_text(&self, edits: &serde_json::Value) -> Result<(), String> {
+		let parsed = parse_text_edits(edits)?;
+		self.state.stage(StagedCommit::Text(parsed));
+		Ok(())
+	}
 
-					// Pre-compute GPUI Style (div and span have no default background)
-					let cached_gpui_style = Some(style.build_gpui_style(None));
+	/// Release deleted elements, whose ids JS may recycle
+	/// They leave the element map once the commit is applied, in order with
+	/// the batches around them
+	pub fn release_elements(&self, ids: &serde_json::Value) -> Result<(), String> {
+		let Some(ids) = ids.as_array() else {
+			return Err("ids must be an array".to_string());
+		};
+		let ids = ids
+			.iter()
+			.map(|id| {
+				id.as_u64().filter(|id| *id != 0).ok_or_else(|| format!("invalid element id {}", id))
+			})
+			.collect::<Result<Vec<u64>, String>>()?;
+		self.state.stage(StagedCommit::Release { window_id: self.window_id, ids });
+		Ok(())
+	}
+}
 
-					let element_kind = ElementKind::from_str(&element_type);
-					let element = Arc::new(ReactElement {
-						global_id,
-						element_type,
-						element_kind,
-						text,
-						children: Vec::new(),
-						style,
-						event_handlers,
-						cached_gpui_style,
-					});
+/// Parse a batch of element updates without touching any window state
+/// Returns each element with its child id list (None if the batch entry
+/// doesn't carry `children`). Fails on a malformed batch instead of guessing.
+pub fn parse_element_batch(
+	elements: &serde_json::Value,
+) -> Result<Vec<(ReactElement, Option<Vec<u64>>)>, String> {
+	let Some(elements_array) = elements.as_array() else {
+		return Err("elements must be an array".to_string());
+	};
+
+	let mut parsed = Vec::with_capacity(elements_array.len());
+	for (index, elem_value) in elements_array.iter().enumerate() {
+		let Some(elem_obj) = elem_value.as_object() else {
+			return Err(format!("element {} is not an object", index));
+		};
+		// Element id 0 is reserved for window-level events
+		let global_id = match elem_obj.get("globalId").and_then(|v| v.as_u64()) {
+			Some(id) if id != 0 => id,
+			_ => return Err(format!("element {} has no valid globalId", index)),
+		};
+
+		let element_type = elem_obj.get("type").and_then(|v| v.as_str()).unwrap_or("").to_string();
+		let text = elem_obj.get("text").and_then(|v| v.as_str()).map(|s| s.to_string());
+		// Identical style props share one parsed style and prebuilt GPUI Style
+		let (style, gpui_style) = style_store::intern(elem_obj.get("style"));
+		let event_handlers = elem_obj.get("eventHandlers").cloned();
+		let cached_gpui_style = Some(gpui_style);
+
+		let children = elem_obj
+			.get("children")
+			.and_then(|v| v.as_array())
+			.map(|arr| arr.iter().filter_map(|c| c.as_u64()).collect());
+
+		let element_kind = ElementKind::from_str(&element_type);
+		parsed.push((
+			ReactElement {
+				global_id,
+				element_type,
+				element_kind,
+				text,
+				children: Vec::new(),
+				style,
+				event_handlers,
+				cached_gpui_style,
+			},
+			children,
+		));
+	}
+	Ok(parsed)
+}
 
-					element_map.insert(global_id, element);
-				}
+/// Parse `[{id, prop, value}]` style patches, grouped by element
+/// A null value removes the prop
+pub fn parse_style_patches(
+	patches: &serde_json::Value,
+) -> Result<Vec<(u64, serde_json::Map<String, serde_json::Value>)>, String> {
+	let Some(patches_array) = patches.as_array() else {
+		return Err("patches must be an array".to_string());
+	};
+
+	let mut grouped: Vec<(u64, serde_json::Map<String, serde_json::Value>)> = Vec::new();
+	for (index, patch) in patches_array.iter().enumerate() {
+		let id = match patch.get("id").and_then(|v| v.as_u64()) {
+			Some(id) if id != 0 => id,
+			_ => return Err(format!("patch {} has no valid id", index)),
+		};
+		let Some(prop) = patch.get("prop").and_then(|v| v.as_str()) else {
+			return Err(format!("patch {} has no prop", index));
+		};
+		let value = patch.get("value").cloned().unwrap_or(serde_json::Value::Null);
+
+		match grouped.iter_mut().find(|(patched, _)| *patched == id) {
+			Some((_, props)) => {
+				props.insert(prop.to_string(), value);
 			}
+			None => grouped.push((id, serde_json::Map::from_iter([(prop.to_string(), value)]))),
+		}
+	}
+	Ok(grouped)
+}
 
-			// Second pass: update children references
-			for elem_value in elements_array {
-				if let Some(elem_obj) = elem_value.as_object() {
-					if let Some(global_id) = elem_obj.get("globalId").and_then(|v| v.as_u64()) {
-						if let Some(children_arr) = elem_obj.get("children").and_then(|v| v.as_array()) {
-							let children_ids: Vec<u64> = children_arr.iter().filter_map(|c| c.as_u64()).collect();
-
-							let mut child_refs: Vec<Arc<ReactElement>> = Vec::new();
-
-							for &cid in &children_ids {
-								if let Some(child) = element_map.get(&cid) {
-									child_refs.push(child.clone());
-								}
-							}
-
-							if let Some(element) = element_map.get_mut(&global_id) {
-								let element_mut = Arc::make_mut(element);
-								element_mut.children = child_refs;
-							}
-						}
-					}
-				}
-			}
-		} // Drop element_map lock before calling update_element_tree
+/// An edit to an element's text, with offsets in UTF-16 code units as in JS
+pub struct TextEdit {
+	pub id:     u64,
+	/// Where the edit starts; None appends
+	pub start:  Option<usize>,
+	/// Code units removed from `start`
+	pub delete: usize,
+	pub text:   String,
+}
 
-		// Rebuild the element tree with updated elements
-		self.state.update_element_tree();
+impl TextEdit {
+	/// Apply the edit to `target`, failing if its offsets are out of range or
+	/// split a character
+	pub fn apply(&self, target: &mut String) -> Result<(), String> {
+		let Some(start) = self.start else {
+			target.push_str(&self.text);
+			return Ok(());
+		};
+		let len = target.encode_utf16().count();
+		let end = start.checked_add(self.delete).filter(|end| *end <= len).ok_or_else(|| {
+			format!("edit of {} at {} is out of range for length {}", self.delete, start, len)
+		})?;
+		let from = utf16_to_byte(target, start)
+			.ok_or_else(|| format!("start {} is not a character boundary", start))?;
+		let to = utf16_to_byte(target, end)
+			.ok_or_else(|| format!("end {} is not a character boundary", end))?;
+		target.replace_range(from..to, &self.text);
+		Ok(())
 	}
 }
 
+/// Byte offset of a UTF-16 offset into `text`
+fn utf16_to_byte(text: &str, offset: usize) -> Option<usize> {
+	let mut units = 0;
+	for (index, ch) in text.char_indices() {
+		if units == offset {
+			return Some(index);
+		}
+		units += ch.len_utf16();
+	}
+	(units == offset).then_some(text.len())
+}
+
+/// Parse `[{id, text, start?, deleteCount?}]` text edits, kept in order
+/// Without `start` the text is appended
+pub fn parse_text_edits(edits: &serde_json::Value) -> Result<Vec<TextEdit>, String> {
+	let Some(edits_array) = edits.as_array() else {
+		return Err("edits must be an array".to_string());
+	};
+
+	let mut parsed = Vec::with_capacity(edits_array.len());
+	for (index, edit) in edits_array.iter().enumerate() {
+		let id = match edit.get("id").and_then(|v| v.as_u64()) {
+			Some(id) if id != 0 => id,
+			_ => return Err(format!("edit {} has no valid id", index)),
+		};
+		let Some(text) = edit.get("text").and_then(|v| v.as_str()) else {
+			return Err(format!("edit {} has no text", index));
+		};
+		parsed.push(TextEdit {
+			id,
+			start: edit.get("start").and_then(|v| v.as_u64()).map(|v| v as usize),
+			delete: edit.get("deleteCount").and_then(|v| v.as_u64()).unwrap_or(0) as usize,
+			text: text.to_string(),
+		});
+	}
+	Ok(parsed)
+}
+
+/// A commit received from JS, waiting for the next apply
+enum StagedCommit {
+	Batch(Vec<(ReactElement, Option<Vec<u64>>)>),
+	Patch(Vec<(u64, serde_json::Map<String, serde_json::Value>)>),
+	Text(Vec<TextEdit>),
+	Release { window_id: u64, ids: Vec<u64> },
+	Root { global_id: u64, children: Vec<u64> },
+}
+
 pub struct WindowState {
 	pub root_element_id: AtomicU64,
 	pub element_map:     Mutex<HashMap<u64, Arc<ReactElement>>>,
 	pub element_tree:    Arc<Mutex<Option<Arc<ReactElement>>>>,
+	/// Tree built from applied commits, published when the next frame starts
+	prepared_tree:       Mutex<Option<Arc<ReactElement>>>,
 	pub render_count:    AtomicU64,
+	/// Bumped every time a new element tree snapshot is published
+	pub tree_generation: AtomicU64,
 	/// Event queue for JS polling (thread-safe)
 	pub event_queue:     Mutex<VecDeque<EventMessage>>,
+	/// Element event types JS wants queued (None: all)
+	event_mask:          Mutex<Option<HashSet<String>>>,
+	/// Commits received since the last apply, applied together off the GPUI
+	/// thread
+	staged:              Mutex<Vec<StagedCommit>>,
+	/// A frame has been requested for the staged commits
+	commit_scheduled:    AtomicBool,
+	/// Minimum time between frames that apply commits, in microseconds
+	frame_budget_us:     AtomicU64,
+	last_commit:         Mutex<Option<Instant>>,
 }
 
 impl WindowState {
 	pub fn new() -> Self {
 		Self {
-			root_element_id: AtomicU64::new(0),
-			element_map:     Mutex::new(HashMap::new()),
-			element_tree:    Arc::new(Mutex::new(None)),
-			render_count:    AtomicU64::new(0),
-			event_queue:     Mutex::new(VecDeque::new()),
+			root_element_id:  AtomicU64::new(0),
+			element_map:      Mutex::new(HashMap::new()),
+			element_tree:     Arc::new(Mutex::new(None)),
+			prepared_tree:    Mutex::new(None),
+			render_count:     AtomicU64::new(0),
+			tree_generation:  AtomicU64::new(0),
+			event_queue:      Mutex::new(VecDeque::new()),
+			event_mask:       Mutex::new(None),
+			staged:           Mutex::new(Vec::new()),
+			commit_scheduled: AtomicBool::new(false),
+			frame_budget_us:  AtomicU64::new(0),
+			last_commit:      Mutex::new(None),
 		}
 	}
 
+	/// Queue a commit for the next apply
+	/// If frames stall (e.g. a hidden window), commits are applied right away
+	/// once too many are waiting. Their tree is then published by the apply
+	/// already scheduled for the first of them, at its frame
+	fn stage(&self, commit: StagedCommit) {
+		let mut staged = self.staged.lock_recover();
+		staged.push(commit);
+		if staged.len() > MAX_STAGED_COMMITS {
+			let staged = std::mem::take(&mut *staged);
+			self.apply(staged);
+		}
+	}
+
+	/// Number of commits waiting for the next apply
+	pub fn staged_commits(&self) -> usize { self.staged.lock_recover().len() }
+
+	/// Returns true if the caller should schedule an apply of the staged
+	/// commits, false if one is already on its way
+	pub fn schedule_commit(&self) -> bool { !self.commit_scheduled.swap(true, Ordering::SeqCst) }
+
+	/// Merge commits from JS for at most one frame per `budget` (zero: every
+	/// frame)
+	pub fn set_frame_budget(&self, budget: Duration) {
+		self.frame_budget_us.store(budget.as_micros() as u64, Ordering::SeqCst);
+	}
+
+	/// Time left before staged commits may be applied, None once the frame
+	/// budget since the last applied commit has elapsed
+	pub fn commit_wait(&self) -> Option<Duration> {
+		let budget = Duration::from_micros(self.frame_budget_us.load(Ordering::SeqCst));
+		let elapsed = (*self.last_commit.lock_recover())?.elapsed();
+		budget.checked_sub(elapsed).filter(|wait| !wait.is_zero())
+	}
+
+	/// Apply all staged commits and prepare the new tree
+	/// Runs on a background thread (see HostCommand::ApplyCommits), never in a
+	/// frame; commits staged from here on schedule the next apply. The tree is
+	/// published by publish_prepared_tree when the next frame starts
+	pub fn apply_staged(&self) {
+		let mut staged = self.staged.lock_recover();
+		let commits = std::mem::take(&mut *staged);
+		self.commit_scheduled.store(false, Ordering::SeqCst);
+		if !commits.is_empty() {
+			self.apply(commits);
+		}
+	}
+
+	/// Apply commits in order, then rebuild the tree once
+	/// Called with the staging lock held so commits never apply out of order.
+	/// Patches that change nothing prepare no tree
+	fn apply(&self, commits: Vec<StagedCommit>) {
+		let mut changed = false;
+		for commit in commits {
+			match commit {
+				StagedCommit::Batch(parsed) => self.apply_batch(parsed),
+				StagedCommit::Patch(patches) => {
+					changed |= self.apply_patches(patches);
+					continue;
+				}
+				StagedCommit::Text(edits) => {
+					changed |= self.apply_text_edits(edits);
+					continue;
+				}
+				// Released elements are already out of the tree
+				StagedCommit::Release { window_id, ids } => {
+					self.apply_release(window_id, ids);
+					continue;
+				}
+				StagedCommit::Root { global_id, children } => self.apply_root(global_id, &children),
+			}
+			changed = true;
+		}
+		if changed {
+			self.update_element_tree();
+		}
+		*self.last_commit.lock_recover() = Some(Instant::now());
+	}
+
+	fn apply_batch(&self, parsed: Vec<(ReactElement, Option<Vec<u64>>)>) {
+		let mut element_map = self.element_map.lock_recover();
+
+		// First pass: create all elements
+		let mut children = Vec::with_capacity(parsed.len());
+		for (element, children_ids) in parsed {
+			if element.element_kind == ElementKind::Canvas {
+				log::trace!(
+					"canvas element: drawCommands={}",
+					element
+						.style
+						.draw_commands
+						.as_ref()
+						.map(|v| v.to_string())
+						.unwrap_or_else(|| "None".to_string())
+				);
+			}
+			if let Some(children_ids) = children_ids {
+				children.push((element.global_id, children_ids));
+			}
+			element_map.insert(element.global_id, Arc::new(element));
+		}
+
+		// Second pass: update children references
+		for (global_id, children_ids) in children {
+			let child_refs: Vec<Arc<ReactElement>> =
+				children_ids.iter().filter_map(|cid| element_map.get(cid).cloned()).collect();
+
+			if let Some(element) = element_map.get_mut(&global_id) {
+				let element_mut = Arc::make_mut(element);
+				element_mut.children = child_refs;
+			}
+		}
+	}
+
+	/// Returns true if any element's style changed
+	fn apply_patches(&self, patches: Vec<(u64, serde_json::Map<String, serde_json::Value>)>) -> bool {
+		let mut element_map = self.element_map.lock_recover();
+		let mut changed = false;
+		for (id, props) in patches {
+			let Some(element) = element_map.get_mut(&id) else {
+				log::warn!("patch_styles: element {} not found", id);
+				continue;
+			};
+			let (style, gpui_style) = style_store::patch(&element.style, &props);
+			// Same shared style: the patch set props to the values they had
+			if Arc::ptr_eq(&style, &element.style) {
+				continue;
+			}
+			let element = Arc::make_mut(element);
+			element.style = style;
+			element.cached_gpui_style = Some(gpui_style);
+			changed = true;
+		}
+		changed
+	}
+
+	/// Returns true if any text changed
+	fn apply_text_edits(&self, edits: Vec<TextEdit>) -> bool {
+		let mut element_map = self.element_map.lock_recover();
+		let mut changed = false;
+		for edit in edits {
+			let Some(element) = element_map.get_mut(&edit.id) else {
+				log::warn!("edit_text: element {} not found", edit.id);
+				continue;
+			};
+
+			// An input's or textarea's value is a style prop; patch it like one
+			if matches!(element.element_kind, ElementKind::Input | ElementKind::TextArea) {
+				let mut value = element.style.value.clone().unwrap_or_default();
+				if let Err(e) = edit.apply(&mut value) {
+					log::warn!("edit_text: skipping edit of input {}: {}", edit.id, e);
+					continue;
+				}
+				let props = serde_json::Map::from_iter([("value".to_string(), value.into())]);
+				let (style, gpui_style) = style_store::patch(&element.style, &props);
+				let element = Arc::make_mut(element);
+				element.style = style;
+				element.cached_gpui_style = Some(gpui_style);
+			} else {
+				let element = Arc::make_mut(element);
+				let text = element.text.get_or_insert_with(String::new);
+				if let Err(e) = edit.apply(text) {
+					log::warn!("edit_text: skipping edit of element {}: {}", edit.id, e);
+					continue;
+				}
+			}
+			changed = true;
+		}
+		changed
+	}
+
+	fn apply_release(&self, window_id: u64, ids: Vec<u64>) {
+		{
+			let mut element_map = self.element_map.lock_recover();
+			for id in &ids {
+				element_map.remove(id);
+			}
+		}
+		// Outside the map lock: some of this state is locked before the map
+		for id in &ids {
+			element::forget_element(window_id, *id);
+		}
+		// Queued after every event of the released elements, and none follow
+		// (dispatch drops events of elements not in the map), so JS may hand
+		// the ids to new elements once it sees this
+		crate::renderer::dispatch_window_event(window_id, "released", serde_json::json!({ "ids": ids }));
+	}
+
+	fn apply_root(&self, global_id: u64, children: &[u64]) {
+		// Elements come from the batches applied before this; anything missing
+		// gets a placeholder
+		let mut element_map = self.element_map.lock_recover();
+		for &child_id in children {
+			if !element_map.contains_key(&child_id) {
+				let placeholder = Arc::new(ReactElement {
+					global_id:         child_id,
+					element_type:      "placeholder".to_string(),
+					element_kind:      ElementKind::Unknown,
+					text:              None,
+					children:          Vec::new(),
+					style:             Arc::new(ElementStyle::default()),
+					event_handlers:    None,
+					cached_gpui_style: None,
+				});
+				element_map.insert(child_id, placeholder);
+			}
+		}
+		drop(element_map);
+
+		self.set_root_element_id(global_id);
+		self.rebuild_tree(global_id, children);
+	}
+
 	/// Push an event to the queue
+	/// Queue an event behind the held events of its window that are due
 	pub fn push_event(&self, event: EventMessage) {
-		if let Ok(mut queue) = self.event_queue.lock() {
-			queue.push_back(event);
-		}
+		let due = rate_limit::take_due(event.window_id);
+		let mut queue = self.event_queue.lock_recover();
+		queue.extend(due);
+		queue.push_back(event);
+	}
+
+	/// Queue the held events of the window that are due
+	pub fn push_due_events(&self, window_id: u64) {
+		let due = rate_limit::take_due(window_id);
+		self.event_queue.lock_recover().extend(due);
+	}
+
+	/// Queue only element events of these types from now on (None: all)
+	pub fn set_event_mask(&self, event_types: Option<HashSet<String>>) {
+		*self.event_mask.lock_recover() = event_types;
+	}
+
+	/// Whether element events of this type are queued at all
+	pub fn wants_event(&self, event_type: &str) -> bool {
+		self.event_mask.lock_recover().as_ref().is_none_or(|mask| mask.contains(event_type))
 	}
 
 	/// Drain all events from the queue
 	pub fn drain_events(&self) -> Vec<EventMessage> {
-		if let Ok(mut queue) = self.event_queue.lock() { queue.drain(..).collect() } else { Vec::new() }
+		self.event_queue.lock_recover().drain(..).collect()
 	}
 
 	pub fn get_root_element_id(&self) -> u64 { self.root_element_id.load(Ordering::SeqCst) }
@@ -212,8 +580,10 @@ impl WindowState {
 
 	pub fn increment_render_count(&self) -> u64 { self.render_count.fetch_add(1, Ordering::SeqCst) }
 
+	pub fn get_tree_generation(&self) -> u64 { self.tree_generation.load(Ordering::SeqCst) }
+
 	pub fn rebuild_tree(&self, root_id: u64, children: &[u64]) {
-		let element_map = self.element_map.lock().expect("Failed to acquire element_map lock");
+		let element_map = self.element_map.lock_recover();
 
 		if let Some(_root) = element_map.get(&root_id) {
 			let child_elements: Vec<Arc<ReactElement>> =
@@ -221,8 +591,7 @@ impl WindowState {
 
 			drop(element_map);
 
-			let mut element_map =
-				self.element_map.lock().expect("Failed to acquire element_map lock (second)");
+			let mut element_map = self.element_map.lock_recover();
 			if let Some(root) = element_map.get_mut(&root_id) {
 				let root_mut = Arc::make_mut(root);
 				root_mut.children = child_elements;
@@ -232,15 +601,17 @@ impl WindowState {
 		}
 	}
 
+	/// Build the element tree from the element map, to be published when the
+	/// next frame starts
 	pub fn update_element_tree(&self) {
-		let mut tree = self.element_tree.lock().expect("Failed to acquire element_tree lock");
+		let mut tree = self.prepared_tree.lock_recover();
 
 		let root_id = self.get_root_element_id();
 		if root_id == 0 {
 			return;
 		}
 
-		let element_map = self.element_map.lock().expect("Failed to acquire element_map lock");
+		let element_map = self.element_map.lock_recover();
 
 		if let Some(root) = element_map.get(&root_id) {
 			let mut new_tree = (**root).clone();
@@ -248,30 +619,150 @@ impl WindowState {
 			fn update_children(
 				element: &mut ReactElement,
 				element_map: &HashMap<u64, Arc<ReactElement>>,
+				ancestors: &mut Vec<u64>,
 			) {
 				let children_ids: Vec<u64> =
 					element.children.iter().filter_map(|c| Some(c.global_id)).collect();
 
+				ancestors.push(element.global_id);
 				let mut new_children = Vec::new();
 				for &cid in &children_ids {
+					// A malformed batch can make an element its own ancestor
+					if ancestors.contains(&cid) || ancestors.len() >= MAX_TREE_DEPTH {
+						log::error!("update_element_tree: dropping cyclic or too deep child {}", cid);
+						continue;
+					}
 					if let Some(child) = element_map.get(&cid) {
 						let mut child_clone = (**child).clone();
-						update_children(&mut child_clone, element_map);
+						update_children(&mut child_clone, element_map, ancestors);
 						new_children.push(Arc::new(child_clone));
 					}
 				}
+				ancestors.pop();
 
 				if !new_children.is_empty() {
 					element.children = new_children;
 				}
 			}
 
-			update_children(&mut new_tree, &element_map);
+			update_children(&mut new_tree, &element_map, &mut Vec::new());
 			*tree = Some(Arc::new(new_tree));
 		}
 	}
+
+	/// Make the prepared tree the one frames render, called from a frame
+	/// callback before the frame is drawn
+	/// Returns false if no tree was prepared since the last publish
+	pub fn publish_prepared_tree(&self) -> bool {
+		let Some(prepared) = self.prepared_tree.lock_recover().take() else {
+			return false;
+		};
+		*self.element_tree.lock_recover() = Some(prepared);
+		self.tree_generation.fetch_add(1, Ordering::SeqCst);
+		true
+	}
 }
 
 impl Default for WindowState {
 	fn default() -> Self { Self::new() }
 }
+
+#[cfg(test)]
+mod tests {
+	use serde_json::json;
+
+	use super::*;
+
+	fn stage_tree(state: &WindowState, root: u64, children: &[u64]) {
+		let mut batch = vec![json!({ "globalId": root, "type": "div", "children": children })];
+		batch.extend(children.iter().map(|id| json!({ "globalId": id, "type": "div" })));
+		state.stage(StagedCommit::Batch(parse_element_batch(&json!(batch)).unwrap()));
+		state.stage(StagedCommit::Root { global_id: root, children: children.to_vec() });
+	}
+
+	#[test]
+	fn applied_commits_wait_for_the_frame() {
+		let state = WindowState::new();
+		stage_tree(&state, 2, &[3, 4]);
+		state.apply_staged();
+		// Prepared off the GPUI thread, not yet visible to frames
+		assert!(state.element_tree.lock_recover().is_none());
+		assert_eq!(state.get_tree_generation(), 0);
+
+		assert!(state.publish_prepared_tree());
+		let tree = state.element_tree.lock_recover().clone().unwrap();
+		assert_eq!(tree.global_id, 2);
+		assert_eq!(tree.children.iter().map(|c| c.global_id).collect::<Vec<_>>(), [3, 4]);
+		assert_eq!(state.get_tree_generation(), 1);
+		// Nothing new to publish at the following frame
+		assert!(!state.publish_prepared_tree());
+		assert_eq!(state.get_tree_generation(), 1);
+	}
+
+	#[test]
+	fn overflowing_commits_are_published_at_the_frame() {
+		let state = WindowState::new();
+		stage_tree(&state, 2, &[3]);
+		for _ in 0..MAX_STAGED_COMMITS - 1 {
+			state.stage(StagedCommit::Root { global_id: 2, children: vec![3] });
+		}
+		// Applied inline once too many waited
+		assert_eq!(state.staged_commits(), 0);
+		assert!(state.element_tree.lock_recover().is_none());
+		// The apply scheduled for the first commit finds nothing staged, and
+		// still publishes the inline apply's tree
+		state.apply_staged();
+		assert!(state.publish_prepared_tree());
+		assert_eq!(state.element_tree.lock_recover().as_ref().map(|t| t.global_id), Some(2));
+	}
+
+	fn edit(start: Option<usize>, delete: usize, text: &str) -> TextEdit {
+		TextEdit { id: 2, start, delete, text: text.to_string() }
+	}
+
+	fn applied(target: &str, edit: TextEdit) -> Result<String, String> {
+		let mut target = target.to_string();
+		edit.apply(&mut target).map(|_| target)
+	}
+
+	#[test]
+	fn utf16_offsets_map_to_char_boundaries() {
+		// "a😀b": the emoji is a surrogate pair, 2 units and 4 bytes
+		let text = "a\u{1F600}b";
+		assert_eq!(utf16_to_byte(text, 0), Some(0));
+		assert_eq!(utf16_to_byte(text, 1), Some(1));
+		assert_eq!(utf16_to_byte(text, 2), None);
+		assert_eq!(utf16_to_byte(text, 3), Some(5));
+		assert_eq!(utf16_to_byte(text, 4), Some(6));
+		assert_eq!(utf16_to_byte(text, 5), None);
+		assert_eq!(utf16_to_byte("", 0), Some(0));
+		assert_eq!(utf16_to_byte("", 1), None);
+	}
+
+	#[test]
+	fn text_edits_splice_at_the_ends() {
+		assert_eq!(applied("hello", edit(None, 0, "!")), Ok("hello!".into()));
+		assert_eq!(applied("hello", edit(Some(0), 0, ">")), Ok(">hello".into()));
+		assert_eq!(applied("hello", edit(Some(5), 0, "!")), Ok("hello!".into()));
+		assert_eq!(applied("hello", edit(Some(0), 1, "J")), Ok("Jello".into()));
+		assert_eq!(applied("hello", edit(Some(4), 1, "")), Ok("hell".into()));
+		assert_eq!(applied("", edit(Some(0), 0, "a")), Ok("a".into()));
+	}
+
+	#[test]
+	fn text_edits_respect_surrogate_pairs() {
+		let text = "a\u{1F600}b";
+		assert_eq!(applied(text, edit(Some(1), 2, "")), Ok("ab".into()));
+		assert_eq!(applied(text, edit(Some(3), 0, "\u{1F601}")), Ok("a\u{1F600}\u{1F601}b".into()));
+		// Either end inside the pair
+		assert!(applied(text, edit(Some(2), 1, "")).is_err());
+		assert!(applied(text, edit(Some(1), 1, "")).is_err());
+	}
+
+	#[test]
+	fn text_edits_past_the_end_fail() {
+		assert!(applied("hello", edit(Some(6), 0, "!")).is_err());
+		assert!(applied("hello", edit(Some(3), 3, "")).is_err());
+		assert!(applied("hello", edit(Some(usize::MAX), 1, "")).is_err());
+	}
+}
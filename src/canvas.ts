@@ -3,6 +3,15 @@ export interface CanvasClearCommand {
     color: string;
 }
 
+/** Erases a rectangle to the canvas background */
+export interface CanvasClearRectCommand {
+    type: "clearRect";
+    x: number;
+    y: number;
+    width: number;
+    height: number;
+}
+
 export interface CanvasFillRectCommand {
     type: "fillRect";
     x: number;
@@ -46,19 +55,58 @@ export interface CanvasPathCommand {
     color: string;
 }
 
+/** Scales the image at `src` (a file path or an http(s) URL) into a rect */
+export interface CanvasDrawImageCommand {
+    type: "drawImage";
+    src: string;
+    x: number;
+    y: number;
+    width: number;
+    height: number;
+}
+
+export interface CanvasGradientStop {
+    /** Position along the gradient, 0 to 1 */
+    offset: number;
+    color: string;
+}
+
+/** Fills a rect with a linear gradient */
+export interface CanvasGradientRectCommand {
+    type: "gradientRect";
+    x: number;
+    y: number;
+    width: number;
+    height: number;
+    stops: CanvasGradientStop[];
+    /** CSS degrees: 0 runs to the top, 90 to the right; 180 (to the bottom) by default */
+    angle?: number;
+}
+
 export type CanvasDrawCommand =
     | CanvasClearCommand
+    | CanvasClearRectCommand
     | CanvasFillRectCommand
     | CanvasCircleCommand
     | CanvasLineCommand
     | CanvasTextCommand
-    | CanvasPathCommand;
+    | CanvasPathCommand
+    | CanvasDrawImageCommand
+    | CanvasGradientRectCommand;
 
 export interface CanvasProps {
     width: number;
     height: number;
     backgroundColor?: string;
     drawCommands?: CanvasDrawCommand[];
+    /**
+     * Keep what was drawn: each new `batch` adds its `drawCommands` on top of
+     * the earlier ones instead of replacing them. "clear" and "clearRect"
+     * drop the commands they paint over.
+     */
+    retained?: boolean;
+    /** Changes whenever `drawCommands` holds a new set to add, for retained canvases */
+    batch?: number;
     style?: React.CSSProperties;
     onMouseDown?: (event: MouseEvent) => void;
     onMouseMove?: (event: MouseEvent) => void;
@@ -80,10 +128,25 @@ export function createDrawCommands(commands: CanvasDrawCommand[]): string {
     return JSON.stringify(commands);
 }
 
+/**
+ * Colors of draw commands are CSS strings, read like style colors: hex with
+ * optional alpha ("#rgb", "#rgba", "#rrggbb", "#rrggbbaa"), rgb()/rgba(),
+ * hsl()/hsla(), named colors or "transparent". Translucent colors blend with
+ * what is painted below.
+ */
 export function clear(color: string): CanvasClearCommand {
     return { type: "clear", color };
 }
 
+export function clearRect(
+    x: number,
+    y: number,
+    width: number,
+    height: number
+): CanvasClearRectCommand {
+    return { type: "clearRect", x, y, width, height };
+}
+
 export function fillRect(
     x: number,
     y: number,
@@ -122,3 +185,24 @@ export function text(
 export function path(points: [number, number][], width: number, color: string): CanvasPathCommand {
     return { type: "path", points, width, color };
 }
+
+export function drawImage(
+    src: string,
+    x: number,
+    y: number,
+    width: number,
+    height: number
+): CanvasDrawImageCommand {
+    return { type: "drawImage", src, x, y, width, height };
+}
+
+export function gradientRect(
+    x: number,
+    y: number,
+    width: number,
+    height: number,
+    stops: CanvasGradientStop[],
+    angle?: number
+): CanvasGradientRectCommand {
+    return { type: "gradientRect", x, y, width, height, stops, angle };
+}
@@ -0,0 +1,20 @@
+import { rustLib } from "./core";
+import type { ClockReading } from "./core";
+
+export type { ClockReading };
+
+/** Current reading of the clock event timestamps use */
+export function getClock(): ClockReading {
+    return rustLib.getClock();
+}
+
+/** Microseconds elapsed since an event timestamp, e.g. for input latency */
+export function elapsedSince(timestamp: number): number {
+    return getClock().nowUs - timestamp;
+}
+
+/** Wall-clock date of an event timestamp */
+export function timestampToDate(timestamp: number): Date {
+    const { epochUnixUs } = getClock();
+    return new Date((epochUnixUs + timestamp) / 1000);
+}
@@ -1,4 +1,5 @@
-import { dlopen, FFIType } from "bun:ffi";
+import { dlopen } from "bun:ffi";
+import { FFI_SYMBOLS } from "./generated";
 import { info } from "../utils/logging";
 import { getNativeLibPath } from "../utils/plat";
 
@@ -9,41 +10,5 @@ import { getNativeLibPath } from "../utils/plat";
 const libPath = getNativeLibPath(import.meta.dir);
 info(`Loading GPUI library from: ${libPath}`);
 
-export const lib = dlopen(libPath, {
-    gpui_init: { args: [FFIType.ptr], returns: FFIType.void },
-    gpui_free_event_string: {
-        args: [FFIType.ptr],
-        returns: FFIType.void,
-    },
-    gpui_poll_events: {
-        args: [FFIType.ptr],
-        returns: FFIType.ptr,
-    },
-    gpui_create_window: {
-        args: [FFIType.ptr, FFIType.ptr],
-        returns: FFIType.void,
-    },
-    gpui_is_ready: { args: [], returns: FFIType.bool },
-    gpui_render_frame: {
-        args: [
-            FFIType.ptr,
-            FFIType.ptr,
-            FFIType.ptr,
-            FFIType.ptr,
-            FFIType.ptr,
-            FFIType.ptr,
-            FFIType.ptr,
-        ],
-        returns: FFIType.void,
-    },
-    gpui_trigger_render: { args: [FFIType.ptr, FFIType.ptr], returns: FFIType.void },
-    gpui_free_result: { args: [FFIType.ptr], returns: FFIType.void },
-    gpui_batch_update_elements: {
-        args: [FFIType.ptr, FFIType.ptr, FFIType.ptr, FFIType.ptr],
-        returns: FFIType.void,
-    },
-    gpui_get_input_value: {
-        args: [FFIType.ptr, FFIType.ptr],
-        returns: FFIType.ptr,
-    },
-});
+// Symbols are generated from rust/src/lib.rs (cargo run --bin codegen)
+export const lib = dlopen(libPath, FFI_SYMBOLS);
@@ -0,0 +1,247 @@
+/**
+ * Auto-generated protocol definitions - DO NOT EDIT
+ * Generated by: cargo run --bin codegen
+ * Source: rust/src/lib.rs, rust/src/element/mod.rs
+ */
+
+import { FFIType } from "bun:ffi";
+
+/** Symbols exported by the native library, for dlopen */
+export const FFI_SYMBOLS = {
+    gpui_init: { args: [FFIType.ptr], returns: FFIType.void },
+    gpui_init_with_options: { args: [FFIType.ptr, FFIType.ptr], returns: FFIType.void },
+    gpui_shutdown: { args: [], returns: FFIType.ptr },
+    gpui_create_window: { args: [FFIType.ptr, FFIType.ptr], returns: FFIType.void },
+    gpui_render_frame: {
+        args: [
+            FFIType.ptr,
+            FFIType.ptr,
+            FFIType.ptr,
+            FFIType.ptr,
+            FFIType.ptr,
+            FFIType.ptr,
+            FFIType.ptr,
+        ],
+        returns: FFIType.void,
+    },
+    gpui_trigger_render: { args: [FFIType.ptr, FFIType.ptr], returns: FFIType.void },
+    gpui_batch_update_elements: {
+        args: [FFIType.ptr, FFIType.ptr, FFIType.ptr, FFIType.ptr],
+        returns: FFIType.void,
+    },
+    gpui_patch_styles: { args: [FFIType.ptr, FFIType.ptr, FFIType.ptr], returns: FFIType.void },
+    gpui_edit_text: { args: [FFIType.ptr, FFIType.ptr, FFIType.ptr], returns: FFIType.void },
+    gpui_release_elements: { args: [FFIType.ptr, FFIType.ptr, FFIType.ptr], returns: FFIType.void },
+    gpui_free_result: { args: [FFIType.ptr], returns: FFIType.void },
+    gpui_free_window_result: { args: [FFIType.ptr], returns: FFIType.void },
+    gpui_is_ready: { args: [], returns: FFIType.bool },
+    gpui_free_event_string: { args: [FFIType.ptr], returns: FFIType.void },
+    gpui_poll_events: { args: [FFIType.ptr], returns: FFIType.ptr },
+    gpui_get_input_value: { args: [FFIType.ptr, FFIType.ptr], returns: FFIType.ptr },
+    gpui_find: { args: [FFIType.ptr, FFIType.ptr, FFIType.ptr], returns: FFIType.ptr },
+    gpui_find_next: { args: [FFIType.ptr, FFIType.ptr, FFIType.bool], returns: FFIType.ptr },
+    gpui_push_sparkline: {
+        args: [FFIType.ptr, FFIType.ptr, FFIType.ptr, FFIType.u32],
+        returns: FFIType.void,
+    },
+    gpui_get_layout_snapshot: { args: [FFIType.ptr], returns: FFIType.ptr },
+    gpui_get_focus_state: { args: [FFIType.ptr], returns: FFIType.ptr },
+    gpui_format_shortcut: { args: [FFIType.ptr], returns: FFIType.ptr },
+    gpui_get_resource_stats: { args: [FFIType.ptr], returns: FFIType.ptr },
+    gpui_set_privacy_options: { args: [FFIType.ptr, FFIType.ptr], returns: FFIType.void },
+    gpui_set_font_fallbacks: { args: [FFIType.ptr, FFIType.ptr], returns: FFIType.void },
+    gpui_register_font: { args: [FFIType.ptr, FFIType.ptr], returns: FFIType.void },
+    gpui_set_hit_test_debug: { args: [FFIType.ptr, FFIType.bool], returns: FFIType.void },
+    gpui_set_hud: { args: [FFIType.ptr, FFIType.bool], returns: FFIType.void },
+    gpui_set_max_fps: { args: [FFIType.ptr, FFIType.f32], returns: FFIType.void },
+    gpui_set_power_saver: { args: [FFIType.bool], returns: FFIType.void },
+    gpui_set_live_resize: { args: [FFIType.ptr, FFIType.u32], returns: FFIType.void },
+    gpui_set_safe_area_insets: {
+        args: [FFIType.ptr, FFIType.ptr, FFIType.ptr],
+        returns: FFIType.void,
+    },
+    gpui_set_event_mask: { args: [FFIType.ptr, FFIType.ptr, FFIType.ptr], returns: FFIType.void },
+    gpui_get_clock: { args: [], returns: FFIType.ptr },
+    gpui_set_latency_tracing: { args: [FFIType.bool], returns: FFIType.void },
+    gpui_get_latency_stats: { args: [FFIType.ptr], returns: FFIType.ptr },
+    gpui_set_key_repeat: { args: [FFIType.ptr, FFIType.ptr], returns: FFIType.void },
+    gpui_set_input_settings: { args: [FFIType.ptr, FFIType.ptr], returns: FFIType.void },
+    gpui_set_watchdog: { args: [FFIType.ptr, FFIType.ptr], returns: FFIType.void },
+    gpui_set_paint_tracking: { args: [FFIType.ptr, FFIType.bool], returns: FFIType.void },
+} as const;
+
+/** Element types the renderer implements; others render as unknown */
+export const ELEMENT_KINDS = [
+    "canvas",
+    "div",
+    "input",
+    "span",
+    "text",
+    "img",
+    "shaderview",
+    "document",
+    "textarea",
+    "checkbox",
+    "radio",
+    "slider",
+    "progress",
+    "overlay",
+    "tooltip",
+    "resizer",
+    "gauge",
+    "sparkline",
+    "qrcode",
+    "avatar",
+    "code",
+    "richtext",
+    "skeleton",
+    "button",
+    "icon",
+] as const;
+
+export type ElementKind = (typeof ELEMENT_KINDS)[number];
+
+/** Keys of the element style object the renderer reads */
+export const STYLE_KEYS = [
+    "hoverStyle",
+    "focusStyle",
+    "activeStyle",
+    "importantStyle",
+    "textColor",
+    "textSize",
+    "fontWeight",
+    "fontFamily",
+    "lineHeight",
+    "textAlign",
+    "letterSpacing",
+    "fontFeatureSettings",
+    "writingMode",
+    "elideMode",
+    "cursor",
+    "visibility",
+    "shapeRendering",
+    "bgColor",
+    "width",
+    "height",
+    "minWidth",
+    "maxWidth",
+    "minHeight",
+    "maxHeight",
+    "aspectRatio",
+    "marginTop",
+    "marginRight",
+    "marginBottom",
+    "marginLeft",
+    "paddingTop",
+    "paddingRight",
+    "paddingBottom",
+    "paddingLeft",
+    "position",
+    "top",
+    "right",
+    "bottom",
+    "left",
+    "overflowX",
+    "overflowY",
+    "contentVisibility",
+    "containIntrinsicWidth",
+    "containIntrinsicHeight",
+    "borderTopWidth",
+    "borderRightWidth",
+    "borderBottomWidth",
+    "borderLeftWidth",
+    "borderStyle",
+    "borderTopStyle",
+    "borderRightStyle",
+    "borderBottomStyle",
+    "borderLeftStyle",
+    "borderColor",
+    "borderTopColor",
+    "borderRightColor",
+    "borderBottomColor",
+    "borderLeftColor",
+    "borderRadius",
+    "outlineWidth",
+    "outlineColor",
+    "outlineOffset",
+    "outlineStyle",
+    "boxShadowOffsetX",
+    "boxShadowOffsetY",
+    "boxShadowBlur",
+    "boxShadowSpread",
+    "boxShadowColor",
+    "elevation",
+    "elevationTint",
+    "display",
+    "flexDirection",
+    "flexWrap",
+    "flexGrow",
+    "flexShrink",
+    "flexBasis",
+    "justifyContent",
+    "alignItems",
+    "alignSelf",
+    "alignContent",
+    "gap",
+    "rowGap",
+    "columnGap",
+    "opacity",
+    "src",
+    "alt",
+    "drawCommands",
+    "canvasRetained",
+    "canvasBatch",
+    "x",
+    "y",
+    "tabIndex",
+    "value",
+    "placeholder",
+    "inputType",
+    "disabled",
+    "readOnly",
+    "maxLength",
+    "multiLine",
+    "rows",
+    "cols",
+    "lineNumbers",
+    "selectionColor",
+    "captureTab",
+    "tabSize",
+    "indentWithTabs",
+    "checked",
+    "name",
+    "accentColor",
+    "min",
+    "max",
+    "step",
+    "gaugeSweep",
+    "gaugeTicks",
+    "gaugeNeedle",
+    "sparklineCapacity",
+    "sparklineInterval",
+    "symbology",
+    "qrEcLevel",
+    "quietZone",
+    "logoSize",
+    "avatarStatus",
+    "text",
+    "codeLanguage",
+    "richRuns",
+    "skeletonVariant",
+    "iconCodepoint",
+    "iconSize",
+    "orientation",
+    "backdrop",
+    "tooltipDelay",
+    "tooltipPlacement",
+    "shader",
+    "shaderParams",
+    "animate",
+    "eventOptions",
+    "privacyMode",
+    "trackPaint",
+    "liveRegion",
+    "inert",
+] as const;
+
+export type StyleKey = (typeof STYLE_KEYS)[number];
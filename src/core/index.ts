@@ -1,6 +1,29 @@
 import { RustLib } from "./rust";
 
-export type { ElementData, WindowOptions } from "./rust";
+export type {
+    ClockReading,
+    ElementData,
+    FindResult,
+    FocusState,
+    InitOptions,
+    InputSettings,
+    KeyRepeatOptions,
+    LatencyPercentiles,
+    LatencyStats,
+    LatencyTrace,
+    LayoutSnapshotNode,
+    NativeWindowOptions,
+    PrivacyOptions,
+    ResourceStats,
+    SafeAreaInsets,
+    SplashElement,
+    StylePatch,
+    TextEdit,
+    WatchdogOptions,
+    WindowEventData,
+    WindowOptions,
+} from "./rust";
+export type { ElementKind, StyleKey } from "./generated";
 
 const rustLib = new RustLib();
 
@@ -2,7 +2,7 @@ import { lib } from "./ffi";
 import { peek, sleep } from "bun";
 import { ptr, read, toArrayBuffer, CString } from "bun:ffi";
 import { info, trace } from "../utils/logging";
-import { decoder, FfiState } from "./ffi-state";
+import { decoder, encoder, FfiState } from "./ffi-state";
 import { EventEmitter } from "events";
 import { eventRouter, createEvent, RawEventData } from "../events";
 
@@ -15,6 +15,199 @@ export interface ElementData {
     eventHandlers?: Record<string, number>;
 }
 
+/** One style prop of an element set in place; a null value removes the prop */
+export interface StylePatch {
+    id: number;
+    prop: string;
+    value: unknown;
+}
+
+/**
+ * An edit to an element's text (an input's value). Offsets are in UTF-16 code
+ * units; without `start` the text is appended.
+ */
+export interface TextEdit {
+    id: number;
+    text: string;
+    start?: number;
+    deleteCount?: number;
+}
+
+/** Match count and current match index of a document search */
+export interface FindResult {
+    count: number;
+    current: number | null;
+}
+
+/** One element of a layout snapshot, as resolved in the last painted frame */
+export interface LayoutSnapshotNode {
+    id: number;
+    type: string;
+    bounds: { x: number; y: number; width: number; height: number } | null;
+    style: Record<string, string | number>;
+    children: LayoutSnapshotNode[];
+}
+
+/** Focus state of a window, for focus utilities and keyboard navigation tests */
+export interface FocusState {
+    windowId: number;
+    focused: number | null;
+    /** Elements Tab visits, in order */
+    tabOrder: number[];
+    /** Every element with a tab index: Tab order first, then negative indices by id */
+    elements: {
+        id: number;
+        /** Null if the element is no longer in the tree */
+        type: string | null;
+        tabIndex: number;
+        /** Enclosing overlay, null in the main tree */
+        group: number | null;
+    }[];
+}
+
+/** Reading of the monotonic clock event timestamps use */
+export interface ClockReading {
+    /** Microseconds since the clock's epoch, comparable with event timestamps */
+    nowUs: number;
+    /** Wall-clock time of the epoch, in microseconds since the Unix epoch */
+    epochUnixUs: number;
+}
+
+/** Percentiles of one latency stage, in microseconds */
+export interface LatencyPercentiles {
+    p50: number;
+    p90: number;
+    p99: number;
+    max: number;
+}
+
+/** Input latency of a window's recent key and mouse button events */
+export interface LatencyStats {
+    windowId: number;
+    enabled: boolean;
+    /** Events traced from queue to paint */
+    samples: number;
+    /** Events still waiting on a poll, commit or paint */
+    pending: number;
+    /** Percentiles are null until an event has been traced */
+    queueToPollUs: LatencyPercentiles | null;
+    pollToCommitUs: LatencyPercentiles | null;
+    commitToPaintUs: LatencyPercentiles | null;
+    totalUs: LatencyPercentiles | null;
+    /** Slowest of the traced events, null until an event has been traced */
+    slowest: LatencyTrace | null;
+}
+
+/** Stage durations of one traced event, in microseconds */
+export interface LatencyTrace {
+    /** The `traceId` in the event's payload */
+    traceId: number;
+    queueToPollUs: number;
+    pollToCommitUs: number;
+    commitToPaintUs: number;
+    totalUs: number;
+}
+
+/** Resource usage of a window and the process, for performance panels */
+export interface ResourceStats {
+    windowId: number;
+    /** Elements known to Rust, including unmounted ones not yet released */
+    elements: number;
+    /** Elements reachable from the root */
+    treeElements: number;
+    queuedEvents: number;
+    stagedCommits: number;
+    renderCount: number;
+    /** Distinct style objects shared by elements, across all windows */
+    uniqueStyles: number;
+    textCache: {
+        entries: number;
+        hits: number;
+        misses: number;
+        evictions: number;
+        hitRate: number;
+    };
+    /** CPU time in milliseconds, null where the platform doesn't report it */
+    cpu: {
+        processMs: number | null;
+        gpuiThreadMs: number | null;
+        callerThreadMs: number | null;
+    };
+}
+
+/**
+ * Scheduling of the GPUI thread, for embedders with realtime threads of their
+ * own. Read from the environment when the library loads:
+ * - GPUI_THREAD_PRIORITY: "low", "normal" or "high" (nice value on Linux, QoS
+ *   class on macOS)
+ * - GPUI_CPU_AFFINITY: comma-separated CPU indices to pin the thread to (Linux)
+ */
+export interface InitOptions {
+    threadPriority?: "low" | "normal" | "high";
+    cpuAffinity?: number[];
+}
+
+function initOptionsFromEnv(): InitOptions {
+    const options: InitOptions = {};
+    const priority = process.env.GPUI_THREAD_PRIORITY?.toLowerCase();
+    if (priority === "low" || priority === "normal" || priority === "high") {
+        options.threadPriority = priority;
+    }
+    const affinity = process.env.GPUI_CPU_AFFINITY?.split(",")
+        .map((cpu) => Number.parseInt(cpu.trim(), 10))
+        .filter((cpu) => Number.isInteger(cpu) && cpu >= 0);
+    if (affinity?.length) {
+        options.cpuAffinity = affinity;
+    }
+    return options;
+}
+
+/** Redaction of user content, see setPrivacyOptions */
+export interface PrivacyOptions {
+    /** Redact the content of every element in Rust logs, not only private ones */
+    redactLogs?: boolean;
+    /** Redact key/value/data of events from privacyMode elements before JS sees them */
+    redactEvents?: boolean;
+}
+
+/** GPUI thread hang detection, see setWatchdog */
+export interface WatchdogOptions {
+    /** Longest a frame stage or a queued command may take, 0 turns the watchdog off */
+    deadlineMs?: number;
+    /** Record a backtrace at every stage entry and report it with hangs */
+    captureStack?: boolean;
+}
+
+/** Pointer input thresholds, see setInputSettings */
+export interface InputSettings {
+    /** Longest gap between the clicks of a double click, default from the OS or 500 */
+    doubleClickMs?: number;
+    /** Pointer travel in pixels that starts a drag, default from the OS or 10 */
+    dragDistance?: number;
+    /** Factor applied to wheel deltas, default 1 */
+    scrollMultiplier?: number;
+    /** Flip wheel deltas, overriding the OS natural-scrolling direction */
+    invertScroll?: boolean;
+}
+
+/** Key repeat behavior, see setKeyRepeat */
+export interface KeyRepeatOptions {
+    /** Synthesize repeats of editing keys (backspace, delete, arrows, home/end, page up/down) */
+    synthesize?: boolean;
+    /** Delay before the first synthesized repeat, default 500 */
+    delayMs?: number;
+    /** Synthesized repeats per second, default 30 */
+    rate?: number;
+}
+
+/** Insets of a window's content covered by window chrome, in pixels */
+export interface SafeAreaInsets {
+    top: number;
+    right: number;
+    bottom: number;
+    left: number;
+}
+
 const RESULT_SIZE = 16;
 
 export interface WindowOptions {
@@ -25,21 +218,76 @@ export interface WindowOptions {
     y?: number;
     resizable?: boolean;
     fullscreen?: boolean;
+    /** Enable the built-in Ctrl+F / Cmd+F find overlay */
+    findOverlay?: boolean;
+    /**
+     * Apply commits at most once per this many milliseconds, merging every
+     * commit made in between. By default commits are applied as soon as possible.
+     */
+    frameBudgetMs?: number;
+    /**
+     * Linux application id (Wayland app_id, X11 WM_CLASS). Use the basename of
+     * the app's `.desktop` file so the desktop groups and themes its windows.
+     */
+    appId?: string;
+    /** Extend content under a transparent titlebar and hide the title (macOS) */
+    titlebarTransparent?: boolean;
+    /** Position of the macOS traffic lights from the window's top left corner */
+    trafficLightPosition?: { x: number; y: number };
+    /** Window background, painted from the first frame instead of the default dark one */
+    backgroundColor?: string;
+    /** Shown natively until React's first commit, then faded out */
+    splash?: SplashElement;
+}
+
+/**
+ * An element of a window's splash tree, e.g. a centered `div` holding a logo
+ * `img` and an indeterminate `progress`. `props` are the element's JSX props
+ * (style, src, ...); event handlers are ignored.
+ */
+export interface SplashElement {
+    type: string;
+    props?: Record<string, any>;
+    text?: string;
+    children?: SplashElement[];
 }
 
+/** WindowOptions as sent to Rust, with the color and splash styles mapped */
+export type NativeWindowOptions = Omit<WindowOptions, "backgroundColor" | "splash"> & {
+    backgroundColor?: number;
+    splash?: Record<string, unknown>;
+};
+
+/** Raw payload of a window-level event (elementId 0) */
+export type WindowEventData = Record<string, any> & {
+    windowId: number;
+    eventType: string;
+    timestamp: number;
+};
+
 export class RustLib {
     ffiStateMap: Map<number, FfiState>;
     private pollIntervals: Map<number, ReturnType<typeof setInterval>> = new Map();
+    private windowEvents = new EventEmitter();
 
     public constructor() {
         this.ffiStateMap = new Map();
+        this.init();
+    }
+
+    /**
+     * Start the GPUI thread, a no-op while it is running
+     * Called by the constructor, and again to restart after shutdown()
+     */
+    public init(): void {
         const resultBuffer = new Uint8Array(RESULT_SIZE);
-        lib.symbols.gpui_init(resultBuffer);
+        const optionsBuffer = encoder.encode(JSON.stringify(initOptionsFromEnv()) + "\0");
+        lib.symbols.gpui_init_with_options(ptr(optionsBuffer), resultBuffer);
         this.checkResult(resultBuffer);
         this.waitReady();
     }
 
-    public createWindow(options: WindowOptions, pollEventInterval?: number): number {
+    public createWindow(options: NativeWindowOptions, pollEventInterval?: number): number {
         const resultBuffer = new Uint8Array(RESULT_SIZE);
         const ffiState = new FfiState();
         const [optionsBuffer, optionsPtr] = ffiState.encodeCString(JSON.stringify(options));
@@ -71,6 +319,54 @@ export class RustLib {
         trace(`Batch update completed`);
     }
 
+    /** Set style props of existing elements without re-sending the elements */
+    public patchStyles(windowId: number, patches: StylePatch[]): void {
+        let ffiState = this.getFfiState(windowId);
+        if (!ffiState) {
+            return;
+        }
+
+        if (patches.length === 0) return;
+        trace(`Patching ${patches.length} style props for window ${windowId}`);
+        ffiState.clear();
+        const [windowIdBuffer, windowIdPtr] = ffiState.createInt64(BigInt(windowId));
+        const [patchesBuffer, patchesPtr] = ffiState.encodeCString(JSON.stringify(patches));
+        const resultBuffer = new Uint8Array(8);
+        lib.symbols.gpui_patch_styles(windowIdPtr, patchesPtr, resultBuffer);
+    }
+
+    /** Append to or splice element text without re-sending the elements */
+    public editText(windowId: number, edits: TextEdit[]): void {
+        let ffiState = this.getFfiState(windowId);
+        if (!ffiState) {
+            return;
+        }
+
+        if (edits.length === 0) return;
+        trace(`Applying ${edits.length} text edits for window ${windowId}`);
+        ffiState.clear();
+        const [windowIdBuffer, windowIdPtr] = ffiState.createInt64(BigInt(windowId));
+        const [editsBuffer, editsPtr] = ffiState.encodeCString(JSON.stringify(edits));
+        const resultBuffer = new Uint8Array(8);
+        lib.symbols.gpui_edit_text(windowIdPtr, editsPtr, resultBuffer);
+    }
+
+    /** Release deleted elements, whose ids may be recycled for new ones */
+    public releaseElements(windowId: number, ids: number[]): void {
+        let ffiState = this.getFfiState(windowId);
+        if (!ffiState) {
+            return;
+        }
+
+        if (ids.length === 0) return;
+        trace(`Releasing ${ids.length} elements for window ${windowId}`);
+        ffiState.clear();
+        const [windowIdBuffer, windowIdPtr] = ffiState.createInt64(BigInt(windowId));
+        const [idsBuffer, idsPtr] = ffiState.encodeCString(JSON.stringify(ids));
+        const resultBuffer = new Uint8Array(8);
+        lib.symbols.gpui_release_elements(windowIdPtr, idsPtr, resultBuffer);
+    }
+
     public renderFrame(windowId: number, element: ElementData): void {
         let ffiState = this.getFfiState(windowId);
         if (!ffiState) {
@@ -145,12 +441,7 @@ export class RustLib {
 
             const events = JSON.parse(jsonStr) as RawEventData[];
 
-            // Process each event
-            for (const rawEvent of events) {
-                const { elementId, eventType } = rawEvent;
-                const gpuiEvent = createEvent(rawEvent);
-                eventRouter.dispatchToHandler(elementId, eventType, gpuiEvent);
-            }
+            this.routeEvents(events);
         } catch (err) {
             console.error("[JS] Event polling error:", err);
         } finally {
@@ -159,6 +450,61 @@ export class RustLib {
         }
     }
 
+    /** Dispatch raw events to element handlers and window-level listeners */
+    private routeEvents(events: RawEventData[]): void {
+        for (const rawEvent of events) {
+            const { windowId, elementId, eventType } = rawEvent;
+            // Window-level events aren't tied to an element
+            if (elementId === 0) {
+                this.windowEvents.emit(`${windowId}:${eventType}`, rawEvent);
+                continue;
+            }
+            const gpuiEvent = createEvent(rawEvent);
+            eventRouter.dispatchToHandler(elementId, eventType, gpuiEvent);
+        }
+    }
+
+    /**
+     * Close every window and stop the GPUI thread
+     * Events still queued, ending with a "shutdown" window event per window,
+     * are dispatched before this returns. Call init() to start again.
+     */
+    public shutdown(): void {
+        for (const windowId of [...this.pollIntervals.keys()]) {
+            this.stopEventPolling(windowId);
+        }
+        this.ffiStateMap.clear();
+
+        const eventsPtr = lib.symbols.gpui_shutdown();
+        if (!eventsPtr) {
+            return;
+        }
+        try {
+            const events = JSON.parse(new CString(eventsPtr).toString()) as RawEventData[];
+            this.routeEvents(events);
+        } catch (err) {
+            console.error("[JS] Shutdown event error:", err);
+        } finally {
+            lib.symbols.gpui_free_event_string(eventsPtr);
+        }
+    }
+
+    /**
+     * Listen for a window-level event (e.g. "findresult")
+     * Returns a function that removes the listener
+     */
+    public onWindowEvent(
+        windowId: number,
+        eventType: string,
+        listener: (event: WindowEventData) => void
+    ): () => void {
+        const key = `${windowId}:${eventType}`;
+        this.windowEvents.on(key, listener);
+        return () => {
+            this.windowEvents.off(key, listener);
+        };
+    }
+
     /**
      * Start periodic event polling for a window
      */
@@ -217,6 +563,387 @@ export class RustLib {
         }
     }
 
+    /**
+     * Search a document element and highlight all matches
+     * The first match becomes current and is scrolled into view
+     */
+    public find(windowId: number, elementId: number, query: string): FindResult | null {
+        let ffiState = this.getFfiState(windowId);
+        if (!ffiState) {
+            return null;
+        }
+
+        ffiState.clear();
+        const [windowIdBuffer, windowIdPtr] = ffiState.createInt64(BigInt(windowId));
+        const [elementIdBuffer, elementIdPtr] = ffiState.createInt64(BigInt(elementId));
+        const [queryBuffer, queryPtr] = ffiState.encodeCString(query);
+        return this.readFindResult(lib.symbols.gpui_find(windowIdPtr, elementIdPtr, queryPtr));
+    }
+
+    /**
+     * Move to the next (or previous) match of the last search
+     */
+    public findNext(windowId: number, elementId: number, backwards = false): FindResult | null {
+        let ffiState = this.getFfiState(windowId);
+        if (!ffiState) {
+            return null;
+        }
+
+        ffiState.clear();
+        const [windowIdBuffer, windowIdPtr] = ffiState.createInt64(BigInt(windowId));
+        const [elementIdBuffer, elementIdPtr] = ffiState.createInt64(BigInt(elementId));
+        return this.readFindResult(
+            lib.symbols.gpui_find_next(windowIdPtr, elementIdPtr, backwards)
+        );
+    }
+
+    /**
+     * Append values to a sparkline's ring buffer, as raw f64s rather than JSON
+     */
+    public pushSparkline(
+        windowId: number,
+        elementId: number,
+        values: Float64Array | number[]
+    ): void {
+        let ffiState = this.getFfiState(windowId);
+        if (!ffiState) {
+            return;
+        }
+
+        const data = values instanceof Float64Array ? values : Float64Array.from(values);
+        if (data.length === 0) return;
+        ffiState.clear();
+        const [windowIdBuffer, windowIdPtr] = ffiState.createInt64(BigInt(windowId));
+        const [elementIdBuffer, elementIdPtr] = ffiState.createInt64(BigInt(elementId));
+        lib.symbols.gpui_push_sparkline(windowIdPtr, elementIdPtr, ptr(data), data.length);
+    }
+
+    /**
+     * Get the resolved layout tree of a window (for snapshot tests and tooling)
+     */
+    public getLayoutSnapshot(windowId: number): LayoutSnapshotNode | null {
+        let ffiState = this.getFfiState(windowId);
+        if (!ffiState) {
+            return null;
+        }
+
+        ffiState.clear();
+        const [windowIdBuffer, windowIdPtr] = ffiState.createInt64(BigInt(windowId));
+        const snapshotPtr = lib.symbols.gpui_get_layout_snapshot(windowIdPtr);
+
+        if (!snapshotPtr) {
+            return null;
+        }
+
+        try {
+            return JSON.parse(new CString(snapshotPtr).toString()) as LayoutSnapshotNode;
+        } catch (err) {
+            console.error("[JS] getLayoutSnapshot error:", err);
+            return null;
+        } finally {
+            lib.symbols.gpui_free_event_string(snapshotPtr);
+        }
+    }
+
+    /**
+     * Get the focused element, Tab order and focus groups of a window
+     */
+    public getFocusState(windowId: number): FocusState | null {
+        let ffiState = this.getFfiState(windowId);
+        if (!ffiState) {
+            return null;
+        }
+
+        ffiState.clear();
+        const [windowIdBuffer, windowIdPtr] = ffiState.createInt64(BigInt(windowId));
+        const statePtr = lib.symbols.gpui_get_focus_state(windowIdPtr);
+
+        if (!statePtr) {
+            return null;
+        }
+
+        try {
+            return JSON.parse(new CString(statePtr).toString()) as FocusState;
+        } catch (err) {
+            console.error("[JS] getFocusState error:", err);
+            return null;
+        } finally {
+            lib.symbols.gpui_free_event_string(statePtr);
+        }
+    }
+
+    public setPrivacyOptions(options: PrivacyOptions): void {
+        const resultBuffer = new Uint8Array(RESULT_SIZE);
+        const optionsBuffer = encoder.encode(JSON.stringify(options) + "\0");
+        lib.symbols.gpui_set_privacy_options(ptr(optionsBuffer), resultBuffer);
+        this.checkResult(resultBuffer);
+    }
+
+    public setKeyRepeat(options: KeyRepeatOptions): void {
+        const resultBuffer = new Uint8Array(RESULT_SIZE);
+        const optionsBuffer = encoder.encode(JSON.stringify(options) + "\0");
+        lib.symbols.gpui_set_key_repeat(ptr(optionsBuffer), resultBuffer);
+        this.checkResult(resultBuffer);
+    }
+
+    public setInputSettings(options: InputSettings): void {
+        const resultBuffer = new Uint8Array(RESULT_SIZE);
+        const optionsBuffer = encoder.encode(JSON.stringify(options) + "\0");
+        lib.symbols.gpui_set_input_settings(ptr(optionsBuffer), resultBuffer);
+        this.checkResult(resultBuffer);
+    }
+
+    public setWatchdog(options: WatchdogOptions): void {
+        const resultBuffer = new Uint8Array(RESULT_SIZE);
+        const optionsBuffer = encoder.encode(JSON.stringify(options) + "\0");
+        lib.symbols.gpui_set_watchdog(ptr(optionsBuffer), resultBuffer);
+        this.checkResult(resultBuffer);
+    }
+
+    public setFontFallbacks(families: string[]): void {
+        const resultBuffer = new Uint8Array(RESULT_SIZE);
+        const familiesBuffer = encoder.encode(JSON.stringify(families) + "\0");
+        lib.symbols.gpui_set_font_fallbacks(ptr(familiesBuffer), resultBuffer);
+        this.checkResult(resultBuffer);
+    }
+
+    /**
+     * Load a font file so elements can use its family, blocking until it is loaded
+     */
+    public registerFont(path: string): void {
+        const resultBuffer = new Uint8Array(RESULT_SIZE);
+        const pathBuffer = encoder.encode(path + "\0");
+        lib.symbols.gpui_register_font(ptr(pathBuffer), resultBuffer);
+        this.checkResult(resultBuffer);
+    }
+
+    /**
+     * Queue a "hittest" window event on every mouse release, describing the
+     * element stack under the cursor
+     */
+    public setHitTestDebug(windowId: number, enabled: boolean): void {
+        let ffiState = this.getFfiState(windowId);
+        if (!ffiState) {
+            return;
+        }
+
+        ffiState.clear();
+        const [windowIdBuffer, windowIdPtr] = ffiState.createInt64(BigInt(windowId));
+        lib.symbols.gpui_set_hit_test_debug(windowIdPtr, enabled);
+    }
+
+    /**
+     * Show or hide the developer HUD (fps, frame time, elements, queued events,
+     * commits) in the window's top-left corner
+     */
+    public setHud(windowId: number, enabled: boolean): void {
+        let ffiState = this.getFfiState(windowId);
+        if (!ffiState) {
+            return;
+        }
+
+        ffiState.clear();
+        const [windowIdBuffer, windowIdPtr] = ffiState.createInt64(BigInt(windowId));
+        lib.symbols.gpui_set_hud(windowIdPtr, enabled);
+    }
+
+    /**
+     * Cap the frame rate of a window's continuous animations (0: no cap)
+     */
+    public setMaxFps(windowId: number, fps: number): void {
+        let ffiState = this.getFfiState(windowId);
+        if (!ffiState) {
+            return;
+        }
+
+        ffiState.clear();
+        const [windowIdBuffer, windowIdPtr] = ffiState.createInt64(BigInt(windowId));
+        lib.symbols.gpui_set_max_fps(windowIdPtr, Math.max(0, fps));
+    }
+
+    /**
+     * Run animations of every window at 15fps at most and stop decorative ones
+     */
+    public setPowerSaver(enabled: boolean): void {
+        lib.symbols.gpui_set_power_saver(enabled);
+    }
+
+    /**
+     * Lay a window's tree out at most once per `intervalMs` while the window is
+     * being resized, keeping the last layout size in between (0: every frame)
+     */
+    public setLiveResize(windowId: number, intervalMs: number): void {
+        let ffiState = this.getFfiState(windowId);
+        if (!ffiState) {
+            return;
+        }
+
+        ffiState.clear();
+        const [windowIdBuffer, windowIdPtr] = ffiState.createInt64(BigInt(windowId));
+        lib.symbols.gpui_set_live_resize(windowIdPtr, Math.max(0, Math.round(intervalMs)));
+    }
+
+    /**
+     * Declare the chrome the app draws over a window's content; the window's
+     * safe area takes the larger of these and the native chrome's insets
+     */
+    public setSafeAreaInsets(windowId: number, insets: Partial<SafeAreaInsets>): void {
+        let ffiState = this.getFfiState(windowId);
+        if (!ffiState) {
+            return;
+        }
+
+        ffiState.clear();
+        const [windowIdBuffer, windowIdPtr] = ffiState.createInt64(BigInt(windowId));
+        const [insetsBuffer, insetsPtr] = ffiState.encodeCString(JSON.stringify(insets));
+        const resultBuffer = new Uint8Array(RESULT_SIZE);
+        lib.symbols.gpui_set_safe_area_insets(windowIdPtr, insetsPtr, resultBuffer);
+        this.checkResult(resultBuffer);
+    }
+
+    /**
+     * Queue only element events of these types for a window (null: all types)
+     */
+    public setEventMask(windowId: number, eventTypes: string[] | null): void {
+        let ffiState = this.getFfiState(windowId);
+        if (!ffiState) {
+            return;
+        }
+
+        ffiState.clear();
+        const [windowIdBuffer, windowIdPtr] = ffiState.createInt64(BigInt(windowId));
+        const [typesBuffer, typesPtr] = ffiState.encodeCString(JSON.stringify(eventTypes));
+        const resultBuffer = new Uint8Array(RESULT_SIZE);
+        lib.symbols.gpui_set_event_mask(windowIdPtr, typesPtr, resultBuffer);
+        this.checkResult(resultBuffer);
+    }
+
+    /**
+     * Queue "paintchanged" window events for trackPaint elements whose
+     * rendering inputs changed after a commit
+     */
+    public setPaintTracking(windowId: number, enabled: boolean): void {
+        let ffiState = this.getFfiState(windowId);
+        if (!ffiState) {
+            return;
+        }
+
+        ffiState.clear();
+        const [windowIdBuffer, windowIdPtr] = ffiState.createInt64(BigInt(windowId));
+        lib.symbols.gpui_set_paint_tracking(windowIdPtr, enabled);
+    }
+
+    /**
+     * Get resource usage counters of a window, to spot leaks in long sessions
+     */
+    public getResourceStats(windowId: number): ResourceStats | null {
+        let ffiState = this.getFfiState(windowId);
+        if (!ffiState) {
+            return null;
+        }
+
+        ffiState.clear();
+        const [windowIdBuffer, windowIdPtr] = ffiState.createInt64(BigInt(windowId));
+        const statsPtr = lib.symbols.gpui_get_resource_stats(windowIdPtr);
+
+        if (!statsPtr) {
+            return null;
+        }
+
+        try {
+            return JSON.parse(new CString(statsPtr).toString()) as ResourceStats;
+        } catch (err) {
+            console.error("[JS] getResourceStats error:", err);
+            return null;
+        } finally {
+            lib.symbols.gpui_free_event_string(statsPtr);
+        }
+    }
+
+    /**
+     * Trace key and mouse button events from queue to paint, for every window
+     */
+    public setLatencyTracing(enabled: boolean): void {
+        lib.symbols.gpui_set_latency_tracing(enabled);
+    }
+
+    /**
+     * Get input latency percentiles of a window
+     */
+    public getLatencyStats(windowId: number): LatencyStats | null {
+        let ffiState = this.getFfiState(windowId);
+        if (!ffiState) {
+            return null;
+        }
+
+        ffiState.clear();
+        const [windowIdBuffer, windowIdPtr] = ffiState.createInt64(BigInt(windowId));
+        const statsPtr = lib.symbols.gpui_get_latency_stats(windowIdPtr);
+
+        if (!statsPtr) {
+            return null;
+        }
+
+        try {
+            return JSON.parse(new CString(statsPtr).toString()) as LatencyStats;
+        } catch (err) {
+            console.error("[JS] getLatencyStats error:", err);
+            return null;
+        } finally {
+            lib.symbols.gpui_free_event_string(statsPtr);
+        }
+    }
+
+    /**
+     * Read the monotonic clock event timestamps are taken from
+     */
+    public getClock(): ClockReading {
+        const clockPtr = lib.symbols.gpui_get_clock();
+        if (!clockPtr) {
+            throw new Error("Failed to read clock");
+        }
+
+        try {
+            return JSON.parse(new CString(clockPtr).toString()) as ClockReading;
+        } finally {
+            lib.symbols.gpui_free_event_string(clockPtr);
+        }
+    }
+
+    /**
+     * Format a shortcut like "cmd-shift-p" the way this platform displays it
+     * ("⇧⌘P" on macOS, "Ctrl+Shift+P" elsewhere); null if it doesn't parse
+     */
+    public formatShortcut(shortcut: string): string | null {
+        const shortcutBuffer = encoder.encode(shortcut + "\0");
+        const displayPtr = lib.symbols.gpui_format_shortcut(ptr(shortcutBuffer));
+
+        if (!displayPtr) {
+            return null;
+        }
+
+        try {
+            return new CString(displayPtr).toString();
+        } finally {
+            lib.symbols.gpui_free_event_string(displayPtr);
+        }
+    }
+
+    private readFindResult(resultPtr: ReturnType<typeof ptr> | null): FindResult | null {
+        if (!resultPtr) {
+            return null;
+        }
+
+        try {
+            return JSON.parse(new CString(resultPtr).toString()) as FindResult;
+        } catch (err) {
+            console.error("[JS] find result error:", err);
+            return null;
+        } finally {
+            lib.symbols.gpui_free_event_string(resultPtr);
+        }
+    }
+
     getFfiState(windowId: number) {
         return this.ffiStateMap.get(windowId);
     }
@@ -0,0 +1,24 @@
+import { rustLib } from "./core";
+import type { FindResult } from "./core";
+import type { ElementStore } from "./reconciler/element-store";
+
+export type { FindResult };
+
+/** What a `ref` on a `<document>` element resolves to */
+export interface DocumentRef {
+    id: number;
+    store: ElementStore;
+}
+
+/**
+ * Search a `<document>` element for `query` (case-insensitive) and highlight
+ * every match. Passing an empty query clears the highlights.
+ */
+export function findInDocument(doc: DocumentRef, query: string): FindResult | null {
+    return rustLib.find(doc.store.getWindowId(), doc.id, query);
+}
+
+/** Move to the next (or previous) match of the last search */
+export function findNextInDocument(doc: DocumentRef, backwards = false): FindResult | null {
+    return rustLib.findNext(doc.store.getWindowId(), doc.id, backwards);
+}
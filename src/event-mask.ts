@@ -0,0 +1,26 @@
+import { useEffect } from "react";
+import { rustLib } from "./core";
+import { useAppContext } from "./reconciler/ctx";
+
+/**
+ * Queue only element events of these types (e.g. `["click", "keydown"]`) for
+ * a window; pass null to queue every type again. Masked events are dropped in
+ * Rust before they are queued, so handlers for them never run. Window-level
+ * events are not affected.
+ */
+export function setEventMask(windowId: number, eventTypes: string[] | null): void {
+    rustLib.setEventMask(windowId, eventTypes);
+}
+
+/**
+ * Mask element events of this component's window while it is mounted
+ */
+export function useEventMask(eventTypes: string[] | null): void {
+    const { windowId } = useAppContext();
+    const key = eventTypes ? JSON.stringify(eventTypes) : null;
+
+    useEffect(() => {
+        rustLib.setEventMask(windowId, key ? JSON.parse(key) : null);
+        return () => rustLib.setEventMask(windowId, null);
+    }, [windowId, key]);
+}
@@ -45,7 +45,10 @@ export interface GPUIBaseEvent {
     /** Window ID this event belongs to */
     readonly windowId: number;
 
-    /** Event timestamp in milliseconds (Unix epoch) */
+    /**
+     * When Rust queued the event, in microseconds on a monotonic clock (see
+     * `getClock` to compare with now or convert to a date)
+     */
     readonly timestamp: number;
 
     /** Current propagation phase */
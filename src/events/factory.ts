@@ -16,6 +16,10 @@ export interface RawEventData {
     windowId: number;
     elementId: number;
     eventType: string;
+    /** Microseconds on the renderer's monotonic clock */
+    timestamp?: number;
+    /** Set on key and mouse button events while latency tracing is on */
+    traceId?: number;
     // Mouse event data
     clientX?: number;
     clientY?: number;
@@ -26,6 +30,7 @@ export interface RawEventData {
     code?: string;
     key?: string;
     repeat?: boolean;
+    repeatCount?: number;
     // Focus event data
     relatedTarget?: number | null;
     // Scroll/Wheel event data
@@ -45,6 +50,7 @@ export interface RawEventData {
     data?: string | null;
     inputType?: string;
     isComposing?: boolean;
+    checked?: boolean | null;
 }
 
 /**
@@ -65,7 +71,7 @@ export function createEvent(raw: RawEventData): GPUIEvent {
         target: raw.elementId,
         currentTarget: raw.elementId,
         windowId: raw.windowId,
-        timestamp: Date.now(),
+        timestamp: raw.timestamp ?? 0,
         phase: "target" as EventPhase,
         get propagationStopped() {
             return methods.propagationStopped;
@@ -100,6 +106,7 @@ export function createEvent(raw: RawEventData): GPUIEvent {
             code: raw.code ?? "",
             key: raw.key ?? "",
             repeat: raw.repeat ?? false,
+            repeatCount: raw.repeatCount ?? 0,
             modifiers,
         };
         return keyboardEvent;
@@ -149,6 +156,7 @@ export function createEvent(raw: RawEventData): GPUIEvent {
             data: raw.data ?? null,
             inputType: raw.inputType ?? "insertText",
             isComposing: raw.isComposing ?? false,
+            checked: raw.checked ?? null,
         };
         return inputEvent;
     }
@@ -178,6 +186,8 @@ function isMouseEventType(type: string): type is MouseEventType {
         "mouseenter",
         "mouseleave",
         "hover",
+        "longpress",
+        "dismiss",
     ].includes(type);
 }
 
@@ -14,6 +14,8 @@ export type GPUIEventType =
     | "mouseenter"
     | "mouseleave"
     | "hover"
+    | "longpress"
+    | "dismiss"
     | "keydown"
     | "keyup"
     | "keypress"
@@ -37,6 +39,8 @@ export type GPUIEventPropName =
     | "onMouseEnter"
     | "onMouseLeave"
     | "onHover"
+    | "onLongPress"
+    | "onDismiss"
     | "onKeyDown"
     | "onKeyUp"
     | "onKeyPress"
@@ -58,6 +62,8 @@ export const EVENT_PROP_TO_TYPE = {
     onMouseEnter: "mouseenter",
     onMouseLeave: "mouseleave",
     onHover: "hover",
+    onLongPress: "longpress",
+    onDismiss: "dismiss",
     onKeyDown: "keydown",
     onKeyUp: "keyup",
     onKeyPress: "keypress",
@@ -80,6 +86,8 @@ export const EVENT_TYPE_TO_PROP = {
     mouseenter: "onMouseEnter",
     mouseleave: "onMouseLeave",
     hover: "onHover",
+    longpress: "onLongPress",
+    dismiss: "onDismiss",
     keydown: "onKeyDown",
     keyup: "onKeyUp",
     keypress: "onKeyPress",
@@ -107,6 +115,8 @@ export const MOUSE_EVENT_TYPES = [
     "mouseenter",
     "mouseleave",
     "hover",
+    "longpress",
+    "dismiss",
 ] as const;
 
 /** Keyboard event types */
@@ -145,6 +155,7 @@ export interface RawKeyboardEventData extends RawEventDataBase {
     key: string;
     code: string;
     repeat: boolean;
+    repeatCount: number;
     ctrlKey: boolean;
     shiftKey: boolean;
     altKey: boolean;
@@ -169,6 +180,7 @@ export interface RawInputEventData extends RawEventDataBase {
     data: string | null | undefined;
     inputType: string;
     isComposing: boolean;
+    checked: boolean | null | undefined;
 }
 
 /** All raw event data types */
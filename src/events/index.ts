@@ -45,6 +45,8 @@ export type {
     GPUIEventHandler,
     AnyGPUIEventHandler,
     GPUIEventHandlerProps,
+    GPUIEventOptions,
+    GPUIEventRateOptions,
     GPUIEventType,
     GPUIEventPropName,
 } from "./types";
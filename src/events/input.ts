@@ -26,6 +26,9 @@ export interface GPUIInputEvent extends GPUIBaseEvent {
 
     /** Whether the event is part of an IME composition */
     readonly isComposing: boolean;
+
+    /** New state of a checkbox or radio, null for other elements */
+    readonly checked: boolean | null;
 }
 
 /**
@@ -45,7 +48,8 @@ export type InputTypeValue =
     | "deleteByCut"
     | "deleteByDrag"
     | "historyUndo"
-    | "historyRedo";
+    | "historyRedo"
+    | "formatOutdent";
 
 /**
  * Type guard: Check if event is an input event
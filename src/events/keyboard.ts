@@ -29,6 +29,9 @@ export interface GPUIKeyboardEvent extends GPUIBaseEvent {
     /** Whether this is a repeat event (key held down) */
     readonly repeat: boolean;
 
+    /** 0 for the press, then 1, 2, ... for each repeat while the key is held */
+    readonly repeatCount: number;
+
     /** Modifier keys state at the time of the event */
     readonly modifiers: ModifierKeys;
 }
@@ -25,7 +25,9 @@ export type MouseEventType =
     | "mousemove"
     | "mouseenter"
     | "mouseleave"
-    | "hover";
+    | "hover"
+    | "longpress"
+    | "dismiss";
 
 /**
  * GPUI Mouse Event
@@ -66,13 +68,15 @@ export function isMouseEvent(event: GPUIBaseEvent): event is GPUIMouseEvent {
         "mouseenter",
         "mouseleave",
         "hover",
+        "longpress",
+        "dismiss",
     ].includes(event.type);
 }
 
 /**
  * Events that should NOT bubble (per DOM spec)
  */
-export const NON_BUBBLING_MOUSE_EVENTS: MouseEventType[] = ["mouseenter", "mouseleave"];
+export const NON_BUBBLING_MOUSE_EVENTS: MouseEventType[] = ["mouseenter", "mouseleave", "dismiss"];
 
 /**
  * Check if a mouse event should bubble
@@ -239,8 +239,8 @@ export class EventRouter {
      * Check if an event type should bubble
      */
     private shouldBubble(eventType: string): boolean {
-        // Mouse events that don't bubble
-        if (eventType === "mouseenter" || eventType === "mouseleave") {
+        // Mouse events that don't bubble; dismiss is for the overlay itself
+        if (eventType === "mouseenter" || eventType === "mouseleave" || eventType === "dismiss") {
             return false;
         }
         // Focus/blur don't bubble (use focusin/focusout for bubbling)
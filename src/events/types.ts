@@ -62,6 +62,8 @@ export interface GPUIEventMap {
     mouseenter: GPUIMouseEvent;
     mouseleave: GPUIMouseEvent;
     hover: GPUIMouseEvent;
+    longpress: GPUIMouseEvent;
+    dismiss: GPUIMouseEvent;
 
     // Keyboard events
     keydown: GPUIKeyboardEvent;
@@ -109,6 +111,8 @@ export interface GPUIEventHandlerProps {
     onMouseEnter?: GPUIEventHandler<"mouseenter">;
     onMouseLeave?: GPUIEventHandler<"mouseleave">;
     onHover?: GPUIEventHandler<"hover">;
+    onLongPress?: GPUIEventHandler<"longpress">;
+    onDismiss?: GPUIEventHandler<"dismiss">;
 
     // Keyboard event handlers
     onKeyDown?: GPUIEventHandler<"keydown">;
@@ -129,6 +133,28 @@ export interface GPUIEventHandlerProps {
     onBeforeInput?: GPUIEventHandler<"beforeinput">;
 }
 
+/**
+ * Rate limiting applied in Rust before an event is queued for JS
+ * throttleMs forwards at most one event per interval and delivers the latest
+ * one when the interval ends; debounceMs forwards only once no new event
+ * arrived for the delay
+ */
+export interface GPUIEventRateOptions {
+    throttleMs?: number;
+    debounceMs?: number;
+    /**
+     * onWheel only: the element consumes wheel events, so onWheel/onScroll of
+     * elements behind it don't fire and documents behind it don't scroll
+     */
+    preventScroll?: boolean;
+}
+
+/**
+ * Per-handler options, e.g. { onMouseMove: { throttleMs: 16 } } or
+ * { onWheel: { preventScroll: true } }
+ */
+export type GPUIEventOptions = Partial<Record<keyof GPUIEventHandlerProps, GPUIEventRateOptions>>;
+
 /**
  * List of all supported event prop names
  */
@@ -0,0 +1,30 @@
+import { useEffect, useState } from "react";
+import { rustLib } from "./core";
+import type { FocusState } from "./core";
+import { useAppContext } from "./reconciler/ctx";
+
+export type { FocusState };
+
+/**
+ * Focused element, Tab order and focus groups (overlays) of a window, as of
+ * its last painted frame; null if the window is gone
+ */
+export function getFocusState(windowId: number): FocusState | null {
+    return rustLib.getFocusState(windowId);
+}
+
+/**
+ * Focus state of the window this component renders in, refreshed every
+ * `intervalMs` (for focus debugging panels)
+ */
+export function useFocusState(intervalMs = 500): FocusState | null {
+    const { windowId } = useAppContext();
+    const [state, setState] = useState<FocusState | null>(() => getFocusState(windowId));
+
+    useEffect(() => {
+        const interval = setInterval(() => setState(getFocusState(windowId)), intervalMs);
+        return () => clearInterval(interval);
+    }, [windowId, intervalMs]);
+
+    return state;
+}
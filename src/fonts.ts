@@ -0,0 +1,19 @@
+import { rustLib } from "./core";
+
+/**
+ * Fonts tried, in order, for characters missing from an element's
+ * `fontFamily` list, e.g. `["Noto Sans CJK JP", "Noto Color Emoji"]`, so
+ * mixed-script text doesn't render as tofu. Applies to every window from the
+ * next frame.
+ */
+export function setFontFallbacks(families: string[]): void {
+    rustLib.setFontFallbacks(families);
+}
+
+/**
+ * Load a TTF/OTF font file so its family can be named in `fontFamily`, e.g. an
+ * icon font for `<icon>` elements. Throws if the file can't be read or parsed.
+ */
+export function registerFont(path: string): void {
+    rustLib.registerFont(path);
+}
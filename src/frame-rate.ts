@@ -0,0 +1,34 @@
+import { useEffect } from "react";
+import { rustLib } from "./core";
+import { useAppContext } from "./reconciler/ctx";
+
+/**
+ * Cap the frame rate of a window's continuous animations (indeterminate
+ * progress, gauges, skeletons, shader views) at `fps`; 0 removes the cap.
+ * Renders for commits and input are not affected.
+ */
+export function setMaxFps(windowId: number, fps: number): void {
+    rustLib.setMaxFps(windowId, fps);
+}
+
+/**
+ * Cap the frame rate of animations in this component's window while it is
+ * mounted
+ */
+export function useMaxFps(fps: number): void {
+    const { windowId } = useAppContext();
+
+    useEffect(() => {
+        rustLib.setMaxFps(windowId, fps);
+        return () => rustLib.setMaxFps(windowId, 0);
+    }, [windowId, fps]);
+}
+
+/**
+ * Save power in every window: animations run at 15fps at most and purely
+ * decorative ones, like the skeleton shimmer, stand still. Turn it on while
+ * the system reports running on battery or low power mode.
+ */
+export function setPowerSaver(enabled: boolean): void {
+    rustLib.setPowerSaver(enabled);
+}
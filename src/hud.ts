@@ -0,0 +1,24 @@
+import { useEffect } from "react";
+import { rustLib } from "./core";
+import { useAppContext } from "./reconciler/ctx";
+
+/**
+ * Show or hide the developer HUD of a window: frames per second, last frame
+ * time, element count, events waiting for JS and commits per second, painted
+ * by Rust in the window's top-left corner
+ */
+export function setDevHud(windowId: number, enabled: boolean): void {
+    rustLib.setHud(windowId, enabled);
+}
+
+/**
+ * Show the developer HUD in this component's window while it is mounted
+ */
+export function useDevHud(enabled = true): void {
+    const { windowId } = useAppContext();
+
+    useEffect(() => {
+        rustLib.setHud(windowId, enabled);
+        return () => rustLib.setHud(windowId, false);
+    }, [windowId, enabled]);
+}
@@ -1,2 +1,21 @@
 export * from "./reconciler/renderer";
 export * from "./canvas";
+export * from "./clock";
+export * from "./document";
+export * from "./event-mask";
+export * from "./focus-state";
+export * from "./fonts";
+export * from "./frame-rate";
+export * from "./hud";
+export * from "./input-settings";
+export * from "./key-repeat";
+export * from "./latency";
+export * from "./live-region";
+export * from "./privacy";
+export * from "./resource-stats";
+export * from "./safe-area";
+export * from "./shortcut";
+export * from "./shutdown";
+export * from "./sparkline";
+export * from "./watchdog";
+export * from "./window-events";
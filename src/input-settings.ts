@@ -0,0 +1,14 @@
+import { rustLib } from "./core";
+import type { InputSettings } from "./core";
+
+export type { InputSettings };
+
+/**
+ * Tune pointer input for every window: the double-click time and drag
+ * distance used to recognize double clicks and long presses (both default
+ * to the OS settings where Rust can read them), and a multiplier and
+ * direction for wheel deltas. Omitted fields keep their current setting.
+ */
+export function setInputSettings(settings: InputSettings): void {
+    rustLib.setInputSettings(settings);
+}
@@ -1,16 +1,206 @@
 /**
  * Custom JSX intrinsic element extensions for gpui-react
  *
- * Extends HTML input element with gpui-specific props
+ * Extends HTML input element with gpui-specific props and declares the
+ * gpui-only intrinsic elements
  */
 
 import "react";
+import type { ReactNode, Ref } from "react";
+import type { RichTextRun, StyleProps } from "./reconciler/styles";
+import type { GPUIEventHandlerProps, GPUIEventOptions } from "./events";
+import type { DocumentRef } from "./document";
+import type { SparklineRef } from "./sparkline";
+
+/** Props shared by every gpui-only intrinsic element */
+interface GPUIIntrinsicProps extends GPUIEventHandlerProps {
+    key?: string | number;
+    style?: StyleProps;
+    tabIndex?: number;
+    eventOptions?: GPUIEventOptions;
+    privacyMode?: boolean;
+    trackPaint?: boolean;
+    liveRegion?: "off" | "polite" | "assertive";
+    /** Paint the subtree but take it out of mouse input, focus and find-in-page */
+    inert?: boolean;
+    children?: ReactNode;
+}
 
 declare module "react" {
     interface InputHTMLAttributes<T> {
-        /** Enable multi-line mode (textarea-like behavior) */
+        /**
+         * Paint the value like a `<textarea>`: clipped, scrolling with the
+         * wheel and sized by `rows`. Like a textarea it doesn't edit the
+         * value yet.
+         */
         multiLine?: boolean;
         /** Number of visible rows for multi-line input */
         rows?: number;
+        /** Tab inserts an indent instead of moving focus; Escape then Tab moves focus */
+        captureTab?: boolean;
+        /** Spaces per indent with captureTab (default 4) */
+        tabSize?: number;
+        /** Indent with a tab character instead of spaces */
+        indentWithTabs?: boolean;
+    }
+
+    /**
+     * A textarea shows its value in a clipped viewport that scrolls with the
+     * wheel; `rows` and `cols` size it where its style doesn't.
+     *
+     * Not editable yet: it has no caret, selection or key input, so typing
+     * into it does nothing. Change `value` from code instead.
+     */
+    interface TextareaHTMLAttributes<T> {
+        /** Paint line numbers in a gutter left of the text */
+        lineNumbers?: boolean;
+    }
+
+    interface HTMLAttributes<T> {
+        /** Throttle or debounce handlers in Rust, e.g. { onMouseMove: { throttleMs: 16 } } */
+        eventOptions?: GPUIEventOptions;
+        /** Keep this element's text and values out of logs (see setPrivacyOptions) */
+        privacyMode?: boolean;
+        /** Report paint changes of this subtree (see usePaintTracking) */
+        trackPaint?: boolean;
+        /** Announce text changes of this subtree (see useAnnouncements) */
+        liveRegion?: "off" | "polite" | "assertive";
+    }
+
+    namespace JSX {
+        interface IntrinsicElements {
+            /** Element painted by a Rust painter registered with register_shader_view */
+            shaderview: GPUIIntrinsicProps & {
+                shader: string;
+                shaderParams?: Record<string, unknown>;
+                animate?: boolean;
+            };
+            /** Check square, toggled by a click or Space; onChange gets `checked` */
+            checkbox: GPUIIntrinsicProps & {
+                checked?: boolean;
+                disabled?: boolean;
+                value?: string;
+            };
+            /** Radio circle; checking it unchecks the radios with the same name */
+            radio: GPUIIntrinsicProps & {
+                checked?: boolean;
+                disabled?: boolean;
+                name?: string;
+                value?: string;
+            };
+            /** Range slider, moved by dragging or the arrow keys; onInput/onChange get `value` */
+            slider: GPUIIntrinsicProps & {
+                value?: number;
+                min?: number;
+                max?: number;
+                step?: number;
+                disabled?: boolean;
+            };
+            /** Splitter or column resize handle; dragging and arrow keys send onInput/onChange */
+            resizer: GPUIIntrinsicProps & {
+                /** Size being resized, in pixels */
+                value?: number;
+                min?: number;
+                max?: number;
+                /** Pixels per arrow key press (default 10) */
+                step?: number;
+                /** Vertical (default) follows the pointer across, horizontal up and down */
+                orientation?: "vertical" | "horizontal";
+                disabled?: boolean;
+            };
+            /** Progress bar filled to value / max; animated while value is missing */
+            progress: GPUIIntrinsicProps & {
+                value?: number;
+                max?: number;
+            };
+            /** Arc from min to max filled up to value; eases to a new value */
+            gauge: GPUIIntrinsicProps & {
+                value?: number;
+                /** Default 0 */
+                min?: number;
+                /** Default 100 */
+                max?: number;
+                /** Degrees of arc, open at the bottom (default 270); 360 for a ring */
+                sweep?: number;
+                /** Number of evenly spaced tick marks */
+                ticks?: number;
+                needle?: boolean;
+            };
+            /** Line of the values pushed with pushSparkline, newest at the right */
+            sparkline: GPUIIntrinsicProps & {
+                /** Values kept (default 100) */
+                capacity?: number;
+                /** Ms between checks for pushed values (default 100) */
+                interval?: number;
+                /** Bottom of the scale (default lowest value kept) */
+                min?: number;
+                /** Top of the scale (default highest value kept) */
+                max?: number;
+                ref?: Ref<SparklineRef>;
+            };
+            /** QR code or barcode of value; children fill the cleared logo square */
+            qrcode: GPUIIntrinsicProps & {
+                value: string;
+                symbology?: "qr" | "code128" | "ean13";
+                /** QR error correction level (default M, or H with a logo) */
+                ecLevel?: "L" | "M" | "Q" | "H";
+                /** Light modules around the code (default 4, 10 for barcodes) */
+                quietZone?: number;
+                /** Middle square cleared for a logo, as a fraction of the width (max 0.3) */
+                logoSize?: number;
+            };
+            /** Round image, or the initials of name while it loads or when it fails */
+            avatar: GPUIIntrinsicProps & {
+                /** File path or http(s) URL */
+                src?: string;
+                name?: string;
+                status?: "online" | "away" | "busy" | "offline";
+            };
+            /** Monospaced, syntax-highlighted source; lines don't wrap */
+            code: GPUIIntrinsicProps & {
+                text?: string;
+                /** rust, typescript, javascript, python, go, c, cpp, shell or json */
+                language?: string;
+                /** Spaces per tab (default 4) */
+                tabSize?: number;
+            };
+            /** One wrapped paragraph of differently styled runs */
+            richtext: GPUIIntrinsicProps & {
+                runs?: RichTextRun[];
+            };
+            /** Loading placeholder with a shimmer animated in Rust */
+            skeleton: GPUIIntrinsicProps & {
+                /** rect is 16px tall, text as tall as the text size, circle 40px across */
+                variant?: "rect" | "text" | "circle";
+            };
+            /** One glyph of a font, painted without text shaping after its first use */
+            icon: GPUIIntrinsicProps & {
+                /** Codepoint (e.g. 0xe88a), or the glyph's character */
+                codepoint?: number | string;
+                /** Glyph size in px, also the default width and height (default: text size) */
+                size?: number;
+                /** Font family, e.g. one loaded with registerFont (default: system font) */
+                font?: string;
+                color?: string;
+            };
+            /** Top layer over the window; onDismiss fires on a mouse down outside the children */
+            overlay: GPUIIntrinsicProps & {
+                backdrop?: string;
+            };
+            /** Shown next to its parent after the pointer rests on it; painted above overlays */
+            tooltip: GPUIIntrinsicProps & {
+                /** Hover time in ms before it shows (default 500) */
+                delay?: number;
+                /** Side of the parent, flipped when it doesn't fit (default top) */
+                placement?: "top" | "bottom" | "left" | "right";
+            };
+            /** Virtualized read-only text viewer with find-in-page */
+            document: GPUIIntrinsicProps & {
+                value: string;
+                /** Paint line numbers in a gutter left of the text */
+                lineNumbers?: boolean;
+                ref?: Ref<DocumentRef>;
+            };
+        }
     }
 }
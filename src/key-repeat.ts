@@ -0,0 +1,15 @@
+import { rustLib } from "./core";
+import type { KeyRepeatOptions } from "./core";
+
+export type { KeyRepeatOptions };
+
+/**
+ * Configure repeats of held keys. Every keydown reports a `repeatCount`;
+ * with `synthesize` the platform's repeats of editing keys are replaced by
+ * ones fired after `delayMs`, `rate` times per second, so long-press
+ * backspace behaves the same on every platform. Omitted fields keep their
+ * current setting.
+ */
+export function setKeyRepeat(options: KeyRepeatOptions): void {
+    rustLib.setKeyRepeat(options);
+}
@@ -0,0 +1,18 @@
+import { rustLib } from "./core";
+import type { LatencyPercentiles, LatencyStats, LatencyTrace } from "./core";
+
+export type { LatencyPercentiles, LatencyStats, LatencyTrace };
+
+/**
+ * Time key and mouse button events from the moment Rust queues them, through
+ * the JS poll and the commit that follows, to the frame that paints it.
+ * Applies to every window; toggling discards collected samples.
+ */
+export function setLatencyTracing(enabled: boolean): void {
+    rustLib.setLatencyTracing(enabled);
+}
+
+/** End-to-end input latency percentiles of a window, in microseconds */
+export function getLatencyStats(windowId: number): LatencyStats | null {
+    return rustLib.getLatencyStats(windowId);
+}
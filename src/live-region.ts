@@ -0,0 +1,92 @@
+import type { Subprocess } from "bun";
+import { useEffect } from "react";
+import { useWindowEvent } from "./window-events";
+import type { WindowEventData } from "./window-events";
+
+/** Payload of the "announce" window event */
+export interface AnnounceEvent extends WindowEventData {
+    /** The liveRegion element whose text changed */
+    targetId: number;
+    /** Visible text of the region, nested regions left out */
+    text: string;
+    politeness: "polite" | "assertive";
+}
+
+/**
+ * Subscribe to announcements of `liveRegion` elements in this component's
+ * window, sent when a region's text changes, to forward them to a screen
+ * reader or speech engine
+ */
+export function useAnnouncements(listener: (event: AnnounceEvent) => void): void {
+    useWindowEvent<AnnounceEvent>("announce", listener);
+}
+
+/**
+ * Speak announcements of this component's window with the platform's
+ * text-to-speech command (`say`, `spd-say` or System.Speech). Polite
+ * announcements queue; assertive ones interrupt and clear the queue.
+ */
+export function useSpokenAnnouncements(enabled = true): void {
+    useAnnouncements((event) => {
+        if (enabled) speak(event);
+    });
+    useEffect(() => {
+        if (!enabled) stopSpeaking();
+    }, [enabled]);
+}
+
+/** Speaks stdin, so announcement text never needs quoting */
+const WINDOWS_SPEAK_SCRIPT =
+    "Add-Type -AssemblyName System.Speech; " +
+    "(New-Object System.Speech.Synthesis.SpeechSynthesizer).Speak([Console]::In.ReadToEnd())";
+
+const speechQueue: string[] = [];
+let speaking: Subprocess | null = null;
+
+function speak(event: AnnounceEvent): void {
+    if (event.politeness === "assertive") stopSpeaking();
+    speechQueue.push(event.text);
+    if (!speaking) speakNext();
+}
+
+function stopSpeaking(): void {
+    speechQueue.length = 0;
+    speaking?.kill();
+    speaking = null;
+}
+
+function speakNext(): void {
+    const text = speechQueue.shift();
+    if (text === undefined) return;
+
+    let proc: Subprocess;
+    try {
+        proc = spawnSpeech(text);
+    } catch (error) {
+        // No speech command on this system
+        console.warn("[gpui-react] Failed to speak announcement:", error);
+        speechQueue.length = 0;
+        return;
+    }
+    speaking = proc;
+    proc.exited.then(() => {
+        if (speaking !== proc) return;
+        speaking = null;
+        speakNext();
+    });
+}
+
+function spawnSpeech(text: string): Subprocess {
+    switch (process.platform) {
+        case "darwin":
+            return Bun.spawn(["say", text], { stdout: "ignore", stderr: "ignore" });
+        case "win32":
+            return Bun.spawn(["powershell", "-NoProfile", "-Command", WINDOWS_SPEAK_SCRIPT], {
+                stdin: new Blob([text]),
+                stdout: "ignore",
+                stderr: "ignore",
+            });
+        default:
+            return Bun.spawn(["spd-say", "--wait", text], { stdout: "ignore", stderr: "ignore" });
+    }
+}
@@ -0,0 +1,15 @@
+import { rustLib } from "./core";
+import type { PrivacyOptions } from "./core";
+
+export type { PrivacyOptions };
+
+/**
+ * Configure how user content is redacted. Elements with `privacyMode` (and
+ * password inputs) are always logged as length and hash; `redactLogs` does
+ * the same for every element, and `redactEvents` also redacts the payloads of
+ * events from `privacyMode` elements, for apps that forward events to
+ * analytics. Omitted fields keep their current setting.
+ */
+export function setPrivacyOptions(options: PrivacyOptions): void {
+    rustLib.setPrivacyOptions(options);
+}
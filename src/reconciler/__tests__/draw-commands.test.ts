@@ -0,0 +1,43 @@
+import { packDrawCommands, parseColor } from "../styles";
+
+console.log("Running draw command tests...");
+
+console.log("Test 1: command colors should be packed like style colors");
+const [fill, circle] = packDrawCommands([
+    { type: "fillRect", x: 0, y: 0, width: 10, height: 10, color: "red" },
+    { type: "circle", x: 5, y: 5, radius: 2, color: "#00ff0080" },
+]) as any[];
+console.log(`  fill = ${fill.color.toString(16)}, circle = ${circle.color.toString(16)}`);
+console.log(`  Pass: ${fill.color === 0xff0000ff && circle.color === 0x00ff0080}`);
+
+console.log("\nTest 2: named colors should not turn black");
+const [named] = packDrawCommands([{ type: "clear", color: "orange" }]) as any[];
+console.log(`  Pass: ${named.color === parseColor("orange") && named.color === 0xffa500ff}`);
+
+console.log("\nTest 3: gradient stops should be packed");
+const [gradient] = packDrawCommands([
+    {
+        type: "gradientRect",
+        x: 0,
+        y: 0,
+        width: 10,
+        height: 10,
+        stops: [
+            { offset: 0, color: "white" },
+            { offset: 1, color: "hsl(240, 100%, 50%)" },
+        ],
+    },
+]) as any[];
+const stops = gradient.stops.map((stop: any) => stop.color);
+console.log(`  Pass: ${stops[0] === 0xffffffff && stops[1] === 0x0000ffff}`);
+
+console.log("\nTest 4: commands given as JSON should be packed too");
+const [fromJson] = packDrawCommands(
+    JSON.stringify([{ type: "line", x1: 0, y1: 0, x2: 1, y2: 1, width: 1, color: "navy" }])
+) as any[];
+const [image] = packDrawCommands([
+    { type: "drawImage", src: "a.png", x: 0, y: 0, width: 1, height: 1 },
+]) as any[];
+console.log(`  Pass: ${fromJson.color === 0x000080ff && image.color === undefined}`);
+
+console.log("\nAll draw command tests passed!");
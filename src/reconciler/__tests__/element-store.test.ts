@@ -50,4 +50,45 @@ try {
     console.log(`  Pass: Threw error as expected: ${e.message}`);
 }
 
+console.log("\nTest 6: releaseElement should forget the element once");
+const releasedId = elementStore.createElement("text", "released");
+const firstRelease = elementStore.releaseElement(releasedId);
+const secondRelease = elementStore.releaseElement(releasedId);
+console.log(`  First release: ${firstRelease}, second release: ${secondRelease}`);
+console.log(`  Element gone: ${elementStore.getElement(releasedId) === undefined}`);
+console.log(
+    `  Pass: ${firstRelease && !secondRelease && elementStore.getElement(releasedId) === undefined}`
+);
+
+console.log("\nTest 7: released ids should be reused by the same type once Rust confirms");
+const divId = elementStore.createElement("div");
+elementStore.releaseElement(divId);
+const unconfirmedId = elementStore.createElement("div");
+elementStore.recycleIds([divId]);
+const textId = elementStore.createElement("text");
+const reusedId = elementStore.createElement("div", "reused");
+console.log(
+    `  released = ${divId}, before confirm = ${unconfirmedId}, text = ${textId}, next div = ${reusedId}`
+);
+console.log(`  Reused element text: ${elementStore.getElement(reusedId)?.text}`);
+console.log(`  Pass: ${unconfirmedId !== divId && textId !== divId && reusedId === divId}`);
+
+console.log("\nTest 7b: confirming a release twice should free the id once");
+const twiceId = elementStore.createElement("span");
+elementStore.releaseElement(twiceId);
+elementStore.recycleIds([twiceId, twiceId]);
+const firstSpan = elementStore.createElement("span");
+const secondSpan = elementStore.createElement("span");
+console.log(`  released = ${twiceId}, next spans = ${firstSpan}, ${secondSpan}`);
+console.log(`  Pass: ${firstSpan === twiceId && secondSpan !== twiceId}`);
+
+console.log("\nTest 8: reset should drop released ids");
+const beforeReset = elementStore.createElement("span");
+elementStore.releaseElement(beforeReset);
+elementStore.reset();
+const afterReset = elementStore.createElement("div");
+const spanAfterReset = elementStore.createElement("span");
+console.log(`  first ids after reset: ${afterReset}, ${spanAfterReset}`);
+console.log(`  Pass: ${afterReset === 2 && spanAfterReset === 3}`);
+
 console.log("\nAll element store tests passed!");
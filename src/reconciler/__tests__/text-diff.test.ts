@@ -0,0 +1,51 @@
+import type { TextEdit } from "../../core";
+import { diffText } from "../text-diff";
+
+/** Apply an edit the way Rust does, on UTF-16 offsets */
+function apply(text: string, edit: TextEdit): string {
+    if (edit.start === undefined) return text + edit.text;
+    return text.slice(0, edit.start) + edit.text + text.slice(edit.start + (edit.deleteCount ?? 0));
+}
+
+/** Whether an offset falls between the two halves of a surrogate pair */
+function splitsPair(text: string, offset: number): boolean {
+    const code = text.charCodeAt(offset - 1);
+    return offset > 0 && offset < text.length && code >= 0xd800 && code <= 0xdbff;
+}
+
+function check(oldText: string, newText: string): TextEdit {
+    const edit = diffText(2, oldText, newText);
+    const end = (edit.start ?? oldText.length) + (edit.deleteCount ?? 0);
+    const whole =
+        edit.start === undefined || (!splitsPair(oldText, edit.start) && !splitsPair(oldText, end));
+    console.log(`  ${JSON.stringify(edit)}`);
+    console.log(`  Pass: ${apply(oldText, edit) === newText && whole}`);
+    return edit;
+}
+
+console.log("Running text diff tests...");
+
+console.log("Test 1: appending should not send offsets");
+const appended = check("hello", "hello world");
+console.log(`  Append: ${appended.start === undefined && appended.text === " world"}`);
+
+console.log("\nTest 2: a middle edit should keep the common prefix and suffix");
+const middle = check("hello world", "hello brave world");
+console.log(`  Splice: ${middle.start === 6 && middle.deleteCount === 0 && middle.text === "brave "}`);
+
+console.log("\nTest 3: edits at the start and end");
+check("hello", "jello");
+check("hello", "hell");
+check("hello", "");
+
+console.log("\nTest 4: a surrogate pair straddling the common prefix should be replaced whole");
+// U+1F600 and U+1F601 share their high surrogate
+const prefixPair = check("a\u{1F600}", "a\u{1F601}");
+console.log(`  Whole pair: ${prefixPair.start === 1 && prefixPair.deleteCount === 2}`);
+
+console.log("\nTest 5: a surrogate pair straddling the common suffix should be replaced whole");
+// U+1F600 and U+1F200 share their low surrogate
+const suffixPair = check("\u{1F600}b", "\u{1F200}b");
+console.log(`  Whole pair: ${suffixPair.start === 0 && suffixPair.deleteCount === 2}`);
+
+console.log("\nAll text diff tests passed!");
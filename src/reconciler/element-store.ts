@@ -3,9 +3,19 @@ import { ElementData } from "../core";
 
 const STORE_SYMBOL = Symbol("store");
 
+/** Released ids kept per element type for reuse */
+const MAX_FREE_IDS_PER_TYPE = 256;
+
 export class ElementStore {
     private store = new Map<number, ElementData>();
     private nextId = 2;
+    /** Ids of released elements by type, recycled so ids stay bounded in long sessions */
+    private freeIds = new Map<string, number[]>();
+    /**
+     * Released ids Rust hasn't confirmed yet, with their type. Events queued
+     * for the old element may still arrive until then.
+     */
+    private releasing = new Map<number, string>();
     private rootId: number | null = null;
     private windowId: number = 0;
 
@@ -19,6 +29,8 @@ export class ElementStore {
 
     reset(): void {
         this.store.clear();
+        this.freeIds.clear();
+        this.releasing.clear();
         this.nextId = 2;
         this.rootId = null;
         this.windowId = 0;
@@ -30,7 +42,7 @@ export class ElementStore {
         style?: Record<string, any>,
         eventHandlers?: Record<string, number>
     ): number {
-        const globalId = this.nextId++;
+        const globalId = this.freeIds.get(type)?.pop() ?? this.nextId++;
         const element: ElementData = {
             globalId,
             type,
@@ -70,6 +82,39 @@ export class ElementStore {
         }
     }
 
+    /**
+     * Forget a deleted element. Its id goes to the next element of the same
+     * type once Rust confirms the release (see recycleIds). Returns false if
+     * it was already released.
+     */
+    releaseElement(globalId: number): boolean {
+        const element = this.store.get(globalId);
+        if (!element) return false;
+        this.store.delete(globalId);
+        this.releasing.set(globalId, element.type);
+        trace("releaseElement", { globalId, type: element.type });
+        return true;
+    }
+
+    /**
+     * Make released ids available again, called with the ids of a "released"
+     * window event. Rust sends it after every event queued for those elements
+     * and drops later ones, so no event of the old element reaches a new one.
+     */
+    recycleIds(ids: number[]): void {
+        for (const globalId of ids) {
+            const type = this.releasing.get(globalId);
+            if (type === undefined) continue;
+            this.releasing.delete(globalId);
+            const free = this.freeIds.get(type) ?? [];
+            if (free.length < MAX_FREE_IDS_PER_TYPE) {
+                free.push(globalId);
+                this.freeIds.set(type, free);
+            }
+        }
+        trace("recycleIds", { ids });
+    }
+
     setContainerChild(childId: number): void {
         if (this.rootId === null) {
             this.rootId = childId;
@@ -1,11 +1,13 @@
 import * as ReactReconciler from "react-reconciler";
 import { ElementStore } from "./element-store";
-import { mapStyleToProps, StyleProps } from "./styles";
+import { mapStyleToProps, splitImportant, StyleProps } from "./styles";
 import { HostConfig, OpaqueHandle } from "react-reconciler";
 import { DefaultEventPriority, NoEventPriority } from "react-reconciler/constants";
 import { trace, info, warn } from "../utils/logging";
-import { rustLib } from "../core";
+import { rustLib, SafeAreaInsets, StylePatch, TextEdit } from "../core";
 import { eventRouter, EVENT_PROP_TO_TYPE, isEventHandlerProp } from "../events";
+import { setSafeArea, usesEnv, withSafeArea } from "./safe-area";
+import { diffText } from "./text-diff";
 
 type ReactContext<T> = ReactReconciler.ReactContext<T>;
 
@@ -15,7 +17,7 @@ type Container = ElementStore;
 type SuspenseInstance = never;
 type HydratableInstance = never;
 type FormInstance = never;
-type PublicInstance = Element;
+type PublicInstance = Instance | TextInstance;
 type HostContext = object;
 type ChildSet = never;
 type TimeoutHandle = number;
@@ -41,11 +43,15 @@ export interface TextInstance {
     store: ElementStore;
 }
 
-function extractStyleProps(props: any): StyleProps {
+export function extractStyleProps(props: any): StyleProps {
     const styleProps: StyleProps = {};
 
     if (props.style) {
-        Object.assign(styleProps, props.style);
+        const { normal, important } = splitImportant(props.style);
+        Object.assign(styleProps, normal);
+        if (important) {
+            styleProps._important = important;
+        }
     }
 
     if (props.className) {
@@ -91,6 +97,132 @@ function extractStyleProps(props: any): StyleProps {
     if (props.maxLength !== undefined) {
         styleProps.maxLength = props.maxLength;
     }
+    if (props.multiLine !== undefined) {
+        styleProps.multiLine = props.multiLine;
+    }
+    // Textarea props
+    if (props.rows !== undefined) {
+        styleProps.rows = props.rows;
+    }
+    if (props.cols !== undefined) {
+        styleProps.cols = props.cols;
+    }
+    if (props.lineNumbers !== undefined) {
+        styleProps.lineNumbers = props.lineNumbers;
+    }
+    if (props.captureTab !== undefined) {
+        styleProps.captureTab = props.captureTab;
+    }
+    if (props.tabSize !== undefined) {
+        styleProps.tabSize = props.tabSize;
+    }
+    if (props.indentWithTabs !== undefined) {
+        styleProps.indentWithTabs = props.indentWithTabs;
+    }
+    if (props.checked !== undefined) {
+        styleProps.checked = props.checked;
+    }
+    if (props.name !== undefined) {
+        styleProps.name = props.name;
+    }
+    for (const prop of ["min", "max", "step", "orientation"] as const) {
+        if (props[prop] !== undefined) {
+            styleProps[prop] = props[prop];
+        }
+    }
+    if (props.backdrop !== undefined) {
+        styleProps.backdrop = props.backdrop;
+    }
+    // Gauge props
+    if (props.sweep !== undefined) {
+        styleProps.gaugeSweep = props.sweep;
+    }
+    if (props.ticks !== undefined) {
+        styleProps.gaugeTicks = props.ticks;
+    }
+    if (props.needle !== undefined) {
+        styleProps.gaugeNeedle = props.needle;
+    }
+    // Sparkline props
+    if (props.capacity !== undefined) {
+        styleProps.sparklineCapacity = props.capacity;
+    }
+    if (props.interval !== undefined) {
+        styleProps.sparklineInterval = props.interval;
+    }
+    // QR code props
+    for (const prop of ["symbology", "quietZone", "logoSize"] as const) {
+        if (props[prop] !== undefined) {
+            styleProps[prop] = props[prop];
+        }
+    }
+    if (props.ecLevel !== undefined) {
+        styleProps.qrEcLevel = props.ecLevel;
+    }
+    // Avatar props
+    if (props.status !== undefined) {
+        styleProps.avatarStatus = props.status;
+    }
+    // Code props
+    if (props.language !== undefined) {
+        styleProps.codeLanguage = props.language;
+    }
+    // Rich text props
+    if (props.runs !== undefined) {
+        styleProps.richRuns = props.runs;
+    }
+    // Skeleton props
+    if (props.variant !== undefined) {
+        styleProps.skeletonVariant = props.variant;
+    }
+    // Icon props
+    if (props.codepoint !== undefined) {
+        styleProps.iconCodepoint = props.codepoint;
+    }
+    if (props.size !== undefined) {
+        styleProps.iconSize = props.size;
+    }
+    if (props.font !== undefined) {
+        styleProps.fontFamily = props.font;
+    }
+    // Canvas props
+    if (props.retained !== undefined) {
+        styleProps.canvasRetained = props.retained;
+    }
+    if (props.batch !== undefined) {
+        styleProps.canvasBatch = props.batch;
+    }
+    // Tooltip props
+    if (props.delay !== undefined) {
+        styleProps.tooltipDelay = props.delay;
+    }
+    if (props.placement !== undefined) {
+        styleProps.tooltipPlacement = props.placement;
+    }
+
+    // Shader view props
+    const shaderViewProps = ["shader", "shaderParams", "animate"];
+    for (const prop of shaderViewProps) {
+        if (props[prop] !== undefined) {
+            (styleProps as any)[prop] = props[prop];
+        }
+    }
+
+    if (props.eventOptions !== undefined) {
+        styleProps.eventOptions = props.eventOptions;
+    }
+    if (props.privacyMode !== undefined) {
+        styleProps.privacyMode = props.privacyMode;
+    }
+    if (props.trackPaint !== undefined) {
+        styleProps.trackPaint = props.trackPaint;
+    }
+    if (props.liveRegion !== undefined) {
+        styleProps.liveRegion = props.liveRegion;
+    }
+    if (props.inert !== undefined) {
+        styleProps.inert = props.inert;
+    }
 
     return styleProps;
 }
@@ -109,11 +241,26 @@ function extractEventHandlers(props: any): Record<string, number> {
 }
 
 // Per-window update queues for multi-window support
-const windowUpdates = new Map<number, { pending: any[]; scheduled: boolean }>();
+const windowUpdates = new Map<
+    number,
+    {
+        pending: any[];
+        patches: StylePatch[];
+        edits: TextEdit[];
+        released: number[];
+        scheduled: boolean;
+    }
+>();
 
 function getWindowUpdateState(windowId: number) {
     if (!windowUpdates.has(windowId)) {
-        windowUpdates.set(windowId, { pending: [], scheduled: false });
+        windowUpdates.set(windowId, {
+            pending: [],
+            patches: [],
+            edits: [],
+            released: [],
+            scheduled: false,
+        });
     }
     return windowUpdates.get(windowId)!;
 }
@@ -131,20 +278,155 @@ function queueElementUpdate(element: any, container: Container): void {
     } else {
         state.pending.push(element);
     }
+    // The full update carries the element's current style and text
+    state.patches = state.patches.filter((p) => p.id !== element.globalId);
+    state.edits = state.edits.filter((e) => e.id !== element.globalId);
 
-    // Schedule a microtask to flush updates if not already scheduled
-    if (!state.scheduled) {
-        state.scheduled = true;
-        queueMicrotask(() => {
-            state.scheduled = false;
-            if (state.pending.length > 0) {
-                trace(`Flushing ${state.pending.length} batched updates for window ${windowId}`);
-                rustLib.batchElementUpdates(windowId, state.pending);
-                rustLib.renderFrame(windowId, container.getRoot());
-                state.pending = [];
-            }
-        });
+    scheduleFlush(windowId, container);
+}
+
+/**
+ * Queue only the style props that changed, for an element whose style is all
+ * that changed. Elements already queued in full just carry the new style.
+ */
+function queueStylePatch(
+    element: any,
+    oldStyles: Record<string, any> | undefined,
+    newStyles: Record<string, any>,
+    container: Container
+): void {
+    const windowId = container.getWindowId();
+    const state = getWindowUpdateState(windowId);
+    if (state.pending.some((e) => e.globalId === element.globalId)) {
+        return;
+    }
+
+    const old = oldStyles ?? {};
+    for (const prop of new Set([...Object.keys(old), ...Object.keys(newStyles)])) {
+        const value = newStyles[prop];
+        if (JSON.stringify(old[prop]) === JSON.stringify(value)) continue;
+        // Typing into a controlled input edits its value rather than replacing it
+        if (
+            prop === "value" &&
+            (element.type === "input" || element.type === "textarea") &&
+            typeof old[prop] === "string" &&
+            typeof value === "string"
+        ) {
+            state.edits.push(diffText(element.globalId, old[prop], value));
+            continue;
+        }
+        state.patches.push({ id: element.globalId, prop, value: value ?? null });
+    }
+
+    scheduleFlush(windowId, container);
+}
+
+/**
+ * Queue the change from `oldText` to `newText` as a text edit. Elements already
+ * queued in full just carry the new text.
+ */
+function queueTextEdit(
+    element: any,
+    oldText: string,
+    newText: string,
+    container: Container
+): void {
+    const windowId = container.getWindowId();
+    const state = getWindowUpdateState(windowId);
+    if (state.pending.some((e) => e.globalId === element.globalId)) {
+        return;
     }
+
+    state.edits.push(diffText(element.globalId, oldText, newText));
+    scheduleFlush(windowId, container);
+}
+
+/**
+ * Queue the release of deleted elements. Their ids may be handed to new
+ * elements before the flush, so releases are sent ahead of the batch.
+ */
+function queueRelease(ids: number[], container: Container): void {
+    const windowId = container.getWindowId();
+    const state = getWindowUpdateState(windowId);
+    const deleted = new Set(ids);
+    state.pending = state.pending.filter((e) => !deleted.has(e.globalId));
+    state.patches = state.patches.filter((p) => !deleted.has(p.id));
+    state.edits = state.edits.filter((e) => !deleted.has(e.id));
+    state.released.push(...ids);
+    scheduleFlush(windowId, container);
+}
+
+// Elements whose styles use env(), per window, with the props to map again
+// when the window's safe area changes
+const envInstances = new Map<number, Map<Instance, Props>>();
+
+/** Map an element's style props, resolving env() against its window */
+function mapInstanceStyles(props: Props, container: Container): Record<string, any> {
+    return withSafeArea(container.getWindowId(), () => mapStyleToProps(extractStyleProps(props)));
+}
+
+function trackEnvUse(instance: Instance, props: Props): void {
+    const windowId = instance.store.getWindowId();
+    let instances = envInstances.get(windowId);
+    if (usesEnv(props.style)) {
+        if (!instances) {
+            instances = new Map();
+            envInstances.set(windowId, instances);
+        }
+        instances.set(instance, props);
+    } else {
+        instances?.delete(instance);
+    }
+}
+
+/**
+ * Record a window's new safe area and patch the styles of its elements that
+ * use env()
+ */
+export function applySafeArea(windowId: number, insets: SafeAreaInsets): void {
+    setSafeArea(windowId, insets);
+    for (const [instance, props] of envInstances.get(windowId) ?? []) {
+        const element = instance.store.getElement(instance.id);
+        if (!element) continue;
+        const newStyles = mapInstanceStyles(props, instance.store);
+        if (JSON.stringify(instance.style) !== JSON.stringify(newStyles)) {
+            queueStylePatch(element, instance.style, newStyles, instance.store);
+            instance.style = newStyles;
+            element.style = newStyles;
+        }
+    }
+}
+
+// Schedule a microtask to flush updates if not already scheduled
+function scheduleFlush(windowId: number, container: Container): void {
+    const state = getWindowUpdateState(windowId);
+    if (state.scheduled) return;
+
+    state.scheduled = true;
+    queueMicrotask(() => {
+        state.scheduled = false;
+        if (state.released.length > 0) {
+            rustLib.releaseElements(windowId, state.released);
+        }
+        if (state.pending.length > 0) {
+            trace(`Flushing ${state.pending.length} batched updates for window ${windowId}`);
+            rustLib.batchElementUpdates(windowId, state.pending);
+        }
+        if (state.patches.length > 0) {
+            rustLib.patchStyles(windowId, state.patches);
+        }
+        if (state.edits.length > 0) {
+            rustLib.editText(windowId, state.edits);
+        }
+        // Patches and edits leave the tree's structure as it is
+        if (state.pending.length > 0) {
+            rustLib.renderFrame(windowId, container.getRoot());
+        }
+        state.pending = [];
+        state.patches = [];
+        state.edits = [];
+        state.released = [];
+    });
 }
 
 let currentUpdatePriority = 0;
@@ -169,8 +451,8 @@ export const hostConfig: HostConfig<
     supportsHydration: false,
     isPrimaryRenderer: true,
 
-    getPublicInstance(_instance: Instance | TextInstance): PublicInstance {
-        return document.createElement("div");
+    getPublicInstance(instance: Instance | TextInstance): PublicInstance {
+        return instance;
     },
 
     getRootHostContext(_rootContainer: Container): HostContext | null {
@@ -227,8 +509,9 @@ export const hostConfig: HostConfig<
         trace("commitTextUpdate", { oldText, newText });
         const element = textInstance.store.getElement(textInstance.id);
         if (element) {
+            const previous = element.text ?? "";
             element.text = String(newText);
-            queueElementUpdate(element, textInstance.store);
+            queueTextEdit(element, previous, element.text, textInstance.store);
         }
     },
 
@@ -239,8 +522,7 @@ export const hostConfig: HostConfig<
         _hostContext: HostContext,
         _internalHandle: OpaqueHandle
     ): Instance {
-        const styleProps = extractStyleProps(props);
-        const styles = mapStyleToProps(styleProps);
+        const styles = mapInstanceStyles(props, rootContainer);
         const eventHandlers = extractEventHandlers(props);
         const id = rootContainer.createElement(type, undefined, styles, eventHandlers);
         trace("createInstance", { type, id, styles, eventHandlers });
@@ -261,6 +543,7 @@ export const hostConfig: HostConfig<
             eventHandlers,
             store: rootContainer,
         };
+        trackEnvUse(instance, props);
         queueElementUpdate(rootContainer.getElement(id), rootContainer);
         return instance;
     },
@@ -356,21 +639,19 @@ export const hostConfig: HostConfig<
         newProps: Props,
         _internalHandle: OpaqueHandle
     ): void {
-        let needsUpdate = false;
-
         // Update style props (including drawCommands for canvas)
-        const styleProps = extractStyleProps(newProps);
-        const newStyles = mapStyleToProps(styleProps);
+        const newStyles = mapInstanceStyles(newProps, instance.store);
         const element = instance.store.getElement(instance.id);
+        trackEnvUse(instance, newProps);
 
         if (element) {
             // Check if styles changed
             const oldStyleStr = JSON.stringify(instance.style);
             const newStyleStr = JSON.stringify(newStyles);
             if (oldStyleStr !== newStyleStr) {
+                queueStylePatch(element, instance.style, newStyles, instance.store);
                 instance.style = newStyles;
                 element.style = newStyles;
-                needsUpdate = true;
             }
         }
 
@@ -403,10 +684,6 @@ export const hostConfig: HostConfig<
             }
         }
         instance.eventHandlers = newHandlers;
-
-        if (needsUpdate && element) {
-            queueElementUpdate(element, instance.store);
-        }
     },
 
     finalizeInitialChildren(
@@ -438,6 +715,16 @@ export const hostConfig: HostConfig<
         };
         const childIds = collectChildIds(instance);
         eventRouter.cleanupElementTree(instance.id, childIds);
+        envInstances.get(instance.store.getWindowId())?.delete(instance);
+
+        // Text instances are never detached on their own; release them with
+        // their parent so their ids can be recycled
+        const released = [instance, ...instance.children.filter((c) => c.type === "text")]
+            .filter((inst) => instance.store.releaseElement(inst.id))
+            .map((inst) => inst.id);
+        if (released.length > 0) {
+            queueRelease(released, instance.store);
+        }
     },
 
     prepareScopeUpdate(_scopeInstance: any, _instance: any): void {},
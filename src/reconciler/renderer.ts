@@ -3,6 +3,8 @@ import { _render, reconciler } from "./reconciler";
 import { ElementStore } from "./element-store";
 import { AppContext } from "./ctx";
 import { rustLib, WindowOptions } from "../core";
+import { nativeWindowOptions } from "./splash";
+import { applySafeArea } from "./host-config";
 
 export type Root = {
     render: (children: React.ReactNode) => void;
@@ -16,11 +18,18 @@ export type RootProps = {
 
 export function createRoot(props: RootProps): Root {
     let container: null = null;
-    const windowId = rustLib.createWindow(props.windowOption, props.pollEventInterval);
+    const windowId = rustLib.createWindow(
+        nativeWindowOptions(props.windowOption),
+        props.pollEventInterval
+    );
     console.log("Created window with id:", windowId);
+    rustLib.onWindowEvent(windowId, "safeareachange", ({ top, right, bottom, left }) =>
+        applySafeArea(windowId, { top, right, bottom, left })
+    );
 
     const elementStore = new ElementStore();
     elementStore.setWindowId(windowId);
+    rustLib.onWindowEvent(windowId, "released", ({ ids }) => elementStore.recycleIds(ids));
     return {
         render(node: React.ReactNode) {
             container = _render(
@@ -0,0 +1,49 @@
+import type { SafeAreaInsets } from "../core";
+
+const NO_INSETS: SafeAreaInsets = { top: 0, right: 0, bottom: 0, left: 0 };
+
+/** Latest "safeareachange" insets of each window */
+const windowInsets = new Map<number, SafeAreaInsets>();
+
+/** Insets env() resolves against while a window's styles are mapped */
+let resolving: SafeAreaInsets = NO_INSETS;
+
+export function getSafeArea(windowId: number): SafeAreaInsets {
+    return windowInsets.get(windowId) ?? NO_INSETS;
+}
+
+export function setSafeArea(windowId: number, insets: SafeAreaInsets): void {
+    windowInsets.set(windowId, insets);
+}
+
+/** Map styles of an element in `windowId`, resolving env() against its insets */
+export function withSafeArea<T>(windowId: number, map: () => T): T {
+    const previous = resolving;
+    resolving = getSafeArea(windowId);
+    try {
+        return map();
+    } finally {
+        resolving = previous;
+    }
+}
+
+/** Value of an env() variable, undefined for unknown names */
+export function envValue(name: string): number | undefined {
+    switch (name) {
+        case "safe-area-inset-top":
+            return resolving.top;
+        case "safe-area-inset-right":
+            return resolving.right;
+        case "safe-area-inset-bottom":
+            return resolving.bottom;
+        case "safe-area-inset-left":
+            return resolving.left;
+        default:
+            return undefined;
+    }
+}
+
+/** Whether a style object has lengths that depend on env() */
+export function usesEnv(style: unknown): boolean {
+    return style !== undefined && JSON.stringify(style).includes("env(");
+}
@@ -0,0 +1,25 @@
+import type { NativeWindowOptions, SplashElement, WindowOptions } from "../core";
+import { extractStyleProps } from "./host-config";
+import { mapStyleToProps, parseColor } from "./styles";
+
+/**
+ * Map the background color and splash tree of window options to the form
+ * Rust reads; the rest passes through unchanged
+ */
+export function nativeWindowOptions(options: WindowOptions): NativeWindowOptions {
+    const { backgroundColor, splash, ...rest } = options;
+    return {
+        ...rest,
+        backgroundColor: backgroundColor !== undefined ? parseColor(backgroundColor) : undefined,
+        splash: splash !== undefined ? mapSplashElement(splash) : undefined,
+    };
+}
+
+function mapSplashElement(element: SplashElement): Record<string, unknown> {
+    return {
+        type: element.type,
+        text: element.text,
+        style: mapStyleToProps(extractStyleProps(element.props ?? {})),
+        children: (element.children ?? []).map(mapSplashElement),
+    };
+}
@@ -4,21 +4,41 @@
  * Handles conversion between React style props and GPUI style values
  */
 
-import type { GPUIEventHandlerProps } from "../events";
+import type { GPUIEventHandlerProps, GPUIEventOptions } from "../events";
+import { envValue } from "./safe-area";
+
+/** Line style of a border side */
+export type BorderLineStyle = "solid" | "dashed" | "dotted" | "none";
+
+/** A piece of a richtext paragraph; unset properties come from the element */
+export interface RichTextRun {
+    text: string;
+    color?: string;
+    background?: string;
+    weight?: string | number;
+    italic?: boolean;
+    underline?: boolean | "wavy";
+    strikethrough?: boolean;
+}
 
 export interface StyleProps extends GPUIEventHandlerProps {
     // Text properties (inheritable)
     color?: string;
     fontSize?: number | string;
     fontWeight?: string | number;
-    fontFamily?: string;
+    fontFamily?: string; // CSS-style list: first family, then fallbacks
     lineHeight?: number | string;
     textAlign?: "left" | "center" | "right";
     letterSpacing?: number | string;
+    // OpenType features: CSS string ('"liga" 0, "tnum"') or { liga: false, tnum: true, ss01: 1 }
+    fontFeatureSettings?: string | Record<string, boolean | number>;
+    writingMode?: "horizontal-tb" | "vertical-rl" | "vertical-lr"; // Upright characters in columns
+    elideMode?: "start" | "middle" | "end"; // One line, "…" where it doesn't fit
 
     // Other inheritable properties
     cursor?: string;
     visibility?: "visible" | "hidden";
+    shapeRendering?: "auto" | "crispEdges"; // crispEdges snaps bounds and borders to device pixels
 
     // Non-inheritable properties
     backgroundColor?: string;
@@ -59,10 +79,15 @@ export interface StyleProps extends GPUIEventHandlerProps {
     overflowX?: "visible" | "hidden" | "scroll" | "clip";
     overflowY?: "visible" | "hidden" | "scroll" | "clip";
 
+    // Skip layout and paint of the subtree while far offscreen
+    contentVisibility?: "visible" | "auto";
+    containIntrinsicWidth?: number | string; // Placeholder size before first measurement
+    containIntrinsicHeight?: number | string;
+
     // Border (shorthand and individual)
     border?: string;
     borderWidth?: number | string;
-    borderStyle?: "solid" | "dashed";
+    borderStyle?: BorderLineStyle;
     borderColor?: string;
     borderTop?: string;
     borderRight?: string;
@@ -76,10 +101,23 @@ export interface StyleProps extends GPUIEventHandlerProps {
     borderRightColor?: string;
     borderBottomColor?: string;
     borderLeftColor?: string;
+    borderTopStyle?: BorderLineStyle;
+    borderRightStyle?: BorderLineStyle;
+    borderBottomStyle?: BorderLineStyle;
+    borderLeftStyle?: BorderLineStyle;
     borderRadius?: number | string;
 
+    // Outline (painted outside the border box, doesn't affect layout)
+    outline?: string;
+    outlineWidth?: number | string;
+    outlineStyle?: BorderLineStyle;
+    outlineColor?: string;
+    outlineOffset?: number | string;
+
     // Box Shadow
     boxShadow?: string;
+    elevation?: number; // 0-24, a key + ambient shadow pair (boxShadow wins if both are set)
+    elevationTint?: boolean; // Lighten the background with elevation, for dark themes
 
     // Flexbox
     display?: "flex" | "block" | "inline" | "inline-block";
@@ -119,13 +157,15 @@ export interface StyleProps extends GPUIEventHandlerProps {
     text?: string;
     textSize?: number | string;
     textColor?: string;
-    drawCommands?: unknown[];
+    drawCommands?: unknown[] | string;
+    canvasRetained?: boolean;
+    canvasBatch?: number;
 
     // Focus properties
     tabIndex?: number; // -1 = programmatic focus only, 0+ = Tab navigation order
 
     // Input element properties
-    value?: string; // Controlled input value
+    value?: string | number; // Controlled input value, or a slider's number
     defaultValue?: string; // Initial value for uncontrolled input
     placeholder?: string; // Placeholder text
     disabled?: boolean; // Whether input is disabled
@@ -133,15 +173,125 @@ export interface StyleProps extends GPUIEventHandlerProps {
     maxLength?: number; // Maximum character length
     type?: "text" | "password" | "number" | "email"; // Input type
     multiLine?: boolean; // Enable multi-line mode (textarea)
-    rows?: number; // Number of visible rows for multi-line input
+    rows?: number; // Number of visible rows for multi-line input or textarea
+    cols?: number; // Textarea width in advances of "0"
+    lineNumbers?: boolean; // Line-number gutter of a textarea or document
     selectionColor?: string; // Selection background color
+    captureTab?: boolean; // Tab indents (beforeinput) instead of moving focus; Esc then Tab leaves
+    tabSize?: number; // Spaces per indent with captureTab, or per tab in code (default 4)
+    indentWithTabs?: boolean; // Indent with a tab character instead of spaces
+
+    // Checkbox and radio properties
+    checked?: boolean;
+    name?: string; // Radios with the same name uncheck each other
+    accentColor?: string; // Fill of a checked control, slider, progress bar or gauge arc
+
+    // Slider, progress, gauge and sparkline properties
+    min?: number; // Default 0
+    max?: number; // Default 100 for sliders and gauges, 1 for progress bars
+    step?: number; // Default 1
+
+    // Gauge properties
+    gaugeSweep?: number; // Degrees of arc (default 270); 360 for a ring
+    gaugeTicks?: number; // Evenly spaced tick marks
+    gaugeNeedle?: boolean;
+
+    // Sparkline properties
+    sparklineCapacity?: number; // Values kept (default 100)
+    sparklineInterval?: number; // Ms between checks for pushed values (default 100)
+
+    // QR code properties
+    symbology?: "qr" | "code128" | "ean13"; // Default qr
+    qrEcLevel?: "L" | "M" | "Q" | "H"; // Default M, or H with a logo
+    quietZone?: number; // Light modules around the code (default 4, 10 for barcodes)
+    logoSize?: number; // Cleared middle square, as a fraction of the width (max 0.3)
+
+    // Avatar properties
+    avatarStatus?: "online" | "away" | "busy" | "offline"; // Dot at the bottom right
+
+    // Code properties
+    codeLanguage?: string; // Unknown languages show plain text
+
+    // Rich text properties
+    richRuns?: RichTextRun[]; // Shaped and wrapped as one paragraph
+
+    // Skeleton properties
+    skeletonVariant?: "rect" | "text" | "circle"; // Default rect
+
+    // Icon properties
+    iconCodepoint?: number | string; // Codepoint, or the glyph's character
+    iconSize?: number; // Glyph size in px (default: text size)
+
+    // Resizer properties
+    orientation?: "vertical" | "horizontal"; // Default vertical: dragged across
+
+    // Overlay properties
+    backdrop?: string; // Fills the window behind an overlay and blocks the pointer
+
+    // Tooltip properties
+    tooltipDelay?: number; // Hover time in ms before it shows (default 500)
+    tooltipPlacement?: "top" | "bottom" | "left" | "right"; // Side of the parent (default top)
 
-    // Hover styles (pseudo-class) - excludes event handlers
-    _hover?: Omit<StyleProps, "_hover" | keyof GPUIEventHandlerProps>;
+    // Shader view properties
+    shader?: string; // Name of a painter registered on the Rust side
+    shaderParams?: Record<string, unknown>; // Passed through to the painter
+    animate?: boolean; // Repaint every frame
+
+    // Per-handler throttle/debounce, applied in Rust before events are queued
+    eventOptions?: GPUIEventOptions;
+
+    // Redact this element's text and values in Rust logs
+    privacyMode?: boolean;
+
+    // Report "paintchanged" window events for this subtree (see usePaintTracking)
+    trackPaint?: boolean;
+
+    // Announce text changes of this subtree (see useAnnouncements)
+    liveRegion?: "off" | "polite" | "assertive";
+
+    // Paint this subtree but make it non-interactive and non-focusable
+    inert?: boolean;
+
+    // State styles (pseudo-classes) - exclude event handlers
+    // While hovered, focused, or pressed; active wins over focus, focus over hover
+    _hover?: StateStyleProps;
+    _focus?: StateStyleProps;
+    _active?: StateStyleProps;
+
+    // Style values marked "!important", split off by splitImportant; they win over state styles
+    _important?: StateStyleProps;
+}
+
+type StateStyleProps = Omit<
+    StyleProps,
+    "_hover" | "_focus" | "_active" | "_important" | keyof GPUIEventHandlerProps
+>;
+
+const IMPORTANT = /\s*!important\s*$/;
+
+/**
+ * Split string style values ending in "!important" from the rest, without the
+ * suffix. Precedence (see rust/src/element/cascade.rs): style < _hover/_focus/_active < !important.
+ */
+export function splitImportant<T extends Record<string, any>>(
+    style: T
+): { normal: T; important: Partial<T> | null } {
+    let normal = style;
+    let important: Partial<T> | null = null;
+    for (const [key, value] of Object.entries(style)) {
+        if (typeof value !== "string" || !IMPORTANT.test(value)) continue;
+        if (important === null) {
+            normal = { ...style };
+            important = {};
+        }
+        (important as any)[key] = value.replace(IMPORTANT, "");
+        delete (normal as any)[key];
+    }
+    return { normal, important };
 }
 
 /**
- * Named color map (common colors only for MVP)
+ * Named color map (common colors only for MVP), as 0xRRGGBB
  */
 const NAMED_COLORS: Record<string, number> = {
     black: 0x000000,
@@ -165,56 +315,130 @@ const NAMED_COLORS: Record<string, number> = {
     lime: 0x00ff00,
     aqua: 0x00ffff,
     silver: 0xc0c0c0,
-    transparent: 0x000000,
 };
 
+/** Opaque black, used for colors that can't be parsed */
+const FALLBACK_COLOR = 0x000000ff;
+
+function packRgba(r: number, g: number, b: number, a: number): number {
+    const byte = (v: number) => Math.round(Math.min(255, Math.max(0, v)));
+    return ((byte(r) << 24) | (byte(g) << 16) | (byte(b) << 8) | byte(a * 255)) >>> 0;
+}
+
+/** A channel of rgb(): 0-255 or a percentage */
+function parseChannel(value: string): number {
+    return value.endsWith("%") ? (parseFloat(value) / 100) * 255 : parseFloat(value);
+}
+
+/** An alpha value: 0-1 or a percentage, opaque if missing */
+function parseAlpha(value: string | undefined): number {
+    if (value === undefined) return 1;
+    const alpha = value.endsWith("%") ? parseFloat(value) / 100 : parseFloat(value);
+    return Math.min(1, Math.max(0, alpha));
+}
+
+/** A hue in degrees: plain number, deg, turn, rad or grad */
+function parseHue(value: string): number {
+    const hue = parseFloat(value);
+    if (value.endsWith("turn")) return hue * 360;
+    if (value.endsWith("grad")) return hue * 0.9;
+    if (value.endsWith("rad")) return (hue * 180) / Math.PI;
+    return hue;
+}
+
+function hslToRgb(h: number, s: number, l: number): [number, number, number] {
+    h = ((h % 360) + 360) % 360;
+    const c = (1 - Math.abs(2 * l - 1)) * s;
+    const x = c * (1 - Math.abs(((h / 60) % 2) - 1));
+    const m = l - c / 2;
+    const [r, g, b] =
+        h < 60
+            ? [c, x, 0]
+            : h < 120
+              ? [x, c, 0]
+              : h < 180
+                ? [0, c, x]
+                : h < 240
+                  ? [0, x, c]
+                  : h < 300
+                    ? [x, 0, c]
+                    : [c, 0, x];
+    return [(r + m) * 255, (g + m) * 255, (b + m) * 255];
+}
+
 /**
- * Parse CSS color string to GPUI RGB value
- * Supports: hex (#RRGGBB, #RGB), rgb(r, g, b), rgba(r, g, b, a), named colors
+ * Parse CSS color string to a GPUI color, packed as 0xRRGGBBAA
+ * Supports: hex (#RGB, #RGBA, #RRGGBB, #RRGGBBAA), rgb()/rgba() and
+ * hsl()/hsla() with comma or space separated arguments, named colors and
+ * transparent
  */
 export function parseColor(color: string): number {
     if (!color) {
-        return 0x000000;
+        return FALLBACK_COLOR;
     }
+    color = color.trim().toLowerCase();
 
     if (color.startsWith("#")) {
         const hex = color.slice(1);
-        if (hex.length === 3) {
-            const r = parseInt(hex[0] + hex[0], 16);
-            const g = parseInt(hex[1] + hex[1], 16);
-            const b = parseInt(hex[2] + hex[2], 16);
-            return (r << 16) | (g << 8) | b;
-        } else if (hex.length === 6) {
-            const r = parseInt(hex.slice(0, 2), 16);
-            const g = parseInt(hex.slice(2, 4), 16);
-            const b = parseInt(hex.slice(4, 6), 16);
-            return (r << 16) | (g << 8) | b;
+        if (/^[0-9a-f]+$/.test(hex)) {
+            if (hex.length === 3 || hex.length === 4) {
+                const [r, g, b, a] = [...hex].map((digit) => parseInt(digit + digit, 16));
+                return packRgba(r, g, b, a === undefined ? 1 : a / 255);
+            } else if (hex.length === 6 || hex.length === 8) {
+                const [r, g, b, a] = hex.match(/../g)!.map((pair) => parseInt(pair, 16));
+                return packRgba(r, g, b, a === undefined ? 1 : a / 255);
+            }
         }
     }
 
-    const rgbMatch = color.match(/rgb\s*\(\s*(\d+)\s*,\s*(\d+)\s*,\s*(\d+)\s*\)/);
-    if (rgbMatch) {
-        const r = parseInt(rgbMatch[1], 10);
-        const g = parseInt(rgbMatch[2], 10);
-        const b = parseInt(rgbMatch[3], 10);
-        return (r << 16) | (g << 8) | b;
+    const fnMatch = color.match(/^(rgba?|hsla?)\s*\(([^)]*)\)$/);
+    if (fnMatch) {
+        // "r, g, b, a" or "r g b / a"
+        const args = fnMatch[2]
+            .split(/[\s,/]+/)
+            .map((arg) => arg.trim())
+            .filter((arg) => arg);
+        if (args.length === 3 || args.length === 4) {
+            const alpha = parseAlpha(args[3]);
+            if (fnMatch[1].startsWith("rgb")) {
+                const [r, g, b] = args.slice(0, 3).map(parseChannel);
+                if (![r, g, b].some(isNaN)) return packRgba(r, g, b, alpha);
+            } else {
+                const h = parseHue(args[0]);
+                const s = parseFloat(args[1]) / 100;
+                const l = parseFloat(args[2]) / 100;
+                if (![h, s, l].some(isNaN)) {
+                    const [r, g, b] = hslToRgb(h, Math.min(1, s), Math.min(1, l));
+                    return packRgba(r, g, b, alpha);
+                }
+            }
+        }
     }
 
-    const rgbaMatch = color.match(/rgba\s*\(\s*(\d+)\s*,\s*(\d+)\s*,\s*(\d+)\s*,\s*[\d.]+\s*\)/);
-    if (rgbaMatch) {
-        const r = parseInt(rgbaMatch[1], 10);
-        const g = parseInt(rgbaMatch[2], 10);
-        const b = parseInt(rgbaMatch[3], 10);
-        return (r << 16) | (g << 8) | b;
+    if (color === "transparent") {
+        return 0x00000000;
     }
-
-    const namedColor = NAMED_COLORS[color.toLowerCase()];
+    const namedColor = NAMED_COLORS[color];
     if (namedColor !== undefined) {
-        return namedColor;
+        return ((namedColor << 8) | 0xff) >>> 0;
     }
 
     console.warn(`Unknown color format: ${color}, using black`);
-    return 0x000000;
+    return FALLBACK_COLOR;
+}
+
+/**
+ * Canvas draw commands (an array or its JSON) with their CSS colors, and
+ * those of gradient stops, packed by parseColor()
+ */
+export function packDrawCommands(commands: unknown[] | string): unknown[] {
+    const parsed: unknown[] = typeof commands === "string" ? JSON.parse(commands) : commands;
+    const pack = (item: any) =>
+        typeof item?.color === "string" ? { ...item, color: parseColor(item.color) } : item;
+    return parsed.map((command: any) => {
+        const packed = pack(command);
+        return Array.isArray(command?.stops) ? { ...packed, stops: command.stops.map(pack) } : packed;
+    });
 }
 
 /**
@@ -227,6 +451,14 @@ export function parseSize(size: number | string): number {
     }
 
     const s = size.trim();
+    if (s.startsWith("calc(") || s.startsWith("env(")) {
+        const value = parseLengthExpression(s);
+        if (value !== undefined) {
+            return value;
+        }
+        console.warn(`Invalid length expression: ${size}, using 0`);
+        return 0;
+    }
     if (s.endsWith("px")) {
         return parseFloat(s.slice(0, -2));
     }
@@ -248,6 +480,52 @@ export function parseSize(size: number | string): number {
     return 0;
 }
 
+/**
+ * Evaluate a `calc()` or `env()` length to pixels, undefined if malformed.
+ * Terms are numbers with an optional px/em/rem unit and `env(name, fallback)`
+ * variables, which resolve against the window whose styles are being mapped:
+ * "calc(env(safe-area-inset-top) + 8px)".
+ */
+function parseLengthExpression(expression: string): number | undefined {
+    const resolved = expression
+        .replace(/env\(\s*([\w-]+)\s*(?:,([^()]*))?\)/g, (_, name: string, fallback?: string) => {
+            const value = envValue(name) ?? (fallback !== undefined ? parseSize(fallback) : 0);
+            return `(${value})`;
+        })
+        .replace(/calc\(/g, "(");
+    const tokens = resolved.match(/\d*\.?\d+(?:e[+-]?\d+)?(?:px|rem|em)?|[-+*/()]|\S/gi) ?? [];
+    let index = 0;
+
+    // expression := term (("+" | "-") term)*, term := factor (("*" | "/") factor)*
+    const factor = (): number => {
+        const token = tokens[index++];
+        if (token === "-") return -factor();
+        if (token === "(") {
+            const value = sum();
+            if (tokens[index++] !== ")") return NaN;
+            return value;
+        }
+        return token !== undefined && /^[\d.]/.test(token) ? parseSize(token) : NaN;
+    };
+    const term = (): number => {
+        let value = factor();
+        while (tokens[index] === "*" || tokens[index] === "/") {
+            value = tokens[index++] === "*" ? value * factor() : value / factor();
+        }
+        return value;
+    };
+    const sum = (): number => {
+        let value = term();
+        while (tokens[index] === "+" || tokens[index] === "-") {
+            value = tokens[index++] === "+" ? value + term() : value - term();
+        }
+        return value;
+    };
+
+    const value = sum();
+    return index === tokens.length && Number.isFinite(value) ? value : undefined;
+}
+
 /**
  * Parse font weight to number (100-900)
  */
@@ -267,6 +545,21 @@ export function parseFontWeight(weight: string | number): number {
     return weightMap[normalized] || parseInt(normalized, 10) || 400;
 }
 
+/**
+ * Normalize font-feature-settings to the CSS string form Rust parses
+ * { liga: false, tnum: true, ss01: 2 } -> '"liga" 0, "tnum" 1, "ss01" 2'
+ */
+export function parseFontFeatureSettings(
+    settings: string | Record<string, boolean | number>
+): string {
+    if (typeof settings === "string") {
+        return settings;
+    }
+    return Object.entries(settings)
+        .map(([tag, value]) => `"${tag}" ${typeof value === "boolean" ? Number(value) : value}`)
+        .join(", ");
+}
+
 /**
  * Parse margin/padding shorthand
  * Supports: 1-4 values (CSS-like syntax)
@@ -277,7 +570,8 @@ export function parseSpacing(value: number | string): [number, number, number, n
         return [value, value, value, value];
     }
 
-    const parts = value.trim().split(/\s+/);
+    // Spaces inside calc() and env() don't separate values
+    const parts = value.trim().match(/(?:[^\s(]|\((?:[^()]|\([^()]*\))*\))+/g) ?? [];
     const values = parts.map((p) => parseSize(p));
 
     switch (values.length) {
@@ -306,7 +600,7 @@ export function parseBorder(value: string): { width: number; style: string; colo
     const parts = value.trim().split(/\s+/);
     let width = 1;
     let style = "solid";
-    let color = 0x000000;
+    let color = FALLBACK_COLOR;
 
     for (const part of parts) {
         // Check if it's a size
@@ -336,12 +630,13 @@ export function parseBoxShadow(
 ): { offsetX: number; offsetY: number; blur: number; spread: number; color: number } | null {
     if (!value || value === "none") return null;
 
-    // Extract color first (can be hex, rgb, rgba, or named)
-    let color = 0x000000;
+    // Extract color first (can be hex, rgb(a), hsl(a), or named), half
+    // transparent black if there is none
+    let color = 0x00000080;
     let remaining = value;
 
-    // Match rgba/rgb first
-    const rgbaMatch = value.match(/rgba?\s*\([^)]+\)/);
+    // Match color functions first
+    const rgbaMatch = value.match(/(?:rgba?|hsla?)\s*\([^)]+\)/);
     if (rgbaMatch) {
         color = parseColor(rgbaMatch[0]);
         remaining = value.replace(rgbaMatch[0], "").trim();
@@ -349,7 +644,8 @@ export function parseBoxShadow(
         // Match hex or named color at the end
         const parts = value.trim().split(/\s+/);
         const lastPart = parts[parts.length - 1];
-        if (lastPart.startsWith("#") || NAMED_COLORS[lastPart.toLowerCase()] !== undefined) {
+        const name = lastPart.toLowerCase();
+        if (lastPart.startsWith("#") || name === "transparent" || NAMED_COLORS[name] !== undefined) {
             color = parseColor(lastPart);
             parts.pop();
             remaining = parts.join(" ");
@@ -424,6 +720,18 @@ export function mapStyleToProps(props: StyleProps): Record<string, any> {
         result.letterSpacing = parseSize(props.letterSpacing);
     }
 
+    if (props.fontFeatureSettings !== undefined) {
+        result.fontFeatureSettings = parseFontFeatureSettings(props.fontFeatureSettings);
+    }
+
+    if (props.writingMode) {
+        result.writingMode = props.writingMode;
+    }
+
+    if (props.elideMode) {
+        result.elideMode = props.elideMode;
+    }
+
     // Other inheritable properties
     if (props.cursor) {
         result.cursor = props.cursor;
@@ -433,6 +741,10 @@ export function mapStyleToProps(props: StyleProps): Record<string, any> {
         result.visibility = props.visibility;
     }
 
+    if (props.shapeRendering) {
+        result.shapeRendering = props.shapeRendering;
+    }
+
     // Non-inheritable properties
     if (props.backgroundColor) {
         result.bgColor = parseColor(props.backgroundColor);
@@ -529,6 +841,17 @@ export function mapStyleToProps(props: StyleProps): Record<string, any> {
         result.overflowY = props.overflowY;
     }
 
+    // Content visibility
+    if (props.contentVisibility) {
+        result.contentVisibility = props.contentVisibility;
+    }
+    if (props.containIntrinsicWidth !== undefined) {
+        result.containIntrinsicWidth = parseSize(props.containIntrinsicWidth);
+    }
+    if (props.containIntrinsicHeight !== undefined) {
+        result.containIntrinsicHeight = parseSize(props.containIntrinsicHeight);
+    }
+
     // Border shorthand
     if (props.border) {
         const borderParsed = parseBorder(props.border);
@@ -564,6 +887,7 @@ export function mapStyleToProps(props: StyleProps): Record<string, any> {
         const parsed = parseBorder(props.borderTop);
         if (parsed) {
             result.borderTopWidth = parsed.width;
+            result.borderTopStyle = parsed.style;
             result.borderTopColor = parsed.color;
         }
     }
@@ -571,6 +895,7 @@ export function mapStyleToProps(props: StyleProps): Record<string, any> {
         const parsed = parseBorder(props.borderRight);
         if (parsed) {
             result.borderRightWidth = parsed.width;
+            result.borderRightStyle = parsed.style;
             result.borderRightColor = parsed.color;
         }
     }
@@ -578,6 +903,7 @@ export function mapStyleToProps(props: StyleProps): Record<string, any> {
         const parsed = parseBorder(props.borderBottom);
         if (parsed) {
             result.borderBottomWidth = parsed.width;
+            result.borderBottomStyle = parsed.style;
             result.borderBottomColor = parsed.color;
         }
     }
@@ -585,6 +911,7 @@ export function mapStyleToProps(props: StyleProps): Record<string, any> {
         const parsed = parseBorder(props.borderLeft);
         if (parsed) {
             result.borderLeftWidth = parsed.width;
+            result.borderLeftStyle = parsed.style;
             result.borderLeftColor = parsed.color;
         }
     }
@@ -604,10 +931,30 @@ export function mapStyleToProps(props: StyleProps): Record<string, any> {
     if (props.borderBottomColor) result.borderBottomColor = parseColor(props.borderBottomColor);
     if (props.borderLeftColor) result.borderLeftColor = parseColor(props.borderLeftColor);
 
+    // Individual border styles
+    if (props.borderTopStyle) result.borderTopStyle = props.borderTopStyle;
+    if (props.borderRightStyle) result.borderRightStyle = props.borderRightStyle;
+    if (props.borderBottomStyle) result.borderBottomStyle = props.borderBottomStyle;
+    if (props.borderLeftStyle) result.borderLeftStyle = props.borderLeftStyle;
+
     if (props.borderRadius) {
         result.borderRadius = parseSize(props.borderRadius);
     }
 
+    // Outline shorthand and individual properties
+    if (props.outline) {
+        const parsed = parseBorder(props.outline);
+        if (parsed) {
+            result.outlineWidth = parsed.width;
+            result.outlineStyle = parsed.style;
+            result.outlineColor = parsed.color;
+        }
+    }
+    if (props.outlineWidth !== undefined) result.outlineWidth = parseSize(props.outlineWidth);
+    if (props.outlineStyle) result.outlineStyle = props.outlineStyle;
+    if (props.outlineColor) result.outlineColor = parseColor(props.outlineColor);
+    if (props.outlineOffset !== undefined) result.outlineOffset = parseSize(props.outlineOffset);
+
     // Box shadow
     if (props.boxShadow) {
         const shadow = parseBoxShadow(props.boxShadow);
@@ -619,6 +966,12 @@ export function mapStyleToProps(props: StyleProps): Record<string, any> {
             result.boxShadowColor = shadow.color;
         }
     }
+    if (props.elevation !== undefined) {
+        result.elevation = props.elevation;
+    }
+    if (props.elevationTint !== undefined) {
+        result.elevationTint = props.elevationTint;
+    }
 
     // Flexbox
     if (props.display) {
@@ -687,9 +1040,23 @@ export function mapStyleToProps(props: StyleProps): Record<string, any> {
         result.alt = props.alt;
     }
 
-    // Hover styles
+    // State styles
+    // They already win over the base style, so "!important" adds nothing
+    const stateStyle = (style: StateStyleProps) => {
+        const { normal, important } = splitImportant(style);
+        return mapStyleToProps({ ...normal, ...important });
+    };
     if (props._hover) {
-        result.hoverStyle = mapStyleToProps(props._hover);
+        result.hoverStyle = stateStyle(props._hover);
+    }
+    if (props._focus) {
+        result.focusStyle = stateStyle(props._focus);
+    }
+    if (props._active) {
+        result.activeStyle = stateStyle(props._active);
+    }
+    if (props._important) {
+        result.importantStyle = mapStyleToProps(props._important);
     }
 
     // Focus properties
@@ -714,7 +1081,13 @@ export function mapStyleToProps(props: StyleProps): Record<string, any> {
     }
 
     if (props.drawCommands !== undefined) {
-        result.drawCommands = props.drawCommands;
+        result.drawCommands = packDrawCommands(props.drawCommands);
+    }
+    if (props.canvasRetained !== undefined) {
+        result.canvasRetained = props.canvasRetained;
+    }
+    if (props.canvasBatch !== undefined) {
+        result.canvasBatch = props.canvasBatch;
     }
 
     // Input element properties
@@ -745,9 +1118,160 @@ export function mapStyleToProps(props: StyleProps): Record<string, any> {
     if (props.rows !== undefined) {
         result.rows = props.rows;
     }
+    if (props.cols !== undefined) {
+        result.cols = props.cols;
+    }
+    if (props.lineNumbers !== undefined) {
+        result.lineNumbers = props.lineNumbers;
+    }
     if (props.selectionColor !== undefined) {
         result.selectionColor = parseColor(props.selectionColor);
     }
+    if (props.captureTab !== undefined) {
+        result.captureTab = props.captureTab;
+    }
+    if (props.tabSize !== undefined) {
+        result.tabSize = props.tabSize;
+    }
+    if (props.indentWithTabs !== undefined) {
+        result.indentWithTabs = props.indentWithTabs;
+    }
+
+    // Checkbox and radio properties
+    if (props.checked !== undefined) {
+        result.checked = props.checked;
+    }
+    if (props.name !== undefined) {
+        result.name = props.name;
+    }
+    if (props.accentColor !== undefined) {
+        result.accentColor = parseColor(props.accentColor);
+    }
+
+    // Slider, progress, gauge and sparkline properties
+    if (props.min !== undefined) {
+        result.min = props.min;
+    }
+    if (props.max !== undefined) {
+        result.max = props.max;
+    }
+    if (props.step !== undefined) {
+        result.step = props.step;
+    }
+
+    // Gauge properties
+    if (props.gaugeSweep !== undefined) {
+        result.gaugeSweep = props.gaugeSweep;
+    }
+    if (props.gaugeTicks !== undefined) {
+        result.gaugeTicks = props.gaugeTicks;
+    }
+    if (props.gaugeNeedle !== undefined) {
+        result.gaugeNeedle = props.gaugeNeedle;
+    }
+
+    // Sparkline properties
+    if (props.sparklineCapacity !== undefined) {
+        result.sparklineCapacity = props.sparklineCapacity;
+    }
+    if (props.sparklineInterval !== undefined) {
+        result.sparklineInterval = props.sparklineInterval;
+    }
+
+    // QR code properties
+    if (props.symbology !== undefined) {
+        result.symbology = props.symbology;
+    }
+    if (props.qrEcLevel !== undefined) {
+        result.qrEcLevel = props.qrEcLevel;
+    }
+    if (props.quietZone !== undefined) {
+        result.quietZone = props.quietZone;
+    }
+    if (props.logoSize !== undefined) {
+        result.logoSize = props.logoSize;
+    }
+
+    // Avatar properties
+    if (props.avatarStatus !== undefined) {
+        result.avatarStatus = props.avatarStatus;
+    }
+
+    // Code properties
+    if (props.codeLanguage !== undefined) {
+        result.codeLanguage = props.codeLanguage;
+    }
+
+    // Rich text properties
+    if (props.richRuns !== undefined) {
+        result.richRuns = props.richRuns.map((run) => ({
+            ...run,
+            color: run.color !== undefined ? parseColor(run.color) : undefined,
+            background: run.background !== undefined ? parseColor(run.background) : undefined,
+            weight: run.weight !== undefined ? parseFontWeight(run.weight) : undefined,
+        }));
+    }
+
+    // Skeleton properties
+    if (props.skeletonVariant !== undefined) {
+        result.skeletonVariant = props.skeletonVariant;
+    }
+
+    // Icon properties
+    if (props.iconCodepoint !== undefined) {
+        result.iconCodepoint =
+            typeof props.iconCodepoint === "string"
+                ? props.iconCodepoint.codePointAt(0)
+                : props.iconCodepoint;
+    }
+    if (props.iconSize !== undefined) {
+        result.iconSize = props.iconSize;
+    }
+
+    // Resizer properties
+    if (props.orientation !== undefined) {
+        result.orientation = props.orientation;
+    }
+
+    // Overlay properties
+    if (props.backdrop !== undefined) {
+        result.backdrop = parseColor(props.backdrop);
+    }
+
+    // Tooltip properties
+    if (props.tooltipDelay !== undefined) {
+        result.tooltipDelay = props.tooltipDelay;
+    }
+    if (props.tooltipPlacement !== undefined) {
+        result.tooltipPlacement = props.tooltipPlacement;
+    }
+
+    // Shader view properties
+    if (props.shader !== undefined) {
+        result.shader = props.shader;
+    }
+    if (props.shaderParams !== undefined) {
+        result.shaderParams = props.shaderParams;
+    }
+    if (props.animate !== undefined) {
+        result.animate = props.animate;
+    }
+
+    if (props.eventOptions !== undefined) {
+        result.eventOptions = props.eventOptions;
+    }
+    if (props.privacyMode !== undefined) {
+        result.privacyMode = props.privacyMode;
+    }
+    if (props.trackPaint !== undefined) {
+        result.trackPaint = props.trackPaint;
+    }
+    if (props.liveRegion !== undefined) {
+        result.liveRegion = props.liveRegion;
+    }
+    if (props.inert !== undefined) {
+        result.inert = props.inert;
+    }
 
     return result;
 }
@@ -0,0 +1,39 @@
+import type { TextEdit } from "../core";
+
+/** Smallest splice turning `oldText` into `newText`; an append when it can be */
+export function diffText(id: number, oldText: string, newText: string): TextEdit {
+    if (newText.startsWith(oldText)) {
+        return { id, text: newText.slice(oldText.length) };
+    }
+
+    let prefix = 0;
+    const shorter = Math.min(oldText.length, newText.length);
+    while (prefix < shorter && oldText[prefix] === newText[prefix]) {
+        prefix++;
+    }
+    let suffix = 0;
+    while (
+        suffix < shorter - prefix &&
+        oldText[oldText.length - 1 - suffix] === newText[newText.length - 1 - suffix]
+    ) {
+        suffix++;
+    }
+    // Never split a surrogate pair
+    if (prefix > 0 && isHighSurrogate(oldText.charCodeAt(prefix - 1))) {
+        prefix--;
+    }
+    if (suffix > 0 && isHighSurrogate(oldText.charCodeAt(oldText.length - suffix - 1))) {
+        suffix--;
+    }
+
+    return {
+        id,
+        text: newText.slice(prefix, newText.length - suffix),
+        start: prefix,
+        deleteCount: oldText.length - suffix - prefix,
+    };
+}
+
+function isHighSurrogate(code: number): boolean {
+    return code >= 0xd800 && code <= 0xdbff;
+}
@@ -0,0 +1,27 @@
+import { useEffect, useState } from "react";
+import { rustLib } from "./core";
+import type { ResourceStats } from "./core";
+import { useAppContext } from "./reconciler/ctx";
+
+export type { ResourceStats };
+
+/** Resource usage of a window and the process, null if the window is gone */
+export function getResourceStats(windowId: number): ResourceStats | null {
+    return rustLib.getResourceStats(windowId);
+}
+
+/**
+ * Resource usage of the window this component renders in, refreshed every
+ * `intervalMs` (for "About" or performance panels)
+ */
+export function useResourceStats(intervalMs = 1000): ResourceStats | null {
+    const { windowId } = useAppContext();
+    const [stats, setStats] = useState<ResourceStats | null>(() => getResourceStats(windowId));
+
+    useEffect(() => {
+        const interval = setInterval(() => setStats(getResourceStats(windowId)), intervalMs);
+        return () => clearInterval(interval);
+    }, [windowId, intervalMs]);
+
+    return stats;
+}
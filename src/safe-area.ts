@@ -0,0 +1,47 @@
+import { useEffect, useState } from "react";
+import { rustLib } from "./core";
+import type { SafeAreaInsets, WindowEventData } from "./core";
+import { useAppContext } from "./reconciler/ctx";
+import { getSafeArea } from "./reconciler/safe-area";
+import { useWindowEvent } from "./window-events";
+
+export type { SafeAreaInsets };
+
+/**
+ * Payload of the "safeareachange" window event, sent on a window's first
+ * frame and whenever its insets change
+ */
+export interface SafeAreaChangeEvent extends WindowEventData, SafeAreaInsets {}
+
+/**
+ * Declare chrome the app draws over a window's content, like a custom
+ * titlebar or a docked panel. Each edge of the safe area is the larger of
+ * these and the native chrome's (a transparent macOS titlebar); style
+ * lengths read it as `env(safe-area-inset-top)` and so on, also inside
+ * `calc()`.
+ */
+export function setSafeAreaInsets(windowId: number, insets: Partial<SafeAreaInsets>): void {
+    rustLib.setSafeAreaInsets(windowId, insets);
+}
+
+/** Declare chrome over this component's window while it is mounted */
+export function useSafeAreaInsets(insets: Partial<SafeAreaInsets>): void {
+    const { windowId } = useAppContext();
+    const { top, right, bottom, left } = insets;
+
+    useEffect(() => {
+        rustLib.setSafeAreaInsets(windowId, { top, right, bottom, left });
+        return () => rustLib.setSafeAreaInsets(windowId, {});
+    }, [windowId, top, right, bottom, left]);
+}
+
+/** Safe area insets of this component's window, updated as they change */
+export function useSafeArea(): SafeAreaInsets {
+    const { windowId } = useAppContext();
+    const [insets, setInsets] = useState(() => getSafeArea(windowId));
+
+    useWindowEvent<SafeAreaChangeEvent>("safeareachange", ({ top, right, bottom, left }) =>
+        setInsets({ top, right, bottom, left })
+    );
+    return insets;
+}
@@ -0,0 +1,11 @@
+import { rustLib } from "./core";
+
+/**
+ * Display string for a key binding, e.g. "cmd-shift-p" -> "⇧⌘P" on macOS or
+ * "Ctrl+Shift+P" elsewhere. Uses the keystroke syntax Rust matches against,
+ * so menu and tooltip hints name the keys that actually trigger the binding.
+ * Falls back to the input if it doesn't parse.
+ */
+export function formatShortcut(shortcut: string): string {
+    return rustLib.formatShortcut(shortcut) ?? shortcut;
+}
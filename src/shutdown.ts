@@ -0,0 +1,15 @@
+import { rustLib } from "./core";
+
+/**
+ * Close every window and stop the renderer thread, e.g. at the end of a test.
+ * Events still queued are dispatched first, ending with a "shutdown" window
+ * event per window (see useWindowEvent). Call restart() to render again.
+ */
+export function shutdown(): void {
+    rustLib.shutdown();
+}
+
+/** Start the renderer thread again after shutdown() */
+export function restart(): void {
+    rustLib.init();
+}
@@ -0,0 +1,21 @@
+import { rustLib } from "./core";
+import type { ElementStore } from "./reconciler/element-store";
+
+/** What a `ref` on a `<sparkline>` element resolves to */
+export interface SparklineRef {
+    id: number;
+    store: ElementStore;
+}
+
+/**
+ * Append values to a `<sparkline>`, dropping the oldest past its capacity.
+ * The values go to Rust as raw floats without a React render; the sparkline
+ * repaints on its own timer, so pushing every tick is cheap.
+ */
+export function pushSparkline(
+    sparkline: SparklineRef,
+    values: number | number[] | Float64Array
+): void {
+    const data = typeof values === "number" ? [values] : values;
+    rustLib.pushSparkline(sparkline.store.getWindowId(), sparkline.id, data);
+}
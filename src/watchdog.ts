@@ -0,0 +1,25 @@
+import { rustLib } from "./core";
+import type { WatchdogOptions, WindowEventData } from "./core";
+
+export type { WatchdogOptions };
+
+/** Payload of the "hang" window event */
+export interface HangEvent extends WindowEventData {
+    /** What the GPUI thread was doing: "command", "render", "paint" or "idle" */
+    stage: string;
+    stalledMs: number;
+    /** Host commands queued and not yet picked up */
+    pendingCommands: number;
+    /** Backtrace of where the stage was entered, with `captureStack` */
+    stack: string | null;
+}
+
+/**
+ * Watch the GPUI thread for hangs. When a frame stage or a queued command
+ * takes longer than `deadlineMs`, every window gets a "hang" event (see
+ * useWindowEvent) once per stall, and the stall is logged. Omitted fields
+ * keep their current setting.
+ */
+export function setWatchdog(options: WatchdogOptions): void {
+    rustLib.setWatchdog(options);
+}
@@ -0,0 +1,165 @@
+import { useEffect, useRef } from "react";
+import { rustLib } from "./core";
+import type { WindowEventData } from "./core";
+import { useAppContext } from "./reconciler/ctx";
+
+export type { WindowEventData };
+
+/** A match found by the built-in find overlay, in window coordinates */
+export interface FindMatch {
+    elementId: number;
+    x: number;
+    y: number;
+    width: number;
+    height: number;
+}
+
+/** Payload of the "findresult" window event */
+export interface FindResultEvent extends WindowEventData {
+    open: boolean;
+    query: string;
+    count: number;
+    current: number | null;
+    matches: FindMatch[];
+    /** Per-document match counts for `<document>` elements */
+    documents: { elementId: number; count: number; current: number | null }[];
+}
+
+/** Payload of the "fullscreenchange" window event, sent on native transitions */
+export interface FullscreenChangeEvent extends WindowEventData {
+    fullscreen: boolean;
+}
+
+/**
+ * Subscribe to a window-level event of the window this component renders in
+ */
+export function useWindowEvent<T extends WindowEventData = WindowEventData>(
+    eventType: string,
+    listener: (event: T) => void
+): void {
+    const { windowId } = useAppContext();
+    const listenerRef = useRef(listener);
+    listenerRef.current = listener;
+
+    useEffect(() => {
+        return rustLib.onWindowEvent(windowId, eventType, (event) =>
+            listenerRef.current(event as T)
+        );
+    }, [windowId, eventType]);
+}
+
+/** Payload of the "resizestart" and "resizeend" window events */
+export interface ResizeEvent extends WindowEventData {
+    /** Window size before the resize for "resizestart", after it for "resizeend" */
+    width: number;
+    height: number;
+}
+
+/**
+ * Coalesce layout while this component's window is being resized: the tree
+ * keeps its last layout size and is laid out again at most once per
+ * `intervalMs`, then at the final size once the window stops changing size.
+ * Listen for "resizestart" and "resizeend" with `useWindowEvent`.
+ */
+export function useLiveResize(intervalMs = 100, enabled = true): void {
+    const { windowId } = useAppContext();
+
+    useEffect(() => {
+        rustLib.setLiveResize(windowId, enabled ? intervalMs : 0);
+        return () => rustLib.setLiveResize(windowId, 0);
+    }, [windowId, intervalMs, enabled]);
+}
+
+/** One element under the cursor in a "hittest" event */
+export interface HitTestEntry {
+    elementId: number;
+    type: string;
+    bounds: { x: number; y: number; width: number; height: number };
+    /** Has mouse handlers */
+    interactive: boolean;
+    /** For interactive elements: false if another element covered its hitbox */
+    reachable: boolean | null;
+    /** Events dispatched to this element for the release, e.g. ["mouseup", "click"] */
+    received: string[];
+}
+
+/** Payload of the "hittest" window event, queued on mouse release while enabled */
+export interface HitTestEvent extends WindowEventData {
+    x: number;
+    y: number;
+    button: number;
+    /** Elements under the cursor, innermost first */
+    stack: HitTestEntry[];
+    /** Innermost element that received an event, null if none did */
+    consumedBy: number | null;
+}
+
+/**
+ * Report the element stack under the cursor on every mouse release of this
+ * component's window, for debugging clicks that don't register
+ */
+export function useHitTestDebug(listener: (event: HitTestEvent) => void, enabled = true): void {
+    const { windowId } = useAppContext();
+
+    useEffect(() => {
+        rustLib.setHitTestDebug(windowId, enabled);
+        return () => rustLib.setHitTestDebug(windowId, false);
+    }, [windowId, enabled]);
+
+    useWindowEvent<HitTestEvent>("hittest", listener);
+}
+
+/** Payload of the "paintchanged" window event */
+export interface PaintChangedEvent extends WindowEventData {
+    /** The trackPaint element whose subtree changed */
+    targetId: number;
+    /** Hash of the subtree's type, text, effective style and bounds */
+    hash: string;
+    /** Null the first time the element is seen */
+    previousHash: string | null;
+    /** Tree generation the change landed in */
+    generation: number;
+}
+
+/**
+ * Report paint changes of `trackPaint` elements in this component's window,
+ * checked once per commit, for visual test tooling
+ */
+export function usePaintTracking(listener: (event: PaintChangedEvent) => void, enabled = true): void {
+    const { windowId } = useAppContext();
+
+    useEffect(() => {
+        rustLib.setPaintTracking(windowId, enabled);
+        return () => rustLib.setPaintTracking(windowId, false);
+    }, [windowId, enabled]);
+
+    useWindowEvent<PaintChangedEvent>("paintchanged", listener);
+}
+
+/** Payload of the "contrastwarning" window event */
+export interface ContrastWarningEvent extends WindowEventData {
+    /** The element whose text is below WCAG AA contrast */
+    targetId: number;
+    /** Contrast of the text against its background, from 1 to 21 */
+    ratio: number;
+    /** 4.5, or 3 for large text */
+    minimum: number;
+    /** Text and background colors as painted, "#rrggbb" */
+    color: string;
+    background: string;
+}
+
+/**
+ * Report text in this component's window whose contrast against the
+ * background behind it is below WCAG AA. The audit only runs in debug builds
+ * of the native library, once per commit, and reports each text once until
+ * its colors change.
+ */
+export function useContrastWarnings(listener: (event: ContrastWarningEvent) => void): void {
+    useWindowEvent<ContrastWarningEvent>("contrastwarning", listener);
+}
+
+/** Subscribe to find overlay results (requires `findOverlay: true`) */
+export function useFindResult(listener: (event: FindResultEvent) => void): void {
+    useWindowEvent<FindResultEvent>("findresult", listener);
+}